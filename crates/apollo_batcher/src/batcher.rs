@@ -64,6 +64,7 @@ use crate::metrics::{
     LAST_BATCHED_BLOCK,
     LAST_PROPOSED_BLOCK,
     LAST_SYNCED_BLOCK,
+    NATIVE_EXECUTION_MISMATCHES,
     REJECTED_TRANSACTIONS,
     REVERTED_BLOCKS,
     STORAGE_HEIGHT,
@@ -535,6 +536,8 @@ impl Batcher {
         LAST_BATCHED_BLOCK.set_lossy(height.0);
         CLASS_CACHE_MISSES.increment(self.block_builder_factory.take_class_cache_miss_counter());
         CLASS_CACHE_HITS.increment(self.block_builder_factory.take_class_cache_hit_counter());
+        NATIVE_EXECUTION_MISMATCHES
+            .increment(self.block_builder_factory.take_native_execution_mismatch_counter());
         BATCHED_TRANSACTIONS.increment(n_txs);
         REJECTED_TRANSACTIONS.increment(n_rejected_txs);
 
@@ -129,6 +129,7 @@ impl Default for MockDependencies {
         let mut block_builder_factory = MockBlockBuilderFactoryTrait::new();
         block_builder_factory.expect_take_class_cache_miss_counter().return_const(0_u64);
         block_builder_factory.expect_take_class_cache_hit_counter().return_const(0_u64);
+        block_builder_factory.expect_take_native_execution_mismatch_counter().return_const(0_u64);
 
         Self {
             storage_reader,
@@ -348,6 +348,8 @@ pub trait BlockBuilderFactoryTrait: Send + Sync {
     fn take_class_cache_miss_counter(&self) -> u64;
 
     fn take_class_cache_hit_counter(&self) -> u64;
+
+    fn take_native_execution_mismatch_counter(&self) -> u64;
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -473,6 +475,10 @@ impl BlockBuilderFactoryTrait for BlockBuilderFactory {
     fn take_class_cache_miss_counter(&self) -> u64 {
         self.contract_class_manager.take_cache_miss_counter()
     }
+
+    fn take_native_execution_mismatch_counter(&self) -> u64 {
+        self.contract_class_manager.take_native_execution_mismatch_counter()
+    }
 }
 
 /// Supplementary information for use by downstream services.
@@ -23,7 +23,8 @@ define_metrics!(
         MetricCounter { FULL_BLOCKS, "batcher_full_blocks", "Counter of blocks closed on full capacity", init = 0 },
         // TODO(yair): move this
         MetricCounter { CLASS_CACHE_MISSES, "class_cache_misses", "Counter of global class cache misses", init=0 },
-        MetricCounter { CLASS_CACHE_HITS, "class_cache_hits", "Counter of global class cache hits", init=0 }
+        MetricCounter { CLASS_CACHE_HITS, "class_cache_hits", "Counter of global class cache hits", init=0 },
+        MetricCounter { NATIVE_EXECUTION_MISMATCHES, "native_execution_mismatches", "Counter of entry point executions whose Cairo native and Cairo VM results differ, in differential execution mode", init=0 }
     },
 );
 
@@ -48,6 +49,7 @@ pub fn register_metrics(storage_height: BlockNumber) {
 
     CLASS_CACHE_MISSES.register();
     CLASS_CACHE_HITS.register();
+    NATIVE_EXECUTION_MISMATCHES.register();
 }
 
 /// A handle to update the proposal metrics when the proposal is created and dropped.
@@ -8,8 +8,9 @@ pub mod sources;
 #[cfg(test)]
 mod sync_test;
 
-use std::cmp::min;
+use std::cmp::{max, min};
 use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -19,7 +20,7 @@ use apollo_config::dumping::{ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_proc_macros::latency_histogram;
 use apollo_starknet_client::reader::PendingData;
-use apollo_storage::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter};
+use apollo_storage::base_layer::{BaseLayerProof, BaseLayerStorageReader, BaseLayerStorageWriter};
 use apollo_storage::body::BodyStorageWriter;
 use apollo_storage::class::{ClassStorageReader, ClassStorageWriter};
 use apollo_storage::class_manager::{ClassManagerStorageReader, ClassManagerStorageWriter};
@@ -35,6 +36,7 @@ use futures::future::pending;
 use futures::stream;
 use futures_util::{pin_mut, select, Stream, StreamExt};
 use indexmap::IndexMap;
+use papyrus_base_layer::L1BlockNumber;
 use papyrus_common::pending_classes::PendingClasses;
 use serde::{Deserialize, Serialize};
 use sources::base_layer::BaseLayerSourceError;
@@ -85,6 +87,9 @@ const SLEEP_TIME_SYNC_PROGRESS: Duration = Duration::from_secs(300);
 // will compile them, in a backward-compatible manner.
 const STARKNET_VERSION_TO_COMPILE_FROM: StarknetVersion = StarknetVersion::V0_12_0;
 
+// The maximal number of base layer blocks that are scanned for state updates at once.
+const BASE_LAYER_SCAN_CHUNK_SIZE: u64 = 1000;
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SyncConfig {
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
@@ -190,6 +195,19 @@ pub struct GenericStateSync<
     writer: Arc<Mutex<StorageWriter>>,
     sequencer_pub_key: Option<SequencerPublicKey>,
     class_manager_client: Option<SharedClassManagerClient>,
+    base_layer_scan: BaseLayerScan,
+}
+
+/// The progress of scanning the base layer for the state updates that proved blocks. The scan
+/// follows the base layer tip and backfills the proofs of the blocks that were proved before it
+/// started. It's kept by the sync so that a failure doesn't restart it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BaseLayerScan {
+    // The base layer blocks that were scanned, which are always consecutive.
+    scanned_l1_blocks: Option<RangeInclusive<L1BlockNumber>>,
+    // Whether the scan reached the state updates that preceded it, i.e., the first state update or
+    // one whose proof was already stored.
+    backfilled: bool,
 }
 
 pub type StateSyncResult = Result<(), StateSyncError>;
@@ -265,6 +283,13 @@ pub enum SyncEvent {
         block_number: BlockNumber,
         block_hash: BlockHash,
     },
+    BaseLayerProofsAvailable {
+        /// The proofs of the state updates that were found, each along with the last block it
+        /// proved.
+        proofs: Vec<(BlockNumber, BaseLayerProof)>,
+        /// The progress of the scan, including the newly scanned base layer blocks.
+        scan: BaseLayerScan,
+    },
 }
 
 impl<
@@ -384,6 +409,7 @@ impl<
                 self.reader.clone(),
                 base_layer_source.clone(),
                 self.config.base_layer_propagation_sleep_duration,
+                self.base_layer_scan.clone(),
             )
             .boxed()
             .fuse(),
@@ -455,6 +481,9 @@ impl<
             SyncEvent::NewBaseLayerBlock { block_number, block_hash } => {
                 self.store_base_layer_block(block_number, block_hash).await
             }
+            SyncEvent::BaseLayerProofsAvailable { proofs, scan } => {
+                self.store_base_layer_proofs(proofs, scan).await
+            }
             SyncEvent::NoProgress => Err(StateSyncError::NoProgress),
         }
     }
@@ -680,7 +709,7 @@ impl<
         block_hash: BlockHash,
     ) -> StateSyncResult {
         self.perform_storage_writes(move |writer| {
-            let txn = writer.begin_rw_txn()?;
+            let mut txn = writer.begin_rw_txn()?;
             // Missing header can be because of a base layer reorg, the matching header may be
             // reverted.
             let expected_hash = txn
@@ -697,14 +726,39 @@ impl<
             }
             if txn.get_base_layer_block_marker()? != block_number.unchecked_next() {
                 info!("Verified block {block_number} hash against base layer.");
-                txn.update_base_layer_block_marker(&block_number.unchecked_next())?.commit()?;
+                txn = txn.update_base_layer_block_marker(&block_number.unchecked_next())?;
                 SYNC_BASE_LAYER_MARKER.set_lossy(block_number.unchecked_next().0);
             }
+            txn.commit()?;
             Ok(())
         })
         .await
     }
 
+    #[instrument(skip(self, proofs), level = "debug", err)]
+    async fn store_base_layer_proofs(
+        &mut self,
+        proofs: Vec<(BlockNumber, BaseLayerProof)>,
+        scan: BaseLayerScan,
+    ) -> StateSyncResult {
+        self.perform_storage_writes(move |writer| {
+            let mut txn = writer.begin_rw_txn()?;
+            for (block_number, proof) in proofs {
+                debug!(
+                    "Blocks {} to {block_number} were proved in base layer block {}.",
+                    proof.first_proved_block, proof.l1_block_number
+                );
+                txn = txn.update_base_layer_proof(block_number, &proof)?;
+            }
+            txn.commit()?;
+            Ok(())
+        })
+        .await?;
+        // The scan advances only once its proofs are stored, so that a failure rescans them.
+        self.base_layer_scan = scan;
+        Ok(())
+    }
+
     // Compares the block's parent hash to the stored block.
     fn verify_parent_block_hash(
         &self,
@@ -962,6 +1016,7 @@ impl StateSync {
             writer: Arc::new(Mutex::new(writer)),
             sequencer_pub_key: None,
             class_manager_client,
+            base_layer_scan: BaseLayerScan::default(),
         }
     }
 }
@@ -1039,6 +1094,7 @@ fn stream_new_base_layer_block<TBaseLayerSource: BaseLayerSourceTrait + Sync>(
     reader: StorageReader,
     base_layer_source: Arc<TBaseLayerSource>,
     base_layer_propagation_sleep_duration: Duration,
+    mut base_layer_scan: BaseLayerScan,
 ) -> impl Stream<Item = Result<SyncEvent, StateSyncError>> {
     try_stream! {
         loop {
@@ -1061,12 +1117,75 @@ fn stream_new_base_layer_block<TBaseLayerSource: BaseLayerSourceTrait + Sync>(
                         "No blocks were proved on the base layer, waiting for blockchain to \
                          advance."
                     );
+                    continue;
                 }
             }
+            // Scans until reaching the base layer tip and backfilling, rather than a chunk per
+            // polling.
+            while let Some((proofs, scan)) =
+                scan_base_layer(&reader, base_layer_source.as_ref(), &base_layer_scan).await?
+            {
+                base_layer_scan = scan.clone();
+                yield SyncEvent::BaseLayerProofsAvailable { proofs, scan };
+            }
         }
     }
 }
 
+// Scans the next chunk of base layer blocks for state updates. The blocks after the scanned ones
+// are scanned first, so that new proofs are found as soon as possible, and then the blocks before
+// them until the proofs are backfilled. Returns None if there's nothing to scan.
+async fn scan_base_layer<TBaseLayerSource: BaseLayerSourceTrait + Sync>(
+    reader: &StorageReader,
+    base_layer_source: &TBaseLayerSource,
+    scan: &BaseLayerScan,
+) -> Result<Option<(Vec<(BlockNumber, BaseLayerProof)>, BaseLayerScan)>, StateSyncError> {
+    let Some(latest_l1_block_number) = base_layer_source.latest_l1_block_number().await? else {
+        return Ok(None);
+    };
+    let l1_block_range = match &scan.scanned_l1_blocks {
+        None => {
+            latest_l1_block_number.saturating_sub(BASE_LAYER_SCAN_CHUNK_SIZE - 1)
+                ..=latest_l1_block_number
+        }
+        Some(scanned_l1_blocks) if *scanned_l1_blocks.end() < latest_l1_block_number => {
+            scanned_l1_blocks.end() + 1
+                ..=min(scanned_l1_blocks.end() + BASE_LAYER_SCAN_CHUNK_SIZE, latest_l1_block_number)
+        }
+        Some(scanned_l1_blocks) if !scan.backfilled && *scanned_l1_blocks.start() > 0 => {
+            scanned_l1_blocks.start().saturating_sub(BASE_LAYER_SCAN_CHUNK_SIZE)
+                ..=scanned_l1_blocks.start() - 1
+        }
+        Some(_) => return Ok(None),
+    };
+    debug!("Scanning base layer blocks {l1_block_range:?} for state updates.");
+    let proofs = base_layer_source.base_layer_proofs(l1_block_range.clone()).await?;
+
+    let scanned_l1_blocks = match &scan.scanned_l1_blocks {
+        Some(scanned_l1_blocks) => {
+            *min(scanned_l1_blocks.start(), l1_block_range.start())
+                ..=*max(scanned_l1_blocks.end(), l1_block_range.end())
+        }
+        None => l1_block_range.clone(),
+    };
+    // The proofs are backfilled once the scan reaches the first base layer block, the state update
+    // that proved the first block or a state update whose proof was already stored.
+    let backfilled = scan.backfilled || *scanned_l1_blocks.start() == 0 || {
+        let txn = reader.begin_ro_txn()?;
+        let mut is_backfilled = false;
+        for (block_number, proof) in &proofs {
+            if proof.first_proved_block == BlockNumber(0)
+                || txn.get_base_layer_proof(*block_number)?.as_ref() == Some(proof)
+            {
+                is_backfilled = true;
+                break;
+            }
+        }
+        is_backfilled
+    };
+    Ok(Some((proofs, BaseLayerScan { scanned_l1_blocks: Some(scanned_l1_blocks), backfilled })))
+}
+
 // This function is used to check if the sync is stuck.
 // TODO(DvirYo): fix the bug and remove this function.
 // TODO(dvir): add a test for this scenario.
@@ -1,12 +1,21 @@
+use std::ops::RangeInclusive;
+
+use apollo_storage::base_layer::BaseLayerProof;
 use async_trait::async_trait;
 #[cfg(test)]
 use mockall::automock;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
-use papyrus_base_layer::BaseLayerContract;
+#[cfg(any(feature = "testing", test))]
+use papyrus_base_layer::fake_base_layer_contract::FakeBaseLayerContract;
+use papyrus_base_layer::{BaseLayerContract, L1BlockNumber};
 use starknet_api::block::{BlockHash, BlockNumber};
 
 pub type EthereumBaseLayerSource = EthereumBaseLayerContract;
 
+/// A base layer source with a scriptable chain, for testing the sync without an L1 node.
+#[cfg(any(feature = "testing", test))]
+pub type FakeBaseLayerSource = FakeBaseLayerContract;
+
 #[derive(thiserror::Error, Debug)]
 pub enum BaseLayerSourceError {
     #[error("Base layer error: {0}")]
@@ -25,6 +34,15 @@ pub trait BaseLayerSourceTrait {
     async fn latest_proved_block(
         &self,
     ) -> Result<Option<(BlockNumber, BlockHash)>, BaseLayerSourceError>;
+
+    async fn latest_l1_block_number(&self) -> Result<Option<L1BlockNumber>, BaseLayerSourceError>;
+
+    /// Returns the proofs of the state updates in the given base layer blocks, each along with the
+    /// last block it proved.
+    async fn base_layer_proofs(
+        &self,
+        l1_block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<(BlockNumber, BaseLayerProof)>, BaseLayerSourceError>;
 }
 
 #[async_trait]
@@ -42,4 +60,49 @@ impl<
             .map(|block| block.map(|block| (block.number, block.hash)))
             .map_err(|e| BaseLayerSourceError::BaseLayerContractError(Box::new(e)))
     }
+
+    async fn latest_l1_block_number(&self) -> Result<Option<L1BlockNumber>, BaseLayerSourceError> {
+        let finality = 0;
+        self.latest_l1_block_number(finality)
+            .await
+            .map_err(|e| BaseLayerSourceError::BaseLayerContractError(Box::new(e)))
+    }
+
+    async fn base_layer_proofs(
+        &self,
+        l1_block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<(BlockNumber, BaseLayerProof)>, BaseLayerSourceError> {
+        let to_base_layer_source_error =
+            |e| BaseLayerSourceError::BaseLayerContractError(Box::new(e));
+        let state_updates =
+            self.state_updates(l1_block_range).await.map_err(to_base_layer_source_error)?;
+        let mut proofs = Vec::with_capacity(state_updates.len());
+        let mut previous_proved_block = None;
+        for state_update in state_updates {
+            // A state update proved the blocks after the ones that the previous state update
+            // proved. For the first state update in the range, the previous state update is the
+            // last one before its base layer block.
+            let first_proved_block = match (previous_proved_block, state_update.block_number) {
+                (Some(previous_proved_block), _) => previous_proved_block.unchecked_next(),
+                (None, BlockNumber(0)) => BlockNumber(0),
+                (None, _) => self
+                    .get_proved_block_at(state_update.l1_block.number.saturating_sub(1))
+                    .await
+                    .map_err(to_base_layer_source_error)?
+                    .number
+                    .unchecked_next(),
+            };
+            previous_proved_block = Some(state_update.block_number);
+            proofs.push((
+                state_update.block_number,
+                BaseLayerProof {
+                    first_proved_block,
+                    l1_block_number: state_update.l1_block.number,
+                    l1_block_hash: state_update.l1_block.hash,
+                    l1_transaction_hash: state_update.l1_transaction_hash,
+                },
+            ));
+        }
+        Ok(proofs)
+    }
 }
@@ -4,7 +4,7 @@ use std::time::Duration;
 
 use apollo_class_manager_types::ClassManagerClient;
 use apollo_starknet_client::reader::PendingData;
-use apollo_storage::base_layer::BaseLayerStorageReader;
+use apollo_storage::base_layer::{BaseLayerProof, BaseLayerStorageReader};
 use apollo_storage::header::HeaderStorageReader;
 use apollo_storage::state::StateStorageReader;
 use apollo_storage::test_utils::get_test_storage;
@@ -35,7 +35,11 @@ use tokio::time::sleep;
 use tracing::{debug, error};
 
 use super::pending::MockPendingSourceTrait;
-use crate::sources::base_layer::{BaseLayerSourceTrait, MockBaseLayerSourceTrait};
+use crate::sources::base_layer::{
+    BaseLayerSourceTrait,
+    FakeBaseLayerSource,
+    MockBaseLayerSourceTrait,
+};
 use crate::sources::central::{
     BlocksStream,
     CompiledClassesStream,
@@ -43,6 +47,7 @@ use crate::sources::central::{
     StateUpdatesStream,
 };
 use crate::{
+    BaseLayerScan,
     CentralError,
     CentralSourceTrait,
     GenericStateSync,
@@ -135,6 +140,7 @@ async fn run_sync(
         reader,
         writer: Arc::new(Mutex::new(writer)),
         sequencer_pub_key: None,
+        base_layer_scan: BaseLayerScan::default(),
         // TODO(shahak): Add test with mock class manager client.
         // TODO(shahak): Add test with post 0.14.0 block and mock class manager client and see that
         // up until that block we call add_class_and_executable_unsafe and from that block we call
@@ -184,26 +190,20 @@ async fn sync_empty_chain() {
     }
 }
 
-#[tokio::test]
-async fn sync_happy_flow() {
-    const N_BLOCKS: u64 = 5;
-    const LATEST_BLOCK_NUMBER: BlockNumber = BlockNumber(N_BLOCKS - 1);
-    // FIXME: (Omri) analyze and set a lower value.
-    const MAX_TIME_TO_SYNC_MS: u64 = 800;
-    let _ = simple_logger::init_with_env();
-
-    // Mock having N_BLOCKS chain in central.
+// Mocks a central with a chain of `n_blocks` blocks.
+fn get_central_mock_with_n_blocks(n_blocks: u64) -> MockCentralSourceTrait {
+    let latest_block_number = BlockNumber(n_blocks - 1);
     let mut central_mock = MockCentralSourceTrait::new();
-    central_mock.expect_get_latest_block().returning(|| {
+    central_mock.expect_get_latest_block().returning(move || {
         Ok(Some(BlockHashAndNumber {
-            number: LATEST_BLOCK_NUMBER,
-            hash: create_block_hash(LATEST_BLOCK_NUMBER, false),
+            number: latest_block_number,
+            hash: create_block_hash(latest_block_number, false),
         }))
     });
     central_mock.expect_stream_new_blocks().returning(move |initial, up_to| {
         let blocks_stream: BlocksStream<'_> = stream! {
             for block_number in initial.iter_up_to(up_to) {
-                if block_number.0 >= N_BLOCKS {
+                if block_number.0 >= n_blocks {
                     yield Err(CentralError::BlockNotFound { block_number });
                 }
                 let header = BlockHeader {
@@ -229,7 +229,7 @@ async fn sync_happy_flow() {
         let state_stream: StateUpdatesStream<'_> = stream! {
             for block_number in initial.iter_up_to(up_to) {
                 // TODO(Eitan): test classes were added to class manager by including declared classes and deprecated declared classes
-                if block_number.0 >= N_BLOCKS {
+                if block_number.0 >= n_blocks {
                     yield Err(CentralError::BlockNotFound { block_number })
                 }
                 yield Ok((
@@ -244,10 +244,22 @@ async fn sync_happy_flow() {
         state_stream
     });
     central_mock.expect_get_block_hash().returning(|bn| Ok(Some(create_block_hash(bn, false))));
+    central_mock
+}
+
+#[tokio::test]
+async fn sync_happy_flow() {
+    const N_BLOCKS: u64 = 5;
+    // FIXME: (Omri) analyze and set a lower value.
+    const MAX_TIME_TO_SYNC_MS: u64 = 800;
+    let _ = simple_logger::init_with_env();
+
+    let central_mock = get_central_mock_with_n_blocks(N_BLOCKS);
 
     // TODO(dvir): find a better way to do this.
     let mut base_layer_mock = MockBaseLayerSourceTrait::new();
     let mut base_layer_call_counter = 0;
+    base_layer_mock.expect_latest_l1_block_number().returning(|| Ok(None));
     base_layer_mock.expect_latest_proved_block().returning(move || {
         base_layer_call_counter += 1;
         Ok(match base_layer_call_counter {
@@ -314,6 +326,87 @@ async fn sync_happy_flow() {
     }
 }
 
+#[tokio::test]
+async fn sync_base_layer_proofs_from_fake_base_layer() {
+    const N_BLOCKS: u64 = 5;
+    const MAX_TIME_TO_SYNC_MS: u64 = 800;
+    let _ = simple_logger::init_with_env();
+
+    let central_mock = get_central_mock_with_n_blocks(N_BLOCKS);
+
+    // The genesis block of the fake base layer proves block 0. Blocks 1 and 2 are proved in L1
+    // block 1, and blocks 3 and 4 in L1 block 3.
+    let base_layer = FakeBaseLayerSource::new();
+    let proved_block = |block_number| BlockHashAndNumber {
+        number: block_number,
+        hash: create_block_hash(block_number, false),
+    };
+    let first_l1_transaction_hash = base_layer.prove_block(proved_block(BlockNumber(2)));
+    let first_l1_block = base_layer.mine_block();
+    base_layer.mine_block();
+    let l1_transaction_hash = base_layer.prove_block(proved_block(BlockNumber(N_BLOCKS - 1)));
+    let l1_block = base_layer.mine_block();
+    let first_expected_proof = BaseLayerProof {
+        first_proved_block: BlockNumber(1),
+        l1_block_number: first_l1_block.number,
+        l1_block_hash: first_l1_block.hash,
+        l1_transaction_hash: first_l1_transaction_hash,
+    };
+    let expected_proof = BaseLayerProof {
+        first_proved_block: BlockNumber(3),
+        l1_block_number: l1_block.number,
+        l1_block_hash: l1_block.hash,
+        l1_transaction_hash,
+    };
+
+    let ((reader, writer), _temp_dir) = get_test_storage();
+    let sync_future = run_sync(
+        reader.clone(),
+        writer,
+        central_mock,
+        base_layer,
+        get_test_sync_config(false),
+        None,
+    );
+
+    // Check that the base layer marker reached N_BLOCKS and that the proofs of the state updates
+    // were stored, within MAX_TIME_TO_SYNC_MS.
+    let check_storage_future =
+        check_storage(reader, Duration::from_millis(MAX_TIME_TO_SYNC_MS), |reader| {
+            let txn = reader.begin_ro_txn().unwrap();
+            let base_layer_marker = txn.get_base_layer_block_marker().unwrap();
+            debug!("Base layer marker currently at {base_layer_marker}");
+            if base_layer_marker < BlockNumber(N_BLOCKS) {
+                return CheckStoragePredicateResult::InProgress;
+            }
+            if base_layer_marker > BlockNumber(N_BLOCKS) {
+                return CheckStoragePredicateResult::Error;
+            }
+
+            let proofs =
+                [BlockNumber(1), BlockNumber(2), BlockNumber(3), BlockNumber(N_BLOCKS - 1)]
+                    .map(|block_number| txn.get_base_layer_proof(block_number).unwrap());
+            let expected_proofs = [
+                Some(first_expected_proof),
+                Some(first_expected_proof),
+                Some(expected_proof),
+                Some(expected_proof),
+            ];
+            // The proofs are stored separately from the base layer marker.
+            if proofs != expected_proofs {
+                debug!("Base layer proofs currently are {proofs:?}");
+                return CheckStoragePredicateResult::InProgress;
+            }
+            CheckStoragePredicateResult::Passed
+        });
+
+    tokio::select! {
+        _ = sleep(Duration::from_secs(1)) => panic!("Test timed out."),
+        sync_result = sync_future => sync_result.unwrap(),
+        storage_check_result = check_storage_future => assert!(storage_check_result),
+    }
+}
+
 #[tokio::test]
 async fn sync_with_revert() {
     let _ = simple_logger::init_with_env();
@@ -1,3 +1,4 @@
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,7 +11,7 @@ use apollo_starknet_client::reader::objects::pending_data::{
 use apollo_starknet_client::reader::objects::state::StateDiff as ClientStateDiff;
 use apollo_starknet_client::reader::objects::transaction::Transaction as ClientTransaction;
 use apollo_starknet_client::reader::{DeclaredClassHashEntry, PendingData};
-use apollo_storage::base_layer::BaseLayerStorageReader;
+use apollo_storage::base_layer::{BaseLayerProof, BaseLayerStorageReader};
 use apollo_storage::header::HeaderStorageWriter;
 use apollo_storage::test_utils::get_test_storage;
 use apollo_storage::{StorageReader, StorageWriter};
@@ -36,6 +37,7 @@ use crate::{
     sort_state_diff,
     stream_new_base_layer_block,
     sync_pending_data,
+    BaseLayerScan,
     GenericStateSync,
     StateSyncError,
     SyncConfig,
@@ -132,8 +134,14 @@ async fn stream_new_base_layer_block_test_header_marker() {
     let mut iter = block_numbers.into_iter().map(|bn| (BlockNumber(bn), BlockHash::default()));
     let mut mock = MockBaseLayerSourceTrait::new();
     mock.expect_latest_proved_block().times(4).returning(move || Ok(iter.next()));
-    let mut stream =
-        stream_new_base_layer_block(reader, Arc::new(mock), Duration::from_millis(0)).boxed();
+    mock.expect_latest_l1_block_number().returning(|| Ok(None));
+    let mut stream = stream_new_base_layer_block(
+        reader,
+        Arc::new(mock),
+        Duration::from_millis(0),
+        BaseLayerScan::default(),
+    )
+    .boxed();
 
     let event = stream.next().await.unwrap().unwrap();
     assert_matches!(event, SyncEvent::NewBaseLayerBlock { block_number: BlockNumber(1), .. });
@@ -154,14 +162,132 @@ async fn stream_new_base_layer_block_no_blocks_on_base_layer() {
     let mut values = vec![None, Some((BlockNumber(1), BlockHash::default()))].into_iter();
     let mut mock = MockBaseLayerSourceTrait::new();
     mock.expect_latest_proved_block().times(2).returning(move || Ok(values.next().unwrap()));
+    mock.expect_latest_l1_block_number().returning(|| Ok(None));
 
-    let mut stream =
-        stream_new_base_layer_block(reader, Arc::new(mock), Duration::from_millis(0)).boxed();
+    let mut stream = stream_new_base_layer_block(
+        reader,
+        Arc::new(mock),
+        Duration::from_millis(0),
+        BaseLayerScan::default(),
+    )
+    .boxed();
 
     let event = stream.next().await.unwrap().unwrap();
     assert_matches!(event, SyncEvent::NewBaseLayerBlock { block_number: BlockNumber(1), .. });
 }
 
+fn proof(block_number: u64) -> BaseLayerProof {
+    BaseLayerProof {
+        first_proved_block: BlockNumber(block_number),
+        l1_block_number: block_number + 100,
+        l1_block_hash: [1; 32],
+        l1_transaction_hash: [2; 32],
+    }
+}
+
+fn scan(scanned_l1_blocks: RangeInclusive<u64>, backfilled: bool) -> BaseLayerScan {
+    BaseLayerScan { scanned_l1_blocks: Some(scanned_l1_blocks), backfilled }
+}
+
+#[tokio::test]
+async fn stream_new_base_layer_block_scans_and_backfills_proofs() {
+    let (reader, mut writer) = get_test_storage().0;
+    add_headers(5, &mut writer);
+
+    let mut mock = MockBaseLayerSourceTrait::new();
+    mock.expect_latest_proved_block()
+        .returning(|| Ok(Some((BlockNumber(1), BlockHash::default()))));
+    // The base layer advances after the proofs are backfilled.
+    let mut latest_l1_block_numbers = [2500; 4].into_iter();
+    mock.expect_latest_l1_block_number()
+        .returning(move || Ok(Some(latest_l1_block_numbers.next().unwrap_or(2600))));
+    // Block 0 was proved in base layer block 100, and blocks 1-2 in base layer block 2550.
+    mock.expect_base_layer_proofs().times(4).returning(|l1_block_range| {
+        Ok([
+            (BlockNumber(0), proof(0)),
+            (BlockNumber(2), BaseLayerProof { l1_block_number: 2550, ..proof(1) }),
+        ]
+        .into_iter()
+        .filter(|(_, proof)| l1_block_range.contains(&proof.l1_block_number))
+        .collect())
+    });
+
+    let mut stream = stream_new_base_layer_block(
+        reader,
+        Arc::new(mock),
+        Duration::from_millis(0),
+        BaseLayerScan::default(),
+    )
+    .boxed();
+
+    // The latest base layer blocks are scanned first, and then the ones before them until the
+    // first state update is found.
+    let expected_events = [
+        (vec![], scan(1501..=2500, false)),
+        (vec![], scan(501..=2500, false)),
+        (vec![(BlockNumber(0), proof(0))], scan(0..=2500, true)),
+    ];
+    assert_matches!(stream.next().await.unwrap().unwrap(), SyncEvent::NewBaseLayerBlock { .. });
+    for (expected_proofs, expected_scan) in expected_events {
+        let event = stream.next().await.unwrap().unwrap();
+        assert_matches!(
+            event,
+            SyncEvent::BaseLayerProofsAvailable { proofs, scan }
+            if proofs == expected_proofs && scan == expected_scan
+        );
+    }
+
+    // Once the base layer advances, only the new blocks are scanned.
+    assert_matches!(stream.next().await.unwrap().unwrap(), SyncEvent::NewBaseLayerBlock { .. });
+    let event = stream.next().await.unwrap().unwrap();
+    assert_matches!(
+        event,
+        SyncEvent::BaseLayerProofsAvailable { proofs, scan: new_scan }
+        if proofs.len() == 1 && new_scan == scan(0..=2600, true)
+    );
+}
+
+#[tokio::test]
+async fn stream_new_base_layer_block_resumes_scan() {
+    let (reader, mut writer) = get_test_storage().0;
+    add_headers(5, &mut writer);
+
+    let mut mock = MockBaseLayerSourceTrait::new();
+    mock.expect_latest_proved_block()
+        .returning(|| Ok(Some((BlockNumber(1), BlockHash::default()))));
+    mock.expect_latest_l1_block_number().returning(|| Ok(Some(2500)));
+    // The blocks that were scanned before aren't scanned again.
+    mock.expect_base_layer_proofs()
+        .withf(|l1_block_range| *l1_block_range == (2001..=2500))
+        .times(1)
+        .returning(|_| Ok(vec![]));
+    mock.expect_base_layer_proofs()
+        .withf(|l1_block_range| *l1_block_range == (1..=1000))
+        .times(1)
+        .returning(|_| Ok(vec![]));
+    mock.expect_base_layer_proofs()
+        .withf(|l1_block_range| *l1_block_range == (0..=0))
+        .times(1)
+        .returning(|_| Ok(vec![]));
+
+    let mut stream = stream_new_base_layer_block(
+        reader,
+        Arc::new(mock),
+        Duration::from_millis(0),
+        scan(1001..=2000, false),
+    )
+    .boxed();
+
+    assert_matches!(stream.next().await.unwrap().unwrap(), SyncEvent::NewBaseLayerBlock { .. });
+    for expected_scan in [scan(1001..=2500, false), scan(1..=2500, false), scan(0..=2500, true)] {
+        let event = stream.next().await.unwrap().unwrap();
+        assert_matches!(
+            event,
+            SyncEvent::BaseLayerProofsAvailable { scan, .. } if scan == expected_scan
+        );
+    }
+}
+
 #[tokio::test]
 async fn store_base_layer_block_test() {
     let (reader, mut writer) = get_test_storage().0;
@@ -195,6 +321,7 @@ async fn store_base_layer_block_test() {
         writer: Arc::new(Mutex::new(writer)),
         sequencer_pub_key: None,
         class_manager_client: None,
+        base_layer_scan: BaseLayerScan::default(),
     };
 
     // Trying to store a block without a header in the storage.
@@ -209,9 +336,18 @@ async fn store_base_layer_block_test() {
     // Happy flow.
     let res = gen_state_sync.store_base_layer_block(BlockNumber(0), header_hash).await;
     assert!(res.is_ok());
-    let base_layer_marker =
-        gen_state_sync.reader.begin_ro_txn().unwrap().get_base_layer_block_marker().unwrap();
-    assert_eq!(base_layer_marker, BlockNumber(1));
+    let txn = gen_state_sync.reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_base_layer_block_marker().unwrap(), BlockNumber(1));
+    drop(txn);
+
+    // Storing proofs advances the scan.
+    let res = gen_state_sync
+        .store_base_layer_proofs(vec![(BlockNumber(0), proof(0))], scan(0..=10, true))
+        .await;
+    assert!(res.is_ok());
+    assert_eq!(gen_state_sync.base_layer_scan, scan(0..=10, true));
+    let txn = gen_state_sync.reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(0)).unwrap(), Some(proof(0)));
 }
 
 // Adds to the storage 'headers_num' headers.
@@ -549,8 +685,8 @@ async fn pending_sync_doesnt_stop_when_data_has_block_hash_field_with_the_same_h
 }
 
 #[tokio::test]
-async fn pending_sync_updates_when_data_has_block_hash_field_with_the_same_hash_and_more_transactions()
- {
+async fn pending_sync_updates_when_data_has_block_hash_field_with_the_same_hash_and_more_transactions(
+) {
     const FIRST_BLOCK_HASH: BlockHash = BlockHash(StarkHash::ONE);
     let genesis_hash = BlockHash(felt!(GENESIS_HASH));
     // Storage with one block header.
@@ -296,6 +296,7 @@ impl ClassHashStorage {
                 growth_step: 1 << 20,     // 1MB.
                 max_object_size: 1 << 10, // 1KB; a class hash is 32B.
             },
+            index_events: false,
         };
         let (reader, writer) = apollo_storage::open_storage(storage_config)?;
 
@@ -25,6 +25,8 @@
 //! ```
 
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
 use std::time::Duration;
 
 use serde::de::Error;
@@ -88,6 +90,29 @@ where
     Ok(Some(map))
 }
 
+/// Serializes a slice to "v1 v2" string structure.
+pub fn serialize_slice<T: Display>(slice: &[T]) -> String {
+    slice.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+/// Deserializes a vector from "v1 v2" string structure.
+pub fn deserialize_vec<'de, D, T>(de: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let raw_str: String = Deserialize::deserialize(de)?;
+    raw_str
+        .split_whitespace()
+        .map(|raw_value| {
+            raw_value.parse().map_err(|error| {
+                D::Error::custom(format!("value \"{raw_value}\" is not valid: {error}"))
+            })
+        })
+        .collect()
+}
+
 /// Serializes a vector to string structure. The vector is expected to be a hex string.
 pub fn serialize_optional_vec_u8(optional_vector: &Option<Vec<u8>>) -> String {
     match optional_vector {
@@ -17,6 +17,7 @@ use validator::Validate;
 pub struct ConsensusManagerConfig {
     pub consensus_config: ConsensusConfig,
     pub context_config: ContextConfig,
+    #[validate]
     pub eth_to_strk_oracle_config: EthToStrkOracleConfig,
     #[validate]
     pub network_config: NetworkConfig,
@@ -14,7 +14,7 @@ use apollo_consensus_orchestrator::cende::CendeAmbassador;
 use apollo_consensus_orchestrator::sequencer_consensus_context::SequencerConsensusContext;
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra_utils::type_name::short_type_name;
-use apollo_l1_gas_price::eth_to_strk_oracle::EthToStrkOracleClient;
+use apollo_l1_gas_price::eth_to_strk_oracle_aggregator::EthToStrkOracleAggregator;
 use apollo_l1_gas_price_types::L1GasPriceProviderClient;
 use apollo_network::gossipsub_impl::Topic;
 use apollo_network::network_manager::metrics::{BroadcastNetworkMetrics, NetworkMetrics};
@@ -149,11 +149,7 @@ impl ConsensusManager {
                 self.config.cende_config.clone(),
                 Arc::clone(&self.class_manager_client),
             )),
-            Arc::new(EthToStrkOracleClient::new(
-                self.config.eth_to_strk_oracle_config.base_url.clone(),
-                self.config.eth_to_strk_oracle_config.headers.clone(),
-                self.config.eth_to_strk_oracle_config.lag_margin_seconds,
-            )),
+            Arc::new(EthToStrkOracleAggregator::new(&self.config.eth_to_strk_oracle_config)),
             self.l1_gas_price_provider.clone(),
         );
 
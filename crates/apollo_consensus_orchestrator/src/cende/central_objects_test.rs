@@ -483,7 +483,9 @@ fn call_info() -> CallInfo {
             accessed_contract_addresses: HashSet::from([contract_address!("0x1")]),
             read_block_hash_values: vec![BlockHash(felt!("0xdeafbee"))],
             accessed_blocks: HashSet::from([BlockNumber(100)]),
+            state_access_trace: None,
         },
+        vm_trace: None,
     }
 }
 
@@ -1,8 +1,9 @@
 use std::cmp::max;
+use std::num::NonZeroU128;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::orchestrator_versioned_constants;
+use crate::orchestrator_versioned_constants::VersionedConstants;
 
 #[cfg(test)]
 mod test;
@@ -16,28 +17,58 @@ pub struct FeeMarketInfo {
     pub next_l2_gas_price: u64,
 }
 
-/// Calculate the base gas price for the next block according to EIP-1559.
+/// The fee market parameters of a single resource.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct FeeMarketParameters {
+    /// A sensitivity parameter that limits the maximum rate of change of the price between
+    /// consecutive blocks: the price changes by at most 1/max_change_denominator of itself.
+    pub max_change_denominator: NonZeroU128,
+    /// The minimum price of the resource.
+    pub min_base_fee: u64,
+}
+
+impl FeeMarketParameters {
+    /// The maximal change of the given price between consecutive blocks.
+    fn max_change(&self, price: u128) -> u128 {
+        price / self.max_change_denominator
+    }
+}
+
+/// Returns the L2 gas target per block, according to the latest versioned constants.
+pub fn l2_gas_target() -> u64 {
+    VersionedConstants::latest_constants().gas_target
+}
+
+/// Calculate the base gas price for the next block according to EIP-1559, with the L2 gas fee
+/// market parameters of the latest versioned constants.
 ///
 /// # Parameters
 /// - `price`: The base gas price per unit (in fri) of the current block.
 /// - `gas_used`: The total gas used in the current block.
-/// - `gas_target`: The target gas usage per block (usually half of a block's gas limit).
+/// - `gas_target`: The target gas usage per block (see [`l2_gas_target`]).
 pub fn calculate_next_base_gas_price(price: u64, gas_used: u64, gas_target: u64) -> u64 {
-    let versioned_constants =
-        orchestrator_versioned_constants::VersionedConstants::latest_constants();
-    // Setting the target at 50% of the max block size balances the rate of gas price changes,
-    // helping to prevent sudden spikes, particularly during increases, for a better user
-    // experience.
-    assert_eq!(
+    calculate_next_base_gas_price_with_parameters(
+        price,
+        gas_used,
         gas_target,
-        versioned_constants.max_block_size / 2,
-        "Gas target must be 50% of max block size to balance price changes."
-    );
+        &VersionedConstants::latest_constants().l2_gas_fee_market(),
+    )
+}
+
+/// Calculate the base gas price for the next block according to EIP-1559, with the given fee
+/// market parameters.
+pub fn calculate_next_base_gas_price_with_parameters(
+    price: u64,
+    gas_used: u64,
+    gas_target: u64,
+    fee_market_parameters: &FeeMarketParameters,
+) -> u64 {
+    assert!(gas_target > 0, "The gas target must be positive.");
     // To prevent precision loss during multiplication and division, we set a minimum gas price.
     // Additionally, a minimum gas price is established to prevent prolonged periods before the
     // price reaches a higher value.
     assert!(
-        price >= versioned_constants.min_gas_price,
+        price >= fee_market_parameters.min_base_fee,
         "The gas price must be at least the minimum to prevent precision loss during \
          multiplication and division."
     );
@@ -59,23 +90,62 @@ pub fn calculate_next_base_gas_price(price: u64, gas_used: u64, gas_target: u64)
     // Calculate the price change, maintaining precision by dividing after scaling up.
     // This avoids significant precision loss that would occur if dividing before
     // multiplication.
-    let price_change_u128 =
-        gas_delta_cost / (gas_target_u128 * versioned_constants.gas_price_max_change_denominator);
+    let price_change_u128 = gas_delta_cost
+        / gas_target_u128.saturating_mul(fee_market_parameters.max_change_denominator.get());
 
-    // Convert back to u64, as the price change should fit within the u64 range.
-    // Since the target is half the maximum block size (which fits within a u64), the gas delta
-    // is bounded by half the maximum block size. Therefore, after dividing by the gas target
-    // (which is half the maximum block size), the result is guaranteed to fit within a u64.
-    let price_change = u64::try_from(price_change_u128)
-        .expect("Result fits u64 after division of a bounded gas delta");
+    // When the target is below half of the block size, the gas delta may exceed the target, so
+    // the price change may exceed the price itself; saturate instead of overflowing.
+    let price_change = u64::try_from(price_change_u128).unwrap_or(u64::MAX);
 
-    let adjusted_price =
-        if gas_used > gas_target { price + price_change } else { price - price_change };
+    let adjusted_price = if gas_used > gas_target {
+        price.saturating_add(price_change)
+    } else {
+        price.saturating_sub(price_change)
+    };
 
     assert!(
         gas_used > gas_target && adjusted_price >= price
             || gas_used <= gas_target && adjusted_price <= price
     );
 
-    max(adjusted_price, versioned_constants.min_gas_price)
+    max(adjusted_price, fee_market_parameters.min_base_fee)
+}
+
+/// Calculate the L1 data gas price for the next block. The price follows the price on L1, but
+/// changes by at most the fee market's maximal change from the price of the previous block, if
+/// it's known, and is at least the minimum base fee.
+pub fn calculate_next_l1_data_gas_price(
+    previous_price: Option<u128>,
+    l1_price: u128,
+    fee_market_parameters: &FeeMarketParameters,
+) -> u128 {
+    let price = match previous_price {
+        Some(previous_price) => {
+            let max_change = fee_market_parameters.max_change(previous_price);
+            l1_price.clamp(
+                previous_price.saturating_sub(max_change),
+                previous_price.saturating_add(max_change),
+            )
+        }
+        None => l1_price,
+    };
+    max(price, u128::from(fee_market_parameters.min_base_fee))
+}
+
+/// Returns whether the L1 data gas price of a proposed block follows the fee market rule given the
+/// price of the previous block, i.e., whether it could be the result of
+/// [`calculate_next_l1_data_gas_price`].
+pub fn is_l1_data_gas_price_valid(
+    previous_price: Option<u128>,
+    price: u128,
+    fee_market_parameters: &FeeMarketParameters,
+) -> bool {
+    let min_base_fee = u128::from(fee_market_parameters.min_base_fee);
+    let Some(previous_price) = previous_price else {
+        return price >= min_base_fee;
+    };
+    let max_change = fee_market_parameters.max_change(previous_price);
+    let lower_bound = max(previous_price.saturating_sub(max_change), min_base_fee);
+    let upper_bound = max(previous_price.saturating_add(max_change), min_base_fee);
+    (lower_bound..=upper_bound).contains(&price)
 }
@@ -1,6 +1,16 @@
+use std::num::NonZeroU128;
 use std::sync::LazyLock;
 
-use crate::fee_market::calculate_next_base_gas_price;
+use starknet_api::block::StarknetVersion;
+
+use crate::fee_market::{
+    calculate_next_base_gas_price,
+    calculate_next_base_gas_price_with_parameters,
+    calculate_next_l1_data_gas_price,
+    is_l1_data_gas_price_valid,
+    l2_gas_target,
+    FeeMarketParameters,
+};
 use crate::orchestrator_versioned_constants::VersionedConstants;
 
 static VERSIONED_CONSTANTS: LazyLock<&VersionedConstants> =
@@ -11,7 +21,8 @@ fn test_price_calculation_snapshot() {
     // Setup: using realistic arbitrary values.
     let init_price: u64 = 1_000_000;
     let max_block_size = VERSIONED_CONSTANTS.max_block_size;
-    let gas_target: u64 = max_block_size / 2;
+    let gas_target: u64 = l2_gas_target();
+    assert_eq!(gas_target, max_block_size / 2);
     let high_congestion_gas_used: u64 = max_block_size * 3 / 4;
     let low_congestion_gas_used: u64 = max_block_size / 4;
     let stable_congestion_gas_used: u64 = gas_target;
@@ -40,7 +51,8 @@ fn test_price_calculation_snapshot() {
 fn test_gas_price_with_extreme_values() {
     let max_block_size = VERSIONED_CONSTANTS.max_block_size;
     let min_gas_price = VERSIONED_CONSTANTS.min_gas_price;
-    let gas_price_max_change_denominator = VERSIONED_CONSTANTS.gas_price_max_change_denominator;
+    let gas_price_max_change_denominator =
+        VERSIONED_CONSTANTS.gas_price_max_change_denominator.get();
 
     let price = min_gas_price;
     let gas_target = max_block_size / 2;
@@ -66,3 +78,98 @@ fn test_gas_price_with_extreme_values() {
     let gas_used = max_block_size;
     calculate_next_base_gas_price(u64::try_from(price_u128).unwrap(), gas_used, gas_target); // Should not panic.
 }
+
+#[test]
+fn test_price_calculation_with_parameters() {
+    let fee_market_parameters = FeeMarketParameters {
+        max_change_denominator: NonZeroU128::new(8).unwrap(),
+        min_base_fee: 10,
+    };
+    let max_block_size = 1000;
+    // A target of 25% utilization.
+    let gas_target = max_block_size / 4;
+
+    // A full block is 3 targets above the target, so the price rises by 3/8.
+    assert_eq!(
+        calculate_next_base_gas_price_with_parameters(
+            800,
+            max_block_size,
+            gas_target,
+            &fee_market_parameters
+        ),
+        800 + 300
+    );
+    // An empty block is a target below the target, so the price drops by 1/8.
+    assert_eq!(
+        calculate_next_base_gas_price_with_parameters(800, 0, gas_target, &fee_market_parameters),
+        800 - 100
+    );
+    // The price doesn't drop below the minimum.
+    assert_eq!(
+        calculate_next_base_gas_price_with_parameters(10, 0, gas_target, &fee_market_parameters),
+        10
+    );
+}
+
+#[test]
+fn test_l1_data_gas_price_follows_l1_gradually() {
+    let fee_market_parameters = FeeMarketParameters {
+        max_change_denominator: NonZeroU128::new(8).unwrap(),
+        min_base_fee: 10,
+    };
+
+    // Without a previous price, the price follows L1 down to the minimum.
+    assert_eq!(calculate_next_l1_data_gas_price(None, 1000, &fee_market_parameters), 1000);
+    assert_eq!(calculate_next_l1_data_gas_price(None, 1, &fee_market_parameters), 10);
+    // Otherwise, it changes by at most 1/8 of the previous price.
+    assert_eq!(calculate_next_l1_data_gas_price(Some(800), 850, &fee_market_parameters), 850);
+    assert_eq!(calculate_next_l1_data_gas_price(Some(800), 2000, &fee_market_parameters), 900);
+    assert_eq!(calculate_next_l1_data_gas_price(Some(800), 1, &fee_market_parameters), 700);
+    assert_eq!(calculate_next_l1_data_gas_price(Some(11), 1, &fee_market_parameters), 10);
+    // A previous price below the minimum (e.g., if the minimum was raised) jumps to the minimum.
+    assert_eq!(calculate_next_l1_data_gas_price(Some(1), 1, &fee_market_parameters), 10);
+
+    for (previous_price, price, expected_validity) in [
+        (None, 10, true),
+        (None, 9, false),
+        (Some(800), 700, true),
+        (Some(800), 900, true),
+        (Some(800), 699, false),
+        (Some(800), 901, false),
+        (Some(11), 10, true),
+        (Some(1), 10, true),
+        (Some(1), 1, false),
+    ] {
+        assert_eq!(
+            is_l1_data_gas_price_valid(previous_price, price, &fee_market_parameters),
+            expected_validity,
+            "previous price: {previous_price:?}, price: {price}"
+        );
+    }
+}
+
+#[test]
+fn test_versioned_constants_for_block() {
+    let first_constants = VersionedConstants::get(&StarknetVersion::V0_14_0).unwrap();
+    // Blocks older than the first version with constants use the constants of that version.
+    assert!(std::ptr::eq(
+        VersionedConstants::for_block(&StarknetVersion::V0_13_4),
+        first_constants
+    ));
+    assert!(std::ptr::eq(
+        VersionedConstants::for_block(&StarknetVersion::V0_14_0),
+        first_constants
+    ));
+    assert!(std::ptr::eq(
+        VersionedConstants::for_block(&StarknetVersion::LATEST),
+        VersionedConstants::latest_constants()
+    ));
+}
+
+#[test]
+fn test_zero_max_change_denominator_is_rejected() {
+    let fee_market_parameters = serde_json::from_str::<FeeMarketParameters>(
+        r#"{"max_change_denominator": 0, "min_base_fee": 1}"#,
+    );
+    assert!(fee_market_parameters.is_err());
+}
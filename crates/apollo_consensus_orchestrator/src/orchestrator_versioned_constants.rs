@@ -1,15 +1,19 @@
+use std::num::NonZeroU128;
+
 use serde::Deserialize;
 use starknet_api::block::StarknetVersion;
 use starknet_api::define_versioned_constants;
 use thiserror::Error;
 
+use crate::fee_market::FeeMarketParameters;
+
 /// Versioned constants for the Consensus.
 #[derive(Clone, Deserialize)]
 pub struct VersionedConstants {
     ///  This is used to calculate the base gas price for the next block according to EIP-1559 and
     /// serves as a sensitivity parameter that limits the maximum rate of change of the gas price
     /// between consecutive blocks.
-    pub gas_price_max_change_denominator: u128,
+    pub gas_price_max_change_denominator: NonZeroU128,
     /// The minimum gas price in fri.
     pub min_gas_price: u64,
     /// The maximum block size in gas units.
@@ -19,6 +23,33 @@ pub struct VersionedConstants {
     /// The margin for the eth to fri rate disagreement, expressed as a percentage (parts per
     /// hundred).
     pub l1_gas_price_margin_percent: u32,
+    /// The parameters of the L1 data gas price, which follows the blob fee on L1 but changes
+    /// gradually between consecutive blocks.
+    pub l1_data_gas_fee_market: FeeMarketParameters,
+}
+
+impl VersionedConstants {
+    /// Returns the constants of blocks of the given Starknet version. Blocks older than the first
+    /// version with constants use the constants of that version, and blocks newer than the latest
+    /// version use the latest constants.
+    pub fn for_block(starknet_version: &StarknetVersion) -> &'static Self {
+        Self::get(starknet_version).unwrap_or_else(|_| {
+            if *starknet_version < StarknetVersion::V0_14_0 {
+                Self::get(&StarknetVersion::V0_14_0)
+                    .expect("The constants of the first version should exist.")
+            } else {
+                Self::latest_constants()
+            }
+        })
+    }
+
+    /// The EIP-1559 parameters of the L2 gas price.
+    pub fn l2_gas_fee_market(&self) -> FeeMarketParameters {
+        FeeMarketParameters {
+            max_change_denominator: self.gas_price_max_change_denominator,
+            min_base_fee: self.min_gas_price,
+        }
+    }
 }
 
 define_versioned_constants!(
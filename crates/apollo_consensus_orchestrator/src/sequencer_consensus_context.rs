@@ -67,6 +67,7 @@ use starknet_api::block::{
     GasPriceVector,
     GasPrices,
     NonzeroGasPrice,
+    StarknetVersion,
 };
 use starknet_api::consensus_transaction::InternalConsensusTransaction;
 use starknet_api::core::{ContractAddress, SequencerContractAddress};
@@ -81,7 +82,13 @@ use tracing::{debug, error, error_span, info, instrument, trace, warn, Instrumen
 
 use crate::cende::{BlobParameters, CendeContext};
 use crate::config::ContextConfig;
-use crate::fee_market::{calculate_next_base_gas_price, FeeMarketInfo};
+use crate::fee_market::{
+    calculate_next_base_gas_price,
+    calculate_next_l1_data_gas_price,
+    is_l1_data_gas_price_valid,
+    l2_gas_target,
+    FeeMarketInfo,
+};
 use crate::metrics::{CONSENSUS_NUM_BATCHES_IN_PROPOSAL, CONSENSUS_NUM_TXS_IN_PROPOSAL};
 use crate::orchestrator_versioned_constants::VersionedConstants;
 
@@ -93,6 +100,9 @@ struct BlockInfoValidation {
     last_block_timestamp: Option<u64>,
     l1_da_mode: L1DataAvailabilityMode,
     l2_gas_price_fri: u64,
+    previous_l1_data_gas_price_wei: Option<u128>,
+    /// The Starknet version of the proposed block, which determines its fee market rules.
+    starknet_version: StarknetVersion,
 }
 
 const EMPTY_BLOCK_COMMITMENT: BlockHash = BlockHash(Felt::ONE);
@@ -177,6 +187,9 @@ pub struct SequencerConsensusContext {
     // validating proposals.
     _l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
     l2_gas_price: u64,
+    // The l1 data gas price of the previous block, which limits the l1 data gas price of the next
+    // block. Unknown until the first decision or sync.
+    l1_data_gas_price_wei: Option<u128>,
     l1_da_mode: L1DataAvailabilityMode,
     last_block_timestamp: Option<u64>,
 }
@@ -222,19 +235,31 @@ impl SequencerConsensusContext {
             eth_to_strk_oracle_client,
             _l1_gas_price_provider: l1_gas_price_provider,
             l2_gas_price: VersionedConstants::latest_constants().min_gas_price,
+            l1_data_gas_price_wei: None,
             l1_da_mode,
             last_block_timestamp: None,
         }
     }
 
-    fn gas_prices(&self) -> GasPrices {
+    /// Returns the gas prices of a proposed block of the given Starknet version.
+    fn gas_prices(&self, starknet_version: &StarknetVersion) -> GasPrices {
+        let l1_data_gas_price_wei = calculate_next_l1_data_gas_price(
+            self.l1_data_gas_price_wei,
+            TEMPORARY_GAS_PRICES.eth_gas_prices.l1_data_gas_price.get().0,
+            &VersionedConstants::for_block(starknet_version).l1_data_gas_fee_market,
+        );
         GasPrices {
+            eth_gas_prices: GasPriceVector {
+                l1_data_gas_price: NonzeroGasPrice::new(GasPrice(l1_data_gas_price_wei)).expect(
+                    "Failed to convert l1_data_gas_price to NonzeroGasPrice, should not be 0.",
+                ),
+                ..TEMPORARY_GAS_PRICES.eth_gas_prices
+            },
             strk_gas_prices: GasPriceVector {
                 l2_gas_price: NonzeroGasPrice::new(self.l2_gas_price.into())
                     .expect("Failed to convert l2_gas_price to NonzeroGasPrice, should not be 0."),
                 ..TEMPORARY_GAS_PRICES.strk_gas_prices
             },
-            ..TEMPORARY_GAS_PRICES
         }
     }
 }
@@ -306,7 +331,7 @@ impl ConsensusContext for SequencerConsensusContext {
             .send((stream_id, proposal_receiver))
             .await
             .expect("Failed to send proposal receiver");
-        let gas_prices = self.gas_prices();
+        let gas_prices = self.gas_prices(&StarknetVersion::LATEST);
         let transaction_converter = self.transaction_converter.clone();
         let builder_address = self.config.builder_address;
 
@@ -379,6 +404,8 @@ impl ConsensusContext for SequencerConsensusContext {
                     last_block_timestamp: self.last_block_timestamp,
                     l1_da_mode: self.l1_da_mode,
                     l2_gas_price_fri: self.l2_gas_price,
+                    previous_l1_data_gas_price_wei: self.l1_data_gas_price_wei,
+                    starknet_version: StarknetVersion::LATEST,
                 };
                 self.validate_current_round_proposal(
                     block_info_validation,
@@ -500,11 +527,8 @@ impl ConsensusContext for SequencerConsensusContext {
             .await
             .expect("Failed to get state diff.");
 
-        let next_l2_gas_price = calculate_next_base_gas_price(
-            self.l2_gas_price,
-            l2_gas_used.0,
-            VersionedConstants::latest_constants().max_block_size / 2,
-        );
+        let next_l2_gas_price =
+            calculate_next_base_gas_price(self.l2_gas_price, l2_gas_used.0, l2_gas_target());
 
         let transaction_hashes =
             transactions.iter().map(|tx| tx.tx_hash()).collect::<Vec<TransactionHash>>();
@@ -549,6 +573,7 @@ impl ConsensusContext for SequencerConsensusContext {
         state_sync_client.add_new_block(sync_block).await.expect("Failed to add new block.");
 
         self.l2_gas_price = next_l2_gas_price;
+        self.l1_data_gas_price_wei = Some(block_info.l1_data_gas_price_wei);
 
         // TODO(dvir): pass here real `BlobParameters` info.
         // TODO(dvir): when passing here the correct `BlobParameters`, also test that
@@ -585,11 +610,17 @@ impl ConsensusContext for SequencerConsensusContext {
             Ok(None) => return false,
             Ok(Some(block)) => block,
         };
+        let versioned_constants =
+            VersionedConstants::for_block(&sync_block.block_header_without_hash.starknet_version);
         // May be default for blocks older than 0.14.0, ensure min gas price is met.
         self.l2_gas_price = max(
             sync_block.block_header_without_hash.next_l2_gas_price,
-            VersionedConstants::latest_constants().min_gas_price,
+            versioned_constants.min_gas_price,
         );
+        self.l1_data_gas_price_wei = Some(max(
+            sync_block.block_header_without_hash.l1_data_gas_price.price_in_wei.0,
+            u128::from(versioned_constants.l1_data_gas_fee_market.min_base_fee),
+        ));
         // TODO(Asmaa): validate starknet_version and parent_hash when they are stored.
         let block_number = sync_block.block_header_without_hash.block_number;
         let timestamp = sync_block.block_header_without_hash.timestamp;
@@ -661,6 +692,8 @@ impl ConsensusContext for SequencerConsensusContext {
             last_block_timestamp: self.last_block_timestamp,
             l1_da_mode: self.l1_da_mode,
             l2_gas_price_fri: self.l2_gas_price,
+            previous_l1_data_gas_price_wei: self.l1_data_gas_price_wei,
+            starknet_version: StarknetVersion::LATEST,
         };
         self.validate_current_round_proposal(
             block_info_validation,
@@ -1036,6 +1069,19 @@ async fn is_block_info_valid(
     {
         return false;
     }
+    let versioned_constants =
+        VersionedConstants::for_block(&block_info_validation.starknet_version);
+    if !is_l1_data_gas_price_valid(
+        block_info_validation.previous_l1_data_gas_price_wei,
+        block_info.l1_data_gas_price_wei,
+        &versioned_constants.l1_data_gas_fee_market,
+    ) {
+        warn!(
+            "L1 data gas price {} doesn't follow the fee market rule, previous price: {:?}",
+            block_info.l1_data_gas_price_wei, block_info_validation.previous_l1_data_gas_price_wei
+        );
+        return false;
+    }
     // TODO(Asmaa, guyn): remove this once calculation l1 gas prices in fri is supported.
     let eth_to_fri_rate =
         match eth_to_strk_oracle_client.eth_to_fri_rate(block_info.timestamp).await {
@@ -1045,8 +1091,7 @@ async fn is_block_info_valid(
                 return false;
             }
         };
-    let l1_gas_price_margin_percent =
-        VersionedConstants::latest_constants().l1_gas_price_margin_percent;
+    let l1_gas_price_margin_percent = versioned_constants.l1_gas_price_margin_percent;
     let allowed_margin = (eth_to_fri_rate * u128::from(l1_gas_price_margin_percent)) / 100;
     block_info.eth_to_fri_rate.abs_diff(eth_to_fri_rate) <= allowed_margin
 }
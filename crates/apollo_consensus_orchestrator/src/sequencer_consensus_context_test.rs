@@ -655,3 +655,31 @@ async fn eth_to_fri_rate_out_of_range() {
         context.validate_proposal(ProposalInit::default(), Duration::MAX, content_receiver).await;
     assert_eq!(fin_receiver.await, Err(Canceled));
 }
+
+#[rstest]
+#[case::l2_gas_price(|info: &mut ConsensusBlockInfo| info.l2_gas_price_fri += 1)]
+#[case::l1_data_gas_price(|info: &mut ConsensusBlockInfo| info.l1_data_gas_price_wei = 0)]
+#[tokio::test]
+async fn gas_price_not_following_fee_market(
+    #[case] modify_block_info: fn(&mut ConsensusBlockInfo),
+) {
+    let mut batcher = MockBatcherClient::new();
+
+    batcher
+        .expect_start_height()
+        .times(1)
+        .withf(|input| input.height == BlockNumber(0))
+        .return_once(|_| Ok(()));
+
+    let (mut context, _network) = setup(batcher, success_cende_ammbassador());
+    context.set_height_and_round(BlockNumber(0), 0).await;
+    let (mut content_sender, content_receiver) = mpsc::channel(context.config.proposal_buffer_size);
+    let mut block_info = block_info(BlockNumber(0));
+    modify_block_info(&mut block_info);
+    content_sender.send(ProposalPart::BlockInfo(block_info)).await.unwrap();
+    // Max timeout to ensure the fin_receiver was canceled due to invalid block_info, not due to a
+    // timeout.
+    let fin_receiver =
+        context.validate_proposal(ProposalInit::default(), Duration::MAX, content_receiver).await;
+    assert_eq!(fin_receiver.await, Err(Canceled));
+}
@@ -103,6 +103,9 @@ pub fn mempool_client_result_to_gw_spec_result(
                 | MempoolError::NonceTooOld { .. } => {
                     Err(GatewaySpecError::InvalidTransactionNonce)
                 }
+                MempoolError::ReplacementRejected { .. } => {
+                    Err(GatewaySpecError::ValidationFailure { data: mempool_error.to_string() })
+                }
                 MempoolError::DuplicateTransaction { .. } => Err(GatewaySpecError::DuplicateTx),
                 // TODO(Dafna): change to a more appropriate error, once we have it.
                 MempoolError::MempoolFull { .. } => {
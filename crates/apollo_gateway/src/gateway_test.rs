@@ -10,7 +10,7 @@ use apollo_mempool_types::communication::{
     MempoolClientResult,
     MockMempoolClient,
 };
-use apollo_mempool_types::errors::MempoolError;
+use apollo_mempool_types::errors::{MempoolError, ReplacementRejectionReason};
 use apollo_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_test_utils::{get_rng, GetTestInstance};
@@ -30,6 +30,7 @@ use starknet_api::rpc_transaction::{
     RpcTransactionLabelValue,
 };
 use starknet_api::test_utils::CHAIN_ID_FOR_TESTS;
+use starknet_api::transaction::fields::Tip;
 use starknet_api::transaction::{
     InvokeTransaction,
     TransactionHash,
@@ -120,6 +121,14 @@ fn create_tx() -> (RpcTransaction, SenderAddress) {
     (tx, sender_address)
 }
 
+fn replacement_rejected() -> MempoolError {
+    MempoolError::ReplacementRejected {
+        address: ContractAddress::default(),
+        nonce: Nonce::default(),
+        reason: ReplacementRejectionReason::TipDecreased { existing: Tip(2), incoming: Tip(1) },
+    }
+}
+
 // TODO(AlonH): add test with Some broadcasted message metadata
 // We use default nonce, address, and tx_hash since Gateway errors drop these details when
 // converting Mempool errors.
@@ -141,6 +150,10 @@ fn create_tx() -> (RpcTransaction, SenderAddress) {
     Err(MempoolClientError::MempoolError(MempoolError::NonceTooLarge(Nonce::default()))),
     Some(GatewaySpecError::InvalidTransactionNonce)
 )]
+#[case::replacement_rejected(
+    Err(MempoolClientError::MempoolError(replacement_rejected())),
+    Some(GatewaySpecError::ValidationFailure { data: replacement_rejected().to_string() })
+)]
 #[tokio::test]
 async fn test_add_tx(
     mut mock_dependencies: MockDependencies,
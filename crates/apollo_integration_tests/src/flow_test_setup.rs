@@ -2,7 +2,6 @@ use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use alloy::node_bindings::AnvilInstance;
 use apollo_consensus_manager::config::ConsensusManagerConfig;
 use apollo_http_server::config::HttpServerConfig;
 use apollo_http_server::test_utils::HttpTestClient;
@@ -16,11 +15,12 @@ use apollo_network::network_manager::test_utils::{
     network_config_into_broadcast_channels,
 };
 use apollo_network::network_manager::BroadcastTopicChannels;
+use apollo_node::base_layer::NodeBaseLayerContract;
 use apollo_node::clients::SequencerNodeClients;
 use apollo_node::config::component_config::ComponentConfig;
 use apollo_node::config::node_config::SequencerNodeConfig;
 use apollo_node::servers::run_component_servers;
-use apollo_node::utils::create_node_modules;
+use apollo_node::utils::create_node_modules_with_base_layer;
 use apollo_protobuf::consensus::{HeightAndRound, ProposalPart, StreamMessage, StreamMessageBody};
 use apollo_state_sync::config::StateSyncConfig;
 use apollo_storage::StorageConfig;
@@ -32,11 +32,8 @@ use mempool_test_utils::starknet_api_test_utils::{
     MultiAccountTransactionGenerator,
 };
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
-use papyrus_base_layer::test_utils::{
-    ethereum_base_layer_config_for_anvil,
-    spawn_anvil_and_deploy_starknet_l1_contract,
-    StarknetL1Contract,
-};
+use papyrus_base_layer::fake_base_layer_contract::FakeBaseLayerContract;
+use papyrus_base_layer::test_utils::ethereum_base_layer_config_for_anvil;
 use starknet_api::block::BlockNumber;
 use starknet_api::consensus_transaction::ConsensusTransaction;
 use starknet_api::core::{ChainId, ContractAddress};
@@ -54,7 +51,7 @@ use crate::utils::{
     create_mempool_p2p_configs,
     create_node_config,
     create_state_sync_configs,
-    send_message_to_l2,
+    send_message_to_l2_on_fake_base_layer,
     set_validator_id,
     spawn_local_eth_to_strk_oracle,
     spawn_local_success_recorder,
@@ -70,10 +67,8 @@ pub struct FlowTestSetup {
     pub sequencer_0: FlowSequencerSetup,
     pub sequencer_1: FlowSequencerSetup,
 
-    // Handle for L1 server: the server is dropped when handle is dropped.
-    #[allow(dead_code)]
-    l1_handle: AnvilInstance,
-    starknet_l1_contract: StarknetL1Contract,
+    // The L1 that the sequencers read from, shared by both of them.
+    base_layer: FakeBaseLayerContract,
 
     // The transactions that were streamed in the consensus proposals, used for asserting the right
     // transactions are batched.
@@ -111,8 +106,7 @@ impl FlowTestSetup {
 
         let base_layer_config =
             ethereum_base_layer_config_for_anvil(Some(available_ports.get_next_port()));
-        let (anvil, starknet_l1_contract) =
-            spawn_anvil_and_deploy_starknet_l1_contract(&base_layer_config).await;
+        let base_layer = FakeBaseLayerContract::new();
 
         // Spawn a thread that listens to proposals and collects batched transactions.
         let accumulated_txs = Arc::new(Mutex::new(AccumulatedTransactions::default()));
@@ -130,6 +124,7 @@ impl FlowTestSetup {
             SEQUENCER_0,
             chain_info.clone(),
             base_layer_config.clone(),
+            NodeBaseLayerContract::Fake(base_layer.clone()),
             sequencer_0_consensus_manager_config,
             sequencer_0_mempool_p2p_config,
             AvailablePorts::new(test_unique_index, 1),
@@ -143,6 +138,7 @@ impl FlowTestSetup {
             SEQUENCER_1,
             chain_info,
             base_layer_config,
+            NodeBaseLayerContract::Fake(base_layer.clone()),
             sequencer_1_consensus_manager_config,
             sequencer_1_mempool_p2p_config,
             AvailablePorts::new(test_unique_index, 2),
@@ -151,7 +147,7 @@ impl FlowTestSetup {
         )
         .await;
 
-        Self { sequencer_0, sequencer_1, l1_handle: anvil, starknet_l1_contract, accumulated_txs }
+        Self { sequencer_0, sequencer_1, base_layer, accumulated_txs }
     }
 
     pub fn chain_id(&self) -> &ChainId {
@@ -161,7 +157,7 @@ impl FlowTestSetup {
 
     pub async fn send_messages_to_l2(&self, l1_to_l2_messages_args: &[L1ToL2MessageArgs]) {
         for l1_to_l2_message_args in l1_to_l2_messages_args {
-            send_message_to_l2(l1_to_l2_message_args, &self.starknet_l1_contract).await;
+            send_message_to_l2_on_fake_base_layer(l1_to_l2_message_args, &self.base_layer);
         }
     }
 }
@@ -196,6 +192,7 @@ impl FlowSequencerSetup {
         node_index: usize,
         chain_info: ChainInfo,
         base_layer_config: EthereumBaseLayerConfig,
+        base_layer: NodeBaseLayerContract,
         mut consensus_manager_config: ConsensusManagerConfig,
         mempool_p2p_config: MempoolP2pConfig,
         mut available_ports: AvailablePorts,
@@ -243,7 +240,8 @@ impl FlowSequencerSetup {
         );
 
         debug!("Sequencer config: {:#?}", node_config);
-        let (clients, servers) = create_node_modules(&node_config).await;
+        let (clients, servers) =
+            create_node_modules_with_base_layer(&node_config, base_layer).await;
 
         let MonitoringEndpointConfig { ip, port, .. } = node_config.monitoring_endpoint_config;
         let monitoring_client = MonitoringClient::new(SocketAddr::from((ip, port)));
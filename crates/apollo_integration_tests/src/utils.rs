@@ -62,14 +62,16 @@ use mempool_test_utils::starknet_api_test_utils::{
     MultiAccountTransactionGenerator,
 };
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
+use papyrus_base_layer::fake_base_layer_contract::FakeBaseLayerContract;
 use papyrus_base_layer::test_utils::StarknetL1Contract;
+use papyrus_base_layer::L1Event;
 use serde::Deserialize;
 use serde_json::{json, to_value};
 use starknet_api::block::BlockNumber;
 use starknet_api::core::{ChainId, ContractAddress};
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::rpc_transaction::RpcTransaction;
-use starknet_api::transaction::fields::ContractAddressSalt;
+use starknet_api::transaction::fields::{ContractAddressSalt, Fee};
 use starknet_api::transaction::{TransactionHash, TransactionHasher};
 use starknet_types_core::felt::Felt;
 use tokio::task::JoinHandle;
@@ -505,6 +507,19 @@ pub(crate) async fn send_message_to_l2(
         .get_receipt().await.expect("Transaction was not received on L1 or receipt retrieval failed.");
 }
 
+/// Mines an L1 block on the given [fake base layer](FakeBaseLayerContract) with a single
+/// transaction that sends the given [L1 handler
+/// transaction](starknet_api::transaction::L1HandlerTransaction) to L2.
+pub(crate) fn send_message_to_l2_on_fake_base_layer(
+    l1_to_l2_message_args: &L1ToL2MessageArgs,
+    base_layer: &FakeBaseLayerContract,
+) {
+    let L1ToL2MessageArgs { tx: l1_handler, l1_tx_nonce } = l1_to_l2_message_args;
+    tracing::info!("Sending message to L2 with the l1 nonce: {l1_tx_nonce}");
+    let fee = Fee(PAID_FEE_ON_L1.to());
+    base_layer.add_block(vec![L1Event::LogMessageToL2 { tx: l1_handler.clone(), fee }]);
+}
+
 async fn send_rpc_txs<'a, Fut>(
     rpc_txs: Vec<RpcTransaction>,
     send_rpc_tx_fn: &'a mut dyn Fn(RpcTransaction) -> Fut,
@@ -1,6 +1,13 @@
 use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
-use apollo_config::converters::{deserialize_optional_map, serialize_optional_map};
+use apollo_config::converters::{
+    deserialize_milliseconds_to_duration,
+    deserialize_optional_map,
+    deserialize_vec,
+    serialize_optional_map,
+    serialize_slice,
+};
 use apollo_config::dumping::{ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
@@ -11,6 +18,9 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use tracing::{debug, info};
 use url::Url;
+use validator::{Validate, ValidationError};
+
+use crate::metrics::MAX_ORACLE_SOURCES;
 
 #[cfg(test)]
 #[path = "eth_to_strk_oracle_test.rs"]
@@ -31,15 +41,33 @@ fn hashmap_to_headermap(hash_map: Option<HashMap<String, String>>) -> HeaderMap
     header_map
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Validate)]
+#[validate(schema(function = "validate_eth_to_strk_oracle_config"))]
 pub struct EthToStrkOracleConfig {
     pub base_url: Url,
+    /// URLs of additional oracle sources, in the same format as `base_url`. The rate is aggregated
+    /// from all the sources.
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub additional_base_urls: Vec<Url>,
     #[serde(deserialize_with = "deserialize_optional_map")]
     pub headers: Option<HashMap<String, String>>,
     /// The time margin (in seconds) to adjust the timestamp before querying the conversion rate.  
     /// Ensures a stable timestamp from providers. Should be configured in alignment with  
     /// relevant query parameters in `base_url`, if required.
     pub lag_margin_seconds: u64,
+    /// The minimal number of sources whose rates must agree for the rate to be used.
+    pub min_sources: usize,
+    /// Rates that deviate from the median rate of all sources by more than this percentage are
+    /// rejected as outliers.
+    pub max_deviation_percent: u64,
+    /// Timestamps are rounded down to a multiple of this value before querying the sources, so
+    /// that all timestamps in a bucket share the same (cached) rate.
+    pub bucket_seconds: u64,
+    /// If the sources don't agree on a rate, the last known good rate is used, as long as it's at
+    /// most this old.
+    pub max_staleness_seconds: u64,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub query_timeout: Duration,
 }
 
 impl SerializeConfig for EthToStrkOracleConfig {
@@ -52,6 +80,13 @@ impl SerializeConfig for EthToStrkOracleConfig {
                  UNIX timestamp.",
                 ParamPrivacyInput::Private,
             ),
+            ser_param(
+                "additional_base_urls",
+                &serialize_slice(&self.additional_base_urls),
+                "Space separated URLs of additional oracle sources, in the same format as \
+                 `base_url`. The headers are sent to all the sources.",
+                ParamPrivacyInput::Private,
+            ),
             ser_param(
                 "headers",
                 &serialize_optional_map(&self.headers),
@@ -66,6 +101,41 @@ impl SerializeConfig for EthToStrkOracleConfig {
                  in alignment with relevant query parameters in `base_url`, if required.",
                 ParamPrivacyInput::Private,
             ),
+            ser_param(
+                "min_sources",
+                &self.min_sources,
+                "The minimal number of oracle sources whose rates must agree for the rate to be \
+                 used.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_deviation_percent",
+                &self.max_deviation_percent,
+                "Rates that deviate from the median rate of all oracle sources by more than this \
+                 percentage are rejected as outliers.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "bucket_seconds",
+                &self.bucket_seconds,
+                "Timestamps are rounded down to a multiple of this value (in seconds) before \
+                 querying the oracle sources, so that all timestamps in a bucket share the same \
+                 cached rate.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_staleness_seconds",
+                &self.max_staleness_seconds,
+                "The maximal age (in seconds) of the last known good rate, which is used if the \
+                 oracle sources don't agree on a rate.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "query_timeout",
+                &self.query_timeout.as_millis(),
+                "Timeout (in milliseconds) of a query to an oracle source.",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
@@ -74,12 +144,42 @@ impl Default for EthToStrkOracleConfig {
     fn default() -> Self {
         Self {
             base_url: Url::parse("https://example.com/api?timestamp=").unwrap(),
+            additional_base_urls: Vec::new(),
             headers: None,
             lag_margin_seconds: 0,
+            min_sources: 1,
+            max_deviation_percent: 10,
+            bucket_seconds: 1,
+            max_staleness_seconds: 300,
+            query_timeout: Duration::from_secs(5),
         }
     }
 }
 
+impl EthToStrkOracleConfig {
+    pub fn base_urls(&self) -> impl Iterator<Item = &Url> {
+        std::iter::once(&self.base_url).chain(&self.additional_base_urls)
+    }
+}
+
+fn validate_eth_to_strk_oracle_config(
+    config: &EthToStrkOracleConfig,
+) -> Result<(), ValidationError> {
+    let n_sources = config.additional_base_urls.len() + 1;
+    if !(1..=n_sources).contains(&config.min_sources) {
+        return Err(ValidationError::new(
+            "min_sources must be positive and at most the number of oracle sources",
+        ));
+    }
+    if n_sources > MAX_ORACLE_SOURCES {
+        return Err(ValidationError::new("too many oracle sources"));
+    }
+    if config.bucket_seconds == 0 {
+        return Err(ValidationError::new("bucket_seconds must be positive"));
+    }
+    Ok(())
+}
+
 /// Client for interacting with the eth to strk Oracle API.
 pub struct EthToStrkOracleClient {
     /// The base URL of the eth to strk Oracle API.
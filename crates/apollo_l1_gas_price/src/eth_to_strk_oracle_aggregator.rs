@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
+use apollo_l1_gas_price_types::EthToStrkOracleClientTrait;
+use async_trait::async_trait;
+use futures::future::join_all;
+use tracing::{debug, warn};
+
+use crate::eth_to_strk_oracle::{EthToStrkOracleClient, EthToStrkOracleConfig};
+use crate::metrics::{
+    register_eth_to_strk_metrics,
+    OracleSourceLabelValue,
+    ETH_TO_STRK_RATE,
+    ETH_TO_STRK_RATE_FALLBACKS,
+    ETH_TO_STRK_RATE_N_SOURCES,
+    ETH_TO_STRK_SOURCE_FAILURES,
+    ETH_TO_STRK_SOURCE_OUTLIERS,
+    ETH_TO_STRK_SOURCE_USED,
+};
+
+#[cfg(test)]
+#[path = "eth_to_strk_oracle_aggregator_test.rs"]
+pub mod eth_to_strk_oracle_aggregator_test;
+
+/// The number of bucket rates kept in the cache.
+const MAX_CACHED_RATES: usize = 100;
+
+/// Aggregates the eth to strk rate from several oracle sources.
+///
+/// Timestamps are rounded down to buckets, and the rate of each bucket is the median of the rates
+/// of the sources, after rejecting outliers that deviate too much from the median of all rates.
+/// Rates are cached per bucket. If too few sources agree on a rate, the last known good rate is
+/// used, as long as it isn't too stale.
+pub struct EthToStrkOracleAggregator {
+    sources: Vec<Box<dyn EthToStrkOracleClientTrait>>,
+    min_sources: usize,
+    max_deviation_percent: u64,
+    bucket_seconds: u64,
+    max_staleness_seconds: u64,
+    query_timeout: Duration,
+    /// Aggregated rates, by the first timestamp of their bucket.
+    cached_rates: Mutex<BTreeMap<u64, u128>>,
+}
+
+impl EthToStrkOracleAggregator {
+    pub fn new(config: &EthToStrkOracleConfig) -> Self {
+        let sources = config
+            .base_urls()
+            .map(|base_url| -> Box<dyn EthToStrkOracleClientTrait> {
+                Box::new(EthToStrkOracleClient::new(
+                    base_url.clone(),
+                    config.headers.clone(),
+                    config.lag_margin_seconds,
+                ))
+            })
+            .collect();
+        Self::new_with_sources(config, sources)
+    }
+
+    /// Creates an aggregator of the given sources, ignoring the URLs of the config.
+    pub fn new_with_sources(
+        config: &EthToStrkOracleConfig,
+        sources: Vec<Box<dyn EthToStrkOracleClientTrait>>,
+    ) -> Self {
+        assert!(
+            (1..=sources.len()).contains(&config.min_sources),
+            "The minimal number of sources, {}, must be positive and at most the number of \
+             sources, {}.",
+            config.min_sources,
+            sources.len()
+        );
+        assert!(config.bucket_seconds > 0, "The bucket size must be positive.");
+        register_eth_to_strk_metrics();
+        Self {
+            sources,
+            min_sources: config.min_sources,
+            max_deviation_percent: config.max_deviation_percent,
+            bucket_seconds: config.bucket_seconds,
+            max_staleness_seconds: config.max_staleness_seconds,
+            query_timeout: config.query_timeout,
+            cached_rates: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    async fn aggregate_rate(&self, timestamp: u64) -> Result<u128, EthToStrkOracleClientError> {
+        let queries = self.sources.iter().map(|source| {
+            tokio::time::timeout(self.query_timeout, source.eth_to_fri_rate(timestamp))
+        });
+        let mut rates = Vec::new();
+        for (source_index, result) in join_all(queries).await.into_iter().enumerate() {
+            match result.unwrap_or(Err(EthToStrkOracleClientError::TimeoutError)) {
+                Ok(rate) => rates.push((source_index, rate)),
+                Err(error) => {
+                    warn!("Failed to query eth to strk oracle source {source_index}: {error}");
+                    ETH_TO_STRK_SOURCE_FAILURES
+                        .increment(1, &OracleSourceLabelValue::label(source_index));
+                }
+            }
+        }
+        if rates.is_empty() {
+            return Err(EthToStrkOracleClientError::InsufficientSourcesError {
+                expected: self.min_sources,
+                found: 0,
+            });
+        }
+
+        let median_rate = median(rates.iter().map(|(_, rate)| *rate));
+        let max_deviation = median_rate * u128::from(self.max_deviation_percent) / 100;
+        let (accepted_rates, outlier_rates): (Vec<_>, Vec<_>) =
+            rates.into_iter().partition(|(_, rate)| rate.abs_diff(median_rate) <= max_deviation);
+        for (source_index, rate) in outlier_rates {
+            warn!(
+                "Rejected rate {rate} of eth to strk oracle source {source_index}, which deviates \
+                 from the median rate {median_rate} by more than {}%.",
+                self.max_deviation_percent
+            );
+            ETH_TO_STRK_SOURCE_OUTLIERS.increment(1, &OracleSourceLabelValue::label(source_index));
+        }
+        if accepted_rates.len() < self.min_sources {
+            return Err(EthToStrkOracleClientError::InsufficientSourcesError {
+                expected: self.min_sources,
+                found: accepted_rates.len(),
+            });
+        }
+
+        let rate = median(accepted_rates.iter().map(|(_, rate)| *rate));
+        ETH_TO_STRK_RATE.set_lossy(rate);
+        ETH_TO_STRK_RATE_N_SOURCES.set_lossy(accepted_rates.len());
+        for source_index in 0..self.sources.len() {
+            let used =
+                accepted_rates.iter().any(|(accepted_index, _)| *accepted_index == source_index);
+            ETH_TO_STRK_SOURCE_USED
+                .set(u8::from(used), &OracleSourceLabelValue::label(source_index));
+        }
+        Ok(rate)
+    }
+
+    /// Returns the last known good rate, if it isn't stale at the given timestamp.
+    fn fallback_rate(&self, timestamp: u64) -> Option<u128> {
+        let cached_rates =
+            self.cached_rates.lock().expect("Cached rates lock should not be poisoned.");
+        let (&bucket_timestamp, &rate) = cached_rates.range(..=timestamp).next_back()?;
+        if timestamp - bucket_timestamp > self.max_staleness_seconds {
+            return None;
+        }
+        warn!("Falling back to the eth to strk rate of timestamp {bucket_timestamp}: {rate}");
+        ETH_TO_STRK_RATE_FALLBACKS.increment(1);
+        Some(rate)
+    }
+}
+
+#[async_trait]
+impl EthToStrkOracleClientTrait for EthToStrkOracleAggregator {
+    async fn eth_to_fri_rate(&self, timestamp: u64) -> Result<u128, EthToStrkOracleClientError> {
+        let bucket_timestamp = timestamp - timestamp % self.bucket_seconds;
+        let cached_rate = self
+            .cached_rates
+            .lock()
+            .expect("Cached rates lock should not be poisoned.")
+            .get(&bucket_timestamp)
+            .copied();
+        if let Some(rate) = cached_rate {
+            debug!("Using cached eth to strk rate for timestamp {timestamp}: {rate}");
+            return Ok(rate);
+        }
+
+        match self.aggregate_rate(bucket_timestamp).await {
+            Ok(rate) => {
+                let mut cached_rates =
+                    self.cached_rates.lock().expect("Cached rates lock should not be poisoned.");
+                cached_rates.insert(bucket_timestamp, rate);
+                while cached_rates.len() > MAX_CACHED_RATES {
+                    cached_rates.pop_first();
+                }
+                debug!("Aggregated eth to strk rate for timestamp {timestamp}: {rate}");
+                Ok(rate)
+            }
+            Err(error) => {
+                warn!(
+                    "Failed to aggregate the eth to strk rate for timestamp {timestamp}: {error}"
+                );
+                self.fallback_rate(timestamp).ok_or(error)
+            }
+        }
+    }
+}
+
+/// Returns the median of the given values, which must not be empty.
+fn median(values: impl Iterator<Item = u128>) -> u128 {
+    let mut values: Vec<_> = values.collect();
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        values[middle - 1] + (values[middle] - values[middle - 1]) / 2
+    }
+}
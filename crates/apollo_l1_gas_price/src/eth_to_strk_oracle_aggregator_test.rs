@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
+use apollo_l1_gas_price_types::{EthToStrkOracleClientTrait, MockEthToStrkOracleClientTrait};
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use metrics_exporter_prometheus::PrometheusBuilder;
+
+use crate::eth_to_strk_oracle::EthToStrkOracleConfig;
+use crate::eth_to_strk_oracle_aggregator::EthToStrkOracleAggregator;
+use crate::metrics::{
+    OracleSourceLabelValue,
+    ETH_TO_STRK_RATE,
+    ETH_TO_STRK_RATE_FALLBACKS,
+    ETH_TO_STRK_RATE_N_SOURCES,
+    ETH_TO_STRK_SOURCE_FAILURES,
+    ETH_TO_STRK_SOURCE_OUTLIERS,
+    ETH_TO_STRK_SOURCE_USED,
+};
+
+fn config(min_sources: usize) -> EthToStrkOracleConfig {
+    EthToStrkOracleConfig {
+        min_sources,
+        query_timeout: Duration::from_millis(100),
+        ..Default::default()
+    }
+}
+
+fn source_with_rate(rate: u128) -> Box<dyn EthToStrkOracleClientTrait> {
+    let mut source = MockEthToStrkOracleClientTrait::new();
+    source.expect_eth_to_fri_rate().returning(move |_| Ok(rate));
+    Box::new(source)
+}
+
+fn failing_source() -> Box<dyn EthToStrkOracleClientTrait> {
+    let mut source = MockEthToStrkOracleClientTrait::new();
+    source
+        .expect_eth_to_fri_rate()
+        .returning(|_| Err(EthToStrkOracleClientError::MissingFieldError("price")));
+    Box::new(source)
+}
+
+struct StalledSource;
+
+#[async_trait]
+impl EthToStrkOracleClientTrait for StalledSource {
+    async fn eth_to_fri_rate(&self, _timestamp: u64) -> Result<u128, EthToStrkOracleClientError> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn aggregates_median_and_rejects_outliers() {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let _recorder_guard = metrics::set_default_local_recorder(&recorder);
+    let aggregator = EthToStrkOracleAggregator::new_with_sources(
+        &config(2),
+        vec![
+            source_with_rate(100),
+            source_with_rate(104),
+            source_with_rate(1000),
+            failing_source(),
+        ],
+    );
+
+    assert_eq!(aggregator.eth_to_fri_rate(1).await.unwrap(), 102);
+
+    let metrics = recorder.handle().render();
+    ETH_TO_STRK_RATE.assert_eq(&metrics, 102);
+    ETH_TO_STRK_RATE_N_SOURCES.assert_eq(&metrics, 2);
+    for (source_index, expected_used, expected_outliers, expected_failures) in
+        [(0, 1, 0, 0), (1, 1, 0, 0), (2, 0, 1, 0), (3, 0, 0, 1)]
+    {
+        let label = OracleSourceLabelValue::label(source_index);
+        assert_eq!(
+            ETH_TO_STRK_SOURCE_USED.parse_numeric_metric::<u64>(&metrics, &label),
+            Some(expected_used)
+        );
+        ETH_TO_STRK_SOURCE_OUTLIERS.assert_eq(&metrics, expected_outliers, &label);
+        ETH_TO_STRK_SOURCE_FAILURES.assert_eq(&metrics, expected_failures, &label);
+    }
+}
+
+#[tokio::test]
+async fn caches_rates_per_bucket() {
+    let mut source = MockEthToStrkOracleClientTrait::new();
+    source
+        .expect_eth_to_fri_rate()
+        .withf(|timestamp| *timestamp == 120)
+        .times(1)
+        .returning(|_| Ok(1));
+    source
+        .expect_eth_to_fri_rate()
+        .withf(|timestamp| *timestamp == 180)
+        .times(1)
+        .returning(|_| Ok(2));
+    let aggregator = EthToStrkOracleAggregator::new_with_sources(
+        &EthToStrkOracleConfig { bucket_seconds: 60, ..config(1) },
+        vec![Box::new(source)],
+    );
+
+    for (timestamp, expected_rate) in [(125, 1), (179, 1), (120, 1), (180, 2), (239, 2)] {
+        assert_eq!(aggregator.eth_to_fri_rate(timestamp).await.unwrap(), expected_rate);
+    }
+}
+
+#[tokio::test]
+async fn falls_back_to_last_good_rate() {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let _recorder_guard = metrics::set_default_local_recorder(&recorder);
+    let mut source = MockEthToStrkOracleClientTrait::new();
+    source.expect_eth_to_fri_rate().withf(|timestamp| *timestamp == 1000).returning(|_| Ok(7));
+    source
+        .expect_eth_to_fri_rate()
+        .returning(|_| Err(EthToStrkOracleClientError::MissingFieldError("price")));
+    let aggregator = EthToStrkOracleAggregator::new_with_sources(
+        &EthToStrkOracleConfig { max_staleness_seconds: 300, ..config(1) },
+        vec![Box::new(source)],
+    );
+
+    assert_eq!(aggregator.eth_to_fri_rate(1000).await.unwrap(), 7);
+    assert_eq!(aggregator.eth_to_fri_rate(1300).await.unwrap(), 7);
+    // The last good rate is too stale.
+    assert_matches!(
+        aggregator.eth_to_fri_rate(1301).await,
+        Err(EthToStrkOracleClientError::InsufficientSourcesError { expected: 1, found: 0 })
+    );
+    // There's no earlier good rate.
+    assert_matches!(
+        aggregator.eth_to_fri_rate(999).await,
+        Err(EthToStrkOracleClientError::InsufficientSourcesError { expected: 1, found: 0 })
+    );
+
+    ETH_TO_STRK_RATE_FALLBACKS.assert_eq(&recorder.handle().render(), 1);
+}
+
+#[tokio::test]
+async fn timed_out_source() {
+    let aggregator = EthToStrkOracleAggregator::new_with_sources(
+        &config(1),
+        vec![Box::new(StalledSource), source_with_rate(5)],
+    );
+    assert_eq!(aggregator.eth_to_fri_rate(1).await.unwrap(), 5);
+
+    let aggregator = EthToStrkOracleAggregator::new_with_sources(
+        &config(2),
+        vec![Box::new(StalledSource), source_with_rate(5)],
+    );
+    assert_matches!(
+        aggregator.eth_to_fri_rate(1).await,
+        Err(EthToStrkOracleClientError::InsufficientSourcesError { expected: 2, found: 1 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "The bucket size must be positive.")]
+fn zero_bucket_size() {
+    EthToStrkOracleAggregator::new_with_sources(
+        &EthToStrkOracleConfig { bucket_seconds: 0, ..config(1) },
+        vec![source_with_rate(5)],
+    );
+}
@@ -16,8 +16,16 @@ pub mod l1_gas_price_provider_test;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct L1GasPriceProviderConfig {
-    // TODO(guyn): these two fields need to go into VersionedConstants.
+    // TODO(guyn): these fields need to go into VersionedConstants.
+    #[validate(range(min = 1))]
     pub number_of_blocks_for_mean: u64,
+    // Blob fees are far more volatile than base fees (EIP-4844), so they are smoothed separately.
+    #[validate(range(min = 1))]
+    pub number_of_blocks_for_blob_fee_mean: u64,
+    // Samples deviating from the median of their window by more than these percentages are
+    // clipped before averaging, e.g., 200 clips to between a third and three times the median.
+    pub max_base_fee_deviation_percent: u64,
+    pub max_blob_fee_deviation_percent: u64,
     // Use seconds not Duration since seconds is the basic quanta of time for both Starknet and
     // Ethereum.
     pub lag_margin_seconds: u64,
@@ -29,6 +37,9 @@ impl Default for L1GasPriceProviderConfig {
         const MEAN_NUMBER_OF_BLOCKS: u64 = 300;
         Self {
             number_of_blocks_for_mean: MEAN_NUMBER_OF_BLOCKS,
+            number_of_blocks_for_blob_fee_mean: MEAN_NUMBER_OF_BLOCKS,
+            max_base_fee_deviation_percent: 200,
+            max_blob_fee_deviation_percent: 400,
             lag_margin_seconds: 60,
             storage_limit: usize::try_from(10 * MEAN_NUMBER_OF_BLOCKS).unwrap(),
         }
@@ -41,7 +52,27 @@ impl SerializeConfig for L1GasPriceProviderConfig {
             ser_param(
                 "number_of_blocks_for_mean",
                 &self.number_of_blocks_for_mean,
-                "Number of blocks to use for the mean gas price calculation",
+                "Number of blocks to use for the mean base fee calculation",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "number_of_blocks_for_blob_fee_mean",
+                &self.number_of_blocks_for_blob_fee_mean,
+                "Number of blocks to use for the mean blob fee calculation",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_base_fee_deviation_percent",
+                &self.max_base_fee_deviation_percent,
+                "Base fees above the median of the mean calculation by more than this percentage, \
+                 or below it by the inverse factor, are clipped before averaging",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_blob_fee_deviation_percent",
+                &self.max_blob_fee_deviation_percent,
+                "Blob fees above the median of the mean calculation by more than this percentage, \
+                 or below it by the inverse factor, are clipped before averaging",
                 ParamPrivacyInput::Public,
             ),
             ser_param(
@@ -105,7 +136,8 @@ impl L1GasPriceProvider {
     }
 
     pub fn make_new_provider_with_fake_data(config: L1GasPriceProviderConfig) -> Self {
-        let number = config.number_of_blocks_for_mean;
+        let number =
+            config.number_of_blocks_for_mean.max(config.number_of_blocks_for_blob_fee_mean);
         let mut provider = Self::new(config);
         for h in 0..number {
             provider
@@ -156,27 +188,49 @@ impl L1GasPriceProvider {
         // We need to convert the index to the forward direction.
         let last_index = self.price_samples_by_block.len() - last_index_rev;
 
-        let num_blocks = usize::try_from(self.config.number_of_blocks_for_mean)
+        let base_fee_num_blocks = usize::try_from(self.config.number_of_blocks_for_mean)
             .expect("number_of_blocks_for_mean is too large to fit into a usize");
+        let blob_fee_num_blocks = usize::try_from(self.config.number_of_blocks_for_blob_fee_mean)
+            .expect("number_of_blocks_for_blob_fee_mean is too large to fit into a usize");
+        let num_blocks = base_fee_num_blocks.max(blob_fee_num_blocks);
         if last_index < num_blocks {
             return Err(L1GasPriceProviderError::InsufficientHistoryError {
                 expected: num_blocks,
                 found: last_index,
             });
         }
-        // Go over all elements between last_index-num_blocks to last_index (non-inclusive).
-        let (gas_price, data_gas_price) =
-            self.price_samples_by_block.iter().skip(last_index - num_blocks).take(num_blocks).fold(
-                (0, 0),
-                |(sum_base, sum_blob), data| {
-                    (sum_base + data.sample.base_fee_per_gas, sum_blob + data.sample.blob_fee)
-                },
-            );
+        // Each mean is over the num_blocks elements before last_index (non-inclusive).
+        let window = |num_blocks| {
+            self.price_samples_by_block
+                .iter()
+                .skip(last_index - num_blocks)
+                .take(num_blocks)
+                .map(|data| &data.sample)
+        };
         Ok(PriceInfo {
-            base_fee_per_gas: gas_price / u128::from(self.config.number_of_blocks_for_mean),
-            blob_fee: data_gas_price / u128::from(self.config.number_of_blocks_for_mean),
+            base_fee_per_gas: clipped_mean(
+                window(base_fee_num_blocks).map(|sample| sample.base_fee_per_gas).collect(),
+                self.config.max_base_fee_deviation_percent,
+            ),
+            blob_fee: clipped_mean(
+                window(blob_fee_num_blocks).map(|sample| sample.blob_fee).collect(),
+                self.config.max_blob_fee_deviation_percent,
+            ),
         })
     }
 }
 
+/// Returns the mean of the given fees, after clipping each fee to within the given percentage of
+/// their median: up to `median * (100 + p) / 100` and down to `median * 100 / (100 + p)`. Fees
+/// change multiplicatively from block to block, hence the symmetric factor.
+fn clipped_mean(mut fees: Vec<u128>, max_deviation_percent: u64) -> u128 {
+    let num_fees = u128::try_from(fees.len()).expect("Number of fees should fit into a u128.");
+    let middle = (fees.len() - 1) / 2;
+    let median = *fees.select_nth_unstable(middle).1;
+    let factor_percent = 100 + u128::from(max_deviation_percent);
+    let upper_bound = median.saturating_mul(factor_percent) / 100;
+    let lower_bound = median.saturating_mul(100) / factor_percent;
+    fees.into_iter().map(|fee| fee.clamp(lower_bound, upper_bound)).sum::<u128>() / num_fees
+}
+
 impl ComponentStarter for L1GasPriceProvider {}
@@ -1,6 +1,10 @@
 use apollo_l1_gas_price_types::PriceInfo;
-use papyrus_base_layer::PriceSample;
+use assert_matches::assert_matches;
+use papyrus_base_layer::{L1BlockNumber, PriceSample};
+use serde::Deserialize;
 use starknet_api::block::BlockTimestamp;
+use starknet_api::test_utils::read_json_file;
+use validator::Validate;
 
 use crate::l1_gas_price_provider::{
     L1GasPriceProvider,
@@ -15,6 +19,7 @@ use crate::l1_gas_price_provider::{
 fn make_provider() -> (L1GasPriceProvider, Vec<PriceSample>) {
     let mut provider = L1GasPriceProvider::new(L1GasPriceProviderConfig {
         number_of_blocks_for_mean: 3,
+        number_of_blocks_for_blob_fee_mean: 3,
         ..Default::default()
     });
     let mut samples = Vec::new();
@@ -108,3 +113,128 @@ fn gas_price_provider_can_start_at_nonzero_height() {
     let sample = PriceSample { timestamp: 0, base_fee_per_gas: 0, blob_fee: 0 };
     provider.add_price_info(42, sample.clone()).unwrap();
 }
+
+#[test]
+fn gas_price_provider_config_rejects_empty_windows() {
+    assert!(L1GasPriceProviderConfig::default().validate().is_ok());
+    let config = L1GasPriceProviderConfig { number_of_blocks_for_mean: 0, ..Default::default() };
+    assert!(config.validate().is_err());
+    let config =
+        L1GasPriceProviderConfig { number_of_blocks_for_blob_fee_mean: 0, ..Default::default() };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn gas_price_provider_separate_windows_and_clipping() {
+    let mut provider = L1GasPriceProvider::new(L1GasPriceProviderConfig {
+        number_of_blocks_for_mean: 4,
+        number_of_blocks_for_blob_fee_mean: 2,
+        max_base_fee_deviation_percent: 200,
+        max_blob_fee_deviation_percent: 100,
+        ..Default::default()
+    });
+    for (block_num, (base_fee_per_gas, blob_fee)) in
+        [(10, 7), (1, 7), (10, 4), (11, 2), (100, 1)].into_iter().enumerate()
+    {
+        let sample =
+            PriceSample { timestamp: block_num.try_into().unwrap(), base_fee_per_gas, blob_fee };
+        provider.add_price_info(block_num.try_into().unwrap(), sample).unwrap();
+    }
+    let lag = provider.config.lag_margin_seconds;
+
+    // The base fee median of blocks 1 to 4 is 10, so the fees are clipped to between 3 and 30.
+    // The blob fee median of blocks 3 to 4 is 1, so the fees are clipped to between 0 and 2.
+    let PriceInfo { base_fee_per_gas, blob_fee } =
+        provider.get_price_info(BlockTimestamp(4 + lag)).unwrap();
+    assert_eq!(base_fee_per_gas, (3 + 10 + 11 + 30) / 4);
+    assert_eq!(blob_fee, (2 + 1) / 2);
+
+    // There are only 3 blocks up to block 2, fewer than needed for the base fee.
+    assert_matches!(
+        provider.get_price_info(BlockTimestamp(2 + lag)),
+        Err(L1GasPriceProviderError::InsufficientHistoryError { expected: 4, found: 3 })
+    );
+}
+
+/// A block of the synthetic fee history fixture. The fixture isn't recorded from L1; its base fee
+/// follows the EIP-1559 update rule, and its blob fee surges and then decays.
+#[derive(Deserialize)]
+struct SyntheticBlock {
+    block_number: L1BlockNumber,
+    timestamp: u64,
+    base_fee_per_gas: u128,
+    blob_fee: u128,
+}
+
+impl SyntheticBlock {
+    fn sample(&self) -> PriceSample {
+        PriceSample {
+            timestamp: self.timestamp,
+            base_fee_per_gas: self.base_fee_per_gas,
+            blob_fee: self.blob_fee,
+        }
+    }
+}
+
+#[test]
+fn gas_price_provider_fee_history_simulation() {
+    // 64 consecutive synthetic L1 blocks, with the blob fee surging from block 17 to block 40 and
+    // then decaying.
+    const LAST_SURGE_BLOCK: usize = 40;
+    let fee_history: Vec<SyntheticBlock> =
+        serde_json::from_value(read_json_file("synthetic_fee_history.json")).unwrap();
+    let config = L1GasPriceProviderConfig {
+        number_of_blocks_for_mean: 10,
+        number_of_blocks_for_blob_fee_mean: 20,
+        max_base_fee_deviation_percent: 200,
+        max_blob_fee_deviation_percent: 100,
+        lag_margin_seconds: 0,
+        storage_limit: fee_history.len(),
+    };
+    let mut provider = L1GasPriceProvider::new(config.clone());
+    let mut unclipped_provider = L1GasPriceProvider::new(L1GasPriceProviderConfig {
+        max_base_fee_deviation_percent: u64::MAX,
+        max_blob_fee_deviation_percent: u64::MAX,
+        ..config
+    });
+
+    let (mut peak_blob_fee, mut unclipped_peak_blob_fee) = (0, 0);
+    for (index, block) in fee_history.iter().enumerate() {
+        provider.add_price_info(block.block_number, block.sample()).unwrap();
+        unclipped_provider.add_price_info(block.block_number, block.sample()).unwrap();
+        let timestamp = BlockTimestamp(block.timestamp);
+        let n_blocks = index + 1;
+        if n_blocks < 20 {
+            assert_matches!(
+                provider.get_price_info(timestamp),
+                Err(L1GasPriceProviderError::InsufficientHistoryError { expected: 20, .. })
+            );
+            continue;
+        }
+
+        let price_info = provider.get_price_info(timestamp).unwrap();
+        let unclipped_price_info = unclipped_provider.get_price_info(timestamp).unwrap();
+        let window = |num_blocks| fee_history[n_blocks - num_blocks..n_blocks].iter();
+        let base_fees = || window(10).map(|block| block.base_fee_per_gas);
+        let blob_fees = || window(20).map(|block| block.blob_fee);
+        assert_eq!(unclipped_price_info.base_fee_per_gas, base_fees().sum::<u128>() / 10);
+        assert_eq!(unclipped_price_info.blob_fee, blob_fees().sum::<u128>() / 20);
+
+        // The base fee changes by at most 12.5% per block (EIP-1559), so it never strays from the
+        // median of 10 blocks by a factor of 3.
+        assert_eq!(price_info.base_fee_per_gas, unclipped_price_info.base_fee_per_gas);
+        // Clipping keeps the blob fee within the range of the window.
+        assert!(blob_fees().min().unwrap() <= price_info.blob_fee);
+        assert!(price_info.blob_fee <= blob_fees().max().unwrap());
+        // Clipping holds back the blob fee while it surges, without raising its peak.
+        if index <= LAST_SURGE_BLOCK {
+            assert!(price_info.blob_fee <= unclipped_price_info.blob_fee);
+        }
+        if index == LAST_SURGE_BLOCK {
+            assert!(price_info.blob_fee < unclipped_price_info.blob_fee);
+        }
+        peak_blob_fee = peak_blob_fee.max(price_info.blob_fee);
+        unclipped_peak_blob_fee = unclipped_peak_blob_fee.max(unclipped_price_info.blob_fee);
+    }
+    assert!(peak_blob_fee <= unclipped_peak_blob_fee);
+}
@@ -1,9 +1,19 @@
 use std::sync::Arc;
 
 use apollo_l1_gas_price_types::MockL1GasPriceProviderClient;
+use assert_matches::assert_matches;
+use papyrus_base_layer::fake_base_layer_contract::{
+    FakeBaseLayerContract,
+    FakeBaseLayerError,
+    FAKE_L1_BLOCK_TIME_SECONDS,
+};
 use papyrus_base_layer::{MockBaseLayerContract, PriceSample};
 
-use crate::l1_gas_price_scraper::{L1GasPriceScraper, L1GasPriceScraperConfig};
+use crate::l1_gas_price_scraper::{
+    L1GasPriceScraper,
+    L1GasPriceScraperConfig,
+    L1GasPriceScraperError,
+};
 
 const BLOCK_TIME: u64 = 2;
 const GAS_PRICE: u128 = 42;
@@ -124,3 +134,35 @@ async fn run_l1_gas_price_scraper_multiple_blocks() {
     // Should update prices from 5 to 10 (not inclusive) and on 10 get a None from base layer.
     scraper.update_prices(START_BLOCK).await.unwrap();
 }
+
+#[tokio::test]
+async fn l1_gas_price_scraper_with_fake_base_layer() {
+    let base_layer = FakeBaseLayerContract::new();
+    base_layer.set_prices(GAS_PRICE, DATA_PRICE);
+    base_layer.mine_block();
+    base_layer.mine_block();
+
+    let mut mock_provider = MockL1GasPriceProviderClient::new();
+    mock_provider
+        .expect_add_price_info()
+        .withf(|&block_number, price_sample| {
+            price_sample.timestamp == block_number * FAKE_L1_BLOCK_TIME_SECONDS
+                && price_sample.base_fee_per_gas == if block_number == 0 { 0 } else { GAS_PRICE }
+        })
+        .times(3)
+        .returning(|_, _| Ok(()));
+    let mut scraper = L1GasPriceScraper::new(
+        L1GasPriceScraperConfig::default(),
+        Arc::new(mock_provider),
+        base_layer.clone(),
+    );
+
+    // Scrapes the genesis block and the two mined blocks.
+    assert_eq!(scraper.update_prices(0).await.unwrap(), 3);
+
+    base_layer.set_unavailable(true);
+    assert_matches!(
+        scraper.update_prices(3).await,
+        Err(L1GasPriceScraperError::BaseLayerError(FakeBaseLayerError::Unavailable))
+    );
+}
@@ -1,4 +1,6 @@
 pub mod communication;
 pub mod eth_to_strk_oracle;
+pub mod eth_to_strk_oracle_aggregator;
 pub mod l1_gas_price_provider;
 pub mod l1_gas_price_scraper;
+pub mod metrics;
@@ -0,0 +1,70 @@
+use apollo_metrics::metrics::{
+    LabeledMetricCounter,
+    LabeledMetricGauge,
+    MetricCounter,
+    MetricGauge,
+};
+use apollo_metrics::{define_metrics, generate_permutation_labels};
+use strum::{EnumVariantNames, VariantNames};
+use strum_macros::{EnumIter, FromRepr, IntoStaticStr};
+
+define_metrics!(
+    L1GasPrice => {
+        MetricGauge { ETH_TO_STRK_RATE, "eth_to_strk_rate", "The latest ETH to STRK rate chosen by the oracle aggregator, in fri per ETH" },
+        MetricGauge { ETH_TO_STRK_RATE_N_SOURCES, "eth_to_strk_rate_n_sources", "The number of oracle sources the latest ETH to STRK rate was aggregated from" },
+        LabeledMetricGauge { ETH_TO_STRK_SOURCE_USED, "eth_to_strk_source_used", "Whether each oracle source was used for the latest ETH to STRK rate (1) or not (0)", labels = ORACLE_SOURCE_LABELS },
+        LabeledMetricCounter { ETH_TO_STRK_SOURCE_FAILURES, "eth_to_strk_source_failures", "Counter of failed or timed out queries to each ETH to STRK oracle source", init = 0, labels = ORACLE_SOURCE_LABELS },
+        LabeledMetricCounter { ETH_TO_STRK_SOURCE_OUTLIERS, "eth_to_strk_source_outliers", "Counter of rates of each ETH to STRK oracle source that were rejected as outliers", init = 0, labels = ORACLE_SOURCE_LABELS },
+        MetricCounter { ETH_TO_STRK_RATE_FALLBACKS, "eth_to_strk_rate_fallbacks", "Counter of ETH to STRK rates that fell back to the last known good rate", init = 0 },
+    },
+);
+
+pub const LABEL_NAME_ORACLE_SOURCE: &str = "source";
+
+generate_permutation_labels! {
+    ORACLE_SOURCE_LABELS,
+    (LABEL_NAME_ORACLE_SOURCE, OracleSourceLabelValue),
+}
+
+/// The index of an ETH to STRK oracle source, by the order of the sources in the config. Sources
+/// are labeled by index since their URLs are private.
+#[derive(Clone, Copy, Debug, EnumIter, EnumVariantNames, FromRepr, IntoStaticStr)]
+#[repr(usize)]
+pub enum OracleSourceLabelValue {
+    #[strum(serialize = "0")]
+    Source0,
+    #[strum(serialize = "1")]
+    Source1,
+    #[strum(serialize = "2")]
+    Source2,
+    #[strum(serialize = "3")]
+    Source3,
+    #[strum(serialize = "4")]
+    Source4,
+    #[strum(serialize = "5")]
+    Source5,
+    #[strum(serialize = "6")]
+    Source6,
+    #[strum(serialize = "7")]
+    Source7,
+}
+
+/// The maximal number of sources of the ETH to STRK oracle aggregator.
+pub const MAX_ORACLE_SOURCES: usize = OracleSourceLabelValue::VARIANTS.len();
+
+impl OracleSourceLabelValue {
+    pub fn label(source_index: usize) -> [(&'static str, &'static str); 1] {
+        let label_value = Self::from_repr(source_index)
+            .unwrap_or_else(|| panic!("Oracle source index {source_index} has no metric label."));
+        [(LABEL_NAME_ORACLE_SOURCE, label_value.into())]
+    }
+}
+
+pub(crate) fn register_eth_to_strk_metrics() {
+    ETH_TO_STRK_RATE.register();
+    ETH_TO_STRK_RATE_N_SOURCES.register();
+    ETH_TO_STRK_SOURCE_USED.register();
+    ETH_TO_STRK_SOURCE_FAILURES.register();
+    ETH_TO_STRK_SOURCE_OUTLIERS.register();
+    ETH_TO_STRK_RATE_FALLBACKS.register();
+}
@@ -30,4 +30,11 @@ pub enum EthToStrkOracleClientError {
     MissingFieldError(&'static str),
     #[error("Invalid decimals value: expected {0}, got {1}")]
     InvalidDecimalsError(u64, u64),
+    #[error(
+        "Insufficient ETH to STRK oracle sources agree on the rate: expected at least {expected}, \
+         found only {found}"
+    )]
+    InsufficientSourcesError { expected: usize, found: usize },
+    #[error("Query of an ETH to STRK oracle source timed out")]
+    TimeoutError,
 }
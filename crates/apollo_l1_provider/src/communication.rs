@@ -32,6 +32,9 @@ impl ComponentRequestHandler<L1ProviderRequest, L1ProviderResponse> for L1Provid
             L1ProviderRequest::CommitBlock { l1_handler_tx_hashes, height } => {
                 L1ProviderResponse::CommitBlock(self.commit_block(&l1_handler_tx_hashes, height))
             }
+            L1ProviderRequest::GetL1HandlerStatus(tx_hash) => {
+                L1ProviderResponse::GetL1HandlerStatus(self.get_l1_handler_status(tx_hash))
+            }
             L1ProviderRequest::GetTransactions { n_txs, height } => {
                 L1ProviderResponse::GetTransactions(self.get_txs(n_txs, height))
             }
@@ -44,6 +47,9 @@ impl ComponentRequestHandler<L1ProviderRequest, L1ProviderResponse> for L1Provid
             L1ProviderRequest::Initialize(events) => {
                 L1ProviderResponse::Initialize(self.initialize(events).await)
             }
+            L1ProviderRequest::RollbackEvents { events } => {
+                L1ProviderResponse::RollbackEvents(self.rollback_l1_events(&events))
+            }
         }
     }
 }
@@ -1,11 +1,13 @@
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_l1_provider_types::errors::L1ProviderError;
 use apollo_l1_provider_types::{
     Event,
+    L1HandlerStatus,
     L1ProviderResult,
     SessionState,
     SharedL1ProviderClient,
@@ -71,7 +73,7 @@ impl L1Provider {
 
         match self.state {
             ProviderState::Propose => {
-                let txs = self.tx_manager.get_txs(n_txs);
+                let txs = self.tx_manager.get_txs(n_txs, unix_now());
                 info!(
                     "Returned {} out of {} transactions, ready for sequencing.",
                     txs.len(),
@@ -100,7 +102,7 @@ impl L1Provider {
     ) -> L1ProviderResult<ValidationStatus> {
         self.validate_height(height)?;
         match self.state {
-            ProviderState::Validate => Ok(self.tx_manager.validate_tx(tx_hash)),
+            ProviderState::Validate => Ok(self.tx_manager.validate_tx(tx_hash, unix_now())),
             ProviderState::Propose => Err(L1ProviderError::ValidateTransactionConsensusBug),
             ProviderState::Pending | ProviderState::Bootstrap(_) => {
                 Err(L1ProviderError::OutOfSessionValidate)
@@ -214,9 +216,77 @@ impl L1Provider {
                     // TODO(Gilad): can we ignore this silently?
                     let _is_known_or_committed = self.tx_manager.add_tx(l1_handler_tx);
                 }
-                _ => todo!(),
+                Event::TransactionCancellationStarted {
+                    tx_hash,
+                    cancellation_request_timestamp,
+                } => {
+                    if !self
+                        .tx_manager
+                        .request_cancellation(tx_hash, cancellation_request_timestamp)
+                    {
+                        debug!(
+                            "Ignoring a cancellation request of committed or canceled L1 handler \
+                             transaction {tx_hash}."
+                        );
+                    }
+                }
+                Event::TransactionCanceled(tx_hash) => {
+                    if !self.tx_manager.cancel_tx(tx_hash) {
+                        error!(
+                            "L1 handler transaction {tx_hash} was canceled on L1 after it was \
+                             committed on L2."
+                        );
+                    }
+                }
+                Event::TransactionConsumed(tx_hash) => self.tx_manager.consume_tx(tx_hash),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the lifecycle state of the given L1 handler transaction.
+    pub fn get_l1_handler_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> L1ProviderResult<L1HandlerStatus> {
+        Ok(self.tx_manager.l1_handler_status(tx_hash))
+    }
+
+    /// Rolls back events that were scraped from L1 blocks that were since reorged out of L1, given
+    /// in the order they were added. Transactions that are already committed on L2 can't be rolled
+    /// back.
+    #[instrument(skip(self), err)]
+    pub fn rollback_l1_events(&mut self, events: &[Event]) -> L1ProviderResult<()> {
+        let mut committed = Vec::new();
+        // Undo the events in reverse order, e.g., a cancellation before the transaction itself.
+        for event in events.iter().rev() {
+            match event {
+                Event::L1HandlerTransaction(tx) => {
+                    if !self.tx_manager.rollback_tx(tx.tx_hash) {
+                        committed.push(tx.tx_hash);
+                    }
+                }
+                Event::TransactionCancellationStarted {
+                    tx_hash,
+                    cancellation_request_timestamp,
+                } => {
+                    self.tx_manager
+                        .rollback_cancellation_request(*tx_hash, *cancellation_request_timestamp);
+                }
+                Event::TransactionCanceled(tx_hash) => {
+                    self.tx_manager.rollback_cancellation(*tx_hash)
+                }
+                Event::TransactionConsumed(tx_hash) => {
+                    self.tx_manager.rollback_consumption(*tx_hash)
+                }
             }
         }
+        if !committed.is_empty() {
+            error!(
+                "L1 handler transactions that were reorged out of L1 are already committed on L2: \
+                 {committed:?}"
+            );
+        }
         Ok(())
     }
 
@@ -238,6 +308,15 @@ impl L1Provider {
 
 impl ComponentStarter for L1Provider {}
 
+// The current unix timestamp in seconds, which is compared to the L1 timestamps of the
+// cancellation requests.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Current time is after the unix epoch.")
+        .as_secs()
+}
+
 /// Initializes L1Provider at specified height (≤ scraper's last state update height).
 /// Bootstrap catch-up height defaults to current sync height.
 #[instrument(skip(l1_provider_client, sync_client, config))]
@@ -285,7 +364,7 @@ pub fn create_l1_provider(
 
     L1Provider {
         current_height: l1_provider_startup_height,
-        tx_manager: TransactionManager::default(),
+        tx_manager: TransactionManager::new(config.l1_handler_cancellation_grace_period),
         state: ProviderState::Bootstrap(bootstrapper),
     }
 }
@@ -1,5 +1,5 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use apollo_l1_provider_types::errors::L1ProviderError;
 use apollo_l1_provider_types::SessionState::{
@@ -7,7 +7,7 @@ use apollo_l1_provider_types::SessionState::{
     Propose as ProposeSession,
     Validate as ValidateSession,
 };
-use apollo_l1_provider_types::{Event, InvalidValidationStatus, ValidationStatus};
+use apollo_l1_provider_types::{Event, InvalidValidationStatus, L1HandlerStatus, ValidationStatus};
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use assert_matches::assert_matches;
 use itertools::Itertools;
@@ -145,6 +145,132 @@ fn process_events_committed_txs() {
     assert_eq!(l1_provider, expected_l1_provider);
 }
 
+#[test]
+fn process_events_cancellation_and_consumption() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2), l1_handler(3), l1_handler(4)])
+        .with_committed([tx_hash!(5), tx_hash!(6)])
+        .build_into_l1_provider();
+
+    // Test.
+    l1_provider
+        .process_l1_events(vec![
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(1),
+                cancellation_request_timestamp: 10,
+            },
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(2),
+                cancellation_request_timestamp: 10,
+            },
+            Event::TransactionCanceled(tx_hash!(2)),
+            Event::TransactionConsumed(tx_hash!(3)),
+            Event::TransactionConsumed(tx_hash!(5)),
+        ])
+        .unwrap();
+    // Canceled transactions aren't added again, e.g., when they're scraped again.
+    l1_provider.process_l1_events(vec![l1_handler_event(tx_hash!(2))]).unwrap();
+
+    let expected_l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(4)])
+        .with_committed([tx_hash!(3), tx_hash!(5), tx_hash!(6)])
+        .build();
+    expected_l1_provider.assert_eq(&l1_provider);
+
+    let expected_statuses = [
+        (1, L1HandlerStatus::CancellationRequested { cancellation_request_timestamp: 10 }),
+        (2, L1HandlerStatus::CanceledOnL1),
+        (3, L1HandlerStatus::ConsumedOnL1),
+        (4, L1HandlerStatus::Pending),
+        (5, L1HandlerStatus::ConsumedOnL1),
+        (6, L1HandlerStatus::CommittedOnL2),
+        (7, L1HandlerStatus::Unknown),
+    ];
+    for (tx_hash, expected_status) in expected_statuses {
+        assert_eq!(l1_provider.get_l1_handler_status(tx_hash!(tx_hash)).unwrap(), expected_status);
+    }
+}
+
+#[test]
+fn consumption_keeps_staged_txs() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2), l1_handler(3)])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+    assert_eq!(l1_provider.get_txs(2, BlockNumber(0)).unwrap(), [l1_handler(1), l1_handler(2)]);
+
+    // Test.
+    l1_provider.process_l1_events(vec![Event::TransactionConsumed(tx_hash!(3))]).unwrap();
+
+    // The staged transactions were already proposed in this block.
+    assert!(l1_provider.get_txs(2, BlockNumber(0)).unwrap().is_empty());
+    assert_eq!(
+        l1_provider.get_l1_handler_status(tx_hash!(3)).unwrap(),
+        L1HandlerStatus::ConsumedOnL1
+    );
+}
+
+#[test]
+fn get_txs_skips_txs_after_cancellation_grace_period() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2), l1_handler(3)])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+    l1_provider.tx_manager.cancellation_grace_period = Duration::from_secs(3600);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    l1_provider
+        .process_l1_events(vec![
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(1),
+                cancellation_request_timestamp: now - 2 * 3600,
+            },
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(2),
+                cancellation_request_timestamp: now,
+            },
+        ])
+        .unwrap();
+
+    // Test.
+    assert_eq!(l1_provider.get_txs(3, BlockNumber(0)).unwrap(), [l1_handler(2), l1_handler(3)]);
+}
+
+#[test]
+fn validate_rejects_txs_after_cancellation_grace_period() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2)])
+        .with_state(ProviderState::Validate)
+        .build_into_l1_provider();
+    l1_provider.tx_manager.cancellation_grace_period = Duration::from_secs(3600);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    l1_provider
+        .process_l1_events(vec![
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(1),
+                cancellation_request_timestamp: now - 2 * 3600,
+            },
+            Event::TransactionCancellationStarted {
+                tx_hash: tx_hash!(2),
+                cancellation_request_timestamp: now,
+            },
+        ])
+        .unwrap();
+
+    // Test.
+    assert_eq!(
+        l1_provider.validate(tx_hash!(1), BlockNumber(0)).unwrap(),
+        ValidationStatus::Invalid(InvalidValidationStatus::CancellationRequestedOnL1)
+    );
+    assert_eq!(
+        l1_provider.validate(tx_hash!(2), BlockNumber(0)).unwrap(),
+        ValidationStatus::Validated
+    );
+}
+
 #[test]
 fn pending_state_errors() {
     // Setup.
@@ -294,6 +420,73 @@ fn commit_block_backlog() {
     expected_l1_provider.assert_eq(&l1_provider);
 }
 
+#[test]
+fn rollback_l1_events() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2), l1_handler(3)])
+        .with_committed([tx_hash!(4)])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+    // Stage a transaction that is then rolled back.
+    assert_eq!(l1_provider.get_txs(1, BlockNumber(0)).unwrap(), [l1_handler(1)]);
+
+    // Test: committed transactions can't be rolled back.
+    l1_provider
+        .rollback_l1_events(&[
+            l1_handler_event(tx_hash!(1)),
+            l1_handler_event(tx_hash!(3)),
+            l1_handler_event(tx_hash!(4)),
+        ])
+        .unwrap();
+
+    let expected_l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(2)])
+        .with_committed([tx_hash!(4)])
+        .build();
+    expected_l1_provider.assert_eq(&l1_provider);
+    assert!(!l1_provider.tx_manager.txs.is_staged(&tx_hash!(1)));
+}
+
+#[test]
+fn rollback_l1_cancellation_and_consumption() {
+    // Setup.
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(1), l1_handler(2), l1_handler(3)])
+        .build_into_l1_provider();
+    let events = vec![
+        Event::TransactionCancellationStarted {
+            tx_hash: tx_hash!(1),
+            cancellation_request_timestamp: 10,
+        },
+        Event::TransactionCanceled(tx_hash!(1)),
+        Event::TransactionCancellationStarted {
+            tx_hash: tx_hash!(2),
+            cancellation_request_timestamp: 20,
+        },
+        Event::TransactionConsumed(tx_hash!(3)),
+    ];
+    l1_provider.process_l1_events(events.clone()).unwrap();
+
+    // Test: only the cancellation of the first transaction is reorged out.
+    l1_provider.rollback_l1_events(&events[1..]).unwrap();
+
+    let expected_l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([l1_handler(2), l1_handler(1)])
+        .with_committed([tx_hash!(3)])
+        .build();
+    expected_l1_provider.assert_eq(&l1_provider);
+    let expected_statuses = [
+        (1, L1HandlerStatus::CancellationRequested { cancellation_request_timestamp: 10 }),
+        (2, L1HandlerStatus::Pending),
+        // Consumed on L1 implies committed on L2, which isn't reorged out.
+        (3, L1HandlerStatus::CommittedOnL2),
+    ];
+    for (tx_hash, expected_status) in expected_statuses {
+        assert_eq!(l1_provider.get_l1_handler_status(tx_hash!(tx_hash)).unwrap(), expected_status);
+    }
+}
+
 #[test]
 fn tx_in_commit_block_before_processed_is_skipped() {
     // Setup
@@ -1,5 +1,5 @@
 use std::any::type_name;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::time::Duration;
 
 use apollo_config::converters::deserialize_float_seconds_to_duration;
@@ -12,16 +12,24 @@ use apollo_l1_provider_types::errors::L1ProviderClientError;
 use apollo_l1_provider_types::{Event, SharedL1ProviderClient};
 use async_trait::async_trait;
 use papyrus_base_layer::constants::EventIdentifier;
-use papyrus_base_layer::{BaseLayerContract, L1BlockNumber, L1BlockReference, L1Event};
+use papyrus_base_layer::{BaseLayerContract, EventData, L1BlockNumber, L1BlockReference, L1Event};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::ChainId;
 use starknet_api::executable_transaction::L1HandlerTransaction as ExecutableL1HandlerTransaction;
+use starknet_api::transaction::{TransactionHash, TransactionHasher};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 use tokio::time::sleep;
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 use validator::Validate;
 
+use crate::metrics::{
+    register_scraper_metrics,
+    L1_REORGS_DETECTED,
+    L1_REORG_DEPTH,
+    L1_REORG_ROLLED_BACK_TXS,
+};
+
 #[cfg(test)]
 #[path = "l1_scraper_tests.rs"]
 pub mod l1_scraper_tests;
@@ -37,6 +45,18 @@ pub struct L1Scraper<B: BaseLayerContract> {
     pub last_l1_block_processed: L1BlockReference,
     pub l1_provider_client: SharedL1ProviderClient,
     tracked_event_identifiers: Vec<EventIdentifier>,
+    /// The recently scraped L1 block ranges, oldest first, used to recover from L1 reorgs. The
+    /// last range ends at `last_l1_block_processed`.
+    scraped_ranges: VecDeque<ScrapedRange>,
+}
+
+/// A range of L1 blocks that was scraped in a single scraping round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ScrapedRange {
+    /// The last block of the range.
+    last_block: L1BlockReference,
+    /// The events scraped from the range, to roll back if the range is reorged out of L1.
+    events: Vec<Event>,
 }
 
 impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
@@ -69,26 +89,32 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
             last_l1_block_processed: block_reference_rewind,
             config,
             tracked_event_identifiers: events_identifiers_to_track.to_vec(),
+            scraped_ranges: VecDeque::from([ScrapedRange {
+                last_block: block_reference_rewind,
+                events: vec![],
+            }]),
         })
     }
 
     #[instrument(skip(self), err)]
     pub async fn initialize(&mut self) -> L1ScraperResult<(), B> {
         let (latest_l1_block, events) = self.fetch_events().await?;
+        let scraped_range = ScrapedRange::new(latest_l1_block, &events);
 
         // If this gets too high, send in batches.
         let initialize_result = self.l1_provider_client.initialize(events).await;
         handle_client_error(initialize_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.add_scraped_range(scraped_range);
 
         Ok(())
     }
 
     pub async fn send_events_to_l1_provider(&mut self) -> L1ScraperResult<(), B> {
-        self.assert_no_l1_reorgs().await?;
+        self.handle_l1_reorg().await?;
 
         let (latest_l1_block, events) = self.fetch_events().await?;
+        let scraped_range = ScrapedRange::new(latest_l1_block, &events);
 
         // Sending even if there are no events, to keep the flow as simple/debuggable as possible.
         // Perf hit is minimal, since the scraper is on the same machine as the provider (no net).
@@ -96,7 +122,7 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         let add_events_result = self.l1_provider_client.add_events(events).await;
         handle_client_error(add_events_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.add_scraped_range(scraped_range);
 
         Ok(())
     }
@@ -129,6 +155,24 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         Ok((latest_l1_block, events))
     }
 
+    /// Records a newly scraped range, and forgets the ranges that are too old to be reorged.
+    fn add_scraped_range(&mut self, scraped_range: ScrapedRange) {
+        self.last_l1_block_processed = scraped_range.last_block;
+        self.scraped_ranges.push_back(scraped_range);
+
+        // Keep one range that ends at least `max_l1_reorg_depth` blocks before the last processed
+        // block, to serve as the fork point of the deepest recoverable reorg.
+        let oldest_fork_point =
+            self.last_l1_block_processed.number.saturating_sub(self.config.max_l1_reorg_depth);
+        while self
+            .scraped_ranges
+            .get(1)
+            .is_some_and(|range| range.last_block.number <= oldest_fork_point)
+        {
+            self.scraped_ranges.pop_front();
+        }
+    }
+
     #[instrument(skip(self), err)]
     async fn run(&mut self) -> L1ScraperResult<(), B> {
         self.initialize().await?;
@@ -148,49 +192,106 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
                     Err(hash_calc_err) => Err(L1ScraperError::HashCalculationError(hash_calc_err)),
                 }
             }
-            L1Event::MessageToL2CancellationStarted(_messsage_data) => todo!(),
-            L1Event::MessageToL2Canceled(_messsage_data) => todo!(),
-            L1Event::ConsumedMessageToL2(_messsage_data) => todo!(),
+            L1Event::MessageToL2CancellationStarted {
+                cancelled_message,
+                cancellation_request_timestamp,
+            } => Ok(Event::TransactionCancellationStarted {
+                tx_hash: self.l1_handler_tx_hash(cancelled_message)?,
+                cancellation_request_timestamp,
+            }),
+            L1Event::MessageToL2Canceled(message) => {
+                Ok(Event::TransactionCanceled(self.l1_handler_tx_hash(message)?))
+            }
+            L1Event::ConsumedMessageToL2(message) => {
+                Ok(Event::TransactionConsumed(self.l1_handler_tx_hash(message)?))
+            }
         }
     }
 
-    async fn assert_no_l1_reorgs(&self) -> L1ScraperResult<(), B> {
-        let last_processed_l1_block_number = self.last_l1_block_processed.number;
-        let last_block_processed_fresh = self
-            .base_layer
-            .l1_block_at(last_processed_l1_block_number)
-            .await
-            .map_err(L1ScraperError::BaseLayerError)?;
+    /// Returns the hash of the L1 handler transaction that executes the given message on L2.
+    fn l1_handler_tx_hash(&self, message: EventData) -> L1ScraperResult<TransactionHash, B> {
+        let tx = message.into_l1_handler_tx();
+        tx.calculate_transaction_hash(&self.config.chain_id, &tx.version)
+            .map_err(L1ScraperError::HashCalculationError)
+    }
 
-        let Some(last_block_processed_fresh) = last_block_processed_fresh else {
-            return Err(L1ScraperError::L1ReorgDetected {
-                reason: format!(
-                    "Last processed L1 block with number {last_processed_l1_block_number} no \
-                     longer exists."
-                ),
-            });
-        };
+    /// Checks whether the last processed L1 block was reorged out of L1. If so, the transactions
+    /// scraped after the fork point are rolled back in the L1 provider, and scraping resumes from
+    /// the fork point. The fork point is the end of the latest scraped range that is still on L1.
+    async fn handle_l1_reorg(&mut self) -> L1ScraperResult<(), B> {
+        if self.is_on_l1(self.last_l1_block_processed).await? {
+            return Ok(());
+        }
+        L1_REORGS_DETECTED.increment(1);
 
-        if last_block_processed_fresh.hash != self.last_l1_block_processed.hash {
+        let mut fork_point_index = None;
+        for (index, range) in self.scraped_ranges.iter().enumerate().rev().skip(1) {
+            if self.is_on_l1(range.last_block).await? {
+                fork_point_index = Some(index);
+                break;
+            }
+        }
+        let Some(fork_point_index) = fork_point_index else {
             return Err(L1ScraperError::L1ReorgDetected {
                 reason: format!(
-                    "Last processed L1 block hash, {}, for block number {}, is different from the \
-                     hash stored, {}",
-                    hex::encode(last_block_processed_fresh.hash),
-                    last_processed_l1_block_number,
+                    "Last processed L1 block with number {} and hash {} no longer exists, and the \
+                     reorg is deeper than the maximal L1 reorg depth, {}",
+                    self.last_l1_block_processed.number,
                     hex::encode(self.last_l1_block_processed.hash),
+                    self.config.max_l1_reorg_depth
                 ),
             });
-        }
+        };
 
+        let rolled_back_events: Vec<_> = self
+            .scraped_ranges
+            .drain(fork_point_index + 1..)
+            .flat_map(|range| range.events)
+            .collect();
+        let n_rolled_back_txs = rolled_back_events
+            .iter()
+            .filter(|event| matches!(event, Event::L1HandlerTransaction(_)))
+            .count();
+        let fork_point = self.scraped_ranges[fork_point_index].last_block;
+        let reorg_depth = self.last_l1_block_processed.number - fork_point.number;
+        warn!(
+            "L1 reorg detected: rescraping {reorg_depth} L1 blocks from L1 block {}, and rolling \
+             back {} L1 events, of which {n_rolled_back_txs} are L1 handler transactions.",
+            fork_point.number,
+            rolled_back_events.len()
+        );
+        L1_REORG_DEPTH.set_lossy(reorg_depth);
+        L1_REORG_ROLLED_BACK_TXS.increment(n_rolled_back_txs.try_into().unwrap());
+
+        let rollback_result = self.l1_provider_client.rollback_events(rolled_back_events).await;
+        handle_client_error(rollback_result)?;
+
+        self.last_l1_block_processed = fork_point;
         Ok(())
     }
+
+    /// Returns whether the given block is still on L1, i.e., it wasn't reorged out.
+    async fn is_on_l1(&self, block: L1BlockReference) -> L1ScraperResult<bool, B> {
+        let block_on_l1 = self
+            .base_layer
+            .l1_block_at(block.number)
+            .await
+            .map_err(L1ScraperError::BaseLayerError)?;
+        Ok(block_on_l1.is_some_and(|block_on_l1| block_on_l1.hash == block.hash))
+    }
+}
+
+impl ScrapedRange {
+    fn new(last_block: L1BlockReference, events: &[Event]) -> Self {
+        Self { last_block, events: events.to_vec() }
+    }
 }
 
 #[async_trait]
 impl<B: BaseLayerContract + Send + Sync> ComponentStarter for L1Scraper<B> {
     async fn start(&mut self) {
         info!("Starting component {}.", type_name::<Self>());
+        register_scraper_metrics();
         self.run().await.unwrap_or_else(|e| panic!("Failed to start L1Scraper component: {}", e))
     }
 }
@@ -202,6 +303,7 @@ pub struct L1ScraperConfig {
     #[validate(custom = "validate_ascii")]
     pub chain_id: ChainId,
     pub finality: u64,
+    pub max_l1_reorg_depth: u64,
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
     pub polling_interval: Duration,
 }
@@ -212,6 +314,7 @@ impl Default for L1ScraperConfig {
             startup_rewind_time: Duration::from_secs(0),
             chain_id: ChainId::Mainnet,
             finality: 0,
+            max_l1_reorg_depth: 64,
             polling_interval: Duration::from_secs(1),
         }
     }
@@ -232,6 +335,13 @@ impl SerializeConfig for L1ScraperConfig {
                 "Number of blocks to wait for finality",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_l1_reorg_depth",
+                &self.max_l1_reorg_depth,
+                "Maximal depth, in L1 blocks, of an L1 reorg that the scraper recovers from. Deeper \
+                 reorgs stop the scraper.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "polling_interval",
                 &self.polling_interval.as_secs(),
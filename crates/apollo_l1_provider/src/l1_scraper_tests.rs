@@ -2,133 +2,92 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use alloy::primitives::U256;
 use apollo_infra::trace_util::configure_tracing;
 use apollo_l1_provider_types::{Event, L1ProviderClient};
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_state_sync_types::state_sync_types::SyncBlock;
+use assert_matches::assert_matches;
 use itertools::Itertools;
-use mempool_test_utils::starknet_api_test_utils::DEFAULT_ANVIL_L1_ACCOUNT_ADDRESS;
-use papyrus_base_layer::ethereum_base_layer_contract::{
-    EthereumBaseLayerConfig,
-    EthereumBaseLayerContract,
-    Starknet,
-};
-use papyrus_base_layer::test_utils::{
-    anvil_instance_from_config,
-    ethereum_base_layer_config_for_anvil,
+use papyrus_base_layer::fake_base_layer_contract::{
+    FakeBaseLayerContract,
+    FAKE_L1_BLOCK_TIME_SECONDS,
 };
+use papyrus_base_layer::{EventData, L1Event};
 use starknet_api::block::BlockNumber;
-use starknet_api::contract_address;
-use starknet_api::core::{EntryPointSelector, Nonce};
+use starknet_api::core::{ChainId, EntryPointSelector, EthAddress, Nonce};
 use starknet_api::executable_transaction::L1HandlerTransaction as ExecutableL1HandlerTransaction;
-use starknet_api::hash::StarkHash;
-use starknet_api::transaction::fields::{Calldata, Fee};
-use starknet_api::transaction::{L1HandlerTransaction, TransactionHasher, TransactionVersion};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::{calldata, contract_address, felt};
 
 use crate::bootstrapper::Bootstrapper;
 use crate::l1_provider::create_l1_provider;
-use crate::l1_scraper::{L1Scraper, L1ScraperConfig};
+use crate::l1_scraper::{L1Scraper, L1ScraperConfig, L1ScraperError};
 use crate::test_utils::FakeL1ProviderClient;
 use crate::{event_identifiers_to_track, L1ProviderConfig};
 
-pub fn in_ci() -> bool {
-    std::env::var("CI").is_ok()
-}
-
 const fn height_add(block_number: BlockNumber, k: u64) -> BlockNumber {
     BlockNumber(block_number.0 + k)
 }
 
-// TODO(Gilad): Replace EthereumBaseLayerContract with a mock that has a provider initialized with
-// `with_recommended_fillers`, in order to be able to create txs from non-default users.
-async fn scraper(
-    base_layer_config: EthereumBaseLayerConfig,
-) -> (L1Scraper<EthereumBaseLayerContract>, Arc<FakeL1ProviderClient>) {
+async fn scraper_with_fake_base_layer(
+    base_layer: &FakeBaseLayerContract,
+    config: L1ScraperConfig,
+) -> (L1Scraper<FakeBaseLayerContract>, Arc<FakeL1ProviderClient>) {
     let fake_client = Arc::new(FakeL1ProviderClient::default());
-    let base_layer = EthereumBaseLayerContract::new(base_layer_config);
-
-    // Deploy a fresh Starknet contract on Anvil from the bytecode in the JSON file.
-    Starknet::deploy(base_layer.contract.provider().clone()).await.unwrap();
-
     let scraper = L1Scraper::new(
-        L1ScraperConfig::default(),
+        config,
         fake_client.clone(),
-        base_layer,
+        base_layer.clone(),
         event_identifiers_to_track(),
     )
     .await
     .unwrap();
-
     (scraper, fake_client)
 }
 
+fn message(nonce: u8) -> EventData {
+    EventData { nonce: Nonce(nonce.into()), ..Default::default() }
+}
+
+/// Returns the event of sending a message to L2, and the L1 handler transaction it's scraped into.
+fn message_to_l2(nonce: u8) -> (L1Event, ExecutableL1HandlerTransaction) {
+    let tx = message(nonce).into_l1_handler_tx();
+    let fee = Fee(1);
+    let executable_tx =
+        ExecutableL1HandlerTransaction::create(tx.clone(), &ChainId::Mainnet, fee).unwrap();
+    (L1Event::LogMessageToL2 { tx, fee }, executable_tx)
+}
+
 #[tokio::test]
-// TODO(Gilad): extract setup stuff into test helpers once more tests are added and patterns emerge.
 async fn txs_happy_flow() {
-    if !in_ci() {
-        return;
-    }
-
-    let base_layer_config = ethereum_base_layer_config_for_anvil(None);
-    let _anvil = anvil_instance_from_config(&base_layer_config);
-    // Setup.
-    let (mut scraper, fake_client) = scraper(base_layer_config).await;
+    let base_layer = FakeBaseLayerContract::new();
+    let (mut scraper, fake_client) =
+        scraper_with_fake_base_layer(&base_layer, L1ScraperConfig::default()).await;
 
-    // Test.
     // Scrape multiple events.
-    let l2_contract_address = "0x12";
-    let l2_entry_point = "0x34";
-
-    let message_to_l2_0 = scraper.base_layer.contract.sendMessageToL2(
-        l2_contract_address.parse().unwrap(),
-        l2_entry_point.parse().unwrap(),
-        vec![U256::from(1_u8), U256::from(2_u8)],
-    );
-    let message_to_l2_1 = scraper.base_layer.contract.sendMessageToL2(
-        l2_contract_address.parse().unwrap(),
-        l2_entry_point.parse().unwrap(),
-        vec![U256::from(3_u8), U256::from(4_u8)],
-    );
-
-    // Send the transactions.
-    for msg in &[message_to_l2_0, message_to_l2_1] {
-        msg.send().await.unwrap().get_receipt().await.unwrap();
-    }
-
-    const EXPECTED_VERSION: TransactionVersion = TransactionVersion(StarkHash::ZERO);
-    let expected_internal_l1_tx = L1HandlerTransaction {
-        version: EXPECTED_VERSION,
-        nonce: Nonce(StarkHash::ZERO),
-        contract_address: contract_address!(l2_contract_address),
-        entry_point_selector: EntryPointSelector(StarkHash::from_hex_unchecked(l2_entry_point)),
-        calldata: Calldata(
-            vec![DEFAULT_ANVIL_L1_ACCOUNT_ADDRESS, StarkHash::ONE, StarkHash::from(2)].into(),
-        ),
-    };
-    let tx = ExecutableL1HandlerTransaction {
-        tx_hash: expected_internal_l1_tx
-            .calculate_transaction_hash(&scraper.config.chain_id, &EXPECTED_VERSION)
-            .unwrap(),
-        tx: expected_internal_l1_tx,
-        paid_fee_on_l1: Fee(0),
+    let message_with_payload = |payload| EventData {
+        from_address: EthAddress::try_from(felt!("0x56")).unwrap(),
+        to_address: contract_address!("0x12"),
+        entry_point_selector: EntryPointSelector(felt!("0x34")),
+        payload,
+        ..Default::default()
     };
-    let first_expected_log = Event::L1HandlerTransaction(tx.clone());
-
-    let expected_internal_l1_tx_2 = L1HandlerTransaction {
-        nonce: Nonce(StarkHash::ONE),
-        calldata: Calldata(
-            vec![DEFAULT_ANVIL_L1_ACCOUNT_ADDRESS, StarkHash::from(3), StarkHash::from(4)].into(),
-        ),
-        ..tx.tx
+    base_layer
+        .send_message_to_l2(message_with_payload(calldata![felt!(1_u8), felt!(2_u8)]), Fee(0));
+    base_layer.mine_block();
+    base_layer
+        .send_message_to_l2(message_with_payload(calldata![felt!(3_u8), felt!(4_u8)]), Fee(0));
+    base_layer.mine_block();
+
+    let expected_event = |nonce: u8, payload| {
+        let tx = EventData { nonce: Nonce(nonce.into()), ..message_with_payload(payload) }
+            .into_l1_handler_tx();
+        Event::L1HandlerTransaction(
+            ExecutableL1HandlerTransaction::create(tx, &scraper.config.chain_id, Fee(0)).unwrap(),
+        )
     };
-    let second_expected_log = Event::L1HandlerTransaction(ExecutableL1HandlerTransaction {
-        tx_hash: expected_internal_l1_tx_2
-            .calculate_transaction_hash(&scraper.config.chain_id, &EXPECTED_VERSION)
-            .unwrap(),
-        tx: expected_internal_l1_tx_2,
-        ..tx
-    });
+    let first_expected_log = expected_event(0, calldata![felt!(1_u8), felt!(2_u8)]);
+    let second_expected_log = expected_event(1, calldata![felt!(3_u8), felt!(4_u8)]);
 
     // Assert.
     scraper.send_events_to_l1_provider().await.unwrap();
@@ -139,6 +98,140 @@ async fn txs_happy_flow() {
     fake_client.assert_add_events_received_with(&[]);
 }
 
+#[tokio::test]
+async fn l1_reorg_rolls_back_and_rescrapes_events() {
+    let chain = FakeBaseLayerContract::new();
+    let (mut scraper, fake_client) =
+        scraper_with_fake_base_layer(&chain, L1ScraperConfig::default()).await;
+    let [(event_0, tx_0), (event_1, tx_1), (event_2, tx_2), (event_3, tx_3)] =
+        [0, 1, 2, 3].map(message_to_l2);
+
+    chain.add_block(vec![event_0]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    chain.add_block(vec![event_1]);
+    chain.add_block(vec![event_2]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_add_events_received_with(&[
+        Event::L1HandlerTransaction(tx_0.clone()),
+        Event::L1HandlerTransaction(tx_1.clone()),
+        Event::L1HandlerTransaction(tx_2.clone()),
+    ]);
+
+    // Reorg out block 3, which was scraped along with block 2; both blocks are rescraped.
+    chain.reorg(2);
+    chain.add_block(vec![event_3]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_rollback_events_received_with(&[
+        Event::L1HandlerTransaction(tx_1.clone()),
+        Event::L1HandlerTransaction(tx_2),
+    ]);
+    fake_client.assert_add_events_received_with(&[
+        Event::L1HandlerTransaction(tx_1),
+        Event::L1HandlerTransaction(tx_3),
+    ]);
+    assert_eq!(scraper.last_l1_block_processed.number, 3);
+
+    // No reorg.
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_rollback_events_received_with(&[]);
+    fake_client.assert_add_events_received_with(&[]);
+}
+
+#[tokio::test]
+async fn scrape_message_cancellation_and_consumption() {
+    let chain = FakeBaseLayerContract::new();
+    let (mut scraper, fake_client) =
+        scraper_with_fake_base_layer(&chain, L1ScraperConfig::default()).await;
+    let (event_0, tx_0) = message_to_l2(0);
+    let (_, tx_1) = message_to_l2(1);
+
+    chain.add_block(vec![
+        event_0,
+        L1Event::MessageToL2CancellationStarted {
+            cancelled_message: message(0),
+            cancellation_request_timestamp: 0,
+        },
+        L1Event::MessageToL2Canceled(message(0)),
+        L1Event::ConsumedMessageToL2(message(1)),
+    ]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_add_events_received_with(&[
+        Event::L1HandlerTransaction(tx_0.clone()),
+        Event::TransactionCancellationStarted {
+            tx_hash: tx_0.tx_hash,
+            // Set to the timestamp of the block of the request.
+            cancellation_request_timestamp: FAKE_L1_BLOCK_TIME_SECONDS,
+        },
+        Event::TransactionCanceled(tx_0.tx_hash),
+        Event::TransactionConsumed(tx_1.tx_hash),
+    ]);
+}
+
+#[tokio::test]
+async fn l1_reorg_rolls_back_cancellation_and_consumption() {
+    let chain = FakeBaseLayerContract::new();
+    let (mut scraper, fake_client) =
+        scraper_with_fake_base_layer(&chain, L1ScraperConfig::default()).await;
+    let (event_0, tx_0) = message_to_l2(0);
+    let (_, tx_1) = message_to_l2(1);
+
+    chain.add_block(vec![event_0]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_add_events_received_with(&[Event::L1HandlerTransaction(tx_0.clone())]);
+    chain.add_block(vec![
+        L1Event::MessageToL2CancellationStarted {
+            cancelled_message: message(0),
+            cancellation_request_timestamp: 0,
+        },
+        L1Event::MessageToL2Canceled(message(0)),
+        L1Event::ConsumedMessageToL2(message(1)),
+    ]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    let reorged_out_events = [
+        Event::TransactionCancellationStarted {
+            tx_hash: tx_0.tx_hash,
+            cancellation_request_timestamp: 2 * FAKE_L1_BLOCK_TIME_SECONDS,
+        },
+        Event::TransactionCanceled(tx_0.tx_hash),
+        Event::TransactionConsumed(tx_1.tx_hash),
+    ];
+    fake_client.assert_add_events_received_with(&reorged_out_events);
+
+    // Reorg out the block of the cancellation and the consumption.
+    chain.reorg(1);
+    chain.add_block(vec![]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_rollback_events_received_with(&reorged_out_events);
+    fake_client.assert_add_events_received_with(&[]);
+}
+
+#[tokio::test]
+async fn l1_reorg_deeper_than_max_depth() {
+    let chain = FakeBaseLayerContract::new();
+    let config = L1ScraperConfig { max_l1_reorg_depth: 1, ..Default::default() };
+    let (mut scraper, fake_client) = scraper_with_fake_base_layer(&chain, config).await;
+
+    for _ in 0..3 {
+        chain.add_block(vec![]);
+        scraper.send_events_to_l1_provider().await.unwrap();
+    }
+
+    // A reorg of depth 1 is recovered from.
+    chain.reorg(2);
+    chain.add_block(vec![]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    fake_client.assert_rollback_events_received_with(&[]);
+
+    // A reorg of depth 2 isn't.
+    chain.reorg(1);
+    chain.add_block(vec![]);
+    chain.add_block(vec![]);
+    assert_matches!(
+        scraper.send_events_to_l1_provider().await,
+        Err(L1ScraperError::L1ReorgDetected { .. })
+    );
+}
+
 // TODO(Gilad): figure out how To setup anvil on a specific L1 block (through genesis.json?) and
 // with a specified L2 block logged to L1 (hopefully without having to use real backup).
 /// This test simulates a bootstrapping flow, in which 3 blocks are synced from L2, during which two
@@ -147,9 +240,6 @@ async fn txs_happy_flow() {
 /// blocks are processed, after which they are processed in order.
 #[tokio::test]
 async fn bootstrap_e2e() {
-    if !in_ci() {
-        return;
-    }
     configure_tracing().await;
 
     // Setup.
@@ -264,9 +354,6 @@ async fn bootstrap_e2e() {
 
 #[tokio::test]
 async fn bootstrap_delayed_sync_state_with_trivial_catch_up() {
-    if !in_ci() {
-        return;
-    }
     configure_tracing().await;
 
     // Setup.
@@ -337,9 +424,6 @@ async fn bootstrap_delayed_sync_state_with_trivial_catch_up() {
 
 #[tokio::test]
 async fn bootstrap_delayed_sync_state_with_sync_behind_batcher() {
-    if !in_ci() {
-        return;
-    }
     configure_tracing().await;
 
     // Setup.
@@ -4,6 +4,7 @@ use apollo_config::converters::deserialize_float_seconds_to_duration;
 pub mod communication;
 pub mod l1_provider;
 pub mod l1_scraper;
+pub mod metrics;
 pub mod soft_delete_index_map;
 pub mod transaction_manager;
 
@@ -18,7 +19,7 @@ use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_l1_provider_types::SessionState;
 use papyrus_base_layer::constants::{
     EventIdentifier,
-    CONSUMED_MESSAGE_TO_L1_EVENT_IDENTIFIER,
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
     LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
     MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
     MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
@@ -88,7 +89,7 @@ impl std::fmt::Display for ProviderState {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Validate, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Validate, PartialEq, Eq)]
 pub struct L1ProviderConfig {
     /// In most cases this can remain None: the provider defaults to using the
     /// LastStateUpdate height at the L1 Height that the L1Scraper is initialized on.
@@ -100,16 +101,45 @@ pub struct L1ProviderConfig {
     pub bootstrap_catch_up_height_override: Option<BlockNumber>,
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
     pub startup_sync_sleep_retry_interval: Duration,
+    /// The time after the cancellation of an L1 handler transaction is requested on L1, during
+    /// which the transaction is still proposed and validated. Should be lower than the L1 message
+    /// cancellation delay of the Starknet contract, with a margin for the time it takes a block to
+    /// be proved on L1.
+    #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
+    pub l1_handler_cancellation_grace_period: Duration,
+}
+
+impl Default for L1ProviderConfig {
+    fn default() -> Self {
+        Self {
+            provider_startup_height_override: None,
+            bootstrap_catch_up_height_override: None,
+            startup_sync_sleep_retry_interval: Duration::ZERO,
+            // The message cancellation delay of the Starknet contract on mainnet is 5 days, and
+            // a day is left for proving the block.
+            l1_handler_cancellation_grace_period: Duration::from_secs(4 * 24 * 60 * 60),
+        }
+    }
 }
 
 impl SerializeConfig for L1ProviderConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let mut dump = BTreeMap::from([ser_param(
-            "startup_sync_sleep_retry_interval",
-            &self.startup_sync_sleep_retry_interval.as_secs_f64(),
-            "Interval in seconds between each retry of syncing with L2 during startup.",
-            ParamPrivacyInput::Public,
-        )]);
+        let mut dump = BTreeMap::from([
+            ser_param(
+                "startup_sync_sleep_retry_interval",
+                &self.startup_sync_sleep_retry_interval.as_secs_f64(),
+                "Interval in seconds between each retry of syncing with L2 during startup.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "l1_handler_cancellation_grace_period",
+                &self.l1_handler_cancellation_grace_period.as_secs_f64(),
+                "Time in seconds after the cancellation of an L1 handler transaction is requested \
+                 on L1, during which the transaction is still proposed and validated. Should be \
+                 lower than the L1 message cancellation delay of the Starknet contract.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
 
         dump.extend(ser_optional_param(
             &self.provider_startup_height_override,
@@ -132,7 +162,7 @@ impl SerializeConfig for L1ProviderConfig {
 pub const fn event_identifiers_to_track() -> &'static [EventIdentifier] {
     &[
         LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
-        CONSUMED_MESSAGE_TO_L1_EVENT_IDENTIFIER,
+        CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
         MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
         MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
     ]
@@ -0,0 +1,16 @@
+use apollo_metrics::define_metrics;
+use apollo_metrics::metrics::{MetricCounter, MetricGauge};
+
+define_metrics!(
+    L1Provider => {
+        MetricCounter { L1_REORGS_DETECTED, "l1_provider_l1_reorgs_detected", "The number of L1 reorgs detected by the L1 scraper", init = 0 },
+        MetricCounter { L1_REORG_ROLLED_BACK_TXS, "l1_provider_l1_reorg_rolled_back_txs", "The number of scraped L1 handler transactions rolled back due to L1 reorgs", init = 0 },
+        MetricGauge { L1_REORG_DEPTH, "l1_provider_l1_reorg_depth", "The number of L1 blocks rescraped due to the last L1 reorg" },
+    },
+);
+
+pub(crate) fn register_scraper_metrics() {
+    L1_REORGS_DETECTED.register();
+    L1_REORG_ROLLED_BACK_TXS.register();
+    L1_REORG_DEPTH.register();
+}
@@ -38,7 +38,16 @@ impl SoftDeleteIndexMap {
 
     /// Soft delete and return a reference to the first unstaged transaction, by insertion order.
     pub fn soft_pop_front(&mut self) -> Option<&L1HandlerTransaction> {
-        let entry = self.txs.iter().find(|(_, tx)| tx.is_available());
+        self.soft_pop_front_filtered(|_| true)
+    }
+
+    /// Soft delete and return a reference to the first unstaged transaction that matches the
+    /// filter, by insertion order.
+    pub fn soft_pop_front_filtered(
+        &mut self,
+        filter: impl Fn(&TransactionHash) -> bool,
+    ) -> Option<&L1HandlerTransaction> {
+        let entry = self.txs.iter().find(|(tx_hash, tx)| tx.is_available() && filter(tx_hash));
         let (&tx_hash, _) = entry?;
         self.soft_remove(tx_hash)
     }
@@ -83,6 +92,13 @@ impl SoftDeleteIndexMap {
         committed.into_iter().map(|(_, entry)| entry.transaction).collect()
     }
 
+    /// Removes the transaction with the given hash entirely, whether it's staged or not, and
+    /// returns it.
+    pub fn remove(&mut self, tx_hash: TransactionHash) -> Option<L1HandlerTransaction> {
+        self.staged_txs.remove(&tx_hash);
+        self.txs.shift_remove(&tx_hash).map(|entry| entry.transaction)
+    }
+
     /// Rolls back all staged transactions, converting them to unstaged.
     pub fn rollback_staging(&mut self) {
         for tx_hash in self.staged_txs.drain() {
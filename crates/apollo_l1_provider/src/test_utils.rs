@@ -4,6 +4,7 @@ use std::sync::Mutex;
 
 use apollo_l1_provider_types::{
     Event,
+    L1HandlerStatus,
     L1ProviderClient,
     L1ProviderClientResult,
     SessionState,
@@ -145,6 +146,7 @@ impl From<TransactionManagerContent> for TransactionManager {
         TransactionManager {
             txs: SoftDeleteIndexMap::from(txs),
             committed: content.committed.unwrap_or_default(),
+            ..Default::default()
         }
     }
 }
@@ -187,6 +189,7 @@ pub struct FakeL1ProviderClient {
     // immutable.
     pub events_received: Mutex<Vec<Event>>,
     pub commit_blocks_received: Mutex<Vec<CommitBlockBacklog>>,
+    pub rolled_back_events_received: Mutex<Vec<Event>>,
 }
 
 impl FakeL1ProviderClient {
@@ -205,6 +208,13 @@ impl FakeL1ProviderClient {
         let events_received = mem::take(&mut *self.events_received.lock().unwrap());
         assert_eq!(events_received, expected);
     }
+
+    #[track_caller]
+    pub fn assert_rollback_events_received_with(&self, expected: &[Event]) {
+        let rolled_back_events_received =
+            mem::take(&mut *self.rolled_back_events_received.lock().unwrap());
+        assert_eq!(rolled_back_events_received, expected);
+    }
 }
 
 #[async_trait]
@@ -230,6 +240,13 @@ impl L1ProviderClient for FakeL1ProviderClient {
         Ok(())
     }
 
+    async fn get_l1_handler_status(
+        &self,
+        _tx_hash: TransactionHash,
+    ) -> L1ProviderClientResult<L1HandlerStatus> {
+        todo!()
+    }
+
     async fn commit_block(
         &self,
         l1_handler_tx_hashes: Vec<TransactionHash>,
@@ -253,4 +270,9 @@ impl L1ProviderClient for FakeL1ProviderClient {
     async fn initialize(&self, _events: Vec<Event>) -> L1ProviderClientResult<()> {
         todo!()
     }
+
+    async fn rollback_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        self.rolled_back_events_received.lock().unwrap().extend(events);
+        Ok(())
+    }
 }
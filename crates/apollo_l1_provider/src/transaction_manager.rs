@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
-use apollo_l1_provider_types::{InvalidValidationStatus, ValidationStatus};
+use apollo_l1_provider_types::{InvalidValidationStatus, L1HandlerStatus, ValidationStatus};
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
 
@@ -10,18 +11,35 @@ use crate::soft_delete_index_map::SoftDeleteIndexMap;
 pub struct TransactionManager {
     pub txs: SoftDeleteIndexMap,
     pub committed: HashSet<TransactionHash>,
+    /// The L1 timestamps of the cancellation requests of uncommitted transactions.
+    pub cancellation_requests: HashMap<TransactionHash, u64>,
+    pub canceled: HashMap<TransactionHash, CanceledTx>,
+    pub consumed: HashSet<TransactionHash>,
+    /// The time after a cancellation request during which the transaction is still proposed.
+    pub cancellation_grace_period: Duration,
 }
 
 impl TransactionManager {
+    pub fn new(cancellation_grace_period: Duration) -> Self {
+        Self { cancellation_grace_period, ..Default::default() }
+    }
+
     pub fn start_block(&mut self) {
         self.txs.rollback_staging();
     }
 
-    pub fn get_txs(&mut self, n_txs: usize) -> Vec<L1HandlerTransaction> {
+    /// Returns up to `n_txs` transactions, skipping the transactions whose cancellation was
+    /// requested more than the grace period before `now` (a unix timestamp in seconds).
+    pub fn get_txs(&mut self, n_txs: usize, now: u64) -> Vec<L1HandlerTransaction> {
         let mut txs = Vec::with_capacity(n_txs);
+        let cancellation_requests = &self.cancellation_requests;
+        let grace_period = self.cancellation_grace_period;
+        let is_proposable = |tx_hash: &TransactionHash| {
+            !is_cancellation_grace_period_over(cancellation_requests, grace_period, tx_hash, now)
+        };
 
         for _ in 0..n_txs {
-            match self.txs.soft_pop_front().cloned() {
+            match self.txs.soft_pop_front_filtered(is_proposable).cloned() {
                 Some(tx) => txs.push(tx),
                 None => break,
             }
@@ -29,11 +47,22 @@ impl TransactionManager {
         txs
     }
 
-    pub fn validate_tx(&mut self, tx_hash: TransactionHash) -> ValidationStatus {
+    /// Validates a transaction of a proposal, rejecting the transactions that `get_txs` wouldn't
+    /// propose at `now` (a unix timestamp in seconds) due to their cancellation.
+    pub fn validate_tx(&mut self, tx_hash: TransactionHash, now: u64) -> ValidationStatus {
         if self.committed.contains(&tx_hash) {
             return ValidationStatus::Invalid(InvalidValidationStatus::AlreadyIncludedOnL2);
         }
 
+        if is_cancellation_grace_period_over(
+            &self.cancellation_requests,
+            self.cancellation_grace_period,
+            &tx_hash,
+            now,
+        ) {
+            return ValidationStatus::Invalid(InvalidValidationStatus::CancellationRequestedOnL1);
+        }
+
         if self.txs.soft_remove(tx_hash).is_some() {
             ValidationStatus::Validated
         } else if self.txs.is_staged(&tx_hash) {
@@ -46,6 +75,9 @@ impl TransactionManager {
     pub fn commit_txs(&mut self, committed_txs: &[TransactionHash]) {
         // Committed L1 transactions are dropped here, do we need to them for anything?
         self.txs.commit(committed_txs);
+        for tx_hash in committed_txs {
+            self.cancellation_requests.remove(tx_hash);
+        }
         // Add all committed tx hashes to the committed buffer, regardless of if they're known or
         // not, in case we haven't scraped them yet and another node did.
         self.committed.extend(committed_txs)
@@ -54,10 +86,127 @@ impl TransactionManager {
     /// Adds a transaction to the transaction manager, return false iff the transaction already
     /// existed.
     pub fn add_tx(&mut self, tx: L1HandlerTransaction) -> bool {
-        self.committed.contains(&tx.tx_hash) || self.txs.insert(tx)
+        self.committed.contains(&tx.tx_hash)
+            || self.canceled.contains_key(&tx.tx_hash)
+            || self.txs.insert(tx)
+    }
+
+    /// Records a request to cancel the given transaction, made at the given L1 timestamp. A
+    /// repeated request restarts the cancellation, as on L1. Returns false iff the transaction is
+    /// already committed or canceled.
+    pub fn request_cancellation(&mut self, tx_hash: TransactionHash, timestamp: u64) -> bool {
+        if self.committed.contains(&tx_hash) || self.canceled.contains_key(&tx_hash) {
+            return false;
+        }
+        self.cancellation_requests.insert(tx_hash, timestamp);
+        true
+    }
+
+    /// Removes a transaction that was canceled on L1. Returns false iff the transaction is already
+    /// committed, i.e., it's canceled on L1 after it was executed on L2.
+    pub fn cancel_tx(&mut self, tx_hash: TransactionHash) -> bool {
+        let cancellation_request_timestamp = self.cancellation_requests.remove(&tx_hash);
+        if self.committed.contains(&tx_hash) {
+            return false;
+        }
+        let tx = self.txs.remove(tx_hash);
+        self.canceled.insert(tx_hash, CanceledTx { tx, cancellation_request_timestamp });
+        true
+    }
+
+    /// Marks a transaction as consumed on L1, which implies that it's committed on L2. Unlike
+    /// committing a block, this doesn't roll back the transactions staged in the current proposal.
+    pub fn consume_tx(&mut self, tx_hash: TransactionHash) {
+        self.txs.remove(tx_hash);
+        self.cancellation_requests.remove(&tx_hash);
+        self.committed.insert(tx_hash);
+        self.consumed.insert(tx_hash);
+    }
+
+    pub fn l1_handler_status(&self, tx_hash: TransactionHash) -> L1HandlerStatus {
+        if self.consumed.contains(&tx_hash) {
+            L1HandlerStatus::ConsumedOnL1
+        } else if self.committed.contains(&tx_hash) {
+            L1HandlerStatus::CommittedOnL2
+        } else if self.canceled.contains_key(&tx_hash) {
+            L1HandlerStatus::CanceledOnL1
+        } else if let Some(&cancellation_request_timestamp) =
+            self.cancellation_requests.get(&tx_hash)
+        {
+            L1HandlerStatus::CancellationRequested { cancellation_request_timestamp }
+        } else if self.txs.txs.contains_key(&tx_hash) {
+            L1HandlerStatus::Pending
+        } else {
+            L1HandlerStatus::Unknown
+        }
+    }
+
+    /// Removes the given transaction, unless it's already committed. Returns false iff the
+    /// transaction is already committed, and thus can't be removed.
+    pub fn rollback_tx(&mut self, tx_hash: TransactionHash) -> bool {
+        if self.committed.contains(&tx_hash) {
+            return false;
+        }
+        self.txs.remove(tx_hash);
+        self.cancellation_requests.remove(&tx_hash);
+        true
+    }
+
+    /// Undoes the cancellation request of the given transaction that was made at the given L1
+    /// timestamp. An earlier request of the same transaction, which this request restarted, isn't
+    /// restored.
+    pub fn rollback_cancellation_request(&mut self, tx_hash: TransactionHash, timestamp: u64) {
+        if self.cancellation_requests.get(&tx_hash) == Some(&timestamp) {
+            self.cancellation_requests.remove(&tx_hash);
+        }
+    }
+
+    /// Undoes the cancellation of the given transaction, restoring it along with its cancellation
+    /// request.
+    pub fn rollback_cancellation(&mut self, tx_hash: TransactionHash) {
+        let Some(CanceledTx { tx, cancellation_request_timestamp }) =
+            self.canceled.remove(&tx_hash)
+        else {
+            return;
+        };
+        if let Some(timestamp) = cancellation_request_timestamp {
+            self.cancellation_requests.insert(tx_hash, timestamp);
+        }
+        if let Some(tx) = tx {
+            self.txs.insert(tx);
+        }
+    }
+
+    /// Undoes the consumption of the given transaction. The transaction stays committed, since the
+    /// consumption on L1 implies that it was committed on L2, regardless of L1 reorgs.
+    pub fn rollback_consumption(&mut self, tx_hash: TransactionHash) {
+        self.consumed.remove(&tx_hash);
     }
 
     pub fn committed_includes(&self, tx_hashes: &[TransactionHash]) -> bool {
         tx_hashes.iter().all(|tx| self.committed.contains(tx))
     }
 }
+
+/// A transaction that was canceled on L1, kept in order to restore it if the cancellation is
+/// reorged out of L1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanceledTx {
+    /// The transaction, if it was scraped before it was canceled.
+    pub tx: Option<L1HandlerTransaction>,
+    /// The L1 timestamp of the cancellation request, if it was scraped.
+    pub cancellation_request_timestamp: Option<u64>,
+}
+
+// Returns true iff the cancellation of the transaction was requested on L1 more than the grace
+// period before `now`.
+fn is_cancellation_grace_period_over(
+    cancellation_requests: &HashMap<TransactionHash, u64>,
+    grace_period: Duration,
+    tx_hash: &TransactionHash,
+    now: u64,
+) -> bool {
+    cancellation_requests
+        .get(tx_hash)
+        .is_some_and(|&requested_at| requested_at.saturating_add(grace_period.as_secs()) <= now)
+}
@@ -9,7 +9,6 @@ use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
 #[cfg(any(feature = "testing", test))]
 use mockall::automock;
-use papyrus_base_layer::L1Event;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
 use starknet_api::executable_transaction::L1HandlerTransaction;
@@ -33,15 +32,38 @@ pub enum ValidationStatus {
 pub enum InvalidValidationStatus {
     AlreadyIncludedInProposedBlock,
     AlreadyIncludedOnL2,
+    /// The cancellation of the transaction was requested on L1 more than the cancellation grace
+    /// period ago.
+    CancellationRequestedOnL1,
     ConsumedOnL1OrUnknown,
 }
 
+/// The lifecycle state of an L1 handler transaction, i.e., of a message from L1 to L2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum L1HandlerStatus {
+    /// Scraped from L1, and not yet committed on L2.
+    Pending,
+    /// Not yet committed on L2, and its cancellation was requested on L1.
+    CancellationRequested {
+        /// The timestamp of the L1 block of the request, in seconds.
+        cancellation_request_timestamp: u64,
+    },
+    /// Canceled on L1, can't be committed on L2.
+    CanceledOnL1,
+    CommittedOnL2,
+    /// Committed on L2, and consumed on L1 by a state update.
+    ConsumedOnL1,
+    Unknown,
+}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum L1ProviderRequest {
     AddEvents(Vec<Event>),
     CommitBlock { l1_handler_tx_hashes: Vec<TransactionHash>, height: BlockNumber },
+    GetL1HandlerStatus(TransactionHash),
     GetTransactions { n_txs: usize, height: BlockNumber },
     Initialize(Vec<Event>),
+    RollbackEvents { events: Vec<Event> },
     StartBlock { state: SessionState, height: BlockNumber },
     Validate { tx_hash: TransactionHash, height: BlockNumber },
 }
@@ -51,8 +73,10 @@ impl_debug_for_infra_requests_and_responses!(L1ProviderRequest);
 pub enum L1ProviderResponse {
     AddEvents(L1ProviderResult<()>),
     CommitBlock(L1ProviderResult<()>),
+    GetL1HandlerStatus(L1ProviderResult<L1HandlerStatus>),
     GetTransactions(L1ProviderResult<Vec<L1HandlerTransaction>>),
     Initialize(L1ProviderResult<()>),
+    RollbackEvents(L1ProviderResult<()>),
     StartBlock(L1ProviderResult<()>),
     Validate(L1ProviderResult<ValidationStatus>),
 }
@@ -88,7 +112,15 @@ pub trait L1ProviderClient: Send + Sync {
     ) -> L1ProviderClientResult<()>;
 
     async fn add_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+
+    async fn get_l1_handler_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> L1ProviderClientResult<L1HandlerStatus>;
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+
+    /// Rolls back previously added events that are no longer on L1, due to an L1 reorg.
+    async fn rollback_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
 }
 
 #[async_trait]
@@ -170,6 +202,21 @@ where
         )
     }
 
+    #[instrument(skip(self))]
+    async fn get_l1_handler_status(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> L1ProviderClientResult<L1HandlerStatus> {
+        let request = L1ProviderRequest::GetL1HandlerStatus(tx_hash);
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            GetL1HandlerStatus,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
         let request = L1ProviderRequest::Initialize(events);
         handle_all_response_variants!(
@@ -180,14 +227,30 @@ where
             Direct
         )
     }
+
+    #[instrument(skip(self))]
+    async fn rollback_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        let request = L1ProviderRequest::RollbackEvents { events };
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            RollbackEvents,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Event {
     L1HandlerTransaction(L1HandlerTransaction),
-    TransactionCanceled(L1Event),
-    TransactionCancellationStarted(L1Event),
-    TransactionConsumed(L1Event),
+    TransactionCanceled(TransactionHash),
+    TransactionCancellationStarted {
+        tx_hash: TransactionHash,
+        /// The timestamp of the L1 block of the request, in seconds.
+        cancellation_request_timestamp: u64,
+    },
+    TransactionConsumed(TransactionHash),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -15,6 +15,7 @@ use async_trait::async_trait;
 use starknet_api::block::NonzeroGasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
+use starknet_api::transaction::fields::Tip;
 
 use crate::config::MempoolConfig;
 use crate::mempool::Mempool;
@@ -104,6 +105,10 @@ impl MempoolCommunicationWrapper {
     fn mempool_snapshot(&self) -> MempoolResult<MempoolSnapshot> {
         self.mempool.mempool_snapshot()
     }
+
+    fn get_suggested_tip(&self, percentile: u8) -> MempoolResult<Tip> {
+        Ok(self.mempool.suggested_tip(percentile))
+    }
 }
 
 #[async_trait]
@@ -130,6 +135,9 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
             MempoolRequest::GetMempoolSnapshot() => {
                 MempoolResponse::GetMempoolSnapshot(self.mempool_snapshot())
             }
+            MempoolRequest::GetSuggestedTip(percentile) => {
+                MempoolResponse::GetSuggestedTip(self.get_suggested_tip(percentile))
+            }
         }
     }
 }
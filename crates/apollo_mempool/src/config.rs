@@ -2,7 +2,7 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use apollo_config::converters::deserialize_seconds_to_duration;
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -11,8 +11,17 @@ use validator::Validate;
 pub struct MempoolConfig {
     pub enable_fee_escalation: bool,
     // TODO(AlonH): consider adding validations; should be bounded?
-    // Percentage increase for tip and max gas price to enable transaction replacement.
+    // Deprecated: percentage increase for tip and max gas price to enable transaction
+    // replacement, used for each of them unless its own percentage is set.
     pub fee_escalation_percentage: u8, // E.g., 10 for a 10% increase.
+    // Percentage increase of the tip required to replace a transaction.
+    pub tip_escalation_percentage: Option<u8>,
+    // Percentage increase of the max L2 gas price required to replace a transaction.
+    pub max_l2_gas_price_escalation_percentage: Option<u8>,
+    // Minimal time between replacements of the transaction of the same account and nonce, in
+    // seconds.
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub min_replacement_interval: Duration,
     // Time-to-live for transactions in the mempool, in seconds.
     // Transactions older than this value will be lazily removed.
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
@@ -32,6 +41,9 @@ impl Default for MempoolConfig {
         MempoolConfig {
             enable_fee_escalation: true,
             fee_escalation_percentage: 10,
+            tip_escalation_percentage: None,
+            max_l2_gas_price_escalation_percentage: None,
+            min_replacement_interval: Duration::from_secs(1),
             transaction_ttl: Duration::from_secs(60), // 1 minute.
             declare_delay: Duration::from_secs(1),
             committed_nonce_retention_block_count: 100,
@@ -40,9 +52,21 @@ impl Default for MempoolConfig {
     }
 }
 
+impl MempoolConfig {
+    /// The percentage increase of the tip required to replace a transaction.
+    pub fn tip_escalation_percentage(&self) -> u8 {
+        self.tip_escalation_percentage.unwrap_or(self.fee_escalation_percentage)
+    }
+
+    /// The percentage increase of the max L2 gas price required to replace a transaction.
+    pub fn max_l2_gas_price_escalation_percentage(&self) -> u8 {
+        self.max_l2_gas_price_escalation_percentage.unwrap_or(self.fee_escalation_percentage)
+    }
+}
+
 impl SerializeConfig for MempoolConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut dump = BTreeMap::from_iter([
             ser_param(
                 "enable_fee_escalation",
                 &self.enable_fee_escalation,
@@ -52,7 +76,17 @@ impl SerializeConfig for MempoolConfig {
             ser_param(
                 "fee_escalation_percentage",
                 &self.fee_escalation_percentage,
-                "Percentage increase for tip and max gas price to enable transaction replacement.",
+                "Deprecated, use tip_escalation_percentage and \
+                 max_l2_gas_price_escalation_percentage. Percentage increase for tip and max gas \
+                 price to enable transaction replacement, used for each of them unless its own \
+                 percentage is set.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "min_replacement_interval",
+                &self.min_replacement_interval.as_secs(),
+                "Minimal time between replacements of the transaction of the same account and \
+                 nonce, in seconds.",
                 ParamPrivacyInput::Public,
             ),
             ser_param(
@@ -80,6 +114,24 @@ impl SerializeConfig for MempoolConfig {
                 "Maximum size of the mempool, in bytes.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+        ]);
+        dump.extend(ser_optional_param(
+            &self.tip_escalation_percentage,
+            self.fee_escalation_percentage,
+            "tip_escalation_percentage",
+            "Percentage increase of the tip required to replace a transaction. If not set, \
+             fee_escalation_percentage is used.",
+            ParamPrivacyInput::Public,
+        ));
+        dump.extend(ser_optional_param(
+            &self.max_l2_gas_price_escalation_percentage,
+            self.fee_escalation_percentage,
+            "max_l2_gas_price_escalation_percentage",
+            "Percentage increase of the max L2 gas price required to replace a transaction. \
+             Transactions whose max L2 gas price is below the gas price threshold are replaced \
+             without increasing the tip. If not set, fee_escalation_percentage is used.",
+            ParamPrivacyInput::Public,
+        ));
+        dump
     }
 }
@@ -2,6 +2,7 @@ pub mod communication;
 pub mod config;
 pub mod mempool;
 pub mod metrics;
+pub(crate) mod replacement_policy;
 pub(crate) mod suspended_transaction_pool;
 pub(crate) mod transaction_pool;
 pub(crate) mod transaction_queue;
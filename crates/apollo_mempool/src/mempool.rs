@@ -26,6 +26,7 @@ use crate::metrics::{
     metric_set_get_txs_size,
     MempoolMetricHandle,
 };
+use crate::replacement_policy::ReplacementPolicy;
 use crate::transaction_pool::TransactionPool;
 use crate::transaction_queue::TransactionQueue;
 use crate::utils::{try_increment_nonce, Clock};
@@ -233,6 +234,8 @@ pub struct Mempool {
     // Transactions eligible for sequencing.
     tx_queue: TransactionQueue,
     state: MempoolState,
+    // Decides whether transactions can replace existing transactions with the same nonce.
+    replacement_policy: ReplacementPolicy,
     clock: Arc<dyn Clock>,
 }
 
@@ -244,6 +247,7 @@ impl Mempool {
             tx_pool: TransactionPool::new(clock.clone()),
             tx_queue: TransactionQueue::default(),
             state: MempoolState::new(config.committed_nonce_retention_block_count),
+            replacement_policy: ReplacementPolicy::new(&config),
             clock,
         }
     }
@@ -463,6 +467,12 @@ impl Mempool {
         self.update_state_metrics();
     }
 
+    /// Returns a tip for new transactions, which is the given percentile of the tips of the
+    /// transactions that are ready to be included in a block.
+    pub fn suggested_tip(&self, percentile: u8) -> Tip {
+        self.tx_queue.tip_percentile(percentile)
+    }
+
     pub fn account_tx_in_pool_or_recent_block(&self, account_address: ContractAddress) -> bool {
         self.state.contains_account(account_address)
             || self.tx_pool.contains_account(account_address)
@@ -518,8 +528,8 @@ impl Mempool {
         Ok(())
     }
 
-    /// If this transaction is already in the pool but the fees have increased beyond the thereshold
-    /// in the config, remove the existing transaction from the queue and the pool.
+    /// If this transaction is already in the pool and the replacement policy allows replacing it,
+    /// remove the existing transaction from the queue and the pool.
     /// Note: This method will **not** add the new incoming transaction.
     #[instrument(level = "debug", skip(self, incoming_tx), err)]
     fn handle_fee_escalation(&mut self, incoming_tx: &InternalRpcTransaction) -> MempoolResult<()> {
@@ -542,13 +552,16 @@ impl Mempool {
             return Ok(());
         };
 
-        if !self.should_replace_tx(&existing_tx_reference, &incoming_tx_reference) {
-            debug!(
-                "{existing_tx_reference} was not replaced by {incoming_tx_reference} due to
-                insufficient fee escalation."
-            );
-            // TODO(Elin): consider adding a more specific error type / message.
-            return Err(MempoolError::DuplicateNonce { address, nonce });
+        let now = self.clock.now();
+        let existing_tx_in_pending_queue = self.tx_queue.in_pending_queue(&existing_tx_reference);
+        if let Err(reason) = self.replacement_policy.validate_replacement(
+            &existing_tx_reference,
+            &incoming_tx_reference,
+            existing_tx_in_pending_queue,
+            now,
+        ) {
+            debug!("{existing_tx_reference} was not replaced by {incoming_tx_reference}: {reason}");
+            return Err(MempoolError::ReplacementRejected { address, nonce, reason });
         }
 
         debug!("{existing_tx_reference} will be replaced by {incoming_tx_reference}.");
@@ -557,42 +570,11 @@ impl Mempool {
         self.tx_pool
             .remove(existing_tx_reference.tx_hash)
             .expect("Transaction hash from pool must exist.");
+        self.replacement_policy.record_replacement(address, nonce, now);
 
         Ok(())
     }
 
-    fn should_replace_tx(
-        &self,
-        existing_tx: &TransactionReference,
-        incoming_tx: &TransactionReference,
-    ) -> bool {
-        let [existing_tip, incoming_tip] =
-            [existing_tx, incoming_tx].map(|tx| u128::from(tx.tip.0));
-        let [existing_max_l2_gas_price, incoming_max_l2_gas_price] =
-            [existing_tx, incoming_tx].map(|tx| tx.max_l2_gas_price.get().0);
-
-        self.increased_enough(existing_tip, incoming_tip)
-            && self.increased_enough(existing_max_l2_gas_price, incoming_max_l2_gas_price)
-    }
-
-    fn increased_enough(&self, existing_value: u128, incoming_value: u128) -> bool {
-        let percentage = u128::from(self.config.fee_escalation_percentage);
-
-        // Note: To reduce precision loss, we first multiply by the percentage and then divide by
-        // 100. This could cause an overflow and an automatic rejection of the transaction, but the
-        // values aren't expected to be large enough for this to be an issue.
-        let Some(escalation_qualified_value) = existing_value
-            .checked_mul(percentage)
-            .map(|v| v / 100)
-            .and_then(|increase| existing_value.checked_add(increase))
-        else {
-            // Overflow occurred during calculation; reject the transaction.
-            return false;
-        };
-
-        incoming_value >= escalation_qualified_value
-    }
-
     fn remove_expired_txs(&mut self) {
         let removed_txs =
             self.tx_pool.remove_txs_older_than(self.config.transaction_ttl, &self.state.staged);
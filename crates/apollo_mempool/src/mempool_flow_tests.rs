@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use apollo_mempool_types::errors::MempoolError;
+use apollo_mempool_types::errors::{MempoolError, ReplacementRejectionReason};
+use mempool_test_utils::starknet_api_test_utils::VALID_L2_GAS_MAX_PRICE_PER_UNIT;
 use rstest::{fixture, rstest};
 use starknet_api::block::{GasPrice, NonzeroGasPrice};
 use starknet_api::{contract_address, nonce};
@@ -100,7 +101,15 @@ fn test_add_same_nonce_tx_after_previous_not_included_in_block(mut mempool: Memp
     add_tx_expect_error(
         &mut mempool,
         &tx_nonce_4_account_nonce_4,
-        MempoolError::DuplicateNonce { address: contract_address!("0x0"), nonce: nonce!(4) },
+        MempoolError::ReplacementRejected {
+            address: contract_address!("0x0"),
+            nonce: nonce!(4),
+            reason: ReplacementRejectionReason::InsufficientMaxL2GasPrice {
+                existing: GasPrice(VALID_L2_GAS_MAX_PRICE_PER_UNIT),
+                incoming: GasPrice(VALID_L2_GAS_MAX_PRICE_PER_UNIT),
+                required_increase_percentage: 10,
+            },
+        },
     );
 
     get_txs_and_assert_expected(
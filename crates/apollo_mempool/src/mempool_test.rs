@@ -4,11 +4,12 @@ use std::time::Duration;
 
 use apollo_mempool_p2p_types::communication::MockMempoolP2pPropagatorClient;
 use apollo_mempool_types::communication::AddTransactionArgsWrapper;
-use apollo_mempool_types::errors::MempoolError;
+use apollo_mempool_types::errors::{MempoolError, ReplacementRejectionReason};
 use apollo_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
 use apollo_metrics::metrics::HistogramValue;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_test_utils::{get_rng, GetTestInstance};
+use assert_matches::assert_matches;
 use mempool_test_utils::starknet_api_test_utils::test_valid_resource_bounds;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use mockall::predicate;
@@ -17,6 +18,7 @@ use rstest::{fixture, rstest};
 use starknet_api::block::{GasPrice, NonzeroGasPrice};
 use starknet_api::rpc_transaction::InternalRpcTransaction;
 use starknet_api::test_utils::declare::{internal_rpc_declare_tx, DeclareTxArgs};
+use starknet_api::transaction::fields::Tip;
 use starknet_api::transaction::TransactionHash;
 use starknet_api::{contract_address, declare_tx_args, nonce, tx_hash};
 
@@ -24,6 +26,7 @@ use super::AddTransactionQueue;
 use crate::communication::MempoolCommunicationWrapper;
 use crate::mempool::{Mempool, MempoolConfig, MempoolContent, MempoolState, TransactionReference};
 use crate::metrics::register_metrics;
+use crate::replacement_policy::ReplacementPolicy;
 use crate::test_utils::{
     add_tx,
     add_tx_expect_error,
@@ -118,6 +121,20 @@ impl MempoolTestContentBuilder {
         self
     }
 
+    fn with_fee_escalation_percentages(
+        mut self,
+        tip_escalation_percentage: u8,
+        max_l2_gas_price_escalation_percentage: u8,
+    ) -> Self {
+        self.config = MempoolConfig {
+            enable_fee_escalation: true,
+            tip_escalation_percentage: Some(tip_escalation_percentage),
+            max_l2_gas_price_escalation_percentage: Some(max_l2_gas_price_escalation_percentage),
+            ..Default::default()
+        };
+        self
+    }
+
     fn build(self) -> MempoolTestContent {
         self.content
     }
@@ -133,6 +150,7 @@ impl MempoolTestContentBuilder {
                 self.gas_price_threshold,
             ),
             state: MempoolState::new(self.config.committed_nonce_retention_block_count),
+            replacement_policy: ReplacementPolicy::new(&self.config),
             clock: Arc::new(FakeClock::default()),
         }
     }
@@ -220,13 +238,10 @@ fn add_txs_and_verify_no_replacement(
     in_pending_queue: bool,
 ) {
     for input in invalid_replacement_inputs {
-        add_tx_expect_error(
-            &mut mempool,
-            &input,
-            MempoolError::DuplicateNonce {
-                address: input.tx.contract_address(),
-                nonce: input.tx.nonce(),
-            },
+        assert_matches!(
+            mempool.add_tx(input.clone()),
+            Err(MempoolError::ReplacementRejected { address, nonce, .. })
+            if address == input.tx.contract_address() && nonce == input.tx.nonce()
         );
     }
 
@@ -392,6 +407,40 @@ fn test_get_txs_with_nonce_gap() {
     expected_mempool_content.assert_eq(&mempool.content());
 }
 
+// `suggested_tip` tests.
+
+#[rstest]
+#[case::zero_percentile(0, 10)]
+#[case::lower_quartile(25, 10)]
+#[case::median(50, 20)]
+#[case::upper_quartile(75, 30)]
+#[case::max_percentile(100, 40)]
+#[case::capped_percentile(200, 40)]
+fn test_suggested_tip(#[case] percentile: u8, #[case] expected_tip: u64) {
+    // Setup.
+    let queue_txs = [
+        tx!(tx_hash: 1, address: "0x0", tip: 30),
+        tx!(tx_hash: 2, address: "0x1", tip: 10),
+        tx!(tx_hash: 3, address: "0x2", tip: 40),
+        tx!(tx_hash: 4, address: "0x3", tip: 20),
+    ];
+    // Pending transactions are not ready to be included in a block, so their tips are ignored.
+    let pending_tx = tx!(tx_hash: 5, address: "0x4", tx_nonce: 0, tip: 100, max_l2_gas_price: 1);
+    let mempool = MempoolTestContentBuilder::new()
+        .with_priority_queue(queue_txs.iter().map(TransactionReference::new))
+        .with_pending_queue([TransactionReference::new(&pending_tx)])
+        .with_gas_price_threshold(2)
+        .build_full_mempool();
+
+    // Test and assert.
+    assert_eq!(mempool.suggested_tip(percentile), Tip(expected_tip));
+}
+
+#[rstest]
+fn test_suggested_tip_of_empty_queue(mempool: Mempool) {
+    assert_eq!(mempool.suggested_tip(50), Tip(0));
+}
+
 // `add_tx` tests.
 
 #[rstest]
@@ -689,23 +738,16 @@ fn test_fee_escalation_valid_replacement(
 }
 
 #[rstest]
-#[case::pool(false, false)]
-#[case::pool_and_priority_queue(true, false)]
-#[case::pool_and_pending_queue(false, true)]
-fn test_fee_escalation_invalid_replacement(
-    #[case] in_priority_queue: bool,
-    #[case] in_pending_queue: bool,
-) {
+#[case::pool(false)]
+#[case::pool_and_priority_queue(true)]
+fn test_fee_escalation_invalid_replacement(#[case] in_priority_queue: bool) {
     // Setup.
     let existing_tx = tx!(tx_hash: 1, tip: 100, max_l2_gas_price: 100);
 
-    let mut builder = builder_with_queue(in_priority_queue, in_pending_queue, &existing_tx)
+    let in_pending_queue = false;
+    let builder = builder_with_queue(in_priority_queue, in_pending_queue, &existing_tx)
         .with_fee_escalation_percentage(10);
 
-    if in_pending_queue {
-        builder = builder.with_gas_price_threshold(1000);
-    }
-
     let mempool = builder.with_pool([existing_tx.clone()]).build_full_mempool();
 
     let input_not_enough_tip = add_tx_input!(tx_hash: 3, tip: 109, max_l2_gas_price: 110);
@@ -724,6 +766,140 @@ fn test_fee_escalation_invalid_replacement(
     );
 }
 
+#[rstest]
+#[case::insufficient_tip(
+    119,
+    200,
+    ReplacementRejectionReason::InsufficientTip {
+        existing: Tip(100),
+        incoming: Tip(119),
+        required_increase_percentage: 20,
+    },
+)]
+#[case::insufficient_max_l2_gas_price(
+    200,
+    104,
+    ReplacementRejectionReason::InsufficientMaxL2GasPrice {
+        existing: GasPrice(100),
+        incoming: GasPrice(104),
+        required_increase_percentage: 5,
+    },
+)]
+fn test_fee_escalation_separate_thresholds(
+    #[case] tip: u64,
+    #[case] max_l2_gas_price: u128,
+    #[case] expected_reason: ReplacementRejectionReason,
+) {
+    // Setup.
+    let existing_tx = tx!(tx_hash: 0, tip: 100, max_l2_gas_price: 100);
+    let mut mempool = MempoolTestContentBuilder::new()
+        .with_pool([existing_tx])
+        .with_fee_escalation_percentages(20, 5)
+        .build_full_mempool();
+
+    // Test and assert.
+    let invalid_replacement_input =
+        add_tx_input!(tx_hash: 1, tip: tip, max_l2_gas_price: max_l2_gas_price);
+    add_tx_expect_error(
+        &mut mempool,
+        &invalid_replacement_input,
+        MempoolError::ReplacementRejected {
+            address: invalid_replacement_input.tx.contract_address(),
+            nonce: invalid_replacement_input.tx.nonce(),
+            reason: expected_reason,
+        },
+    );
+
+    let valid_replacement_input = add_tx_input!(tx_hash: 2, tip: 120, max_l2_gas_price: 105);
+    add_tx_and_verify_replacement_in_pool(mempool, valid_replacement_input);
+}
+
+#[rstest]
+fn test_fee_escalation_pending_queue_replacement() {
+    // Setup.
+    let existing_tx = tx!(tx_hash: 0, tip: 100, max_l2_gas_price: 100);
+    let in_priority_queue = false;
+    let in_pending_queue = true;
+    let mut mempool = builder_with_queue(in_priority_queue, in_pending_queue, &existing_tx)
+        .with_pool([existing_tx])
+        .with_gas_price_threshold(1000)
+        .with_fee_escalation_percentage(10)
+        .build_full_mempool();
+
+    // Test and assert: the tip must not decrease, and the max L2 gas price must increase enough.
+    let input_decreased_tip = add_tx_input!(tx_hash: 1, tip: 99, max_l2_gas_price: 110);
+    let input_not_enough_gas_price = add_tx_input!(tx_hash: 2, tip: 200, max_l2_gas_price: 109);
+    for (input, expected_reason) in [
+        (
+            &input_decreased_tip,
+            ReplacementRejectionReason::TipDecreased { existing: Tip(100), incoming: Tip(99) },
+        ),
+        (
+            &input_not_enough_gas_price,
+            ReplacementRejectionReason::InsufficientMaxL2GasPrice {
+                existing: GasPrice(100),
+                incoming: GasPrice(109),
+                required_increase_percentage: 10,
+            },
+        ),
+    ] {
+        add_tx_expect_error(
+            &mut mempool,
+            input,
+            MempoolError::ReplacementRejected {
+                address: input.tx.contract_address(),
+                nonce: input.tx.nonce(),
+                reason: expected_reason,
+            },
+        );
+    }
+
+    // Test and assert: the tip of a pending transaction doesn't have to increase.
+    let valid_replacement_input = add_tx_input!(tx_hash: 3, tip: 100, max_l2_gas_price: 110);
+    add_tx_and_verify_replacement(
+        mempool,
+        valid_replacement_input,
+        in_priority_queue,
+        in_pending_queue,
+    );
+}
+
+#[rstest]
+fn test_fee_escalation_min_replacement_interval() {
+    // Setup.
+    let fake_clock = Arc::new(FakeClock::default());
+    let mut mempool = Mempool::new(
+        MempoolConfig {
+            enable_fee_escalation: true,
+            fee_escalation_percentage: 0,
+            min_replacement_interval: Duration::from_secs(5),
+            ..Default::default()
+        },
+        fake_clock.clone(),
+    );
+    add_tx(&mut mempool, &add_tx_input!(tx_hash: 0, tip: 10, max_l2_gas_price: 10));
+    add_tx(&mut mempool, &add_tx_input!(tx_hash: 1, tip: 10, max_l2_gas_price: 10));
+
+    // Test and assert: the nonce can't be replaced again before the interval has passed.
+    fake_clock.advance(Duration::from_secs(4));
+    let input = add_tx_input!(tx_hash: 2, tip: 10, max_l2_gas_price: 10);
+    add_tx_expect_error(
+        &mut mempool,
+        &input,
+        MempoolError::ReplacementRejected {
+            address: input.tx.contract_address(),
+            nonce: input.tx.nonce(),
+            reason: ReplacementRejectionReason::ReplacedTooRecently {
+                seconds_since_last_replacement: 4,
+                min_replacement_interval_seconds: 5,
+            },
+        },
+    );
+
+    fake_clock.advance(Duration::from_secs(1));
+    add_tx(&mut mempool, &input);
+}
+
 #[rstest]
 fn fee_escalation_queue_removal() {
     // Setup.
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use apollo_mempool_types::errors::ReplacementRejectionReason;
+use starknet_api::core::{ContractAddress, Nonce};
+
+use crate::config::MempoolConfig;
+use crate::mempool::TransactionReference;
+
+/// Decides whether an incoming transaction may replace an existing transaction of the same account
+/// and nonce.
+///
+/// A replacement must increase the tip and the max L2 gas price by the configured percentages. If
+/// the existing transaction is in the pending queue, i.e., its max L2 gas price is below the gas
+/// price threshold, its tip doesn't affect its priority, so it only has to not decrease. A nonce
+/// can't be replaced again before the minimal replacement interval has passed.
+#[derive(Debug)]
+pub(crate) struct ReplacementPolicy {
+    tip_escalation_percentage: u8,
+    max_l2_gas_price_escalation_percentage: u8,
+    min_replacement_interval: Duration,
+    // The time of the last replacement of each account nonce that was replaced within the minimal
+    // replacement interval.
+    last_replacements: HashMap<(ContractAddress, Nonce), Instant>,
+}
+
+impl ReplacementPolicy {
+    pub fn new(config: &MempoolConfig) -> Self {
+        Self {
+            tip_escalation_percentage: config.tip_escalation_percentage(),
+            max_l2_gas_price_escalation_percentage: config.max_l2_gas_price_escalation_percentage(),
+            min_replacement_interval: config.min_replacement_interval,
+            last_replacements: HashMap::new(),
+        }
+    }
+
+    /// Validates that the incoming transaction may replace the existing one at the given time.
+    pub fn validate_replacement(
+        &self,
+        existing_tx: &TransactionReference,
+        incoming_tx: &TransactionReference,
+        existing_tx_in_pending_queue: bool,
+        now: Instant,
+    ) -> Result<(), ReplacementRejectionReason> {
+        if let Some(last_replacement) =
+            self.last_replacements.get(&(existing_tx.address, existing_tx.nonce))
+        {
+            let since_last_replacement = now.saturating_duration_since(*last_replacement);
+            if since_last_replacement < self.min_replacement_interval {
+                return Err(ReplacementRejectionReason::ReplacedTooRecently {
+                    seconds_since_last_replacement: since_last_replacement.as_secs(),
+                    min_replacement_interval_seconds: self.min_replacement_interval.as_secs(),
+                });
+            }
+        }
+
+        let [existing_max_l2_gas_price, incoming_max_l2_gas_price] =
+            [existing_tx, incoming_tx].map(|tx| tx.max_l2_gas_price.get());
+        if !increased_enough(
+            existing_max_l2_gas_price.0,
+            incoming_max_l2_gas_price.0,
+            self.max_l2_gas_price_escalation_percentage,
+        ) {
+            return Err(ReplacementRejectionReason::InsufficientMaxL2GasPrice {
+                existing: existing_max_l2_gas_price,
+                incoming: incoming_max_l2_gas_price,
+                required_increase_percentage: self.max_l2_gas_price_escalation_percentage,
+            });
+        }
+
+        if existing_tx_in_pending_queue {
+            if incoming_tx.tip < existing_tx.tip {
+                return Err(ReplacementRejectionReason::TipDecreased {
+                    existing: existing_tx.tip,
+                    incoming: incoming_tx.tip,
+                });
+            }
+        } else if !increased_enough(
+            existing_tx.tip.0.into(),
+            incoming_tx.tip.0.into(),
+            self.tip_escalation_percentage,
+        ) {
+            return Err(ReplacementRejectionReason::InsufficientTip {
+                existing: existing_tx.tip,
+                incoming: incoming_tx.tip,
+                required_increase_percentage: self.tip_escalation_percentage,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Records that the transaction of the given account nonce was replaced at the given time.
+    pub fn record_replacement(&mut self, address: ContractAddress, nonce: Nonce, now: Instant) {
+        self.last_replacements.retain(|_, last_replacement| {
+            now.saturating_duration_since(*last_replacement) < self.min_replacement_interval
+        });
+        self.last_replacements.insert((address, nonce), now);
+    }
+}
+
+fn increased_enough(existing_value: u128, incoming_value: u128, percentage: u8) -> bool {
+    let percentage = u128::from(percentage);
+
+    // Note: To reduce precision loss, we first multiply by the percentage and then divide by
+    // 100. This could cause an overflow and an automatic rejection of the transaction, but the
+    // values aren't expected to be large enough for this to be an issue.
+    let Some(escalation_qualified_value) = existing_value
+        .checked_mul(percentage)
+        .map(|v| v / 100)
+        .and_then(|increase| existing_value.checked_add(increase))
+    else {
+        // Overflow occurred during calculation; reject the transaction.
+        return false;
+    };
+
+    incoming_value >= escalation_qualified_value
+}
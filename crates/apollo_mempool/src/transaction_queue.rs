@@ -101,6 +101,20 @@ impl TransactionQueue {
         }
     }
 
+    /// Returns the tip at the given percentile (capped at 100) of the ready transactions, by the
+    /// nearest-rank method, or a zero tip if there are none.
+    pub fn tip_percentile(&self, percentile: u8) -> Tip {
+        let percentile = usize::from(percentile.min(100));
+        let rank = (percentile * self.priority_queue.len()).div_ceil(100);
+        self.priority_queue.iter().nth(rank.saturating_sub(1)).map_or(Tip(0), |tx| tx.tip)
+    }
+
+    /// Returns whether the transaction is in the pending queue, i.e., it's queued but its max L2
+    /// gas price is below the gas price threshold.
+    pub fn in_pending_queue(&self, tx_reference: &TransactionReference) -> bool {
+        self.pending_queue.contains(&PendingTransaction::from(*tx_reference))
+    }
+
     pub fn has_ready_txs(&self) -> bool {
         !self.priority_queue.is_empty()
     }
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::NonzeroGasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
+use starknet_api::transaction::fields::Tip;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 
@@ -48,6 +49,9 @@ pub trait MempoolClient: Send + Sync {
     ) -> MempoolClientResult<bool>;
     async fn update_gas_price(&self, gas_price: NonzeroGasPrice) -> MempoolClientResult<()>;
     async fn get_mempool_snapshot(&self) -> MempoolClientResult<MempoolSnapshot>;
+    /// Returns a suggested tip for new transactions: the given percentile (capped at 100) of the
+    /// tips of the transactions that are ready to be included in a block.
+    async fn get_suggested_tip(&self, percentile: u8) -> MempoolClientResult<Tip>;
 }
 
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
@@ -59,6 +63,7 @@ pub enum MempoolRequest {
     // TODO(yair): Rename to `StartBlock` and add cleanup of staged txs.
     UpdateGasPrice(NonzeroGasPrice),
     GetMempoolSnapshot(),
+    GetSuggestedTip(u8),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolRequest);
 
@@ -70,6 +75,7 @@ pub enum MempoolResponse {
     AccountTxInPoolOrRecentBlock(MempoolResult<bool>),
     UpdateGasPrice(MempoolResult<()>),
     GetMempoolSnapshot(MempoolResult<MempoolSnapshot>),
+    GetSuggestedTip(MempoolResult<Tip>),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolResponse);
 
@@ -154,4 +160,15 @@ where
             Direct
         )
     }
+
+    async fn get_suggested_tip(&self, percentile: u8) -> MempoolClientResult<Tip> {
+        let request = MempoolRequest::GetSuggestedTip(percentile);
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetSuggestedTip,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
 }
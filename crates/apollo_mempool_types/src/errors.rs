@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use starknet_api::block::GasPrice;
 use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::transaction::fields::Tip;
 use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
 
@@ -19,4 +21,43 @@ pub enum MempoolError {
     TransactionNotFound { tx_hash: TransactionHash },
     #[error("Transaction rejected: mempool capacity exceeded.")]
     MempoolFull,
+    #[error(
+        "Transaction with nonce {nonce} of account {address} cannot replace the existing \
+         transaction with the same nonce: {reason}"
+    )]
+    ReplacementRejected {
+        address: ContractAddress,
+        nonce: Nonce,
+        reason: ReplacementRejectionReason,
+    },
+}
+
+/// The reason a transaction was rejected as a replacement of an existing transaction of the same
+/// account and nonce.
+#[derive(Clone, Debug, Error, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplacementRejectionReason {
+    #[error(
+        "Tip {incoming} is not at least {required_increase_percentage}% higher than the existing \
+         tip {existing}."
+    )]
+    InsufficientTip { existing: Tip, incoming: Tip, required_increase_percentage: u8 },
+    #[error(
+        "Max L2 gas price {incoming} is not at least {required_increase_percentage}% higher than \
+         the existing max L2 gas price {existing}."
+    )]
+    InsufficientMaxL2GasPrice {
+        existing: GasPrice,
+        incoming: GasPrice,
+        required_increase_percentage: u8,
+    },
+    #[error("Tip {incoming} is lower than the existing tip {existing}.")]
+    TipDecreased { existing: Tip, incoming: Tip },
+    #[error(
+        "The transaction was replaced {seconds_since_last_replacement} seconds ago, and can be \
+         replaced at most once every {min_replacement_interval_seconds} seconds."
+    )]
+    ReplacedTooRecently {
+        seconds_since_last_replacement: u64,
+        min_replacement_interval_seconds: u64,
+    },
 }
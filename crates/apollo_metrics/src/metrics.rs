@@ -25,12 +25,15 @@ pub static COLLECT_SEQUENCER_PROFILING_METRICS: OnceLock<bool> = OnceLock::new()
 /// Relevant components for which metrics can be defined.
 #[derive(Clone, Copy, Debug)]
 pub enum MetricScope {
+    BaseLayer,
     Batcher,
     ClassManager,
     Consensus,
     Gateway,
     HttpServer,
     Infra,
+    L1GasPrice,
+    L1Provider,
     Mempool,
     MempoolP2p,
     StateSync,
@@ -229,7 +232,7 @@ macro_rules! into_f64 {
         )*
     };
 }
-into_f64!(u64, usize, i64);
+into_f64!(u64, u128, usize, i64);
 
 pub struct LabeledMetricGauge {
     scope: MetricScope,
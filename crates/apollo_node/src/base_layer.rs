@@ -0,0 +1,135 @@
+use std::ops::RangeInclusive;
+
+use async_trait::async_trait;
+use papyrus_base_layer::ethereum_base_layer_contract::{
+    EthereumBaseLayerContract,
+    EthereumBaseLayerError,
+};
+#[cfg(any(feature = "testing", test))]
+use papyrus_base_layer::fake_base_layer_contract::{FakeBaseLayerContract, FakeBaseLayerError};
+use papyrus_base_layer::quorum_base_layer_contract::{
+    QuorumBaseLayerContract,
+    QuorumBaseLayerError,
+};
+use papyrus_base_layer::{
+    BaseLayerContract,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+    L1StateUpdate,
+    L1TransactionHash,
+    MessageToL2Cancellation,
+    PriceSample,
+};
+use starknet_api::block::BlockHashAndNumber;
+use starknet_api::transaction::L1HandlerTransaction;
+
+/// The base layer contract that the L1 components of the node read from: a single Ethereum node,
+/// or a quorum of Ethereum nodes. Tests can replace it with an in-process fake, so that the node
+/// runs without an L1 node.
+#[derive(Clone, Debug)]
+pub enum NodeBaseLayerContract {
+    Ethereum(EthereumBaseLayerContract),
+    Quorum(QuorumBaseLayerContract<EthereumBaseLayerContract>),
+    #[cfg(any(feature = "testing", test))]
+    Fake(FakeBaseLayerContract),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum NodeBaseLayerError {
+    #[error(transparent)]
+    Ethereum(#[from] EthereumBaseLayerError),
+    #[error(transparent)]
+    Quorum(#[from] QuorumBaseLayerError<EthereumBaseLayerError>),
+    #[cfg(any(feature = "testing", test))]
+    #[error(transparent)]
+    Fake(#[from] FakeBaseLayerError),
+}
+
+// Calls the given method of the contract that the node reads from.
+macro_rules! delegate {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        match $self {
+            NodeBaseLayerContract::Ethereum(contract) => Ok(contract.$method($($arg),*).await?),
+            NodeBaseLayerContract::Quorum(contract) => Ok(contract.$method($($arg),*).await?),
+            #[cfg(any(feature = "testing", test))]
+            NodeBaseLayerContract::Fake(contract) => Ok(contract.$method($($arg),*).await?),
+        }
+    };
+}
+
+#[async_trait]
+impl BaseLayerContract for NodeBaseLayerContract {
+    type Error = NodeBaseLayerError;
+
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> Result<BlockHashAndNumber, NodeBaseLayerError> {
+        delegate!(self.get_proved_block_at(l1_block))
+    }
+
+    async fn latest_proved_block(
+        &self,
+        finality: u64,
+    ) -> Result<Option<BlockHashAndNumber>, NodeBaseLayerError> {
+        delegate!(self.latest_proved_block(finality))
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockNumber>, NodeBaseLayerError> {
+        delegate!(self.latest_l1_block_number(finality))
+    }
+
+    async fn latest_l1_block(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockReference>, NodeBaseLayerError> {
+        delegate!(self.latest_l1_block(finality))
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<L1BlockReference>, NodeBaseLayerError> {
+        delegate!(self.l1_block_at(block_number))
+    }
+
+    async fn events<'a>(
+        &'a self,
+        block_range: RangeInclusive<L1BlockNumber>,
+        event_identifiers: &'a [&'a str],
+    ) -> Result<Vec<L1Event>, NodeBaseLayerError> {
+        delegate!(self.events(block_range, event_identifiers))
+    }
+
+    async fn transaction_events(
+        &self,
+        transaction_hash: L1TransactionHash,
+    ) -> Result<Vec<L1Event>, NodeBaseLayerError> {
+        delegate!(self.transaction_events(transaction_hash))
+    }
+
+    async fn get_price_sample(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<PriceSample>, NodeBaseLayerError> {
+        delegate!(self.get_price_sample(block_number))
+    }
+
+    async fn state_updates(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1StateUpdate>, NodeBaseLayerError> {
+        delegate!(self.state_updates(block_range))
+    }
+
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> Result<MessageToL2Cancellation, NodeBaseLayerError> {
+        delegate!(self.message_to_l2_cancellation(message))
+    }
+}
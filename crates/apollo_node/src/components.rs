@@ -20,10 +20,10 @@ use apollo_monitoring_endpoint::monitoring_endpoint::{
 use apollo_sierra_multicompile::{create_sierra_compiler, SierraCompiler};
 use apollo_state_sync::runner::StateSyncRunner;
 use apollo_state_sync::{create_state_sync_and_runner, StateSync};
-use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
 use papyrus_base_layer::BaseLayerContract;
 use tracing::warn;
 
+use crate::base_layer::NodeBaseLayerContract;
 use crate::clients::SequencerNodeClients;
 use crate::config::component_execution_config::{
     ActiveComponentExecutionMode,
@@ -38,9 +38,9 @@ pub struct SequencerNodeComponents {
     pub consensus_manager: Option<ConsensusManager>,
     pub gateway: Option<Gateway>,
     pub http_server: Option<HttpServer>,
-    pub l1_scraper: Option<L1Scraper<EthereumBaseLayerContract>>,
+    pub l1_scraper: Option<L1Scraper<NodeBaseLayerContract>>,
     pub l1_provider: Option<L1Provider>,
-    pub l1_gas_price_scraper: Option<L1GasPriceScraper<EthereumBaseLayerContract>>,
+    pub l1_gas_price_scraper: Option<L1GasPriceScraper<NodeBaseLayerContract>>,
     pub l1_gas_price_provider: Option<L1GasPriceProvider>,
     pub mempool: Option<MempoolCommunicationWrapper>,
     pub monitoring_endpoint: Option<MonitoringEndpoint>,
@@ -54,6 +54,7 @@ pub struct SequencerNodeComponents {
 pub async fn create_node_components(
     config: &SequencerNodeConfig,
     clients: &SequencerNodeClients,
+    base_layer: NodeBaseLayerContract,
 ) -> SequencerNodeComponents {
     let batcher = match config.components.batcher.execution_mode {
         ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
@@ -221,12 +222,11 @@ pub async fn create_node_components(
         ActiveComponentExecutionMode::Enabled => {
             let l1_provider_client = clients.get_l1_provider_shared_client().unwrap();
             let l1_scraper_config = config.l1_scraper_config.clone();
-            let base_layer = EthereumBaseLayerContract::new(config.base_layer_config.clone());
             Some(
                 L1Scraper::new(
                     l1_scraper_config,
                     l1_provider_client,
-                    base_layer,
+                    base_layer.clone(),
                     event_identifiers_to_track(),
                 )
                 .await
@@ -311,7 +311,6 @@ pub async fn create_node_components(
                 .get_l1_gas_price_shared_client()
                 .expect("L1 gas price client should be available");
             let l1_gas_price_scraper_config = config.l1_gas_price_scraper_config.clone();
-            let base_layer = EthereumBaseLayerContract::new(config.base_layer_config.clone());
 
             Some(L1GasPriceScraper::new(
                 l1_gas_price_scraper_config,
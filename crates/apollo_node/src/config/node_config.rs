@@ -9,6 +9,7 @@ use apollo_class_manager::config::FsClassManagerConfig;
 use apollo_config::dumping::{
     append_sub_config_name,
     generate_struct_pointer,
+    ser_optional_sub_config,
     ser_pointer_target_param,
     set_pointing_param_paths,
     ConfigPointers,
@@ -33,6 +34,7 @@ use apollo_sierra_multicompile::config::SierraCompilationConfig;
 use apollo_state_sync::config::StateSyncConfig;
 use clap::Command;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
+use papyrus_base_layer::quorum_base_layer_contract::QuorumBaseLayerConfig;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
@@ -147,6 +149,10 @@ pub struct SequencerNodeConfig {
     // Business-logic component configs.
     #[validate]
     pub base_layer_config: EthereumBaseLayerConfig,
+    /// If set, the L1 components read from a quorum of Ethereum nodes, which includes the node of
+    /// the base layer config.
+    #[validate]
+    pub base_layer_quorum_config: Option<QuorumBaseLayerConfig>,
     #[validate]
     pub batcher_config: BatcherConfig,
     #[validate]
@@ -183,6 +189,7 @@ impl SerializeConfig for SequencerNodeConfig {
             append_sub_config_name(self.components.dump(), "components"),
             append_sub_config_name(self.monitoring_config.dump(), "monitoring_config"),
             append_sub_config_name(self.base_layer_config.dump(), "base_layer_config"),
+            ser_optional_sub_config(&self.base_layer_quorum_config, "base_layer_quorum_config"),
             append_sub_config_name(self.batcher_config.dump(), "batcher_config"),
             append_sub_config_name(self.class_manager_config.dump(), "class_manager_config"),
             append_sub_config_name(
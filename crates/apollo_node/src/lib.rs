@@ -1,3 +1,4 @@
+pub mod base_layer;
 pub mod clients;
 pub mod communication;
 pub mod components;
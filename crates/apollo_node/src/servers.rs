@@ -90,8 +90,8 @@ use apollo_state_sync::runner::StateSyncRunnerServer;
 use apollo_state_sync::{LocalStateSyncServer, RemoteStateSyncServer};
 use futures::stream::FuturesUnordered;
 use futures::{Future, FutureExt, StreamExt};
-use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
 
+use crate::base_layer::NodeBaseLayerContract;
 use crate::clients::SequencerNodeClients;
 use crate::communication::SequencerNodeCommunication;
 use crate::components::SequencerNodeComponents;
@@ -118,9 +118,9 @@ struct LocalServers {
 struct WrapperServers {
     pub(crate) consensus_manager: Option<Box<ConsensusManagerServer>>,
     pub(crate) http_server: Option<Box<HttpServer>>,
-    pub(crate) l1_scraper_server: Option<Box<L1ScraperServer<EthereumBaseLayerContract>>>,
+    pub(crate) l1_scraper_server: Option<Box<L1ScraperServer<NodeBaseLayerContract>>>,
     pub(crate) l1_gas_price_scraper_server:
-        Option<Box<L1GasPriceScraperServer<EthereumBaseLayerContract>>>,
+        Option<Box<L1GasPriceScraperServer<NodeBaseLayerContract>>>,
     pub(crate) monitoring_endpoint: Option<Box<MonitoringEndpointServer>>,
     pub(crate) mempool_p2p_runner: Option<Box<MempoolP2pRunnerServer>>,
     pub(crate) state_sync_runner: Option<Box<StateSyncRunnerServer>>,
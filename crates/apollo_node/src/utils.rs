@@ -3,8 +3,11 @@ use std::process::exit;
 use apollo_config::presentation::get_config_presentation;
 use apollo_config::validators::config_validate;
 use apollo_config::ConfigError;
+use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerContract;
+use papyrus_base_layer::quorum_base_layer_contract::QuorumBaseLayerContract;
 use tracing::{error, info};
 
+use crate::base_layer::NodeBaseLayerContract;
 use crate::clients::{create_node_clients, SequencerNodeClients};
 use crate::communication::create_node_channels;
 use crate::components::create_node_components;
@@ -13,10 +16,30 @@ use crate::servers::{create_node_servers, SequencerNodeServers};
 
 pub async fn create_node_modules(
     config: &SequencerNodeConfig,
+) -> (SequencerNodeClients, SequencerNodeServers) {
+    let base_layer = match config.base_layer_quorum_config.clone() {
+        Some(base_layer_quorum_config) => {
+            NodeBaseLayerContract::Quorum(QuorumBaseLayerContract::from_config(
+                config.base_layer_config.clone(),
+                base_layer_quorum_config,
+            ))
+        }
+        None => NodeBaseLayerContract::Ethereum(EthereumBaseLayerContract::new(
+            config.base_layer_config.clone(),
+        )),
+    };
+    create_node_modules_with_base_layer(config, base_layer).await
+}
+
+/// Creates the node modules, where the L1 components read from the given base layer instead of the
+/// one in the config.
+pub async fn create_node_modules_with_base_layer(
+    config: &SequencerNodeConfig,
+    base_layer: NodeBaseLayerContract,
 ) -> (SequencerNodeClients, SequencerNodeServers) {
     let mut channels = create_node_channels();
     let clients = create_node_clients(config, &mut channels);
-    let components = create_node_components(config, &clients).await;
+    let components = create_node_components(config, &clients, base_layer).await;
     let servers = create_node_servers(config, &mut channels, components, &clients);
 
     (clients, servers)
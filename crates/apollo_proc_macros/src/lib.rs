@@ -43,9 +43,13 @@ use syn::{
 ///     fn block_number(&self) -> Result<BlockNumber, Error>;
 /// }
 /// ```
+///
+/// The namespace of the methods is "starknet" unless another namespace is given as a second
+/// argument, e.g. `#[versioned_rpc("V0_6_0", "apollo")]`.
 #[proc_macro_attribute]
 pub fn versioned_rpc(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let version = parse_macro_input!(attr as syn::LitStr);
+    let VersionedRpcMacroInput { version, namespace } =
+        parse_macro_input!(attr as VersionedRpcMacroInput);
     let item_trait = parse_macro_input!(input as ItemTrait);
 
     let trait_name = &item_trait.ident;
@@ -95,7 +99,7 @@ pub fn versioned_rpc(attr: TokenStream, input: TokenStream) -> TokenStream {
 
     // generate the versioned trait with the new method signatures
     let versioned_trait = syn::ItemTrait {
-        attrs: vec![syn::parse_quote!(#[rpc(server, client, namespace = "starknet")])],
+        attrs: vec![syn::parse_quote!(#[rpc(server, client, namespace = #namespace)])],
         vis: visibility.clone(),
         unsafety: None,
         auto_token: None,
@@ -112,6 +116,23 @@ pub fn versioned_rpc(attr: TokenStream, input: TokenStream) -> TokenStream {
     versioned_trait.to_token_stream().into()
 }
 
+struct VersionedRpcMacroInput {
+    version: LitStr,
+    namespace: LitStr,
+}
+
+impl Parse for VersionedRpcMacroInput {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let version: LitStr = input.parse()?;
+        let namespace = if input.parse::<Option<Token![,]>>()?.is_some() {
+            input.parse()?
+        } else {
+            LitStr::new("starknet", version.span())
+        };
+        Ok(VersionedRpcMacroInput { version, namespace })
+    }
+}
+
 /// This macro will emit a histogram metric with the given name and the latency of the function.
 /// In addition, also a debug log with the metric name and the execution time will be emitted.
 /// The macro also receives a boolean for whether it will be emitted only when
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use apollo_mempool_types::communication::SharedMempoolClient;
 use apollo_rpc_execution::ExecutionConfig;
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::StarknetWriter;
@@ -12,7 +13,15 @@ use starknet_api::core::{ChainId, ContractAddress, EntryPointSelector};
 use starknet_api::transaction::fields::Calldata;
 use tokio::sync::RwLock;
 
+use crate::base_layer::BaseLayerMessagesSource;
 use crate::v0_8::api::api_impl::JsonRpcServerImpl as JsonRpcServerV0_8Impl;
+use crate::v0_8::state_history::StateHistoryRpcImpl as StateHistoryRpcV0_8Impl;
+use crate::v0_8::subscriptions::SubscriptionsRpcImpl;
+use crate::v0_9::api::api_impl::JsonRpcServerImpl as JsonRpcServerV0_9Impl;
+use crate::v0_9::fee_history::{FeeHistoryRpcImpl, SuggestedTipRpcImpl, SuggestedTipRpcV0_9Server};
+use crate::v0_9::messages::{MessagesRpcImpl, MessagesRpcV0_9Server};
+use crate::v0_9::reexecution::ReexecutionRpcImpl;
+use crate::v0_9::state_history::StateHistoryRpcImpl as StateHistoryRpcV0_9Impl;
 use crate::version_config;
 
 #[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -62,6 +71,9 @@ pub fn get_methods_from_supported_apis(
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
     starknet_writer: Arc<dyn StarknetWriter>,
+    base_layer: Option<Arc<dyn BaseLayerMessagesSource>>,
+    mempool_client: Option<SharedMempoolClient>,
+    serve_admin_methods: bool,
 ) -> Methods {
     let mut methods: Methods = Methods::new();
     let server_gen = JsonRpcServerImplGenerator {
@@ -85,7 +97,41 @@ pub fn get_methods_from_supported_apis(
                 version_config::VersionState::Supported => {
                     let methods = match *version {
                         version_config::VERSION_0_8 => {
-                            server_gen.clone().generator::<JsonRpcServerV0_8Impl>()
+                            let mut methods =
+                                server_gen.clone().generator::<JsonRpcServerV0_8Impl>();
+                            let _res = methods
+                                .merge(server_gen.clone().generator::<StateHistoryRpcV0_8Impl>());
+                            methods
+                        }
+                        version_config::VERSION_0_9 => {
+                            let mut methods =
+                                server_gen.clone().generator::<JsonRpcServerV0_9Impl>();
+                            let _res = methods
+                                .merge(server_gen.clone().generator::<StateHistoryRpcV0_9Impl>());
+                            // The subscription methods are not versioned, so they should be
+                            // merged only for the latest version.
+                            let _res = methods
+                                .merge(server_gen.clone().generator::<SubscriptionsRpcImpl>());
+                            let _res =
+                                methods.merge(server_gen.clone().generator::<FeeHistoryRpcImpl>());
+                            // The suggested tip requires access to the mempool.
+                            if let Some(mempool_client) = mempool_client.clone() {
+                                let suggested_tip_rpc = SuggestedTipRpcImpl { mempool_client };
+                                let _res = methods.merge(suggested_tip_rpc.into_rpc());
+                            }
+                            // The messages status requires access to L1.
+                            if let Some(base_layer) = base_layer.clone() {
+                                let messages_rpc = MessagesRpcImpl {
+                                    rpc: server_gen.clone().server::<JsonRpcServerV0_8Impl>(),
+                                    base_layer,
+                                };
+                                let _res = methods.merge(messages_rpc.into_rpc());
+                            }
+                            if serve_admin_methods {
+                                let _res = methods
+                                    .merge(server_gen.clone().generator::<ReexecutionRpcImpl>());
+                            }
+                            methods
                         }
                         // TODO(yair): remove this once the version is an enum instead of a string.
                         _ => unreachable!("Unrecognized RPC spec version: {}", version),
@@ -163,7 +209,7 @@ impl JsonRpcServerImplGenerator {
         )
     }
 
-    fn generator<T>(self) -> Methods
+    fn server<T>(self) -> T
     where
         T: JsonRpcServerTrait,
     {
@@ -179,20 +225,24 @@ impl JsonRpcServerImplGenerator {
             pending_classes,
             starknet_writer,
         ) = self.get_params();
-        Into::<Methods>::into(
-            T::new(
-                chain_id,
-                fee_contract_address,
-                storage_reader,
-                max_events_chunk_size,
-                max_events_keys,
-                starting_block,
-                shared_highest_block,
-                pending_data,
-                pending_classes,
-                starknet_writer,
-            )
-            .into_rpc_module(),
+        T::new(
+            chain_id,
+            fee_contract_address,
+            storage_reader,
+            max_events_chunk_size,
+            max_events_keys,
+            starting_block,
+            shared_highest_block,
+            pending_data,
+            pending_classes,
+            starknet_writer,
         )
     }
+
+    fn generator<T>(self) -> Methods
+    where
+        T: JsonRpcServerTrait,
+    {
+        Into::<Methods>::into(self.server::<T>().into_rpc_module())
+    }
 }
@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+#[cfg(test)]
+use mockall::automock;
+use papyrus_base_layer::{BaseLayerContract, L1Event, L1TransactionHash, MessageToL2Cancellation};
+use starknet_api::transaction::L1HandlerTransaction;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BaseLayerMessagesSourceError {
+    #[error("Base layer error: {0}")]
+    BaseLayerContractError(Box<dyn std::error::Error + Sync + Send>),
+}
+
+/// A source of the messages that were sent from L1 to L2.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait BaseLayerMessagesSource: Sync + Send {
+    /// Returns the L1 handler transactions of the messages that the given L1 transaction sent to
+    /// L2.
+    async fn messages_to_l2(
+        &self,
+        l1_transaction_hash: L1TransactionHash,
+    ) -> Result<Vec<L1HandlerTransaction>, BaseLayerMessagesSourceError>;
+
+    /// Returns the cancellation state of the message that is executed by the given L1 handler
+    /// transaction.
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> Result<MessageToL2Cancellation, BaseLayerMessagesSourceError>;
+}
+
+#[async_trait]
+impl<
+    Error: std::error::Error + 'static + Sync + Send,
+    BaseLayerSource: BaseLayerContract<Error = Error> + Sync + Send,
+> BaseLayerMessagesSource for BaseLayerSource
+{
+    async fn messages_to_l2(
+        &self,
+        l1_transaction_hash: L1TransactionHash,
+    ) -> Result<Vec<L1HandlerTransaction>, BaseLayerMessagesSourceError> {
+        let events = self
+            .transaction_events(l1_transaction_hash)
+            .await
+            .map_err(|e| BaseLayerMessagesSourceError::BaseLayerContractError(Box::new(e)))?;
+        Ok(events
+            .into_iter()
+            .filter_map(|event| match event {
+                L1Event::LogMessageToL2 { tx, .. } => Some(tx),
+                _ => None,
+            })
+            .collect())
+    }
+
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> Result<MessageToL2Cancellation, BaseLayerMessagesSourceError> {
+        BaseLayerContract::message_to_l2_cancellation(self, message)
+            .await
+            .map_err(|e| BaseLayerMessagesSourceError::BaseLayerContractError(Box::new(e)))
+    }
+}
@@ -3,6 +3,7 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
 mod api;
+mod base_layer;
 mod middleware;
 mod pending;
 mod rpc_metrics;
@@ -12,6 +13,7 @@ mod syncing_state;
 #[cfg(test)]
 mod test_utils;
 mod v0_8;
+mod v0_9;
 mod version_config;
 
 use std::collections::BTreeMap;
@@ -21,6 +23,7 @@ use std::sync::Arc;
 use apollo_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
 use apollo_config::validators::validate_ascii;
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use apollo_mempool_types::communication::SharedMempoolClient;
 use apollo_rpc_execution::ExecutionConfig;
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::StarknetGatewayClient;
@@ -43,12 +46,13 @@ use starknet_api::block::{BlockHashAndNumber, BlockNumber, BlockStatus};
 use starknet_api::core::ChainId;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument};
-// Aliasing the latest version of the RPC.
-use v0_8 as latest;
 pub use v0_8::api::CompiledContractClass;
+// Aliasing the latest version of the RPC.
+use v0_9 as latest;
 use validator::Validate;
 
 use crate::api::get_methods_from_supported_apis;
+pub use crate::base_layer::{BaseLayerMessagesSource, BaseLayerMessagesSourceError};
 use crate::middleware::{deny_requests_with_unsupported_path, proxy_rpc_request};
 use crate::syncing_state::get_last_synced_block;
 pub use crate::v0_8::transaction::{
@@ -75,6 +79,7 @@ pub struct RpcConfig {
     pub max_events_keys: usize,
     // TODO(lev,shahak): remove once we remove papyrus.
     pub collect_metrics: bool,
+    pub serve_admin_methods: bool,
     pub starknet_url: String,
     pub apollo_gateway_retry_config: RetryConfig,
     pub execution_config: ExecutionConfig,
@@ -88,6 +93,7 @@ impl Default for RpcConfig {
             max_events_chunk_size: 1000,
             max_events_keys: 100,
             collect_metrics: false,
+            serve_admin_methods: false,
             starknet_url: String::from("https://alpha-mainnet.starknet.io/"),
             apollo_gateway_retry_config: RetryConfig {
                 retry_base_millis: 50,
@@ -132,6 +138,13 @@ impl SerializeConfig for RpcConfig {
                 "If true, collect metrics for the rpc.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "serve_admin_methods",
+                &self.serve_admin_methods,
+                "If true, serve the admin methods of the apollo namespace, such as re-executing \
+                 blocks. These methods are expensive and shouldn't be exposed publicly.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "starknet_url",
                 &self.starknet_url,
@@ -209,6 +222,8 @@ pub async fn run_server(
     pending_classes: Arc<RwLock<PendingClasses>>,
     storage_reader: StorageReader,
     node_version: &'static str,
+    base_layer: Option<Arc<dyn BaseLayerMessagesSource>>,
+    mempool_client: Option<SharedMempoolClient>,
 ) -> anyhow::Result<(SocketAddr, ServerHandle)> {
     let starting_block = get_last_synced_block(storage_reader.clone())?;
     debug!("Starting JSON-RPC.");
@@ -227,6 +242,9 @@ pub async fn run_server(
             node_version,
             config.apollo_gateway_retry_config,
         )?),
+        base_layer,
+        mempool_client,
+        config.serve_admin_methods,
     );
     let addr;
     let handle;
@@ -9,7 +9,7 @@ use crate::SERVER_MAX_BODY_SIZE;
 
 /// [`Tower`] middleware intended to proxy method requests to the right version of the API.
 /// The middleware reads the JsonRPC request body and request path
-/// then prefixes the method name with the appropriate version identifier.
+/// then prefixes the method name with the appropriate version identifier, after its namespace.
 /// It returns a new [`hyper::Request`] object with the new method name.
 ///
 /// # Arguments
@@ -18,6 +18,12 @@ use crate::SERVER_MAX_BODY_SIZE;
 /// [`Tower`]: https://crates.io/crates/tower
 pub(crate) async fn proxy_rpc_request(req: Request<Body>) -> Result<Request<Body>, BoxError> {
     debug!("proxy_rpc_request -> Request received: {:?}", req);
+    // The messages of a WebSocket connection don't pass through the middleware, so the
+    // subscription methods are registered without a version and the upgrade request is passed as
+    // is.
+    if is_websocket_upgrade(&req) {
+        return Ok(req);
+    }
     let uri = &req.uri().clone();
     let prefix = get_version_as_prefix(uri.path())?;
     let (parts, body) = req.into_parts();
@@ -37,6 +43,13 @@ pub(crate) async fn proxy_rpc_request(req: Request<Body>) -> Result<Request<Body
     Ok(Request::from_parts(parts, new_body.into()))
 }
 
+fn is_websocket_upgrade(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(hyper::header::UPGRADE)
+        .and_then(|upgrade| upgrade.to_str().ok())
+        .is_some_and(|upgrade| upgrade.eq_ignore_ascii_case("websocket"))
+}
+
 /// ['Tower`] middleware intended to deny requests with unsupported paths.
 /// supported paths are paths that starts with '/rpc/' followed by a supported version id.
 ///
@@ -63,10 +76,12 @@ fn add_version_to_method_name_in_body(
     let Ok(vec_body) = vec_body
         .iter_mut()
         .map(|body| {
-            let Some(stripped_method) = strip_starknet_from_method(body.method.as_ref()) else {
+            let Some((namespace, stripped_method)) =
+                split_namespace_from_method(body.method.as_ref())
+            else {
                 return Err(BoxError::from("Method name has unexpected format"));
             };
-            body.method = format!("starknet_{prefix}_{stripped_method}").into();
+            body.method = format!("{namespace}_{prefix}_{stripped_method}").into();
             Ok(body)
         })
         .collect::<Result<Vec<_>, _>>()
@@ -81,10 +96,11 @@ fn add_version_to_method_name_in_body(
 }
 
 /// this assumes that all methods are of the form:
-/// starknet_OnlyOneUnderScoreAndMethodNameIsCamleCased
-fn strip_starknet_from_method(method: &str) -> Option<&str> {
+/// namespace_OnlyOneUnderScoreAndMethodNameIsCamleCased
+/// where the namespace is starknet, or apollo for the methods that are not part of the spec.
+fn split_namespace_from_method(method: &str) -> Option<(&str, &str)> {
     let split_method_name = method.split('_').collect::<Vec<_>>();
-    split_method_name.get(1).copied()
+    Some((split_method_name.first().copied()?, split_method_name.get(1).copied()?))
 }
 
 #[instrument(level = "debug", err)]
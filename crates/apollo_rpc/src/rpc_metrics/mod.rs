@@ -110,13 +110,13 @@ impl Logger for MetricLogger {
 // Example: method_name: starknet_V0_6_0_blockNumber; output: (blockNumber, V0_6_0).
 fn get_method_and_version(method_name: &str) -> (String, String) {
     // The structure of method_name is in the following format: "starknet_V0_6_0_blockNumber".
-    // Only method in this format will arrive to this point in the code.
-    let last_underscore_index = method_name
-        .rfind('_')
+    // The subscription methods arrive over WebSocket without a version, e.g.
+    // "starknet_subscribeNewHeads", and get an empty version.
+    let (_namespace, versioned_method) = method_name
+        .split_once('_')
         .expect("method_name should be in the following format: starknet_V0_6_0_blockNumber");
-
-    (
-        method_name[last_underscore_index + 1..].to_string(),
-        method_name[9..last_underscore_index].to_string(),
-    )
+    match versioned_method.rsplit_once('_') {
+        Some((version, method)) => (method.to_string(), version.to_string()),
+        None => (versioned_method.to_string(), String::new()),
+    }
 }
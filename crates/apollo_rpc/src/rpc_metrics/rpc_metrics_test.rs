@@ -38,6 +38,10 @@ fn get_method_and_version_test() {
     let (method, version) = get_method_and_version(method_name);
     assert_eq!(method, "blockNumber");
     assert_eq!(version, "V0_8_0");
+
+    let (method, version) = get_method_and_version("starknet_subscribeNewHeads");
+    assert_eq!(method, "subscribeNewHeads");
+    assert_eq!(version, "");
 }
 
 // Ignored because server_metrics test is running in parallel and we are unable to install multiple
@@ -168,6 +172,8 @@ async fn server_metrics() {
         get_test_pending_classes(),
         storage_reader,
         "NODE VERSION",
+        None,
+        None,
     )
     .await
     .unwrap();
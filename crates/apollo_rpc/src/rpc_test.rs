@@ -48,6 +48,8 @@ async fn run_server_no_blocks() {
         pending_classes,
         storage_reader,
         "NODE VERSION",
+        None,
+        None,
     )
     .await
     .unwrap();
@@ -5,7 +5,7 @@ use apollo_rpc_execution::ExecutionConfig;
 use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::MockStarknetWriter;
 use apollo_storage::test_utils::get_test_storage_by_scope;
-use apollo_storage::{StorageScope, StorageWriter};
+use apollo_storage::{StorageReader, StorageScope, StorageWriter};
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::server::RpcModule;
 use jsonrpsee::types::ErrorObjectOwned;
@@ -54,8 +54,8 @@ pub(crate) fn get_test_pending_classes() -> Arc<RwLock<PendingClasses>> {
     Arc::new(RwLock::new(PendingClasses::default()))
 }
 
-pub(crate) fn get_test_rpc_server_and_storage_writer<T: JsonRpcServerTrait>()
--> (RpcModule<T>, StorageWriter) {
+pub(crate) fn get_test_rpc_server_and_storage_writer<T: JsonRpcServerTrait>(
+) -> (RpcModule<T>, StorageWriter) {
     get_test_rpc_server_and_storage_writer_from_params(None, None, None, None, None)
 }
 
@@ -66,33 +66,69 @@ pub(crate) fn get_test_rpc_server_and_storage_writer_from_params<T: JsonRpcServe
     pending_classes: Option<Arc<RwLock<PendingClasses>>>,
     storage_scope: Option<StorageScope>,
 ) -> (RpcModule<T>, StorageWriter) {
-    let mock_client = mock_client.unwrap_or_default();
-    let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
-    let pending_data = pending_data.unwrap_or(get_test_pending_data());
-    let pending_classes = pending_classes.unwrap_or(get_test_pending_classes());
-    let storage_scope = storage_scope.unwrap_or_default();
+    let (server, storage_writer) = get_test_rpc_server_impl_and_storage_writer_from_params::<T>(
+        mock_client,
+        shared_highest_block,
+        pending_data,
+        pending_classes,
+        storage_scope,
+    );
+    (server.into_rpc_module(), storage_writer)
+}
 
+// Same as `get_test_rpc_server_and_storage_writer_from_params`, but returns the server itself
+// instead of its module, for servers that need to be composed before they're served.
+pub(crate) fn get_test_rpc_server_impl_and_storage_writer_from_params<T: JsonRpcServerTrait>(
+    mock_client: Option<MockStarknetWriter>,
+    shared_highest_block: Option<Arc<RwLock<Option<BlockHashAndNumber>>>>,
+    pending_data: Option<Arc<RwLock<PendingData>>>,
+    pending_classes: Option<Arc<RwLock<PendingClasses>>>,
+    storage_scope: Option<StorageScope>,
+) -> (T, StorageWriter) {
+    let storage_scope = storage_scope.unwrap_or_default();
     let ((storage_reader, storage_writer), _temp_dir) = get_test_storage_by_scope(storage_scope);
-    let config = get_test_rpc_config();
-    let mock_client_arc = Arc::new(mock_client);
     (
-        T::new(
-            config.chain_id,
-            config.execution_config,
-            storage_reader,
-            config.max_events_chunk_size,
-            config.max_events_keys,
-            BlockHashAndNumber::default(),
+        get_test_rpc_server_impl_from_storage(
+            mock_client,
             shared_highest_block,
             pending_data,
             pending_classes,
-            mock_client_arc,
-        )
-        .into_rpc_module(),
+            storage_reader,
+        ),
         storage_writer,
     )
 }
 
+// Returns a server that reads from the given storage, for tests that need a storage with a
+// non-default config.
+pub(crate) fn get_test_rpc_server_impl_from_storage<T: JsonRpcServerTrait>(
+    mock_client: Option<MockStarknetWriter>,
+    shared_highest_block: Option<Arc<RwLock<Option<BlockHashAndNumber>>>>,
+    pending_data: Option<Arc<RwLock<PendingData>>>,
+    pending_classes: Option<Arc<RwLock<PendingClasses>>>,
+    storage_reader: StorageReader,
+) -> T {
+    let mock_client = mock_client.unwrap_or_default();
+    let shared_highest_block = shared_highest_block.unwrap_or(get_test_highest_block());
+    let pending_data = pending_data.unwrap_or(get_test_pending_data());
+    let pending_classes = pending_classes.unwrap_or(get_test_pending_classes());
+
+    let config = get_test_rpc_config();
+    let mock_client_arc = Arc::new(mock_client);
+    T::new(
+        config.chain_id,
+        config.execution_config,
+        storage_reader,
+        config.max_events_chunk_size,
+        config.max_events_keys,
+        BlockHashAndNumber::default(),
+        shared_highest_block,
+        pending_data,
+        pending_classes,
+        mock_client_arc,
+    )
+}
+
 // Call a method on the `RPC module` without having to spin up a server.
 // Returns the raw `result field` in JSON-RPC response and the deserialized result if successful.
 // `params_obj` should be serialized to the format that JSON-RPC expects, which is either an array
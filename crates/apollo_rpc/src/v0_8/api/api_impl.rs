@@ -1,6 +1,9 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use apollo_rpc_execution::execution_utils::stored_txn_to_executable_txn;
 use apollo_rpc_execution::objects::{FeeEstimation, PendingData as ExecutionPendingData};
+use apollo_rpc_execution::state_overrides::StateOverrides;
 use apollo_rpc_execution::{
     estimate_fee as exec_estimate_fee,
     execute_call,
@@ -8,6 +11,8 @@ use apollo_rpc_execution::{
     simulate_transactions as exec_simulate_transactions,
     ExecutableTransactionInput,
     ExecutionConfig,
+    ExecutionError,
+    TraceOptions,
 };
 use apollo_starknet_client::reader::objects::pending_data::{
     DeprecatedPendingBlock,
@@ -22,6 +27,7 @@ use apollo_starknet_client::reader::PendingData;
 use apollo_starknet_client::writer::{StarknetWriter, WriterClientError};
 use apollo_starknet_client::ClientError;
 use apollo_storage::body::events::{EventIndex, EventsReader};
+use apollo_storage::body::events_index::EventsIndexReader;
 use apollo_storage::body::{BodyStorageReader, TransactionIndex};
 use apollo_storage::compiled_class::CasmStorageReader;
 use apollo_storage::db::{TransactionKind, RO};
@@ -55,6 +61,7 @@ use starknet_api::transaction::fields::Fee;
 use starknet_api::transaction::{
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     Transaction as StarknetApiTransaction,
     TransactionHash,
     TransactionOffsetInBlock,
@@ -126,7 +133,6 @@ use super::super::write_api_result::{
 };
 use super::{
     execution_error_to_error_object_owned,
-    stored_txn_to_executable_txn,
     BlockHashAndNumber,
     BlockId,
     CallRequest,
@@ -738,46 +744,92 @@ impl JsonRpcServer for JsonRpcServerImpl {
         // pointing to the next relevant event. Otherwise, we return a continuation token None.
         let mut filtered_events = vec![];
         if start_event_index.0.0 <= latest_block_number {
-            for ((from_address, event_index), content) in txn
-                .iter_events(filter.address, start_event_index, to_block_number)
-                .map_err(internal_server_error)?
-            {
-                let block_number = (event_index.0).0;
-                if block_number > to_block_number {
-                    break;
+            // The events indexes are used only if all the requested blocks are indexed. Requests
+            // with an address are served by the events table, which is keyed by the address.
+            let event_index_marker = txn.get_event_index_marker().map_err(internal_server_error)?;
+            let use_events_index = filter.address.is_none() && to_block_number < event_index_marker;
+            let first_keys = filter
+                .keys
+                .first()
+                .filter(|first_keys| use_events_index && !first_keys.is_empty())
+                .map(|first_keys| first_keys.iter().cloned().collect::<Vec<_>>());
+            let use_events_bloom = use_events_index
+                && first_keys.is_none()
+                && filter.keys.iter().any(|keys| !keys.is_empty());
+
+            // The iteration restarts from the next block whenever the bloom filter of a block
+            // shows that none of its events match.
+            let mut next_start_event_index = Some(start_event_index);
+            while let Some(iteration_start_event_index) = next_start_event_index.take() {
+                let events_iter = match &first_keys {
+                    Some(first_keys) => txn.iter_events_by_first_keys(
+                        first_keys.clone(),
+                        iteration_start_event_index,
+                        to_block_number,
+                    ),
+                    None => txn.iter_events(
+                        filter.address,
+                        iteration_start_event_index,
+                        to_block_number,
+                    ),
                 }
-                if let Some(filter_address) = filter.address {
-                    if from_address != filter_address {
-                        // The iterator of this loop outputs only events that have the filter's
-                        // address, unless there are no more such events and then it outputs other
-                        // events, and we can stop the iteration.
+                .map_err(internal_server_error)?;
+                let mut last_checked_block_number = None;
+                for ((from_address, event_index), content) in events_iter {
+                    let block_number = (event_index.0).0;
+                    if block_number > to_block_number {
                         break;
                     }
-                }
-                // TODO(Shahak): Consider changing empty sets in the filer keys to None.
-                if do_event_keys_match_filter(&content, &filter) {
-                    if filtered_events.len() == filter.chunk_size {
-                        return Ok(EventsChunk {
-                            events: filtered_events,
-                            continuation_token: Some(ContinuationToken::new(
-                                ContinuationTokenAsStruct(event_index),
-                            )?),
-                        });
+                    if use_events_bloom && last_checked_block_number != Some(block_number) {
+                        last_checked_block_number = Some(block_number);
+                        let may_match = txn
+                            .get_events_bloom(block_number)
+                            .map_err(internal_server_error)?
+                            .is_none_or(|bloom| bloom.may_match(None, &filter.keys));
+                        if !may_match {
+                            next_start_event_index = Some(EventIndex(
+                                TransactionIndex(
+                                    block_number.unchecked_next(),
+                                    TransactionOffsetInBlock(0),
+                                ),
+                                EventIndexInTransactionOutput(0),
+                            ));
+                            break;
+                        }
+                    }
+                    if let Some(filter_address) = filter.address {
+                        if from_address != filter_address {
+                            // The iterator of this loop outputs only events that have the filter's
+                            // address, unless there are no more such events and then it outputs
+                            // other events, and we can stop the iteration.
+                            break;
+                        }
+                    }
+                    // TODO(Shahak): Consider changing empty sets in the filer keys to None.
+                    if do_event_keys_match_filter(&content, &filter.keys) {
+                        if filtered_events.len() == filter.chunk_size {
+                            return Ok(EventsChunk {
+                                events: filtered_events,
+                                continuation_token: Some(ContinuationToken::new(
+                                    ContinuationTokenAsStruct(event_index),
+                                )?),
+                            });
+                        }
+                        let header: BlockHeader = get_block_header_by_number(&txn, block_number)
+                            .map_err(internal_server_error)?
+                            .into();
+                        let transaction_hash = txn
+                            .get_transaction_hash_by_idx(&event_index.0)
+                            .map_err(internal_server_error)?
+                            .ok_or_else(|| internal_server_error("Unknown internal error."))?;
+                        let emitted_event = Event {
+                            block_hash: Some(header.block_hash),
+                            block_number: Some(block_number),
+                            transaction_hash,
+                            event: starknet_api::transaction::Event { from_address, content },
+                        };
+                        filtered_events.push(emitted_event);
                     }
-                    let header: BlockHeader = get_block_header_by_number(&txn, block_number)
-                        .map_err(internal_server_error)?
-                        .into();
-                    let transaction_hash = txn
-                        .get_transaction_hash_by_idx(&event_index.0)
-                        .map_err(internal_server_error)?
-                        .ok_or_else(|| internal_server_error("Unknown internal error."))?;
-                    let emitted_event = Event {
-                        block_hash: Some(header.block_hash),
-                        block_number: Some(block_number),
-                        transaction_hash,
-                        event: starknet_api::transaction::Event { from_address, content },
-                    };
-                    filtered_events.push(emitted_event);
                 }
             }
         }
@@ -814,7 +866,7 @@ impl JsonRpcServer for JsonRpcServerImpl {
                             )?),
                         });
                     }
-                    if !do_event_keys_match_filter(&event.content, &filter) {
+                    if !do_event_keys_match_filter(&event.content, &filter.keys) {
                         continue;
                     }
                     if let Some(filter_address) = filter.address {
@@ -857,52 +909,7 @@ impl JsonRpcServer for JsonRpcServerImpl {
 
     #[instrument(skip(self), level = "debug", err, ret)]
     async fn call(&self, request: CallRequest, block_id: BlockId) -> RpcResult<Vec<Felt>> {
-        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
-        let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
-            Some(client_pending_data_to_execution_pending_data(
-                read_pending_data(&self.pending_data, &txn).await?,
-                self.pending_classes.read().await.clone(),
-            ))
-        } else {
-            None
-        };
-        let block_number = get_accepted_block_number(&txn, block_id)?;
-        let block_not_reverted_validator = BlockNotRevertedValidator::new(block_number, &txn)?;
-        drop(txn);
-        let state_number = StateNumber::unchecked_right_after_block(block_number);
-        let execution_config = self.execution_config;
-
-        let chain_id = self.chain_id.clone();
-        let reader = self.storage_reader.clone();
-        let contract_address_copy = request.contract_address;
-
-        let res = tokio::task::spawn_blocking(move || {
-            execute_call(
-                reader,
-                maybe_pending_data,
-                &chain_id,
-                state_number,
-                block_number,
-                &contract_address_copy,
-                request.entry_point_selector,
-                request.calldata,
-                &execution_config,
-                DONT_IGNORE_L1_DA_MODE,
-            )
-        })
-        .await
-        .map_err(internal_server_error)?
-        .map_err(execution_error_to_error_object_owned)?;
-
-        if res.failed {
-            let contract_err = ContractError { revert_error: format_panic_data(&res.retdata.0) };
-            let rpc_err: JsonRpcError<ContractError> = contract_err.into();
-            return Err(rpc_err.into());
-        }
-
-        block_not_reverted_validator.validate(&self.storage_reader)?;
-
-        Ok(res.retdata.0)
+        self.call_with_state_overrides(request, block_id, StateOverrides::default()).await
     }
 
     #[instrument(skip(self), level = "debug", err, ret)]
@@ -965,78 +972,181 @@ impl JsonRpcServer for JsonRpcServerImpl {
         simulation_flags: Vec<SimulationFlag>,
         block_id: BlockId,
     ) -> RpcResult<Vec<FeeEstimation>> {
-        trace!("Estimating fee of transactions: {:#?}", transactions);
-        let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
+        self.estimate_fee_with_state_overrides(
+            transactions,
+            simulation_flags,
+            block_id,
+            StateOverrides::default(),
+        )
+        .await
+    }
+
+    #[instrument(skip(self, transactions), level = "debug", err, ret)]
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.simulate_transactions_with_trace_flags(
+            block_id,
+            transactions,
+            simulation_flags,
+            TraceOptions::default(),
+            StateOverrides::default(),
+        )
+        .await
+    }
+
+    #[instrument(skip(self), level = "debug", err)]
+    async fn trace_transaction(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<TransactionTrace> {
+        self.trace_transaction_with_trace_flags(transaction_hash, TraceOptions::default()).await
+    }
 
+    #[instrument(skip(self), level = "debug", err)]
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<TransactionTraceWithHash>> {
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
 
-        let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
-            Some(client_pending_data_to_execution_pending_data(
-                read_pending_data(&self.pending_data, &storage_txn).await?,
-                self.pending_classes.read().await.clone(),
-            ))
+        let maybe_client_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
+            Some(read_pending_data(&self.pending_data, &storage_txn).await?)
         } else {
             None
         };
 
-        let executable_txns =
-            transactions.into_iter().map(|tx| tx.try_into()).collect::<Result<_, _>>()?;
-
         let block_number = get_accepted_block_number(&storage_txn, block_id)?;
+
         let block_not_reverted_validator =
             BlockNotRevertedValidator::new(block_number, &storage_txn)?;
+
+        let (maybe_pending_data, block_transactions, transaction_hashes, state_number) =
+            match maybe_client_pending_data {
+                Some(client_pending_data) => (
+                    Some(ExecutionPendingData {
+                        timestamp: client_pending_data.block.timestamp(),
+                        l1_gas_price: client_pending_data.block.l1_gas_price(),
+                        l1_data_gas_price: client_pending_data.block.l1_data_gas_price(),
+                        l2_gas_price: client_pending_data.block.l2_gas_price(),
+                        l1_da_mode: client_pending_data.block.l1_da_mode(),
+                        sequencer: client_pending_data.block.sequencer_address(),
+                        // The pending state diff should be empty since we look at the state in the
+                        // start of the pending block.
+                        // Not using ..Default::default() to avoid missing fields in the future.
+                        storage_diffs: Default::default(),
+                        deployed_contracts: Default::default(),
+                        declared_classes: Default::default(),
+                        old_declared_contracts: Default::default(),
+                        nonces: Default::default(),
+                        replaced_classes: Default::default(),
+                        classes: Default::default(),
+                    }),
+                    client_pending_data
+                        .block
+                        .transactions()
+                        .iter()
+                        .map(|client_transaction| {
+                            client_transaction.clone().try_into().map_err(internal_server_error)
+                        })
+                        .collect::<Result<Vec<_>, ErrorObjectOwned>>()?,
+                    client_pending_data
+                        .block
+                        .transaction_receipts()
+                        .iter()
+                        .map(|receipt| receipt.transaction_hash)
+                        .collect(),
+                    StateNumber::unchecked_right_after_block(block_number),
+                ),
+                None => (
+                    None,
+                    storage_txn
+                        .get_block_transactions(block_number)
+                        .map_err(internal_server_error)?
+                        .ok_or_else(|| {
+                            internal_server_error(StorageError::DBInconsistency {
+                                msg: format!("Missing block {block_number} transactions"),
+                            })
+                        })?,
+                    storage_txn
+                        .get_block_transaction_hashes(block_number)
+                        .map_err(internal_server_error)?
+                        .ok_or_else(|| {
+                            internal_server_error(StorageError::DBInconsistency {
+                                msg: format!("Missing block {block_number} transactions"),
+                            })
+                        })?,
+                    StateNumber::right_before_block(block_number),
+                ),
+            };
+
+        let executable_txns = block_transactions
+            .into_iter()
+            .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
+            .collect::<Result<_, _>>()
+            .map_err(execution_error_to_error_object_owned)?;
+
         drop(storage_txn);
-        let state_number = StateNumber::unchecked_right_after_block(block_number);
+
         let execution_config = self.execution_config;
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
+        let transaction_hashes_clone = transaction_hashes.clone();
 
-        let estimate_fee_result = tokio::task::spawn_blocking(move || {
-            exec_estimate_fee(
+        let simulation_results = tokio::task::spawn_blocking(move || {
+            exec_simulate_transactions(
                 executable_txns,
+                Some(transaction_hashes_clone),
                 &chain_id,
                 reader,
                 maybe_pending_data,
                 state_number,
                 block_number,
                 &execution_config,
-                validate,
+                true,
+                true,
                 DONT_IGNORE_L1_DA_MODE,
+                TraceOptions::default(),
+                StateOverrides::default(),
             )
         })
         .await
-        .map_err(internal_server_error)?;
+        .map_err(internal_server_error)?
+        .map_err(execution_error_to_error_object_owned)?;
 
         block_not_reverted_validator.validate(&self.storage_reader)?;
 
-        match estimate_fee_result {
-            Ok(Ok(fees)) => Ok(fees),
-            Ok(Err(reverted_tx)) => {
-                Err(ErrorObjectOwned::from(JsonRpcError::<TransactionExecutionError>::from(
-                    TransactionExecutionError {
-                        transaction_index: reverted_tx.index,
-                        execution_error: reverted_tx.revert_reason,
-                    },
-                )))
-            }
-            Err(err) => Err(internal_server_error(err)),
+        let mut res = vec![];
+        for (simulation_output, transaction_hash) in
+            simulation_results.into_iter().zip(transaction_hashes)
+        {
+            let state_diff = self
+                .convert_thin_state_diff(
+                    simulation_output.induced_state_diff,
+                    block_id,
+                    block_number,
+                )
+                .await?;
+            res.push(TransactionTraceWithHash {
+                transaction_hash,
+                trace_root: (simulation_output.transaction_trace, state_diff).into(),
+            });
         }
+        Ok(res)
     }
 
-    #[instrument(skip(self, transactions), level = "debug", err, ret)]
-    async fn simulate_transactions(
+    #[instrument(skip(self, message), level = "debug", err)]
+    async fn estimate_message_fee(
         &self,
+        message: MessageFromL1,
         block_id: BlockId,
-        transactions: Vec<BroadcastedTransaction>,
-        simulation_flags: Vec<SimulationFlag>,
-    ) -> RpcResult<Vec<SimulatedTransaction>> {
-        trace!("Simulating transactions: {:#?}", transactions);
-        let executable_txns =
-            transactions.into_iter().map(|tx| tx.try_into()).collect::<Result<_, _>>()?;
-
+    ) -> RpcResult<FeeEstimation> {
+        trace!("Estimating fee of message: {:#?}", message);
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
-
         let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
             Some(client_pending_data_to_execution_pending_data(
                 read_pending_data(&self.pending_data, &storage_txn).await?,
@@ -1045,6 +1155,12 @@ impl JsonRpcServer for JsonRpcServerImpl {
         } else {
             None
         };
+        // Convert the message to an L1 handler transaction, and estimate the fee of the
+        // transaction.
+        // The fee input is used to bound the amount of fee used. Because we want to estimate the
+        // fee, we pass u128::MAX so the execution won't fail.
+        let executable_txns =
+            vec![ExecutableTransactionInput::L1Handler(message.into(), Fee(u128::MAX), false)];
 
         let block_number = get_accepted_block_number(&storage_txn, block_id)?;
         let block_not_reverted_validator =
@@ -1056,336 +1172,282 @@ impl JsonRpcServer for JsonRpcServerImpl {
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
 
-        let charge_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge);
-        let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
-
-        let simulation_results = tokio::task::spawn_blocking(move || {
-            exec_simulate_transactions(
+        let estimate_fee_result = tokio::task::spawn_blocking(move || {
+            exec_estimate_fee(
                 executable_txns,
-                None,
                 &chain_id,
                 reader,
                 maybe_pending_data,
                 state_number,
                 block_number,
                 &execution_config,
-                charge_fee,
-                validate,
+                false,
                 DONT_IGNORE_L1_DA_MODE,
+                StateOverrides::default(),
             )
         })
         .await
-        .map_err(internal_server_error)?
-        .map_err(execution_error_to_error_object_owned)?;
+        .map_err(internal_server_error)?;
 
         block_not_reverted_validator.validate(&self.storage_reader)?;
 
-        let mut res = vec![];
-        for simulation_output in simulation_results {
-            let state_diff = self
-                .convert_thin_state_diff(
-                    simulation_output.induced_state_diff,
-                    block_id,
-                    block_number,
-                )
-                .await?;
-            res.push(SimulatedTransaction {
-                transaction_trace: (simulation_output.transaction_trace, state_diff).into(),
-                fee_estimation: simulation_output.fee_estimation,
-            });
+        match estimate_fee_result {
+            Ok(Ok(mut fee_as_vec)) => {
+                if fee_as_vec.len() != 1 {
+                    return Err(internal_server_error(format!(
+                        "Expected a single fee, got {}",
+                        fee_as_vec.len()
+                    )));
+                }
+                let Some(fee_estimation) = fee_as_vec.pop() else {
+                    return Err(internal_server_error(
+                        "Expected a single fee, got an empty vector",
+                    ));
+                };
+                Ok(fee_estimation)
+            }
+            // Error in the execution of the contract.
+            Ok(Err(reverted_tx)) => Err(JsonRpcError::<ContractError>::from(ContractError {
+                revert_error: reverted_tx.revert_reason,
+            })
+            .into()),
+            // Internal error during the execution.
+            Err(err) => Err(internal_server_error(err)),
         }
-        Ok(res)
     }
 
     #[instrument(skip(self), level = "debug", err)]
-    async fn trace_transaction(
+    fn get_compiled_class(
         &self,
-        transaction_hash: TransactionHash,
-    ) -> RpcResult<TransactionTrace> {
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> RpcResult<(CompiledContractClass, SierraVersion)> {
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let state_reader = storage_txn.get_state_reader().map_err(internal_server_error)?;
+        let block_number = get_accepted_block_number(&storage_txn, block_id)?;
 
-        let pending_block = read_pending_data(&self.pending_data, &storage_txn).await?.block;
-        // Search for the transaction inside the pending block.
-        let (
-            maybe_pending_data,
-            executable_transactions,
-            transaction_hashes,
-            block_number,
-            state_number,
-        ) = if let Some((pending_transaction_offset, _)) = pending_block
-            .transaction_receipts()
-            .iter()
-            .enumerate()
-            .find(|(_, receipt)| receipt.transaction_hash == transaction_hash)
+        // Check if this class exists in the Cairo1 classes table.
+        if let Some(class_definition_block_number) = state_reader
+            .get_class_definition_block_number(&class_hash)
+            .map_err(internal_server_error)?
         {
-            // If there are no blocks in the network and there is a pending block, as an edge
-            // case we treat this as if the pending block is empty.
-            let block_number =
-                get_latest_block_number(&storage_txn)?.ok_or(INVALID_TRANSACTION_HASH)?;
-            let state_number = StateNumber::unchecked_right_after_block(block_number);
-            let executable_transactions = pending_block
-                .transactions()
-                .iter()
-                .take(pending_transaction_offset + 1)
-                .map(|client_transaction| {
-                    let starknet_api_transaction: StarknetApiTransaction =
-                        client_transaction.clone().try_into().map_err(internal_server_error)?;
-                    stored_txn_to_executable_txn(
-                        starknet_api_transaction,
-                        &storage_txn,
-                        state_number,
-                    )
-                })
-                .collect::<Result<_, _>>()?;
-            let transaction_hashes = pending_block
-                .transaction_receipts()
-                .iter()
-                .map(|receipt| receipt.transaction_hash)
-                .collect();
-            let maybe_pending_data = Some(ExecutionPendingData {
-                timestamp: pending_block.timestamp(),
-                l1_gas_price: pending_block.l1_gas_price(),
-                l1_data_gas_price: pending_block.l1_data_gas_price(),
-                l2_gas_price: pending_block.l2_gas_price(),
-                l1_da_mode: pending_block.l1_da_mode(),
-                sequencer: pending_block.sequencer_address(),
-                // The pending state diff should be empty since we look at the state in the
-                // start of the pending block.
-                // Not using ..Default::default() to avoid missing fields in the future.
-                storage_diffs: Default::default(),
-                deployed_contracts: Default::default(),
-                declared_classes: Default::default(),
-                old_declared_contracts: Default::default(),
-                nonces: Default::default(),
-                replaced_classes: Default::default(),
-                classes: Default::default(),
-            });
-            (
-                maybe_pending_data,
-                executable_transactions,
-                transaction_hashes,
-                block_number,
-                state_number,
-            )
-        } else {
-            // Transaction is not inside the pending block. Search for it in the storage.
-            let TransactionIndex(block_number, tx_offset) = storage_txn
-                .get_transaction_idx_by_hash(&transaction_hash)
-                .map_err(internal_server_error)?
-                .ok_or(TRANSACTION_HASH_NOT_FOUND)?;
+            if class_definition_block_number > block_number {
+                return Err(ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND));
+            }
+            let (option_casm, option_sierra) = storage_txn
+                .get_casm_and_sierra(&class_hash)
+                .map_err(internal_server_error_with_msg)?;
 
-            let block_transactions = storage_txn
-                .get_block_transactions(block_number)
-                .map_err(internal_server_error)?
-                .ok_or_else(|| {
-                    internal_server_error(StorageError::DBInconsistency {
-                        msg: format!("Missing block {block_number} transactions"),
-                    })
-                })?;
+            // Check if both options are `Some`.
+            let (casm, sierra) = option_casm
+                .zip(option_sierra)
+                .ok_or_else(|| ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND))?;
+            let sierra_version = SierraVersion::extract_from_program(&sierra.sierra_program)
+                .map_err(internal_server_error_with_msg)?;
+            return Ok((CompiledContractClass::V1(casm), sierra_version));
+        }
 
-            let transaction_hashes = storage_txn
-                .get_block_transaction_hashes(block_number)
-                .map_err(internal_server_error)?
-                .ok_or_else(|| {
-                    internal_server_error(StorageError::DBInconsistency {
-                        msg: format!("Missing block {block_number} transactions"),
-                    })
-                })?;
+        // Check if this class exists in the Cairo0 classes table.
+        let state_number = StateNumber::right_after_block(block_number)
+            .ok_or_else(|| internal_server_error("Could not compute state number"))?;
+        let deprecated_compiled_contract_class = state_reader
+            .get_deprecated_class_definition_at(state_number, &class_hash)
+            .map_err(internal_server_error)?
+            .ok_or_else(|| ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND))?;
+        Ok((
+            CompiledContractClass::V0(deprecated_compiled_contract_class),
+            SierraVersion::DEPRECATED,
+        ))
+    }
+}
 
-            let state_number = StateNumber::right_before_block(block_number);
-            let executable_transactions = block_transactions
-                .into_iter()
-                .take(tx_offset.0 + 1)
-                .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
-                .collect::<Result<_, _>>()?;
+async fn read_pending_data<Mode: TransactionKind>(
+    pending_data: &Arc<RwLock<PendingData>>,
+    txn: &StorageTxn<'_, Mode>,
+) -> RpcResult<PendingData> {
+    let latest_header = match get_latest_block_number(txn)? {
+        Some(latest_block_number) => get_block_header_by_number(txn, latest_block_number)?,
+        None => starknet_api::block::BlockHeader {
+            block_header_without_hash: BlockHeaderWithoutHash {
+                parent_hash: BlockHash(StarkHash::from_hex_unchecked(GENESIS_HASH)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    };
+    let pending_data = &pending_data.read().await;
+    if pending_data.block.parent_block_hash() == latest_header.block_hash {
+        Ok((*pending_data).clone())
+    } else {
+        Ok(PendingData {
+            block: PendingBlockOrDeprecated::Deprecated(DeprecatedPendingBlock {
+                parent_block_hash: latest_header.block_hash,
+                eth_l1_gas_price: latest_header.block_header_without_hash.l1_gas_price.price_in_wei,
+                strk_l1_gas_price: latest_header
+                    .block_header_without_hash
+                    .l1_gas_price
+                    .price_in_fri,
+                timestamp: latest_header.block_header_without_hash.timestamp,
+                sequencer_address: latest_header.block_header_without_hash.sequencer,
+                starknet_version: latest_header
+                    .block_header_without_hash
+                    .starknet_version
+                    .to_string(),
+                ..Default::default()
+            }),
+            state_update: ClientPendingStateUpdate {
+                old_root: latest_header.block_header_without_hash.state_root,
+                state_diff: Default::default(),
+            },
+        })
+    }
+}
 
-            (None, executable_transactions, transaction_hashes, block_number, state_number)
+impl JsonRpcServerImpl {
+    /// Executes the call like `starknet_call`, on top of the state with the given overrides.
+    pub(crate) async fn call_with_state_overrides(
+        &self,
+        request: CallRequest,
+        block_id: BlockId,
+        state_overrides: StateOverrides,
+    ) -> RpcResult<Vec<Felt>> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
+            Some(client_pending_data_to_execution_pending_data(
+                read_pending_data(&self.pending_data, &txn).await?,
+                self.pending_classes.read().await.clone(),
+            ))
+        } else {
+            None
         };
-
-        let block_not_reverted_validator =
-            BlockNotRevertedValidator::new(block_number, &storage_txn)?;
-
-        drop(storage_txn);
-
+        let block_number = get_accepted_block_number(&txn, block_id)?;
+        let block_not_reverted_validator = BlockNotRevertedValidator::new(block_number, &txn)?;
+        drop(txn);
+        let state_number = StateNumber::unchecked_right_after_block(block_number);
         let execution_config = self.execution_config;
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
+        let contract_address_copy = request.contract_address;
 
-        let is_pending = maybe_pending_data.is_some();
-        let mut simulation_results = tokio::task::spawn_blocking(move || {
-            exec_simulate_transactions(
-                executable_transactions,
-                Some(transaction_hashes),
-                &chain_id,
+        let res = tokio::task::spawn_blocking(move || {
+            execute_call(
                 reader,
                 maybe_pending_data,
+                &chain_id,
                 state_number,
                 block_number,
+                &contract_address_copy,
+                request.entry_point_selector,
+                request.calldata,
                 &execution_config,
-                true,
-                true,
                 DONT_IGNORE_L1_DA_MODE,
+                state_overrides,
             )
         })
         .await
         .map_err(internal_server_error)?
         .map_err(execution_error_to_error_object_owned)?;
 
-        block_not_reverted_validator.validate(&self.storage_reader)?;
+        if res.failed {
+            let contract_err = ContractError { revert_error: format_panic_data(&res.retdata.0) };
+            let rpc_err: JsonRpcError<ContractError> = contract_err.into();
+            return Err(rpc_err.into());
+        }
 
-        let simulation_result =
-            simulation_results.pop().expect("Should have transaction exeuction result");
+        block_not_reverted_validator.validate(&self.storage_reader)?;
 
-        let block_id = if is_pending {
-            BlockId::Tag(Tag::Pending)
-        } else {
-            BlockId::HashOrNumber(BlockHashOrNumber::Number(block_number))
-        };
-        let state_diff = self
-            .convert_thin_state_diff(simulation_result.induced_state_diff, block_id, block_number)
-            .await?;
-        Ok((simulation_result.transaction_trace, state_diff).into())
+        Ok(res.retdata.0)
     }
 
-    #[instrument(skip(self), level = "debug", err)]
-    async fn trace_block_transactions(
+    /// Estimates the fee of the transactions like `starknet_estimateFee`, executing them on top of
+    /// the state with the given overrides.
+    pub(crate) async fn estimate_fee_with_state_overrides(
         &self,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
         block_id: BlockId,
-    ) -> RpcResult<Vec<TransactionTraceWithHash>> {
+        state_overrides: StateOverrides,
+    ) -> RpcResult<Vec<FeeEstimation>> {
+        trace!("Estimating fee of transactions: {:#?}", transactions);
+        let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
+
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
 
-        let maybe_client_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
-            Some(read_pending_data(&self.pending_data, &storage_txn).await?)
+        let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
+            Some(client_pending_data_to_execution_pending_data(
+                read_pending_data(&self.pending_data, &storage_txn).await?,
+                self.pending_classes.read().await.clone(),
+            ))
         } else {
             None
         };
 
-        let block_number = get_accepted_block_number(&storage_txn, block_id)?;
+        let executable_txns =
+            transactions.into_iter().map(|tx| tx.try_into()).collect::<Result<_, _>>()?;
 
+        let block_number = get_accepted_block_number(&storage_txn, block_id)?;
         let block_not_reverted_validator =
             BlockNotRevertedValidator::new(block_number, &storage_txn)?;
-
-        let (maybe_pending_data, block_transactions, transaction_hashes, state_number) =
-            match maybe_client_pending_data {
-                Some(client_pending_data) => (
-                    Some(ExecutionPendingData {
-                        timestamp: client_pending_data.block.timestamp(),
-                        l1_gas_price: client_pending_data.block.l1_gas_price(),
-                        l1_data_gas_price: client_pending_data.block.l1_data_gas_price(),
-                        l2_gas_price: client_pending_data.block.l2_gas_price(),
-                        l1_da_mode: client_pending_data.block.l1_da_mode(),
-                        sequencer: client_pending_data.block.sequencer_address(),
-                        // The pending state diff should be empty since we look at the state in the
-                        // start of the pending block.
-                        // Not using ..Default::default() to avoid missing fields in the future.
-                        storage_diffs: Default::default(),
-                        deployed_contracts: Default::default(),
-                        declared_classes: Default::default(),
-                        old_declared_contracts: Default::default(),
-                        nonces: Default::default(),
-                        replaced_classes: Default::default(),
-                        classes: Default::default(),
-                    }),
-                    client_pending_data
-                        .block
-                        .transactions()
-                        .iter()
-                        .map(|client_transaction| {
-                            client_transaction.clone().try_into().map_err(internal_server_error)
-                        })
-                        .collect::<Result<Vec<_>, ErrorObjectOwned>>()?,
-                    client_pending_data
-                        .block
-                        .transaction_receipts()
-                        .iter()
-                        .map(|receipt| receipt.transaction_hash)
-                        .collect(),
-                    StateNumber::unchecked_right_after_block(block_number),
-                ),
-                None => (
-                    None,
-                    storage_txn
-                        .get_block_transactions(block_number)
-                        .map_err(internal_server_error)?
-                        .ok_or_else(|| {
-                            internal_server_error(StorageError::DBInconsistency {
-                                msg: format!("Missing block {block_number} transactions"),
-                            })
-                        })?,
-                    storage_txn
-                        .get_block_transaction_hashes(block_number)
-                        .map_err(internal_server_error)?
-                        .ok_or_else(|| {
-                            internal_server_error(StorageError::DBInconsistency {
-                                msg: format!("Missing block {block_number} transactions"),
-                            })
-                        })?,
-                    StateNumber::right_before_block(block_number),
-                ),
-            };
-
-        let executable_txns = block_transactions
-            .into_iter()
-            .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
-            .collect::<Result<_, _>>()?;
-
         drop(storage_txn);
-
+        let state_number = StateNumber::unchecked_right_after_block(block_number);
         let execution_config = self.execution_config;
 
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
-        let transaction_hashes_clone = transaction_hashes.clone();
 
-        let simulation_results = tokio::task::spawn_blocking(move || {
-            exec_simulate_transactions(
+        let estimate_fee_result = tokio::task::spawn_blocking(move || {
+            exec_estimate_fee(
                 executable_txns,
-                Some(transaction_hashes_clone),
                 &chain_id,
                 reader,
                 maybe_pending_data,
                 state_number,
                 block_number,
                 &execution_config,
-                true,
-                true,
+                validate,
                 DONT_IGNORE_L1_DA_MODE,
+                state_overrides,
             )
         })
         .await
-        .map_err(internal_server_error)?
-        .map_err(execution_error_to_error_object_owned)?;
+        .map_err(internal_server_error)?;
 
         block_not_reverted_validator.validate(&self.storage_reader)?;
 
-        let mut res = vec![];
-        for (simulation_output, transaction_hash) in
-            simulation_results.into_iter().zip(transaction_hashes)
-        {
-            let state_diff = self
-                .convert_thin_state_diff(
-                    simulation_output.induced_state_diff,
-                    block_id,
-                    block_number,
-                )
-                .await?;
-            res.push(TransactionTraceWithHash {
-                transaction_hash,
-                trace_root: (simulation_output.transaction_trace, state_diff).into(),
-            });
+        match estimate_fee_result {
+            Ok(Ok(fees)) => Ok(fees),
+            Ok(Err(reverted_tx)) => {
+                Err(ErrorObjectOwned::from(JsonRpcError::<TransactionExecutionError>::from(
+                    TransactionExecutionError {
+                        transaction_index: reverted_tx.index,
+                        execution_error: reverted_tx.revert_reason,
+                    },
+                )))
+            }
+            Err(err @ ExecutionError::ConflictingStateOverrides { .. }) => {
+                Err(execution_error_to_error_object_owned(err))
+            }
+            Err(err) => Err(internal_server_error(err)),
         }
-        Ok(res)
     }
 
-    #[instrument(skip(self, message), level = "debug", err)]
-    async fn estimate_message_fee(
+    /// Simulates the transactions like `starknet_simulateTransactions`, on top of the state with
+    /// the given overrides, adding the debugging data that `trace_options` requests to the traces.
+    pub(crate) async fn simulate_transactions_with_trace_flags(
         &self,
-        message: MessageFromL1,
         block_id: BlockId,
-    ) -> RpcResult<FeeEstimation> {
-        trace!("Estimating fee of message: {:#?}", message);
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        trace_options: TraceOptions,
+        state_overrides: StateOverrides,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        trace!("Simulating transactions: {:#?}", transactions);
+        let executable_txns =
+            transactions.into_iter().map(|tx| tx.try_into()).collect::<Result<_, _>>()?;
+
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+
         let maybe_pending_data = if let BlockId::Tag(Tag::Pending) = block_id {
             Some(client_pending_data_to_execution_pending_data(
                 read_pending_data(&self.pending_data, &storage_txn).await?,
@@ -1394,12 +1456,6 @@ impl JsonRpcServer for JsonRpcServerImpl {
         } else {
             None
         };
-        // Convert the message to an L1 handler transaction, and estimate the fee of the
-        // transaction.
-        // The fee input is used to bound the amount of fee used. Because we want to estimate the
-        // fee, we pass u128::MAX so the execution won't fail.
-        let executable_txns =
-            vec![ExecutableTransactionInput::L1Handler(message.into(), Fee(u128::MAX), false)];
 
         let block_number = get_accepted_block_number(&storage_txn, block_id)?;
         let block_not_reverted_validator =
@@ -1411,137 +1467,206 @@ impl JsonRpcServer for JsonRpcServerImpl {
         let chain_id = self.chain_id.clone();
         let reader = self.storage_reader.clone();
 
-        let estimate_fee_result = tokio::task::spawn_blocking(move || {
-            exec_estimate_fee(
+        let charge_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge);
+        let validate = !simulation_flags.contains(&SimulationFlag::SkipValidate);
+
+        let simulation_results = tokio::task::spawn_blocking(move || {
+            exec_simulate_transactions(
                 executable_txns,
+                None,
                 &chain_id,
                 reader,
                 maybe_pending_data,
                 state_number,
                 block_number,
                 &execution_config,
-                false,
+                charge_fee,
+                validate,
                 DONT_IGNORE_L1_DA_MODE,
+                trace_options,
+                state_overrides,
             )
         })
         .await
-        .map_err(internal_server_error)?;
+        .map_err(internal_server_error)?
+        .map_err(execution_error_to_error_object_owned)?;
 
         block_not_reverted_validator.validate(&self.storage_reader)?;
 
-        match estimate_fee_result {
-            Ok(Ok(mut fee_as_vec)) => {
-                if fee_as_vec.len() != 1 {
-                    return Err(internal_server_error(format!(
-                        "Expected a single fee, got {}",
-                        fee_as_vec.len()
-                    )));
-                }
-                let Some(fee_estimation) = fee_as_vec.pop() else {
-                    return Err(internal_server_error(
-                        "Expected a single fee, got an empty vector",
-                    ));
-                };
-                Ok(fee_estimation)
-            }
-            // Error in the execution of the contract.
-            Ok(Err(reverted_tx)) => Err(JsonRpcError::<ContractError>::from(ContractError {
-                revert_error: reverted_tx.revert_reason,
-            })
-            .into()),
-            // Internal error during the execution.
-            Err(err) => Err(internal_server_error(err)),
+        let mut res = vec![];
+        for simulation_output in simulation_results {
+            let state_diff = self
+                .convert_thin_state_diff(
+                    simulation_output.induced_state_diff,
+                    block_id,
+                    block_number,
+                )
+                .await?;
+            res.push(SimulatedTransaction {
+                transaction_trace: (simulation_output.transaction_trace, state_diff).into(),
+                fee_estimation: simulation_output.fee_estimation,
+            });
         }
+        Ok(res)
     }
 
-    #[instrument(skip(self), level = "debug", err)]
-    fn get_compiled_class(
+    /// Traces the transaction like `starknet_traceTransaction`, adding the debugging data that
+    /// `trace_options` requests to the trace.
+    pub(crate) async fn trace_transaction_with_trace_flags(
         &self,
-        block_id: BlockId,
-        class_hash: ClassHash,
-    ) -> RpcResult<(CompiledContractClass, SierraVersion)> {
+        transaction_hash: TransactionHash,
+        trace_options: TraceOptions,
+    ) -> RpcResult<TransactionTrace> {
         let storage_txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
-        let state_reader = storage_txn.get_state_reader().map_err(internal_server_error)?;
-        let block_number = get_accepted_block_number(&storage_txn, block_id)?;
 
-        // Check if this class exists in the Cairo1 classes table.
-        if let Some(class_definition_block_number) = state_reader
-            .get_class_definition_block_number(&class_hash)
-            .map_err(internal_server_error)?
+        let pending_block = read_pending_data(&self.pending_data, &storage_txn).await?.block;
+        // Search for the transaction inside the pending block.
+        let (
+            maybe_pending_data,
+            executable_transactions,
+            transaction_hashes,
+            block_number,
+            state_number,
+        ) = if let Some((pending_transaction_offset, _)) = pending_block
+            .transaction_receipts()
+            .iter()
+            .enumerate()
+            .find(|(_, receipt)| receipt.transaction_hash == transaction_hash)
         {
-            if class_definition_block_number > block_number {
-                return Err(ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND));
-            }
-            let (option_casm, option_sierra) = storage_txn
-                .get_casm_and_sierra(&class_hash)
-                .map_err(internal_server_error_with_msg)?;
+            // If there are no blocks in the network and there is a pending block, as an edge
+            // case we treat this as if the pending block is empty.
+            let block_number =
+                get_latest_block_number(&storage_txn)?.ok_or(INVALID_TRANSACTION_HASH)?;
+            let state_number = StateNumber::unchecked_right_after_block(block_number);
+            let executable_transactions = pending_block
+                .transactions()
+                .iter()
+                .take(pending_transaction_offset + 1)
+                .map(|client_transaction| {
+                    let starknet_api_transaction: StarknetApiTransaction =
+                        client_transaction.clone().try_into().map_err(internal_server_error)?;
+                    stored_txn_to_executable_txn(
+                        starknet_api_transaction,
+                        &storage_txn,
+                        state_number,
+                    )
+                    .map_err(execution_error_to_error_object_owned)
+                })
+                .collect::<Result<_, _>>()?;
+            let transaction_hashes = pending_block
+                .transaction_receipts()
+                .iter()
+                .map(|receipt| receipt.transaction_hash)
+                .collect();
+            let maybe_pending_data = Some(ExecutionPendingData {
+                timestamp: pending_block.timestamp(),
+                l1_gas_price: pending_block.l1_gas_price(),
+                l1_data_gas_price: pending_block.l1_data_gas_price(),
+                l2_gas_price: pending_block.l2_gas_price(),
+                l1_da_mode: pending_block.l1_da_mode(),
+                sequencer: pending_block.sequencer_address(),
+                // The pending state diff should be empty since we look at the state in the
+                // start of the pending block.
+                // Not using ..Default::default() to avoid missing fields in the future.
+                storage_diffs: Default::default(),
+                deployed_contracts: Default::default(),
+                declared_classes: Default::default(),
+                old_declared_contracts: Default::default(),
+                nonces: Default::default(),
+                replaced_classes: Default::default(),
+                classes: Default::default(),
+            });
+            (
+                maybe_pending_data,
+                executable_transactions,
+                transaction_hashes,
+                block_number,
+                state_number,
+            )
+        } else {
+            // Transaction is not inside the pending block. Search for it in the storage.
+            let TransactionIndex(block_number, tx_offset) = storage_txn
+                .get_transaction_idx_by_hash(&transaction_hash)
+                .map_err(internal_server_error)?
+                .ok_or(TRANSACTION_HASH_NOT_FOUND)?;
 
-            // Check if both options are `Some`.
-            let (casm, sierra) = option_casm
-                .zip(option_sierra)
-                .ok_or_else(|| ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND))?;
-            let sierra_version = SierraVersion::extract_from_program(&sierra.sierra_program)
-                .map_err(internal_server_error_with_msg)?;
-            return Ok((CompiledContractClass::V1(casm), sierra_version));
-        }
+            let block_transactions = storage_txn
+                .get_block_transactions(block_number)
+                .map_err(internal_server_error)?
+                .ok_or_else(|| {
+                    internal_server_error(StorageError::DBInconsistency {
+                        msg: format!("Missing block {block_number} transactions"),
+                    })
+                })?;
 
-        // Check if this class exists in the Cairo0 classes table.
-        let state_number = StateNumber::right_after_block(block_number)
-            .ok_or_else(|| internal_server_error("Could not compute state number"))?;
-        let deprecated_compiled_contract_class = state_reader
-            .get_deprecated_class_definition_at(state_number, &class_hash)
-            .map_err(internal_server_error)?
-            .ok_or_else(|| ErrorObjectOwned::from(CLASS_HASH_NOT_FOUND))?;
-        Ok((
-            CompiledContractClass::V0(deprecated_compiled_contract_class),
-            SierraVersion::DEPRECATED,
-        ))
-    }
-}
+            let transaction_hashes = storage_txn
+                .get_block_transaction_hashes(block_number)
+                .map_err(internal_server_error)?
+                .ok_or_else(|| {
+                    internal_server_error(StorageError::DBInconsistency {
+                        msg: format!("Missing block {block_number} transactions"),
+                    })
+                })?;
 
-async fn read_pending_data<Mode: TransactionKind>(
-    pending_data: &Arc<RwLock<PendingData>>,
-    txn: &StorageTxn<'_, Mode>,
-) -> RpcResult<PendingData> {
-    let latest_header = match get_latest_block_number(txn)? {
-        Some(latest_block_number) => get_block_header_by_number(txn, latest_block_number)?,
-        None => starknet_api::block::BlockHeader {
-            block_header_without_hash: BlockHeaderWithoutHash {
-                parent_hash: BlockHash(StarkHash::from_hex_unchecked(GENESIS_HASH)),
-                ..Default::default()
-            },
-            ..Default::default()
-        },
-    };
-    let pending_data = &pending_data.read().await;
-    if pending_data.block.parent_block_hash() == latest_header.block_hash {
-        Ok((*pending_data).clone())
-    } else {
-        Ok(PendingData {
-            block: PendingBlockOrDeprecated::Deprecated(DeprecatedPendingBlock {
-                parent_block_hash: latest_header.block_hash,
-                eth_l1_gas_price: latest_header.block_header_without_hash.l1_gas_price.price_in_wei,
-                strk_l1_gas_price: latest_header
-                    .block_header_without_hash
-                    .l1_gas_price
-                    .price_in_fri,
-                timestamp: latest_header.block_header_without_hash.timestamp,
-                sequencer_address: latest_header.block_header_without_hash.sequencer,
-                starknet_version: latest_header
-                    .block_header_without_hash
-                    .starknet_version
-                    .to_string(),
-                ..Default::default()
-            }),
-            state_update: ClientPendingStateUpdate {
-                old_root: latest_header.block_header_without_hash.state_root,
-                state_diff: Default::default(),
-            },
+            let state_number = StateNumber::right_before_block(block_number);
+            let executable_transactions = block_transactions
+                .into_iter()
+                .take(tx_offset.0 + 1)
+                .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
+                .collect::<Result<_, _>>()
+                .map_err(execution_error_to_error_object_owned)?;
+
+            (None, executable_transactions, transaction_hashes, block_number, state_number)
+        };
+
+        let block_not_reverted_validator =
+            BlockNotRevertedValidator::new(block_number, &storage_txn)?;
+
+        drop(storage_txn);
+
+        let execution_config = self.execution_config;
+
+        let chain_id = self.chain_id.clone();
+        let reader = self.storage_reader.clone();
+
+        let is_pending = maybe_pending_data.is_some();
+        let mut simulation_results = tokio::task::spawn_blocking(move || {
+            exec_simulate_transactions(
+                executable_transactions,
+                Some(transaction_hashes),
+                &chain_id,
+                reader,
+                maybe_pending_data,
+                state_number,
+                block_number,
+                &execution_config,
+                true,
+                true,
+                DONT_IGNORE_L1_DA_MODE,
+                trace_options,
+                StateOverrides::default(),
+            )
         })
+        .await
+        .map_err(internal_server_error)?
+        .map_err(execution_error_to_error_object_owned)?;
+
+        block_not_reverted_validator.validate(&self.storage_reader)?;
+
+        let simulation_result =
+            simulation_results.pop().expect("Should have transaction exeuction result");
+
+        let block_id = if is_pending {
+            BlockId::Tag(Tag::Pending)
+        } else {
+            BlockId::HashOrNumber(BlockHashOrNumber::Number(block_number))
+        };
+        let state_diff = self
+            .convert_thin_state_diff(simulation_result.induced_state_diff, block_id, block_number)
+            .await?;
+        Ok((simulation_result.transaction_trace, state_diff).into())
     }
-}
 
-impl JsonRpcServerImpl {
     // Get the block with the given ID and the given custom logic for getting the transactions.
     async fn get_block(
         &self,
@@ -1717,8 +1842,11 @@ fn client_receipt_to_rpc_pending_receipt(
     }))
 }
 
-fn do_event_keys_match_filter(event_content: &EventContent, filter: &EventFilter) -> bool {
-    filter.keys.iter().enumerate().all(|(i, keys)| {
+pub(crate) fn do_event_keys_match_filter(
+    event_content: &EventContent,
+    filter_keys: &[HashSet<EventKey>],
+) -> bool {
+    filter_keys.iter().enumerate().all(|(i, keys)| {
         event_content.keys.len() > i && (keys.is_empty() || keys.contains(&event_content.keys[i]))
     })
 }
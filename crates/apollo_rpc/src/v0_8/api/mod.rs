@@ -3,16 +3,13 @@ use std::io::Read;
 
 use apollo_proc_macros::versioned_rpc;
 use apollo_rpc_execution::objects::FeeEstimation;
-use apollo_rpc_execution::{AbiSize, ExecutableTransactionInput, ExecutionError, SierraSize};
-use apollo_storage::compiled_class::CasmStorageReader;
+use apollo_rpc_execution::{ExecutableTransactionInput, ExecutionError};
 use apollo_storage::db::serialization::StorageSerdeError;
-use apollo_storage::db::RO;
-use apollo_storage::state::StateStorageReader;
-use apollo_storage::StorageTxn;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use flate2::bufread::GzDecoder;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::ErrorCode;
 use jsonrpsee::types::ErrorObjectOwned;
 use papyrus_common::deprecated_class_abi::calculate_deprecated_class_abi_length;
 use papyrus_common::pending_classes::ApiContractClass;
@@ -24,8 +21,7 @@ use starknet_api::deprecated_contract_class::{
     ContractClass as StarknetApiDeprecatedContractClass,
     Program,
 };
-use starknet_api::state::{StateNumber, StorageKey};
-use starknet_api::transaction::fields::Fee;
+use starknet_api::state::StorageKey;
 use starknet_api::transaction::{EventKey, TransactionHash, TransactionOffsetInBlock};
 use starknet_types_core::felt::Felt;
 use tracing::debug;
@@ -338,144 +334,6 @@ impl TryFrom<BroadcastedTransaction> for ExecutableTransactionInput {
     }
 }
 
-pub(crate) fn stored_txn_to_executable_txn(
-    stored_txn: starknet_api::transaction::Transaction,
-    storage_txn: &StorageTxn<'_, RO>,
-    state_number: StateNumber,
-) -> Result<ExecutableTransactionInput, ErrorObjectOwned> {
-    match stored_txn {
-        starknet_api::transaction::Transaction::Declare(
-            starknet_api::transaction::DeclareTransaction::V0(value),
-        ) => {
-            // Copy the class hash before the value moves.
-            let class_hash = value.class_hash;
-            let deprecated_class =
-                get_deprecated_class_for_re_execution(storage_txn, state_number, class_hash)?;
-            let abi_length = calculate_deprecated_class_abi_length(&deprecated_class)
-                .map_err(internal_server_error)?;
-            Ok(ExecutableTransactionInput::DeclareV0(value, deprecated_class, abi_length, false))
-        }
-        starknet_api::transaction::Transaction::Declare(
-            starknet_api::transaction::DeclareTransaction::V1(value),
-        ) => {
-            // Copy the class hash before the value moves.
-            let class_hash = value.class_hash;
-            let deprecated_class =
-                get_deprecated_class_for_re_execution(storage_txn, state_number, class_hash)?;
-            let abi_length = calculate_deprecated_class_abi_length(&deprecated_class)
-                .map_err(internal_server_error)?;
-            Ok(ExecutableTransactionInput::DeclareV1(value, deprecated_class, abi_length, false))
-        }
-        starknet_api::transaction::Transaction::Declare(
-            starknet_api::transaction::DeclareTransaction::V2(value),
-        ) => {
-            let casm = storage_txn
-                .get_casm(&value.class_hash)
-                .map_err(internal_server_error)?
-                .ok_or_else(|| {
-                    internal_server_error(format!(
-                        "Missing casm of class hash {}.",
-                        value.class_hash
-                    ))
-                })?;
-            let (sierra_program_length, abi_length, sierra_version) =
-                get_class_lengths(storage_txn, state_number, value.class_hash)?;
-            Ok(ExecutableTransactionInput::DeclareV2(
-                value,
-                casm,
-                sierra_program_length,
-                abi_length,
-                false,
-                sierra_version,
-            ))
-        }
-        starknet_api::transaction::Transaction::Declare(
-            starknet_api::transaction::DeclareTransaction::V3(value),
-        ) => {
-            let casm = storage_txn
-                .get_casm(&value.class_hash)
-                .map_err(internal_server_error)?
-                .ok_or_else(|| {
-                    internal_server_error(format!(
-                        "Missing casm of class hash {}.",
-                        value.class_hash
-                    ))
-                })?;
-            let (sierra_program_length, abi_length, sierra_version) =
-                get_class_lengths(storage_txn, state_number, value.class_hash)?;
-            Ok(ExecutableTransactionInput::DeclareV3(
-                value,
-                casm,
-                sierra_program_length,
-                abi_length,
-                false,
-                sierra_version,
-            ))
-        }
-        starknet_api::transaction::Transaction::Deploy(_) => {
-            Err(internal_server_error("Deploy txns not supported in execution"))
-        }
-        starknet_api::transaction::Transaction::DeployAccount(deploy_account_tx) => {
-            Ok(ExecutableTransactionInput::DeployAccount(deploy_account_tx, false))
-        }
-        starknet_api::transaction::Transaction::Invoke(value) => {
-            Ok(ExecutableTransactionInput::Invoke(value, false))
-        }
-        starknet_api::transaction::Transaction::L1Handler(value) => {
-            // todo(yair): This is a temporary solution until we have a better way to get the l1
-            // fee.
-            let paid_fee_on_l1 = Fee(1);
-            Ok(ExecutableTransactionInput::L1Handler(value, paid_fee_on_l1, false))
-        }
-    }
-}
-
-// For re-execution (traceTransaction, traceBlockTransactions) we need to get the class definition
-// of declare transactions from the storage before the execution. They are stored in the state after
-// the block in which they appeared, so we need to get it from the state after given block.
-fn get_deprecated_class_for_re_execution(
-    storage_txn: &StorageTxn<'_, RO>,
-    state_number: StateNumber,
-    class_hash: ClassHash,
-) -> Result<starknet_api::deprecated_contract_class::ContractClass, ErrorObjectOwned> {
-    let state_number_after_block =
-        StateNumber::unchecked_right_after_block(state_number.block_after());
-    storage_txn
-        .get_state_reader()
-        .map_err(internal_server_error)?
-        .get_deprecated_class_definition_at(state_number_after_block, &class_hash)
-        .map_err(internal_server_error)?
-        .ok_or_else(|| {
-            internal_server_error(format!("Missing deprecated class definition of {class_hash}."))
-        })
-}
-
-fn get_class_lengths(
-    storage_txn: &StorageTxn<'_, RO>,
-    state_number: StateNumber,
-    class_hash: ClassHash,
-) -> Result<(SierraSize, AbiSize, SierraVersion), ErrorObjectOwned> {
-    let state_number_after_block =
-        StateNumber::unchecked_right_after_block(state_number.block_after());
-
-    storage_txn
-        .get_state_reader()
-        .map_err(internal_server_error)?
-        .get_class_definition_at(state_number_after_block, &class_hash)
-        .map_err(internal_server_error)?
-        .ok_or_else(|| {
-            internal_server_error(format!("Missing deprecated class definition of {class_hash}."))
-        })
-        .and_then(|contract_class| {
-            let sierra_program_len = contract_class.sierra_program.len();
-            let abi_len = contract_class.abi.len();
-            let sierra_program =
-                SierraVersion::extract_from_program(&contract_class.sierra_program)
-                    .map_err(internal_server_error)?;
-            Ok((sierra_program_len, abi_len, sierra_program))
-        })
-}
-
 impl TryFrom<BroadcastedDeclareTransaction> for ExecutableTransactionInput {
     type Error = ErrorObjectOwned;
     fn try_from(value: BroadcastedDeclareTransaction) -> Result<Self, Self::Error> {
@@ -663,6 +521,9 @@ pub(crate) fn execution_error_to_error_object_owned(err: ExecutionError) -> Erro
             rpc_err.into()
         }
         ExecutionError::ContractNotFound { .. } => CONTRACT_NOT_FOUND.into(),
+        ExecutionError::ConflictingStateOverrides { .. } => {
+            ErrorObjectOwned::owned(ErrorCode::InvalidParams.code(), err.to_string(), None::<()>)
+        }
         _ => internal_server_error(err),
     }
 }
@@ -39,12 +39,13 @@ use apollo_starknet_client::writer::{MockStarknetWriter, WriterClientError, Writ
 use apollo_starknet_client::ClientError;
 use apollo_storage::base_layer::BaseLayerStorageWriter;
 use apollo_storage::body::events::EventIndex;
+use apollo_storage::body::events_index::EventsIndexReader;
 use apollo_storage::body::{BodyStorageWriter, TransactionIndex};
 use apollo_storage::class::ClassStorageWriter;
 use apollo_storage::compiled_class::CasmStorageWriter;
 use apollo_storage::header::HeaderStorageWriter;
 use apollo_storage::state::StateStorageWriter;
-use apollo_storage::test_utils::get_test_storage;
+use apollo_storage::test_utils::{get_test_storage, TestStorageBuilder};
 use apollo_storage::StorageScope;
 use apollo_test_utils::{
     auto_impl_get_test_instance,
@@ -176,7 +177,7 @@ use super::super::write_api_result::{
 };
 use super::api_impl::JsonRpcServerImpl;
 use super::{ContinuationToken, EventFilter, GatewayContractClass};
-use crate::api::{BlockHashOrNumber, BlockId, Tag};
+use crate::api::{BlockHashOrNumber, BlockId, JsonRpcServerTrait, Tag};
 use crate::syncing_state::SyncStatus;
 use crate::test_utils::{
     call_and_validate_schema_for_result,
@@ -191,6 +192,7 @@ use crate::test_utils::{
     get_test_rpc_config,
     get_test_rpc_server_and_storage_writer,
     get_test_rpc_server_and_storage_writer_from_params,
+    get_test_rpc_server_impl_from_storage,
     method_name_to_spec_method_name,
     raw_call,
     validate_schema,
@@ -2880,6 +2882,28 @@ impl BlockMetadata {
 }
 
 async fn test_get_events(
+    block_metadatas: Vec<BlockMetadata>,
+    pending_block_metadata: Option<BlockMetadata>,
+    is_pending_up_to_date: bool,
+    filter: EventFilter,
+    expected_result_by_index: Vec<(Vec<EventIndex>, Option<ContinuationTokenAsStruct>)>,
+) {
+    // The events read through the events indexes should be the same as those read without them.
+    for index_events in [false, true] {
+        test_get_events_with_events_index(
+            index_events,
+            block_metadatas.clone(),
+            pending_block_metadata.clone(),
+            is_pending_up_to_date,
+            filter.clone(),
+            expected_result_by_index.clone(),
+        )
+        .await;
+    }
+}
+
+async fn test_get_events_with_events_index(
+    index_events: bool,
     block_metadatas: Vec<BlockMetadata>,
     pending_block_metadata: Option<BlockMetadata>,
     is_pending_up_to_date: bool,
@@ -2888,9 +2912,16 @@ async fn test_get_events(
 ) {
     let method_name = "starknet_V0_8_getEvents";
     let pending_data = get_test_pending_data();
-    let (module, mut storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
-        JsonRpcServerImpl,
-    >(None, None, Some(pending_data.clone()), None, None);
+    let ((storage_reader, mut storage_writer), _config, _temp_dir) =
+        TestStorageBuilder::new(None).index_events(index_events).build();
+    let module = get_test_rpc_server_impl_from_storage::<JsonRpcServerImpl>(
+        None,
+        None,
+        Some(pending_data.clone()),
+        None,
+        storage_reader.clone(),
+    )
+    .into_rpc_module();
     let mut rng = get_rng();
 
     let mut event_index_to_event = HashMap::<EventIndex, Event>::new();
@@ -2937,6 +2968,15 @@ async fn test_get_events(
             .unwrap();
     }
     rw_txn.commit().unwrap();
+    let expected_event_index_marker = if index_events {
+        BlockNumber(u64::try_from(block_metadatas.len()).expect("usize should fit in u64"))
+    } else {
+        BlockNumber(0)
+    };
+    assert_eq!(
+        storage_reader.begin_ro_txn().unwrap().get_event_index_marker().unwrap(),
+        expected_event_index_marker
+    );
 
     if let Some(pending_block_metadata) = pending_block_metadata {
         if !is_pending_up_to_date {
@@ -3219,6 +3259,46 @@ async fn get_events_keys_filter() {
     .await;
 }
 
+#[tokio::test]
+async fn get_events_keys_filter_without_first_keys() {
+    // The filter has no first keys, so the events bloom filters are used instead of the first keys
+    // index when the events are indexed. The events of the second block don't match, so the
+    // bloom filter of the second block skips it.
+    let mut blocks_metadata = BLOCKS_METADATA_FOR_KEYS_FILTER_TEST.clone();
+    blocks_metadata.insert(1, BlockMetadata(vec![vec![DEFAULT_EVENT_METADATA; 2]]));
+    let filter = EventFilter {
+        chunk_size: 6,
+        keys: vec![
+            HashSet::from([]),
+            HashSet::from([]),
+            HashSet::from([KEY2_0.clone(), KEY2_1.clone()]),
+        ],
+        ..Default::default()
+    };
+    let expected_result_by_index = vec![(
+        [1, 2, 4]
+            .into_iter()
+            .map(|i_event| {
+                EventIndex(
+                    TransactionIndex(BlockNumber(2), TransactionOffsetInBlock(0)),
+                    EventIndexInTransactionOutput(i_event),
+                )
+            })
+            .collect(),
+        None,
+    )];
+    let pending_block_metadata = None;
+    let is_pending_up_to_date = true;
+    test_get_events(
+        blocks_metadata,
+        pending_block_metadata,
+        is_pending_up_to_date,
+        filter,
+        expected_result_by_index,
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn get_events_pending_keys_filter() {
     let mut blocks_metadata = BLOCKS_METADATA_FOR_KEYS_FILTER_TEST.clone();
@@ -3563,6 +3643,8 @@ async fn serialize_returns_valid_json() {
         get_test_pending_classes(),
         storage_reader,
         NODE_VERSION,
+        None,
+        None,
     )
     .await
     .unwrap();
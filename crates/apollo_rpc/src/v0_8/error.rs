@@ -121,6 +121,15 @@ pub const UNSUPPORTED_TX_VERSION: JsonRpcError<String> =
 pub const UNSUPPORTED_CONTRACT_CLASS_VERSION: JsonRpcError<String> =
     JsonRpcError { code: 62, message: "the contract class version is not supported", data: None };
 
+pub const TOO_MANY_ADDRESSES_IN_FILTER: JsonRpcError<String> = JsonRpcError {
+    code: 67,
+    message: "Too many addresses in filter sender_address filter",
+    data: None,
+};
+
+pub const TOO_MANY_BLOCKS_BACK: JsonRpcError<String> =
+    JsonRpcError { code: 68, message: "Cannot go back more than 1024 blocks", data: None };
+
 pub fn unexpected_error(data: String) -> JsonRpcError<String> {
     JsonRpcError { code: 63, message: "An unexpected error occurred", data: Some(data) }
 }
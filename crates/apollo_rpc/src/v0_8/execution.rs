@@ -7,7 +7,9 @@ use apollo_rpc_execution::objects::{
     OrderedL2ToL1Message,
     Retdata,
     RevertReason,
+    StateAccesses,
     TransactionTrace as ExecutionTransactionTrace,
+    VmTrace,
 };
 use serde::{Deserialize, Serialize};
 use starknet_api::contract_class::EntryPointType;
@@ -127,6 +129,14 @@ pub struct FunctionInvocation {
     pub messages: Vec<OrderedL2ToL1Message>,
     /// The VM execution resources used by this invocation.
     pub execution_resources: ComputationResources,
+    /// The state accesses made by this invocation. Not part of the specification, and returned
+    /// only if requested with a trace flag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_accesses: Option<StateAccesses>,
+    /// The Cairo steps run by this invocation. Not part of the specification, and returned only if
+    /// requested with a trace flag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_trace: Option<VmTrace>,
 }
 
 impl From<(ExecutionTransactionTrace, ThinStateDiff)> for TransactionTrace {
@@ -235,6 +245,8 @@ impl From<ExecutionFunctionInvocation> for FunctionInvocation {
             events: invocation.events,
             messages: invocation.messages,
             execution_resources: invocation.execution_resources.into(),
+            state_accesses: invocation.state_accesses,
+            vm_trace: invocation.vm_trace,
         }
     }
 }
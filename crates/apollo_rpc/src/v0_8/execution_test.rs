@@ -1459,6 +1459,8 @@ impl GetTestInstance for FunctionInvocation {
             messages: Vec::<OrderedL2ToL1Message>::get_test_instance(rng),
             execution_resources:
                 starknet_api::execution_resources::ExecutionResources::get_test_instance(rng).into(),
+            state_accesses: None,
+            vm_trace: None,
         }
     }
 }
@@ -1646,7 +1648,7 @@ async fn write_block_0_as_pending(
     }
 }
 
-fn prepare_storage_for_execution(mut storage_writer: StorageWriter) -> StorageWriter {
+pub(crate) fn prepare_storage_for_execution(mut storage_writer: StorageWriter) -> StorageWriter {
     let class1 = serde_json::from_value::<SN_API_DeprecatedContractClass>(read_json_file(
         "deprecated_class.json",
     ))
@@ -5,8 +5,14 @@ pub mod deprecated_contract_class;
 pub mod error;
 pub mod execution;
 #[cfg(test)]
-mod execution_test;
+pub(crate) mod execution_test;
 pub mod state;
+pub mod state_history;
+#[cfg(test)]
+mod state_history_test;
+pub mod subscriptions;
+#[cfg(test)]
+mod subscriptions_test;
 pub mod transaction;
 pub mod write_api_error;
 pub mod write_api_result;
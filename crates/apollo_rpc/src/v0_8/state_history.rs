@@ -0,0 +1,256 @@
+//! Queries over the state history of a contract. These methods are not part of the Starknet
+//! JSON-RPC specification, so they are served under the `apollo` namespace, e.g.
+//! `apollo_getStorageChanges`.
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use apollo_proc_macros::versioned_rpc;
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::db::RO;
+use apollo_storage::state::history::StorageChange;
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::{StorageReader, StorageTxn};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use papyrus_common::pending_classes::PendingClasses;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+use starknet_api::core::{ChainId, ClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use super::api::ContinuationToken;
+use super::block::get_accepted_block_number;
+use super::error::{INVALID_CONTINUATION_TOKEN, PAGE_SIZE_TOO_BIG};
+use crate::api::{BlockId, JsonRpcServerTrait};
+use crate::internal_server_error;
+
+/// The maximal number of items that can be requested in a single chunk.
+pub const MAX_STATE_HISTORY_CHUNK_SIZE: usize = 1000;
+
+#[versioned_rpc("V0_8", "apollo")]
+#[async_trait]
+pub trait StateHistoryRpc {
+    /// Gets the changes of the storage of a contract in the given blocks, ordered by the storage
+    /// key and then by the block number.
+    #[method(name = "getStorageChanges")]
+    async fn get_storage_changes(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageChangesChunk>;
+
+    /// Gets the storage keys that were ever written in the storage of a contract, in ascending
+    /// order.
+    #[method(name = "getStorageKeys")]
+    async fn get_storage_keys(
+        &self,
+        contract_address: ContractAddress,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageKeysChunk>;
+
+    /// Gets the nonce changes of a contract in the given blocks.
+    #[method(name = "getNonceHistory")]
+    async fn get_nonce_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<NonceChange>>;
+
+    /// Gets the class hash changes of a contract in the given blocks. These are its deployment
+    /// and the replacements of its class.
+    #[method(name = "getClassHashHistory")]
+    async fn get_class_hash_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<ClassHashChange>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StorageChangesChunk {
+    pub changes: Vec<StorageChange>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<ContinuationToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StorageKeysChunk {
+    pub keys: Vec<StorageKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<ContinuationToken>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NonceChange {
+    pub block_number: BlockNumber,
+    pub nonce: Nonce,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClassHashChange {
+    pub block_number: BlockNumber,
+    pub class_hash: ClassHash,
+}
+
+/// Rpc server of the state history methods.
+pub struct StateHistoryRpcImpl {
+    pub storage_reader: StorageReader,
+}
+
+#[async_trait]
+impl StateHistoryRpcV0_8Server for StateHistoryRpcImpl {
+    #[instrument(skip(self), level = "debug", err)]
+    async fn get_storage_changes(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageChangesChunk> {
+        verify_chunk_size(chunk_size)?;
+        let start = continuation_token.as_ref().map(parse_continuation_token).transpose()?;
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let blocks = get_block_range(&txn, from_block, to_block)?;
+        let state_reader = txn.get_state_reader().map_err(internal_server_error)?;
+
+        let mut changes = state_reader
+            .iter_storage_changes(contract_address, blocks, start)
+            .map_err(internal_server_error)?
+            .take(chunk_size + 1)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(internal_server_error)?;
+        let continuation_token = if changes.len() > chunk_size {
+            changes
+                .pop()
+                .map(|next| new_continuation_token(&(next.key, next.block_number)))
+                .transpose()?
+        } else {
+            None
+        };
+        Ok(StorageChangesChunk { changes, continuation_token })
+    }
+
+    #[instrument(skip(self), level = "debug", err)]
+    async fn get_storage_keys(
+        &self,
+        contract_address: ContractAddress,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageKeysChunk> {
+        verify_chunk_size(chunk_size)?;
+        let start_key = continuation_token.as_ref().map(parse_continuation_token).transpose()?;
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let state_reader = txn.get_state_reader().map_err(internal_server_error)?;
+
+        let mut keys = state_reader
+            .iter_storage_keys(contract_address, start_key)
+            .map_err(internal_server_error)?
+            .take(chunk_size + 1)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(internal_server_error)?;
+        let continuation_token = if keys.len() > chunk_size {
+            keys.pop().map(|next_key| new_continuation_token(&next_key)).transpose()?
+        } else {
+            None
+        };
+        Ok(StorageKeysChunk { keys, continuation_token })
+    }
+
+    #[instrument(skip(self), level = "debug", err, ret)]
+    async fn get_nonce_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<NonceChange>> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let blocks = get_block_range(&txn, from_block, to_block)?;
+        Ok(txn
+            .get_state_reader()
+            .map_err(internal_server_error)?
+            .get_nonce_history(contract_address, blocks)
+            .map_err(internal_server_error)?
+            .into_iter()
+            .map(|(block_number, nonce)| NonceChange { block_number, nonce })
+            .collect())
+    }
+
+    #[instrument(skip(self), level = "debug", err, ret)]
+    async fn get_class_hash_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<ClassHashChange>> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let blocks = get_block_range(&txn, from_block, to_block)?;
+        Ok(txn
+            .get_state_reader()
+            .map_err(internal_server_error)?
+            .get_class_hash_history(contract_address, blocks)
+            .map_err(internal_server_error)?
+            .into_iter()
+            .map(|(block_number, class_hash)| ClassHashChange { block_number, class_hash })
+            .collect())
+    }
+}
+
+fn verify_chunk_size(chunk_size: usize) -> RpcResult<()> {
+    if chunk_size > MAX_STATE_HISTORY_CHUNK_SIZE {
+        return Err(ErrorObjectOwned::from(PAGE_SIZE_TOO_BIG));
+    }
+    Ok(())
+}
+
+fn get_block_range(
+    txn: &StorageTxn<'_, RO>,
+    from_block: BlockId,
+    to_block: BlockId,
+) -> RpcResult<RangeInclusive<BlockNumber>> {
+    Ok(get_accepted_block_number(txn, from_block)?..=get_accepted_block_number(txn, to_block)?)
+}
+
+fn parse_continuation_token<T: DeserializeOwned>(token: &ContinuationToken) -> RpcResult<T> {
+    serde_json::from_str(&token.0).map_err(|_| ErrorObjectOwned::from(INVALID_CONTINUATION_TOKEN))
+}
+
+fn new_continuation_token<T: Serialize>(value: &T) -> RpcResult<ContinuationToken> {
+    Ok(ContinuationToken(serde_json::to_string(value).map_err(internal_server_error)?))
+}
+
+impl JsonRpcServerTrait for StateHistoryRpcImpl {
+    fn new(
+        _chain_id: ChainId,
+        _execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        _max_events_chunk_size: usize,
+        _max_events_keys: usize,
+        _starting_block: BlockHashAndNumber,
+        _shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        _pending_data: Arc<RwLock<PendingData>>,
+        _pending_classes: Arc<RwLock<PendingClasses>>,
+        _writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self { storage_reader }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
@@ -0,0 +1,182 @@
+use apollo_storage::state::history::StorageChange;
+use apollo_storage::state::StateStorageWriter;
+use apollo_storage::StorageWriter;
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use jsonrpsee::core::Error;
+use jsonrpsee::RpcModule;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{class_hash, contract_address, felt, storage_key};
+
+use super::api::ContinuationToken;
+use super::error::{BLOCK_NOT_FOUND, INVALID_CONTINUATION_TOKEN};
+use super::state_history::{
+    ClassHashChange,
+    NonceChange,
+    StateHistoryRpcImpl,
+    StorageChangesChunk,
+    StorageKeysChunk,
+};
+use crate::api::{BlockHashOrNumber, BlockId, Tag};
+use crate::test_utils::get_test_rpc_server_and_storage_writer;
+
+fn contract() -> ContractAddress {
+    contract_address!("0x100")
+}
+
+fn block_id(block_number: u64) -> BlockId {
+    BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(block_number)))
+}
+
+// Writes 3 blocks. Every block writes a new value to the storage keys 0x10 and 0x11 of the
+// contract, and sets its nonce. The first block deploys the contract.
+fn write_history(storage_writer: &mut StorageWriter) {
+    for block_number in 0..3_u8 {
+        let diff = ThinStateDiff {
+            deployed_contracts: if block_number == 0 {
+                indexmap! { contract() => class_hash!("0x1000") }
+            } else {
+                Default::default()
+            },
+            storage_diffs: indexmap! { contract() => indexmap! {
+                storage_key!("0x10") => felt!(block_number),
+                storage_key!("0x11") => felt!(block_number + 10),
+            }},
+            nonces: indexmap! { contract() => Nonce(felt!(block_number)) },
+            ..Default::default()
+        };
+        storage_writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number.into()), diff)
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+fn setup() -> (RpcModule<StateHistoryRpcImpl>, StorageWriter) {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<StateHistoryRpcImpl>();
+    write_history(&mut storage_writer);
+    (module, storage_writer)
+}
+
+#[tokio::test]
+async fn get_storage_changes() {
+    let method_name = "apollo_V0_8_getStorageChanges";
+    let (module, _storage_writer) = setup();
+
+    let chunk = module
+        .call::<_, StorageChangesChunk>(
+            method_name,
+            (contract(), block_id(1), BlockId::Tag(Tag::Latest), 3, None::<ContinuationToken>),
+        )
+        .await
+        .unwrap();
+    let change = |key, block_number: u8, value: u8| StorageChange {
+        key,
+        block_number: BlockNumber(block_number.into()),
+        value: felt!(value),
+    };
+    assert_eq!(
+        chunk.changes,
+        vec![
+            change(storage_key!("0x10"), 1, 1),
+            change(storage_key!("0x10"), 2, 2),
+            change(storage_key!("0x11"), 1, 11),
+        ]
+    );
+    let continuation_token = chunk.continuation_token.unwrap();
+
+    let chunk = module
+        .call::<_, StorageChangesChunk>(
+            method_name,
+            (contract(), block_id(1), BlockId::Tag(Tag::Latest), 3, Some(continuation_token)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        chunk,
+        StorageChangesChunk {
+            changes: vec![change(storage_key!("0x11"), 2, 12)],
+            continuation_token: None
+        }
+    );
+
+    let err = module
+        .call::<_, StorageChangesChunk>(
+            method_name,
+            (contract(), block_id(0), block_id(3), 3, None::<ContinuationToken>),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    let err = module
+        .call::<_, StorageChangesChunk>(
+            method_name,
+            (contract(), block_id(0), block_id(2), 3, Some(ContinuationToken("junk".to_owned()))),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        Error::Call(err) if err == INVALID_CONTINUATION_TOKEN.into()
+    );
+}
+
+#[tokio::test]
+async fn get_storage_keys() {
+    let method_name = "apollo_V0_8_getStorageKeys";
+    let (module, _storage_writer) = setup();
+
+    let chunk = module
+        .call::<_, StorageKeysChunk>(method_name, (contract(), 1, None::<ContinuationToken>))
+        .await
+        .unwrap();
+    assert_eq!(chunk.keys, vec![storage_key!("0x10")]);
+    let chunk = module
+        .call::<_, StorageKeysChunk>(method_name, (contract(), 1, chunk.continuation_token))
+        .await
+        .unwrap();
+    assert_eq!(
+        chunk,
+        StorageKeysChunk { keys: vec![storage_key!("0x11")], continuation_token: None }
+    );
+}
+
+#[tokio::test]
+async fn get_nonce_and_class_hash_history() {
+    let (module, _storage_writer) = setup();
+
+    let res = module
+        .call::<_, Vec<NonceChange>>(
+            "apollo_V0_8_getNonceHistory",
+            (contract(), block_id(1), block_id(2)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            NonceChange { block_number: BlockNumber(1), nonce: Nonce(felt!(1_u8)) },
+            NonceChange { block_number: BlockNumber(2), nonce: Nonce(felt!(2_u8)) },
+        ]
+    );
+
+    let res = module
+        .call::<_, Vec<ClassHashChange>>(
+            "apollo_V0_8_getClassHashHistory",
+            (contract(), block_id(0), BlockId::Tag(Tag::Latest)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![ClassHashChange { block_number: BlockNumber(0), class_hash: class_hash!("0x1000") }]
+    );
+}
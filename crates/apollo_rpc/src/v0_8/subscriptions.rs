@@ -0,0 +1,635 @@
+//! The WebSocket subscription methods of the Starknet JSON-RPC specification.
+//!
+//! The subscriptions follow the storage, which is written by the sync (central or p2p), and the
+//! pending data. The subscriptions that follow the storage are woken up by the storage commits, and
+//! the subscriptions that follow the pending data poll it in memory, since it's replaced by the
+//! pending sync without a notification. When blocks that a subscription was notified about are
+//! reverted, e.g. by `apollo_reverts`, the subscription is notified with a
+//! `starknet_subscriptionReorg` notification and continues from the first reverted block.
+//!
+//! WebSocket messages don't go through the middleware that adds the version to the method names,
+//! so unlike the other methods, the subscription methods are registered with their names in the
+//! specification, e.g. `starknet_subscribeNewHeads`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_starknet_client::reader::objects::transaction::Transaction as ClientTransaction;
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::db::TransactionKind;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::{StorageReader, StorageTxn};
+use async_trait::async_trait;
+use jsonrpsee::core::{RpcResult, SubscriptionResult};
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::{ErrorObjectOwned, SubscriptionId};
+use jsonrpsee::{PendingSubscriptionSink, RpcModule, SubscriptionMessage, SubscriptionSink};
+use papyrus_common::pending_classes::PendingClasses;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
+use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::transaction::{EventKey, Transaction as StarknetApiTransaction, TransactionHash};
+use tokio::sync::{oneshot, watch, RwLock};
+use tracing::debug;
+
+use super::api::api_impl::{do_event_keys_match_filter, JsonRpcServerImpl};
+use super::api::JsonRpcV0_8Server;
+use super::block::{get_accepted_block_number, get_block_header_by_number, BlockHeader};
+use super::error::{
+    TOO_MANY_ADDRESSES_IN_FILTER,
+    TOO_MANY_BLOCKS_BACK,
+    TOO_MANY_KEYS_IN_FILTER,
+    TRANSACTION_HASH_NOT_FOUND,
+};
+use super::transaction::{
+    Event,
+    TransactionFinalityStatus,
+    TransactionStatus,
+    TransactionWithHash,
+};
+use crate::api::{BlockId, JsonRpcServerTrait};
+use crate::{get_latest_block_number, internal_server_error, verify_storage_scope};
+
+/// The maximal number of blocks before the latest block that a subscription can start from.
+pub const MAX_BLOCKS_BACK: u64 = 1024;
+/// The maximal number of addresses in the sender address filter of pending transactions.
+pub const MAX_ADDRESSES_IN_FILTER: usize = 1024;
+/// The interval in which the subscriptions poll the pending data. Polling the pending data doesn't
+/// access the storage.
+pub const PENDING_DATA_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const REORG_NOTIFICATION_METHOD: &str = "starknet_subscriptionReorg";
+
+#[rpc(server, namespace = "starknet")]
+pub trait SubscriptionsRpc {
+    /// Notifies about new block headers, starting from the given block.
+    #[subscription(
+        name = "subscribeNewHeads" => "subscriptionNewHeads",
+        unsubscribe = "unsubscribeNewHeads",
+        item = BlockHeader
+    )]
+    async fn subscribe_new_heads(&self, block_id: Option<BlockId>) -> SubscriptionResult;
+
+    /// Notifies about the events of new blocks that match the given filter, starting from the
+    /// given block.
+    #[subscription(
+        name = "subscribeEvents" => "subscriptionEvents",
+        unsubscribe = "unsubscribeEvents",
+        item = Event
+    )]
+    async fn subscribe_events(
+        &self,
+        from_address: Option<ContractAddress>,
+        keys: Option<Vec<HashSet<EventKey>>>,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult;
+
+    /// Notifies about the changes of the status of a transaction. The subscription ends when the
+    /// transaction is accepted on L1.
+    #[subscription(
+        name = "subscribeTransactionStatus" => "subscriptionTransactionStatus",
+        unsubscribe = "unsubscribeTransactionStatus",
+        item = NewTransactionStatus
+    )]
+    async fn subscribe_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> SubscriptionResult;
+
+    /// Notifies about the transactions that are added to the pending block.
+    #[subscription(
+        name = "subscribePendingTransactions" => "subscriptionPendingTransactions",
+        unsubscribe = "unsubscribePendingTransactions",
+        item = PendingTransaction
+    )]
+    async fn subscribe_pending_transactions(
+        &self,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<ContractAddress>>,
+    ) -> SubscriptionResult;
+
+    /// Closes a subscription of any kind.
+    #[method(name = "unsubscribe")]
+    fn unsubscribe(&self, subscription_id: serde_json::Value) -> RpcResult<bool>;
+}
+
+/// The blocks that were reverted after a subscription was notified about them.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ReorgData {
+    pub starting_block_hash: BlockHash,
+    pub starting_block_number: BlockNumber,
+    pub ending_block_hash: BlockHash,
+    pub ending_block_number: BlockNumber,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct NewTransactionStatus {
+    pub transaction_hash: TransactionHash,
+    pub status: TransactionStatus,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum PendingTransaction {
+    Full(TransactionWithHash),
+    Hash(TransactionHash),
+}
+
+/// Rpc server of the subscription methods.
+pub struct SubscriptionsRpcImpl {
+    rpc: JsonRpcServerImpl,
+    active_subscriptions: ActiveSubscriptions,
+}
+
+#[async_trait]
+impl SubscriptionsRpcServer for SubscriptionsRpcImpl {
+    async fn subscribe_new_heads(
+        &self,
+        pending: PendingSubscriptionSink,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        let start = match self.get_start_block_number(block_id) {
+            Ok(start) => start,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(());
+            }
+        };
+        let sink = pending.accept().await?;
+        let mut subscription = self.active_subscriptions.add(&sink);
+        let mut commits = self.rpc.storage_reader.subscribe_to_commits();
+        let mut blocks = NotifiedBlocks::new(start);
+        loop {
+            let messages = {
+                let txn = self.rpc.storage_reader.begin_ro_txn()?;
+                let latest_block_number = get_latest_block_number(&txn)?;
+                let (reorg, headers) = blocks.poll(&txn, latest_block_number)?;
+                let mut messages = reorg_message(&sink, reorg)?.into_iter().collect::<Vec<_>>();
+                for header in headers {
+                    messages.push(SubscriptionMessage::from_json(&header)?);
+                }
+                messages
+            };
+            if !send_and_wait(&sink, &mut subscription, messages, wait_for_commit(&mut commits))
+                .await
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn subscribe_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        from_address: Option<ContractAddress>,
+        keys: Option<Vec<HashSet<EventKey>>>,
+        block_id: Option<BlockId>,
+    ) -> SubscriptionResult {
+        let keys = keys.unwrap_or_default();
+        let start = verify_storage_scope(&self.rpc.storage_reader)
+            .and_then(|()| {
+                if keys.len() > self.rpc.max_events_keys {
+                    return Err(ErrorObjectOwned::from(TOO_MANY_KEYS_IN_FILTER));
+                }
+                Ok(())
+            })
+            .and_then(|()| self.get_start_block_number(block_id));
+        let start = match start {
+            Ok(start) => start,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(());
+            }
+        };
+        let sink = pending.accept().await?;
+        let mut subscription = self.active_subscriptions.add(&sink);
+        let mut commits = self.rpc.storage_reader.subscribe_to_commits();
+        let mut blocks = NotifiedBlocks::new(start);
+        loop {
+            let messages = {
+                let txn = self.rpc.storage_reader.begin_ro_txn()?;
+                // The events are taken from the bodies, which may be behind the state diffs.
+                let latest_block_number = get_latest_block_number(&txn)?
+                    .min(txn.get_body_marker().map_err(internal_server_error)?.prev());
+                let (reorg, headers) = blocks.poll(&txn, latest_block_number)?;
+                let mut messages = reorg_message(&sink, reorg)?.into_iter().collect::<Vec<_>>();
+                for header in headers {
+                    for event in get_block_events(&txn, &header, from_address, &keys)? {
+                        messages.push(SubscriptionMessage::from_json(&event)?);
+                    }
+                }
+                messages
+            };
+            if !send_and_wait(&sink, &mut subscription, messages, wait_for_commit(&mut commits))
+                .await
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn subscribe_transaction_status(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_hash: TransactionHash,
+    ) -> SubscriptionResult {
+        if let Err(err) = verify_storage_scope(&self.rpc.storage_reader) {
+            pending.reject(err).await;
+            return Ok(());
+        }
+        let sink = pending.accept().await?;
+        let mut subscription = self.active_subscriptions.add(&sink);
+        let mut commits = self.rpc.storage_reader.subscribe_to_commits();
+        let mut last_status = None;
+        loop {
+            // The transaction may be in the storage or in the pending block.
+            let pending_block_id = get_pending_block_id(&self.rpc.pending_data).await;
+            let status = match self.rpc.get_transaction_status(transaction_hash).await {
+                Ok(status) => Some(status),
+                // The transaction wasn't received yet, or its block was reverted.
+                Err(err) if err.code() == TRANSACTION_HASH_NOT_FOUND.code => None,
+                Err(err) => return Err(err.into()),
+            };
+            let mut messages = vec![];
+            if status != last_status {
+                if let Some(status) = &status {
+                    messages.push(SubscriptionMessage::from_json(&NewTransactionStatus {
+                        transaction_hash,
+                        status: status.clone(),
+                    })?);
+                }
+            }
+            let is_final = status.as_ref().is_some_and(|status| {
+                status.finality_status == TransactionFinalityStatus::AcceptedOnL1
+            });
+            last_status = status;
+            if is_final {
+                send_and_wait(&sink, &mut subscription, messages, async {}).await;
+                return Ok(());
+            }
+            let pending_block_change =
+                wait_for_pending_block_change(&self.rpc.pending_data, pending_block_id);
+            let next_update = async {
+                tokio::select! {
+                    _ = wait_for_commit(&mut commits) => {},
+                    _ = pending_block_change => {},
+                }
+            };
+            if !send_and_wait(&sink, &mut subscription, messages, next_update).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn subscribe_pending_transactions(
+        &self,
+        pending: PendingSubscriptionSink,
+        transaction_details: Option<bool>,
+        sender_address: Option<Vec<ContractAddress>>,
+    ) -> SubscriptionResult {
+        let transaction_details = transaction_details.unwrap_or_default();
+        let sender_addresses =
+            sender_address.map(|addresses| addresses.into_iter().collect::<HashSet<_>>());
+        if let Err(err) = verify_storage_scope(&self.rpc.storage_reader) {
+            pending.reject(err).await;
+            return Ok(());
+        }
+        if sender_addresses
+            .as_ref()
+            .is_some_and(|addresses| addresses.len() > MAX_ADDRESSES_IN_FILTER)
+        {
+            pending.reject(ErrorObjectOwned::from(TOO_MANY_ADDRESSES_IN_FILTER)).await;
+            return Ok(());
+        }
+        let sink = pending.accept().await?;
+        let mut subscription = self.active_subscriptions.add(&sink);
+        // The transactions that were notified from the current pending block, which is identified
+        // by its parent.
+        let mut pending_parent_hash = None;
+        let mut notified_transactions = HashSet::new();
+        loop {
+            let mut messages = vec![];
+            let pending_block_id = {
+                let pending_data = self.rpc.pending_data.read().await;
+                let parent_hash = pending_data.block.parent_block_hash();
+                if pending_parent_hash != Some(parent_hash) {
+                    pending_parent_hash = Some(parent_hash);
+                    notified_transactions.clear();
+                }
+                for transaction in pending_data.block.transactions() {
+                    let transaction_hash = transaction.transaction_hash();
+                    if !notified_transactions.insert(transaction_hash) {
+                        continue;
+                    }
+                    if let Some(sender_addresses) = &sender_addresses {
+                        if !get_sender_address(transaction)
+                            .is_some_and(|address| sender_addresses.contains(&address))
+                        {
+                            continue;
+                        }
+                    }
+                    let pending_transaction = if transaction_details {
+                        let transaction: StarknetApiTransaction =
+                            transaction.clone().try_into().map_err(internal_server_error)?;
+                        PendingTransaction::Full(TransactionWithHash {
+                            transaction: transaction.try_into()?,
+                            transaction_hash,
+                        })
+                    } else {
+                        PendingTransaction::Hash(transaction_hash)
+                    };
+                    messages.push(SubscriptionMessage::from_json(&pending_transaction)?);
+                }
+                PendingBlockId::new(&pending_data)
+            };
+            let next_update =
+                wait_for_pending_block_change(&self.rpc.pending_data, pending_block_id);
+            if !send_and_wait(&sink, &mut subscription, messages, next_update).await {
+                return Ok(());
+            }
+        }
+    }
+
+    fn unsubscribe(&self, subscription_id: serde_json::Value) -> RpcResult<bool> {
+        // An id that isn't a valid subscription id can't belong to an active subscription.
+        Ok(SubscriptionId::try_from(subscription_id)
+            .is_ok_and(|subscription_id| self.active_subscriptions.cancel(&subscription_id)))
+    }
+}
+
+impl SubscriptionsRpcImpl {
+    // Returns the block to start the notifications from. The default is the latest block.
+    fn get_start_block_number(&self, block_id: Option<BlockId>) -> RpcResult<BlockNumber> {
+        let txn = self.rpc.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let Some(latest_block_number) = get_latest_block_number(&txn)? else {
+            return Ok(BlockNumber(0));
+        };
+        let Some(block_id) = block_id else {
+            return Ok(latest_block_number);
+        };
+        let block_number = get_accepted_block_number(&txn, block_id)?;
+        if latest_block_number.0 - block_number.0 > MAX_BLOCKS_BACK {
+            return Err(ErrorObjectOwned::from(TOO_MANY_BLOCKS_BACK));
+        }
+        Ok(block_number)
+    }
+}
+
+// The blocks that a subscription was notified about, used for detecting reorgs.
+struct NotifiedBlocks {
+    next_block_number: BlockNumber,
+    // The last notified blocks, up to MAX_BLOCKS_BACK of them, in ascending order.
+    blocks: VecDeque<BlockHashAndNumber>,
+}
+
+impl NotifiedBlocks {
+    fn new(next_block_number: BlockNumber) -> Self {
+        Self { next_block_number, blocks: VecDeque::new() }
+    }
+
+    // Returns the reverted blocks, if any, and the headers of the blocks to notify about, up to
+    // the given latest block.
+    fn poll<Mode: TransactionKind>(
+        &mut self,
+        txn: &StorageTxn<'_, Mode>,
+        latest_block_number: Option<BlockNumber>,
+    ) -> RpcResult<(Option<ReorgData>, Vec<BlockHeader>)> {
+        let mut reverted_blocks = vec![];
+        while let Some(block) = self.blocks.back() {
+            let current_hash = match latest_block_number {
+                Some(latest_block_number) if block.number <= latest_block_number => txn
+                    .get_block_header(block.number)
+                    .map_err(internal_server_error)?
+                    .map(|header| header.block_hash),
+                _ => None,
+            };
+            if current_hash == Some(block.hash) {
+                break;
+            }
+            reverted_blocks.extend(self.blocks.pop_back());
+        }
+        let reorg =
+            reverted_blocks.first().zip(reverted_blocks.last()).map(|(ending, starting)| {
+                ReorgData {
+                    starting_block_hash: starting.hash,
+                    starting_block_number: starting.number,
+                    ending_block_hash: ending.hash,
+                    ending_block_number: ending.number,
+                }
+            });
+        if let Some(reorg) = &reorg {
+            debug!("Blocks {:?} were reverted.", reorg);
+            self.next_block_number = reorg.starting_block_number;
+        }
+
+        let mut headers = vec![];
+        while latest_block_number.is_some_and(|latest| self.next_block_number <= latest) {
+            let header: BlockHeader =
+                get_block_header_by_number(txn, self.next_block_number)?.into();
+            if self.blocks.len() as u64 == MAX_BLOCKS_BACK {
+                self.blocks.pop_front();
+            }
+            self.blocks.push_back(BlockHashAndNumber {
+                hash: header.block_hash,
+                number: header.block_number,
+            });
+            self.next_block_number = self.next_block_number.unchecked_next();
+            headers.push(header);
+        }
+        Ok((reorg, headers))
+    }
+}
+
+// The reorg notification has a different method than the other notifications of the subscription.
+fn reorg_message(
+    sink: &SubscriptionSink,
+    reorg: Option<ReorgData>,
+) -> Result<Option<SubscriptionMessage>, serde_json::Error> {
+    reorg
+        .map(|reorg| {
+            SubscriptionMessage::new(REORG_NOTIFICATION_METHOD, sink.subscription_id(), &reorg)
+        })
+        .transpose()
+}
+
+fn get_block_events<Mode: TransactionKind>(
+    txn: &StorageTxn<'_, Mode>,
+    header: &BlockHeader,
+    from_address: Option<ContractAddress>,
+    keys: &[HashSet<EventKey>],
+) -> RpcResult<Vec<Event>> {
+    let missing_body =
+        || internal_server_error(format!("Missing body of block {}.", header.block_number));
+    let transaction_outputs = txn
+        .get_block_transaction_outputs(header.block_number)
+        .map_err(internal_server_error)?
+        .ok_or_else(missing_body)?;
+    let transaction_hashes = txn
+        .get_block_transaction_hashes(header.block_number)
+        .map_err(internal_server_error)?
+        .ok_or_else(missing_body)?;
+    Ok(transaction_outputs
+        .iter()
+        .zip(transaction_hashes)
+        .flat_map(|(transaction_output, transaction_hash)| {
+            transaction_output.events().iter().map(move |event| (transaction_hash, event))
+        })
+        .filter(|(_, event)| {
+            from_address.is_none_or(|address| event.from_address == address)
+                && do_event_keys_match_filter(&event.content, keys)
+        })
+        .map(|(transaction_hash, event)| Event {
+            block_hash: Some(header.block_hash),
+            block_number: Some(header.block_number),
+            transaction_hash,
+            event: event.clone(),
+        })
+        .collect())
+}
+
+fn get_sender_address(transaction: &ClientTransaction) -> Option<ContractAddress> {
+    match transaction {
+        ClientTransaction::Declare(transaction) => Some(transaction.sender_address),
+        ClientTransaction::Invoke(transaction) => Some(transaction.sender_address),
+        ClientTransaction::DeployAccount(_) => transaction.contract_address(),
+        ClientTransaction::Deploy(_) | ClientTransaction::L1Handler(_) => None,
+    }
+}
+
+// Sends the messages and waits for the next update. Returns false if the subscription was closed.
+async fn send_and_wait(
+    sink: &SubscriptionSink,
+    subscription: &mut ActiveSubscription,
+    messages: Vec<SubscriptionMessage>,
+    next_update: impl Future<Output = ()>,
+) -> bool {
+    for message in messages {
+        if sink.send(message).await.is_err() {
+            return false;
+        }
+    }
+    tokio::select! {
+        _ = sink.closed() => false,
+        _ = &mut subscription.unsubscribed => false,
+        _ = next_update => true,
+    }
+}
+
+// Waits until a RW transaction is committed to the storage.
+async fn wait_for_commit(commits: &mut watch::Receiver<()>) {
+    if commits.changed().await.is_err() {
+        // The storage was closed, so there won't be new data.
+        std::future::pending::<()>().await;
+    }
+}
+
+// Identifies the content of the pending block. The pending sync only adds transactions to the
+// pending block until it's replaced by a pending block of the next block.
+#[derive(Clone, Copy, Eq, PartialEq)]
+struct PendingBlockId {
+    parent_hash: BlockHash,
+    n_transactions: usize,
+}
+
+impl PendingBlockId {
+    fn new(pending_data: &PendingData) -> Self {
+        Self {
+            parent_hash: pending_data.block.parent_block_hash(),
+            n_transactions: pending_data.block.transactions().len(),
+        }
+    }
+}
+
+async fn get_pending_block_id(pending_data: &RwLock<PendingData>) -> PendingBlockId {
+    PendingBlockId::new(&pending_data.read().await)
+}
+
+// Polls the pending data until the pending block is different from the given one.
+async fn wait_for_pending_block_change(
+    pending_data: &RwLock<PendingData>,
+    pending_block_id: PendingBlockId,
+) {
+    loop {
+        tokio::time::sleep(PENDING_DATA_POLL_INTERVAL).await;
+        if get_pending_block_id(pending_data).await != pending_block_id {
+            return;
+        }
+    }
+}
+
+// The subscriptions that can be closed with `starknet_unsubscribe`. The subscription ids are
+// random, so they are unique across the connections.
+#[derive(Clone, Default)]
+struct ActiveSubscriptions(Arc<Mutex<HashMap<SubscriptionId<'static>, oneshot::Sender<()>>>>);
+
+impl ActiveSubscriptions {
+    fn add(&self, sink: &SubscriptionSink) -> ActiveSubscription {
+        let (sender, unsubscribed) = oneshot::channel();
+        let id = sink.subscription_id();
+        self.lock().insert(id.clone(), sender);
+        ActiveSubscription { id, unsubscribed, active_subscriptions: self.clone() }
+    }
+
+    fn cancel(&self, id: &SubscriptionId<'static>) -> bool {
+        self.lock().remove(id).is_some_and(|sender| sender.send(()).is_ok())
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<SubscriptionId<'static>, oneshot::Sender<()>>> {
+        self.0.lock().expect("The active subscriptions lock should not be poisoned.")
+    }
+}
+
+// Removes the subscription from the active subscriptions when it ends.
+struct ActiveSubscription {
+    id: SubscriptionId<'static>,
+    unsubscribed: oneshot::Receiver<()>,
+    active_subscriptions: ActiveSubscriptions,
+}
+
+impl Drop for ActiveSubscription {
+    fn drop(&mut self) {
+        self.active_subscriptions.lock().remove(&self.id);
+    }
+}
+
+impl JsonRpcServerTrait for SubscriptionsRpcImpl {
+    fn new(
+        chain_id: ChainId,
+        execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        max_events_chunk_size: usize,
+        max_events_keys: usize,
+        starting_block: BlockHashAndNumber,
+        shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        pending_data: Arc<RwLock<PendingData>>,
+        pending_classes: Arc<RwLock<PendingClasses>>,
+        writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self {
+            rpc: JsonRpcServerImpl::new(
+                chain_id,
+                execution_config,
+                storage_reader,
+                max_events_chunk_size,
+                max_events_keys,
+                starting_block,
+                shared_highest_block,
+                pending_data,
+                pending_classes,
+                writer_client,
+            ),
+            active_subscriptions: ActiveSubscriptions::default(),
+        }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
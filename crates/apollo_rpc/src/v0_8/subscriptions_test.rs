@@ -0,0 +1,292 @@
+use std::time::Duration;
+
+use apollo_starknet_client::reader::objects::transaction::{
+    IntermediateInvokeTransaction,
+    Transaction as ClientTransaction,
+};
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use apollo_storage::StorageWriter;
+use apollo_test_utils::get_test_block;
+use assert_matches::assert_matches;
+use jsonrpsee::core::server::Subscription;
+use jsonrpsee::core::Error;
+use pretty_assertions::assert_eq;
+use serde::de::DeserializeOwned;
+use starknet_api::block::{
+    BlockBody,
+    BlockHash,
+    BlockHeader as StarknetApiBlockHeader,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+};
+use starknet_api::core::ContractAddress;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{contract_address, felt};
+
+use super::block::BlockHeader;
+use super::error::{BLOCK_NOT_FOUND, TOO_MANY_ADDRESSES_IN_FILTER};
+use super::subscriptions::{
+    NewTransactionStatus,
+    PendingTransaction,
+    ReorgData,
+    SubscriptionsRpcImpl,
+    MAX_ADDRESSES_IN_FILTER,
+};
+use super::transaction::{Event, TransactionFinalityStatus};
+use crate::api::{BlockHashOrNumber, BlockId};
+use crate::test_utils::{
+    get_test_pending_data,
+    get_test_rpc_server_and_storage_writer,
+    get_test_rpc_server_and_storage_writer_from_params,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn block_id(block_number: u64) -> BlockId {
+    BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(block_number)))
+}
+
+fn header(block_number: u64, block_hash: u64) -> StarknetApiBlockHeader {
+    StarknetApiBlockHeader {
+        block_hash: BlockHash(felt!(block_hash)),
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: BlockNumber(block_number),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn append_block(storage_writer: &mut StorageWriter, header: &StarknetApiBlockHeader) {
+    let block_number = header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, header)
+        .unwrap()
+        .append_state_diff(block_number, ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+async fn next<T: DeserializeOwned>(subscription: &mut Subscription) -> T {
+    tokio::time::timeout(TIMEOUT, subscription.next::<T>())
+        .await
+        .expect("Timed out waiting for a notification.")
+        .expect("The subscription was closed.")
+        .unwrap()
+        .0
+}
+
+#[tokio::test]
+async fn subscribe_new_heads_with_reorg() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<SubscriptionsRpcImpl>();
+    append_block(&mut storage_writer, &header(0, 0x10));
+    append_block(&mut storage_writer, &header(1, 0x11));
+
+    // Starts from the latest block by default.
+    let mut subscription =
+        module.subscribe_unbounded("starknet_subscribeNewHeads", [None::<BlockId>]).await.unwrap();
+    let res = next::<BlockHeader>(&mut subscription).await;
+    assert_eq!(res.block_number, BlockNumber(1));
+
+    append_block(&mut storage_writer, &header(2, 0x12));
+    let res = next::<BlockHeader>(&mut subscription).await;
+    assert_eq!(res.block_hash, BlockHash(felt!(0x12_u8)));
+
+    // Replace block 2 with another block in a single transaction.
+    let (txn, _) =
+        storage_writer.begin_rw_txn().unwrap().revert_state_diff(BlockNumber(2)).unwrap();
+    let (txn, _, _) = txn.revert_header(BlockNumber(2)).unwrap();
+    let new_header = header(2, 0x22);
+    txn.append_header(BlockNumber(2), &new_header)
+        .unwrap()
+        .append_state_diff(BlockNumber(2), ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    assert_eq!(
+        next::<ReorgData>(&mut subscription).await,
+        ReorgData {
+            starting_block_hash: BlockHash(felt!(0x12_u8)),
+            starting_block_number: BlockNumber(2),
+            ending_block_hash: BlockHash(felt!(0x12_u8)),
+            ending_block_number: BlockNumber(2),
+        }
+    );
+    let res = next::<BlockHeader>(&mut subscription).await;
+    assert_eq!(res.block_hash, BlockHash(felt!(0x22_u8)));
+}
+
+#[tokio::test]
+async fn subscribe_new_heads_from_unknown_block() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<SubscriptionsRpcImpl>();
+    append_block(&mut storage_writer, &header(0, 0x10));
+
+    let err =
+        module.subscribe_unbounded("starknet_subscribeNewHeads", [block_id(5)]).await.unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+}
+
+#[tokio::test]
+async fn subscribe_events_and_unsubscribe() {
+    let from_address = contract_address!("0x100");
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<SubscriptionsRpcImpl>();
+    let block =
+        get_test_block(2, Some(2), Some(vec![from_address, contract_address!("0x200")]), None);
+    let block_number = block.header.block_header_without_hash.block_number;
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block_number, &block.header)
+        .unwrap()
+        .append_body(block_number, block.body.clone())
+        .unwrap()
+        .append_state_diff(block_number, ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    let expected_events = expected_events(&block.header, &block.body, from_address);
+
+    let mut subscription = module
+        .subscribe_unbounded(
+            "starknet_subscribeEvents",
+            (Some(from_address), None::<Vec<Vec<()>>>, Some(block_id(0))),
+        )
+        .await
+        .unwrap();
+    for expected_event in expected_events {
+        assert_eq!(next::<Event>(&mut subscription).await, expected_event);
+    }
+
+    let res = module
+        .call::<_, bool>("starknet_unsubscribe", [subscription.subscription_id()])
+        .await
+        .unwrap();
+    assert!(res);
+    let res = tokio::time::timeout(TIMEOUT, subscription.next::<Event>()).await.unwrap();
+    assert!(res.is_none());
+    let res = module
+        .call::<_, bool>("starknet_unsubscribe", [subscription.subscription_id()])
+        .await
+        .unwrap();
+    assert!(!res);
+}
+
+fn expected_events(
+    header: &StarknetApiBlockHeader,
+    body: &BlockBody,
+    from_address: ContractAddress,
+) -> Vec<Event> {
+    body.transaction_outputs
+        .iter()
+        .zip(&body.transaction_hashes)
+        .flat_map(|(output, transaction_hash)| {
+            output.events().iter().map(move |event| (*transaction_hash, event))
+        })
+        .filter(|(_, event)| event.from_address == from_address)
+        .map(|(transaction_hash, event)| Event {
+            block_hash: Some(header.block_hash),
+            block_number: Some(header.block_header_without_hash.block_number),
+            transaction_hash,
+            event: event.clone(),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn subscribe_transaction_status() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<SubscriptionsRpcImpl>();
+    let block = get_test_block(1, None, None, None);
+    let transaction_hash = block.body.transaction_hashes[0];
+
+    let mut subscription = module
+        .subscribe_unbounded("starknet_subscribeTransactionStatus", [transaction_hash])
+        .await
+        .unwrap();
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(block.header.block_header_without_hash.block_number, &block.header)
+        .unwrap()
+        .append_body(block.header.block_header_without_hash.block_number, block.body)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let res = next::<NewTransactionStatus>(&mut subscription).await;
+    assert_eq!(res.transaction_hash, transaction_hash);
+    assert_eq!(res.status.finality_status, TransactionFinalityStatus::AcceptedOnL2);
+}
+
+fn invoke_transaction(transaction_hash: u8, sender_address: ContractAddress) -> ClientTransaction {
+    ClientTransaction::Invoke(IntermediateInvokeTransaction {
+        transaction_hash: TransactionHash(felt!(transaction_hash)),
+        sender_address,
+        ..Default::default()
+    })
+}
+
+#[tokio::test]
+async fn subscribe_pending_transactions() {
+    let sender_address = contract_address!("0x100");
+    let pending_data = get_test_pending_data();
+    let (module, _storage_writer) = get_test_rpc_server_and_storage_writer_from_params::<
+        SubscriptionsRpcImpl,
+    >(None, None, Some(pending_data.clone()), None, None);
+    pending_data.write().await.block.transactions_mutable().extend([
+        invoke_transaction(1, sender_address),
+        invoke_transaction(2, contract_address!("0x200")),
+    ]);
+
+    let mut subscription = module
+        .subscribe_unbounded(
+            "starknet_subscribePendingTransactions",
+            (Some(false), Some(vec![sender_address])),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        next::<PendingTransaction>(&mut subscription).await,
+        PendingTransaction::Hash(TransactionHash(felt!(1_u8)))
+    );
+
+    // Transactions that were already notified are not notified again.
+    pending_data
+        .write()
+        .await
+        .block
+        .transactions_mutable()
+        .push(invoke_transaction(3, sender_address));
+    assert_eq!(
+        next::<PendingTransaction>(&mut subscription).await,
+        PendingTransaction::Hash(TransactionHash(felt!(3_u8)))
+    );
+}
+
+#[tokio::test]
+async fn subscribe_pending_transactions_with_too_many_addresses() {
+    let (module, _storage_writer) =
+        get_test_rpc_server_and_storage_writer::<SubscriptionsRpcImpl>();
+    let addresses = (0..=MAX_ADDRESSES_IN_FILTER)
+        .map(|index| ContractAddress::from(u128::try_from(index).unwrap()))
+        .collect::<Vec<_>>();
+
+    let err = module
+        .subscribe_unbounded(
+            "starknet_subscribePendingTransactions",
+            (Some(false), Some(addresses)),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TOO_MANY_ADDRESSES_IN_FILTER.into());
+}
@@ -0,0 +1,435 @@
+use std::sync::Arc;
+
+use apollo_rpc_execution::objects::FeeEstimation;
+use apollo_rpc_execution::{ExecutionConfig, TraceOptions};
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::base_layer::BaseLayerStorageReader;
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::db::TransactionKind;
+use apollo_storage::{StorageReader, StorageTxn};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use papyrus_common::pending_classes::PendingClasses;
+use starknet_api::block::{BlockHashAndNumber, BlockNumber, BlockStatus};
+use starknet_api::contract_class::SierraVersion;
+use starknet_api::core::{ChainId, ClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{TransactionHash, TransactionOffsetInBlock};
+use starknet_types_core::felt::Felt;
+use tokio::sync::RwLock;
+
+use super::{
+    Block,
+    BlockId,
+    EventFilter,
+    JsonRpcV0_9Server as JsonRpcServer,
+    L1Reference,
+    StateOverrides,
+    Tag,
+    TraceFlag,
+    TransactionStatus,
+};
+use crate::api::{
+    BlockHashOrNumber,
+    BlockId as V0_8BlockId,
+    CallRequest,
+    JsonRpcServerTrait,
+    Tag as V0_8Tag,
+};
+use crate::internal_server_error;
+use crate::syncing_state::SyncingState;
+use crate::v0_8::api::api_impl::JsonRpcServerImpl as V0_8JsonRpcServerImpl;
+use crate::v0_8::api::{
+    CompiledContractClass,
+    EventFilter as V0_8EventFilter,
+    EventsChunk,
+    GatewayContractClass,
+    JsonRpcV0_8Server,
+    SimulatedTransaction,
+    SimulationFlag,
+    TransactionTraceWithHash,
+};
+use crate::v0_8::block::{Block as V0_8Block, GeneralBlockHeader};
+use crate::v0_8::broadcasted_transaction::{BroadcastedDeclareTransaction, BroadcastedTransaction};
+use crate::v0_8::error::BLOCK_NOT_FOUND;
+use crate::v0_8::execution::TransactionTrace;
+use crate::v0_8::state::StateUpdate;
+use crate::v0_8::transaction::{
+    GeneralTransactionReceipt,
+    MessageFromL1,
+    TransactionFinalityStatus,
+    TransactionWithHash,
+    TypedDeployAccountTransaction,
+    TypedInvokeTransaction,
+};
+use crate::v0_8::write_api_result::{
+    AddDeclareOkResult,
+    AddDeployAccountOkResult,
+    AddInvokeOkResult,
+};
+use crate::version_config::VERSION_0_9 as VERSION;
+
+/// Rpc server. The methods that didn't change since the previous version are served by the
+/// server of the previous version, after resolving the block ids that are new in this version.
+pub struct JsonRpcServerImpl {
+    pub v0_8: V0_8JsonRpcServerImpl,
+}
+
+#[async_trait]
+impl JsonRpcServer for JsonRpcServerImpl {
+    fn spec_version(&self) -> RpcResult<String> {
+        Ok(format!("{VERSION}"))
+    }
+
+    fn block_number(&self) -> RpcResult<BlockNumber> {
+        self.v0_8.block_number()
+    }
+
+    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber> {
+        self.v0_8.block_hash_and_number()
+    }
+
+    async fn get_block_w_transaction_hashes(&self, block_id: BlockId) -> RpcResult<Block> {
+        let block =
+            self.v0_8.get_block_w_transaction_hashes(self.resolve_block_id(block_id)?).await?;
+        self.add_l1_reference(block)
+    }
+
+    async fn get_block_w_full_transactions(&self, block_id: BlockId) -> RpcResult<Block> {
+        let block =
+            self.v0_8.get_block_w_full_transactions(self.resolve_block_id(block_id)?).await?;
+        self.add_l1_reference(block)
+    }
+
+    async fn get_block_w_full_transactions_and_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Block> {
+        let block = self
+            .v0_8
+            .get_block_w_full_transactions_and_receipts(self.resolve_block_id(block_id)?)
+            .await?;
+        self.add_l1_reference(block)
+    }
+
+    async fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        block_id: BlockId,
+    ) -> RpcResult<Felt> {
+        self.v0_8.get_storage_at(contract_address, key, self.resolve_block_id(block_id)?).await
+    }
+
+    async fn get_transaction_by_hash(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<TransactionWithHash> {
+        self.v0_8.get_transaction_by_hash(transaction_hash).await
+    }
+
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockId,
+        index: TransactionOffsetInBlock,
+    ) -> RpcResult<TransactionWithHash> {
+        self.v0_8
+            .get_transaction_by_block_id_and_index(self.resolve_block_id(block_id)?, index)
+            .await
+    }
+
+    async fn get_block_transaction_count(&self, block_id: BlockId) -> RpcResult<usize> {
+        self.v0_8.get_block_transaction_count(self.resolve_block_id(block_id)?).await
+    }
+
+    async fn get_state_update(&self, block_id: BlockId) -> RpcResult<StateUpdate> {
+        self.v0_8.get_state_update(self.resolve_block_id(block_id)?).await
+    }
+
+    async fn get_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<TransactionStatus> {
+        let status = self.v0_8.get_transaction_status(transaction_hash).await?;
+        let l1_reference = match status.finality_status {
+            TransactionFinalityStatus::AcceptedOnL1 => {
+                let txn = self.v0_8.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+                match txn
+                    .get_transaction_idx_by_hash(&transaction_hash)
+                    .map_err(internal_server_error)?
+                {
+                    Some(transaction_index) => get_l1_reference(&txn, transaction_index.0)?,
+                    None => None,
+                }
+            }
+            TransactionFinalityStatus::AcceptedOnL2 => None,
+        };
+        Ok(TransactionStatus { status, l1_reference })
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<GeneralTransactionReceipt> {
+        self.v0_8.get_transaction_receipt(transaction_hash).await
+    }
+
+    async fn get_class(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> RpcResult<GatewayContractClass> {
+        self.v0_8.get_class(self.resolve_block_id(block_id)?, class_hash).await
+    }
+
+    async fn get_class_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<GatewayContractClass> {
+        self.v0_8.get_class_at(self.resolve_block_id(block_id)?, contract_address).await
+    }
+
+    async fn get_class_hash_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<ClassHash> {
+        self.v0_8.get_class_hash_at(self.resolve_block_id(block_id)?, contract_address).await
+    }
+
+    async fn get_nonce(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<Nonce> {
+        self.v0_8.get_nonce(self.resolve_block_id(block_id)?, contract_address).await
+    }
+
+    fn chain_id(&self) -> RpcResult<String> {
+        self.v0_8.chain_id()
+    }
+
+    async fn get_events(&self, filter: EventFilter) -> RpcResult<EventsChunk> {
+        let filter = V0_8EventFilter {
+            from_block: filter
+                .from_block
+                .map(|block_id| self.resolve_block_id(block_id))
+                .transpose()?,
+            to_block: filter
+                .to_block
+                .map(|block_id| self.resolve_block_id(block_id))
+                .transpose()?,
+            continuation_token: filter.continuation_token,
+            chunk_size: filter.chunk_size,
+            address: filter.address,
+            keys: filter.keys,
+        };
+        self.v0_8.get_events(filter).await
+    }
+
+    async fn syncing(&self) -> RpcResult<SyncingState> {
+        self.v0_8.syncing().await
+    }
+
+    async fn call(
+        &self,
+        request: CallRequest,
+        block_id: BlockId,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<Felt>> {
+        self.v0_8
+            .call_with_state_overrides(
+                request,
+                self.resolve_block_id(block_id)?,
+                state_overrides.unwrap_or_default().try_into()?,
+            )
+            .await
+    }
+
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: TypedInvokeTransaction,
+    ) -> RpcResult<AddInvokeOkResult> {
+        self.v0_8.add_invoke_transaction(invoke_transaction).await
+    }
+
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: TypedDeployAccountTransaction,
+    ) -> RpcResult<AddDeployAccountOkResult> {
+        self.v0_8.add_deploy_account_transaction(deploy_account_transaction).await
+    }
+
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedDeclareTransaction,
+    ) -> RpcResult<AddDeclareOkResult> {
+        self.v0_8.add_declare_transaction(declare_transaction).await
+    }
+
+    async fn estimate_fee(
+        &self,
+        request: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        block_id: BlockId,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<FeeEstimation>> {
+        self.v0_8
+            .estimate_fee_with_state_overrides(
+                request,
+                simulation_flags,
+                self.resolve_block_id(block_id)?,
+                state_overrides.unwrap_or_default().try_into()?,
+            )
+            .await
+    }
+
+    async fn estimate_message_fee(
+        &self,
+        message: MessageFromL1,
+        block_id: BlockId,
+    ) -> RpcResult<FeeEstimation> {
+        self.v0_8.estimate_message_fee(message, self.resolve_block_id(block_id)?).await
+    }
+
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        trace_flags: Option<Vec<TraceFlag>>,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.v0_8
+            .simulate_transactions_with_trace_flags(
+                self.resolve_block_id(block_id)?,
+                transactions,
+                simulation_flags,
+                trace_options(trace_flags),
+                state_overrides.unwrap_or_default().try_into()?,
+            )
+            .await
+    }
+
+    async fn trace_transaction(
+        &self,
+        transaction_hash: TransactionHash,
+        trace_flags: Option<Vec<TraceFlag>>,
+    ) -> RpcResult<TransactionTrace> {
+        self.v0_8
+            .trace_transaction_with_trace_flags(transaction_hash, trace_options(trace_flags))
+            .await
+    }
+
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<TransactionTraceWithHash>> {
+        self.v0_8.trace_block_transactions(self.resolve_block_id(block_id)?).await
+    }
+
+    fn get_compiled_class(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> RpcResult<(CompiledContractClass, SierraVersion)> {
+        self.v0_8.get_compiled_class(self.resolve_block_id(block_id)?, class_hash)
+    }
+}
+
+impl JsonRpcServerImpl {
+    fn resolve_block_id(&self, block_id: BlockId) -> RpcResult<V0_8BlockId> {
+        resolve_block_id(&self.v0_8.storage_reader, block_id)
+    }
+
+    // Adds the base layer transaction that proved the block to a block that was accepted on L1.
+    fn add_l1_reference(&self, block: V0_8Block) -> RpcResult<Block> {
+        let l1_reference = match (&block.status, &block.header) {
+            (Some(BlockStatus::AcceptedOnL1), GeneralBlockHeader::BlockHeader(header)) => {
+                let txn = self.v0_8.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+                get_l1_reference(&txn, header.block_number)?
+            }
+            _ => None,
+        };
+        Ok(Block { block, l1_reference })
+    }
+}
+
+fn get_l1_reference<Mode: TransactionKind>(
+    txn: &StorageTxn<'_, Mode>,
+    block_number: BlockNumber,
+) -> RpcResult<Option<L1Reference>> {
+    Ok(txn.get_base_layer_proof(block_number).map_err(internal_server_error)?.map(Into::into))
+}
+
+// Converts the block id to a block id of the previous version. The most recent block that was
+// accepted on L1 is converted to its number.
+pub(crate) fn resolve_block_id(
+    storage_reader: &StorageReader,
+    block_id: BlockId,
+) -> RpcResult<V0_8BlockId> {
+    Ok(match block_id {
+        BlockId::HashOrNumber(block_hash_or_number) => {
+            V0_8BlockId::HashOrNumber(block_hash_or_number)
+        }
+        BlockId::Tag(Tag::Latest) => V0_8BlockId::Tag(V0_8Tag::Latest),
+        BlockId::Tag(Tag::Pending) => V0_8BlockId::Tag(V0_8Tag::Pending),
+        BlockId::Tag(Tag::L1Accepted) => {
+            let block_number = storage_reader
+                .begin_ro_txn()
+                .map_err(internal_server_error)?
+                .get_base_layer_block_marker()
+                .map_err(internal_server_error)?
+                .prev()
+                .ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?;
+            V0_8BlockId::HashOrNumber(BlockHashOrNumber::Number(block_number))
+        }
+    })
+}
+
+fn trace_options(trace_flags: Option<Vec<TraceFlag>>) -> TraceOptions {
+    let trace_flags = trace_flags.unwrap_or_default();
+    TraceOptions {
+        state_accesses: trace_flags.contains(&TraceFlag::TraceStateAccesses),
+        vm_steps: trace_flags.contains(&TraceFlag::TraceVmSteps),
+        last_transaction_only: false,
+    }
+}
+
+impl JsonRpcServerTrait for JsonRpcServerImpl {
+    fn new(
+        chain_id: ChainId,
+        execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        max_events_chunk_size: usize,
+        max_events_keys: usize,
+        starting_block: BlockHashAndNumber,
+        shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        pending_data: Arc<RwLock<PendingData>>,
+        pending_classes: Arc<RwLock<PendingClasses>>,
+        writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self {
+            v0_8: V0_8JsonRpcServerImpl::new(
+                chain_id,
+                execution_config,
+                storage_reader,
+                max_events_chunk_size,
+                max_events_keys,
+                starting_block,
+                shared_highest_block,
+                pending_data,
+                pending_classes,
+                writer_client,
+            ),
+        }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
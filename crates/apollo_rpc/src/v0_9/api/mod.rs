@@ -0,0 +1,462 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use apollo_proc_macros::versioned_rpc;
+use apollo_rpc_execution::objects::FeeEstimation;
+use apollo_rpc_execution::state_overrides::{
+    ContractOverride as ExecutionContractOverride,
+    InjectedClass as ExecutionInjectedClass,
+    StateOverrides as ExecutionStateOverrides,
+};
+use apollo_storage::base_layer::BaseLayerProof;
+use ethers::types::H256;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::types::ErrorObjectOwned;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+use starknet_api::contract_class::SierraVersion;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{EventKey, TransactionHash, TransactionOffsetInBlock};
+use starknet_types_core::felt::Felt;
+
+use crate::api::{BlockHashOrNumber, CallRequest};
+use crate::syncing_state::SyncingState;
+use crate::v0_8::api::{
+    CompiledContractClass,
+    ContinuationToken,
+    EventsChunk,
+    GatewayContractClass,
+    SimulatedTransaction,
+    SimulationFlag,
+    TransactionTraceWithHash,
+};
+use crate::v0_8::block::Block as V0_8Block;
+use crate::v0_8::broadcasted_transaction::{BroadcastedDeclareTransaction, BroadcastedTransaction};
+use crate::v0_8::execution::TransactionTrace;
+use crate::v0_8::state::{StateUpdate, StorageEntry};
+use crate::v0_8::transaction::{
+    GeneralTransactionReceipt,
+    MessageFromL1,
+    TransactionStatus as V0_8TransactionStatus,
+    TransactionWithHash,
+    TypedDeployAccountTransaction,
+    TypedInvokeTransaction,
+};
+use crate::v0_8::write_api_result::{
+    AddDeclareOkResult,
+    AddDeployAccountOkResult,
+    AddInvokeOkResult,
+};
+
+pub mod api_impl;
+#[cfg(test)]
+mod test;
+
+#[versioned_rpc("V0_9")]
+#[async_trait]
+pub trait JsonRpc {
+    /// Returns the version of the Starknet JSON-RPC specification being used
+    #[method(name = "specVersion")]
+    fn spec_version(&self) -> RpcResult<String>;
+
+    /// Gets the most recent accepted block number.
+    #[method(name = "blockNumber")]
+    fn block_number(&self) -> RpcResult<BlockNumber>;
+
+    /// Gets the most recent accepted block hash and number.
+    #[method(name = "blockHashAndNumber")]
+    fn block_hash_and_number(&self) -> RpcResult<BlockHashAndNumber>;
+
+    /// Gets block information with transaction hashes given a block identifier.
+    #[method(name = "getBlockWithTxHashes")]
+    async fn get_block_w_transaction_hashes(&self, block_id: BlockId) -> RpcResult<Block>;
+
+    /// Gets block information with full transactions given a block identifier.
+    #[method(name = "getBlockWithTxs")]
+    async fn get_block_w_full_transactions(&self, block_id: BlockId) -> RpcResult<Block>;
+
+    /// Gets block information with full transactions given a block identifier.
+    #[method(name = "getBlockWithReceipts")]
+    async fn get_block_w_full_transactions_and_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Block>;
+
+    /// Gets the value of the storage at the given address, key, and block.
+    #[method(name = "getStorageAt")]
+    async fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        block_id: BlockId,
+    ) -> RpcResult<Felt>;
+
+    /// Gets the details of a submitted transaction.
+    #[method(name = "getTransactionByHash")]
+    async fn get_transaction_by_hash(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<TransactionWithHash>;
+
+    /// Gets the details of a transaction by a given block id and index.
+    #[method(name = "getTransactionByBlockIdAndIndex")]
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        block_id: BlockId,
+        index: TransactionOffsetInBlock,
+    ) -> RpcResult<TransactionWithHash>;
+
+    /// Gets the number of transactions in a block given a block id.
+    #[method(name = "getBlockTransactionCount")]
+    async fn get_block_transaction_count(&self, block_id: BlockId) -> RpcResult<usize>;
+
+    /// Gets the information about the result of executing the requested block.
+    #[method(name = "getStateUpdate")]
+    async fn get_state_update(&self, block_id: BlockId) -> RpcResult<StateUpdate>;
+
+    /// Gets the transaction status, and the base layer transaction that proved the block of the
+    /// transaction if it was accepted on L1.
+    #[method(name = "getTransactionStatus")]
+    async fn get_transaction_status(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<TransactionStatus>;
+
+    /// Gets the transaction receipt by the transaction hash.
+    #[method(name = "getTransactionReceipt")]
+    async fn get_transaction_receipt(
+        &self,
+        transaction_hash: TransactionHash,
+    ) -> RpcResult<GeneralTransactionReceipt>;
+
+    /// Gets the contract class definition associated with the given hash.
+    #[method(name = "getClass")]
+    async fn get_class(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> RpcResult<GatewayContractClass>;
+
+    /// Gets the contract class definition in the given block at the given address.
+    #[method(name = "getClassAt")]
+    async fn get_class_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<GatewayContractClass>;
+
+    /// Gets the contract class hash in the given block for the contract deployed at the given
+    /// address.
+    #[method(name = "getClassHashAt")]
+    async fn get_class_hash_at(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<ClassHash>;
+
+    /// Gets the nonce associated with the given address in the given block.
+    #[method(name = "getNonce")]
+    async fn get_nonce(
+        &self,
+        block_id: BlockId,
+        contract_address: ContractAddress,
+    ) -> RpcResult<Nonce>;
+
+    /// Returns the currently configured StarkNet chain id.
+    #[method(name = "chainId")]
+    fn chain_id(&self) -> RpcResult<String>;
+
+    /// Returns all events matching the given filter.
+    #[method(name = "getEvents")]
+    async fn get_events(&self, filter: EventFilter) -> RpcResult<EventsChunk>;
+
+    /// Returns the synching status of the node, or false if the node is not synching.
+    #[method(name = "syncing")]
+    async fn syncing(&self) -> RpcResult<SyncingState>;
+
+    /// Executes the entry point of the contract at the given address with the given calldata,
+    /// returns the result (Retdata). The state overrides replace parts of the state at the block.
+    #[method(name = "call")]
+    async fn call(
+        &self,
+        request: CallRequest,
+        block_id: BlockId,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<Felt>>;
+
+    /// Submits a new invoke transaction to be added to the chain.
+    #[method(name = "addInvokeTransaction")]
+    async fn add_invoke_transaction(
+        &self,
+        invoke_transaction: TypedInvokeTransaction,
+    ) -> RpcResult<AddInvokeOkResult>;
+
+    /// Submits a new deploy account transaction to be added to the chain.
+    #[method(name = "addDeployAccountTransaction")]
+    async fn add_deploy_account_transaction(
+        &self,
+        deploy_account_transaction: TypedDeployAccountTransaction,
+    ) -> RpcResult<AddDeployAccountOkResult>;
+
+    /// Submits a new declare transaction to be added to the chain.
+    #[method(name = "addDeclareTransaction")]
+    async fn add_declare_transaction(
+        &self,
+        declare_transaction: BroadcastedDeclareTransaction,
+    ) -> RpcResult<AddDeclareOkResult>;
+
+    /// Estimates the fee of a series of transactions. The state overrides replace parts of the
+    /// state at the block.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(
+        &self,
+        request: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        block_id: BlockId,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<FeeEstimation>>;
+
+    /// Estimates the fee of a message from L1.
+    #[method(name = "estimateMessageFee")]
+    async fn estimate_message_fee(
+        &self,
+        message: MessageFromL1,
+        block_id: BlockId,
+    ) -> RpcResult<FeeEstimation>;
+
+    /// Simulates execution of a series of transactions. The trace flags add debugging data to the
+    /// traces, and the state overrides replace parts of the state at the block.
+    #[method(name = "simulateTransactions")]
+    async fn simulate_transactions(
+        &self,
+        block_id: BlockId,
+        transactions: Vec<BroadcastedTransaction>,
+        simulation_flags: Vec<SimulationFlag>,
+        trace_flags: Option<Vec<TraceFlag>>,
+        state_overrides: Option<StateOverrides>,
+    ) -> RpcResult<Vec<SimulatedTransaction>>;
+
+    /// Calculates the transaction trace of a transaction that is already included in a block. The
+    /// trace flags add debugging data to the trace.
+    #[method(name = "traceTransaction")]
+    async fn trace_transaction(
+        &self,
+        transaction_hash: TransactionHash,
+        trace_flags: Option<Vec<TraceFlag>>,
+    ) -> RpcResult<TransactionTrace>;
+
+    /// Calculates the transaction trace of all of the transactions in a block.
+    #[method(name = "traceBlockTransactions")]
+    async fn trace_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Vec<TransactionTraceWithHash>>;
+
+    /// Returns the compiled class associated with the given class hash.
+    #[method(name = "getCompiledContractClass")]
+    fn get_compiled_class(
+        &self,
+        block_id: BlockId,
+        class_hash: ClassHash,
+    ) -> RpcResult<(CompiledContractClass, SierraVersion)>;
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Tag {
+    /// The most recent block that was accepted on L1
+    #[serde(rename = "l1_accepted")]
+    L1Accepted,
+    /// The most recent fully constructed block
+    #[serde(rename = "latest")]
+    Latest,
+    /// Currently constructed block
+    #[serde(rename = "pending")]
+    Pending,
+}
+
+/// The base layer transaction of the state update that proved a block.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct L1Reference {
+    pub l1_block_number: u64,
+    pub l1_block_hash: H256,
+    pub l1_transaction_hash: H256,
+}
+
+impl From<BaseLayerProof> for L1Reference {
+    fn from(proof: BaseLayerProof) -> Self {
+        Self {
+            l1_block_number: proof.l1_block_number,
+            l1_block_hash: H256(proof.l1_block_hash),
+            l1_transaction_hash: H256(proof.l1_transaction_hash),
+        }
+    }
+}
+
+/// A block that, unlike the block of the previous version, includes the base layer transaction
+/// that proved it.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Block {
+    #[serde(flatten)]
+    pub block: V0_8Block,
+    /// Missing if the block wasn't accepted on L1 yet, or if the state update that proved it
+    /// wasn't synced, e.g., when the node started syncing the base layer after it was proved.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub l1_reference: Option<L1Reference>,
+}
+
+/// A transaction status that, unlike the status of the previous version, includes the base layer
+/// transaction that proved the block of the transaction.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TransactionStatus {
+    #[serde(flatten)]
+    pub status: V0_8TransactionStatus,
+    /// Missing if the block of the transaction wasn't accepted on L1 yet, or if the state update
+    /// that proved it wasn't synced.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub l1_reference: Option<L1Reference>,
+}
+
+/// A block id that, unlike the block id of the previous versions, can refer to the most recent
+/// block that was accepted on L1.
+#[derive(Copy, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BlockId {
+    HashOrNumber(BlockHashOrNumber),
+    Tag(Tag),
+}
+
+/// Flags that add data which is not part of the specification to execution traces.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TraceFlag {
+    /// Add the storage reads and writes and the class hash changes of each function invocation,
+    /// and the nonce change of the account that sent the transaction.
+    TraceStateAccesses,
+    /// Add the PC, AP and FP of each Cairo step run by the function invocations that run on the
+    /// Cairo VM.
+    TraceVmSteps,
+}
+
+/// Values that replace the state at the block on top of which transactions and calls are executed.
+/// Everything that isn't overridden keeps its value at the block.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StateOverrides {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contracts: Vec<ContractOverride>,
+    /// Classes to execute as if they were declared, in the format of
+    /// `starknet_getCompiledContractClass`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub classes: Vec<InjectedClass>,
+}
+
+/// Overrides of the state of a contract. Setting the class hash of an address without a contract
+/// deploys a contract there.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ContractOverride {
+    pub contract_address: ContractAddress,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<ClassHash>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Nonce>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storage: Vec<StorageEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strk_balance: Option<Felt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_balance: Option<Felt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InjectedClass {
+    pub class_hash: ClassHash,
+    pub compiled_class: CompiledContractClass,
+    pub sierra_version: SierraVersion,
+}
+
+impl TryFrom<StateOverrides> for ExecutionStateOverrides {
+    type Error = ErrorObjectOwned;
+
+    /// Fails if an address, a storage key of a contract or a class hash is overridden more than
+    /// once, instead of silently applying only one of the overrides.
+    fn try_from(state_overrides: StateOverrides) -> Result<Self, Self::Error> {
+        let contracts = state_overrides
+            .contracts
+            .into_iter()
+            .map(|contract_override| {
+                let storage = collect_unique(
+                    contract_override
+                        .storage
+                        .into_iter()
+                        .map(|StorageEntry { key, value }| (key, value)),
+                    "storage key",
+                )?;
+                Ok::<_, ErrorObjectOwned>((
+                    contract_override.contract_address,
+                    ExecutionContractOverride {
+                        class_hash: contract_override.class_hash,
+                        nonce: contract_override.nonce,
+                        storage,
+                        strk_balance: contract_override.strk_balance,
+                        eth_balance: contract_override.eth_balance,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let classes = state_overrides.classes.into_iter().map(|injected_class| {
+            let class = match injected_class.compiled_class {
+                CompiledContractClass::V0(class) => ExecutionInjectedClass::Cairo0(class),
+                CompiledContractClass::V1(casm) => {
+                    ExecutionInjectedClass::Cairo1(casm, injected_class.sierra_version)
+                }
+            };
+            (injected_class.class_hash, class)
+        });
+        Ok(Self {
+            contracts: collect_unique(contracts, "contract address")?,
+            classes: collect_unique(classes, "class hash")?,
+        })
+    }
+}
+
+// Collects the entries to a map, failing on a key that appears more than once.
+fn collect_unique<K: Eq + Hash + Debug, V>(
+    entries: impl IntoIterator<Item = (K, V)>,
+    key_name: &str,
+) -> Result<HashMap<K, V>, ErrorObjectOwned> {
+    let mut map = HashMap::new();
+    for (key, value) in entries {
+        match map.entry(key) {
+            Entry::Occupied(entry) => {
+                return Err(ErrorObjectOwned::owned(
+                    ErrorCode::InvalidParams.code(),
+                    format!("The {key_name} {:?} is overridden more than once.", entry.key()),
+                    None::<()>,
+                ));
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+        }
+    }
+    Ok(map)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EventFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<ContinuationToken>,
+    pub chunk_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<ContractAddress>,
+    #[serde(default)]
+    pub keys: Vec<HashSet<EventKey>>,
+}
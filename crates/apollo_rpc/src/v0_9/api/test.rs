@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use apollo_rpc_execution::execution_utils::selector_from_name;
+use apollo_rpc_execution::objects::NonceChange;
+use apollo_rpc_execution::state_overrides::{
+    ContractOverride as ExecutionContractOverride,
+    StateOverrides as ExecutionStateOverrides,
+};
+use apollo_storage::base_layer::{BaseLayerProof, BaseLayerStorageWriter};
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::class::ClassStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use apollo_storage::StorageWriter;
+use apollo_test_utils::get_test_block;
+use assert_matches::assert_matches;
+use ethers::types::H256;
+use indexmap::indexmap;
+use itertools::Itertools;
+use jsonrpsee::core::Error;
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::Methods;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::test_utils::invoke::invoke_tx;
+use starknet_api::transaction::{
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::{calldata, contract_address, felt, invoke_tx_args, nonce, storage_key, tx_hash};
+
+use super::api_impl::JsonRpcServerImpl;
+use super::{Block, BlockId, L1Reference, StateOverrides, Tag, TraceFlag, TransactionStatus};
+use crate::api::BlockHashOrNumber;
+use crate::test_utils::{
+    get_starknet_spec_api_schema_for_components,
+    get_test_rpc_server_and_storage_writer,
+    method_name_to_spec_method_name,
+    validate_schema,
+    SpecFile,
+};
+use crate::v0_8::api::api_impl::JsonRpcServerImpl as V0_8JsonRpcServerImpl;
+use crate::v0_8::error::BLOCK_NOT_FOUND;
+use crate::v0_8::execution::{FunctionInvocationResult, InvokeTransactionTrace, TransactionTrace};
+use crate::v0_8::execution_test::{
+    prepare_storage_for_execution,
+    ACCOUNT_ADDRESS,
+    BLOCK_TIMESTAMP,
+    DEPRECATED_CONTRACT_ADDRESS,
+    GAS_PRICE,
+    MAX_FEE,
+    SEQUENCER_ADDRESS,
+};
+use crate::v0_8::transaction::TransactionFinalityStatus;
+use crate::version_config::{VERSION_0_8, VERSION_0_9};
+
+fn write_blocks(storage_writer: &mut StorageWriter, n_blocks: u64) {
+    for block_number in 0..n_blocks {
+        let mut block = get_test_block(1, None, None, None);
+        block.header.block_hash = BlockHash(felt!(block_number + 1));
+        block.header.block_header_without_hash.block_number = BlockNumber(block_number);
+        storage_writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(BlockNumber(block_number), &block.header)
+            .unwrap()
+            .append_body(BlockNumber(block_number), block.body)
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number), ThinStateDiff::default())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn spec_version() {
+    let (module, _) = get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let res = module.call::<_, String>("starknet_V0_9_specVersion", [""; 0]).await.unwrap();
+    assert_eq!(res, VERSION_0_9.to_string());
+}
+
+#[tokio::test]
+async fn l1_accepted_block_id() {
+    let method_name = "starknet_V0_9_getBlockWithTxHashes";
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    write_blocks(&mut storage_writer, 2);
+
+    // No block was accepted on L1.
+    let err = module
+        .call::<_, serde_json::Value>(method_name, [BlockId::Tag(Tag::L1Accepted)])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(1))
+        .unwrap()
+        .commit()
+        .unwrap();
+    let res = module
+        .call::<_, serde_json::Value>(method_name, [BlockId::Tag(Tag::L1Accepted)])
+        .await
+        .unwrap();
+    let expected = module
+        .call::<_, serde_json::Value>(
+            method_name,
+            [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(0)))],
+        )
+        .await
+        .unwrap();
+    assert_eq!(res, expected);
+    // The objects of this version are defined by the specification of the previous version.
+    let schema = get_starknet_spec_api_schema_for_components(
+        &[(SpecFile::StarknetApiOpenrpc, &["BLOCK_WITH_TX_HASHES"])],
+        &VERSION_0_8,
+    );
+    assert!(validate_schema(&schema, &res));
+
+    // The latest block is not accepted on L1 yet.
+    let latest = module
+        .call::<_, serde_json::Value>(method_name, [BlockId::Tag(Tag::Latest)])
+        .await
+        .unwrap();
+    assert_ne!(res, latest);
+}
+
+#[tokio::test]
+async fn l1_reference() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let transaction_hash = |block_number: u64| TransactionHash(felt!(block_number + 200));
+    let mut txn = storage_writer.begin_rw_txn().unwrap();
+    for block_number in 0..4 {
+        let header = BlockHeader {
+            block_hash: BlockHash(felt!(block_number + 100)),
+            block_header_without_hash: BlockHeaderWithoutHash {
+                block_number: BlockNumber(block_number),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let body = BlockBody {
+            transactions: vec![Transaction::Invoke(invoke_tx(invoke_tx_args!()))],
+            transaction_outputs: vec![
+                TransactionOutput::Invoke(InvokeTransactionOutput::default()),
+            ],
+            transaction_hashes: vec![transaction_hash(block_number)],
+        };
+        txn = txn
+            .append_header(BlockNumber(block_number), &header)
+            .unwrap()
+            .append_body(BlockNumber(block_number), body)
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number), ThinStateDiff::default())
+            .unwrap();
+    }
+    // Block 0 was proved before the base layer was synced, and blocks 1 and 2 were proved by a
+    // single state update.
+    txn.update_base_layer_block_marker(&BlockNumber(3))
+        .unwrap()
+        .update_base_layer_proof(
+            BlockNumber(2),
+            &BaseLayerProof {
+                first_proved_block: BlockNumber(1),
+                l1_block_number: 10,
+                l1_block_hash: [1; 32],
+                l1_transaction_hash: [10; 32],
+            },
+        )
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let l1_reference = L1Reference {
+        l1_block_number: 10,
+        l1_block_hash: H256([1; 32]),
+        l1_transaction_hash: H256([10; 32]),
+    };
+    let expected = [
+        (TransactionFinalityStatus::AcceptedOnL1, None),
+        (TransactionFinalityStatus::AcceptedOnL1, Some(l1_reference)),
+        (TransactionFinalityStatus::AcceptedOnL1, Some(l1_reference)),
+        (TransactionFinalityStatus::AcceptedOnL2, None),
+    ];
+    for (block_number, (expected_finality_status, expected_l1_reference)) in (0..).zip(expected) {
+        let block = module
+            .call::<_, Block>(
+                "starknet_V0_9_getBlockWithTxHashes",
+                [BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(block_number)))],
+            )
+            .await
+            .unwrap();
+        assert_eq!(block.l1_reference, expected_l1_reference);
+
+        let status = module
+            .call::<_, TransactionStatus>(
+                "starknet_V0_9_getTransactionStatus",
+                [transaction_hash(block_number)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(status.status.finality_status, expected_finality_status);
+        assert_eq!(status.l1_reference, expected_l1_reference);
+    }
+}
+
+// Every method of the previous version is served in this version.
+#[test]
+fn previous_version_methods_coverage() {
+    let spec_method_names = |methods: Methods| {
+        methods.method_names().map(method_name_to_spec_method_name).sorted().collect::<Vec<_>>()
+    };
+    let (module, _) = get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let (v0_8_module, _) = get_test_rpc_server_and_storage_writer::<V0_8JsonRpcServerImpl>();
+    assert_eq!(spec_method_names(module.into()), spec_method_names(v0_8_module.into()));
+}
+
+#[tokio::test]
+async fn trace_transaction_with_nonce_change() {
+    let (module, storage_writer) = get_test_rpc_server_and_storage_writer::<JsonRpcServerImpl>();
+    let mut storage_writer = prepare_storage_for_execution(storage_writer);
+    let tx_hash = tx_hash!(0x1234);
+    let tx = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+        max_fee: *MAX_FEE,
+        sender_address: *ACCOUNT_ADDRESS,
+        calldata: calldata![
+            *DEPRECATED_CONTRACT_ADDRESS.0.key(),  // Contract address.
+            selector_from_name("return_result").0, // EP selector.
+            felt!(1_u8),                           // Calldata length.
+            felt!(2_u8)                            // Calldata: num.
+        ],
+        nonce: nonce!(0_u8),
+        ..Default::default()
+    }));
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(
+            BlockNumber(3),
+            &BlockHeader {
+                block_hash: BlockHash(felt!("0x3")),
+                block_header_without_hash: BlockHeaderWithoutHash {
+                    block_number: BlockNumber(3),
+                    l1_gas_price: *GAS_PRICE,
+                    sequencer: *SEQUENCER_ADDRESS,
+                    timestamp: *BLOCK_TIMESTAMP,
+                    parent_hash: BlockHash(felt!("0x2")),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_body(
+            BlockNumber(3),
+            BlockBody {
+                transactions: vec![tx],
+                transaction_outputs: vec![TransactionOutput::Invoke(
+                    InvokeTransactionOutput::default(),
+                )],
+                transaction_hashes: vec![tx_hash],
+            },
+        )
+        .unwrap()
+        .append_state_diff(
+            BlockNumber(3),
+            ThinStateDiff {
+                nonces: indexmap!(*ACCOUNT_ADDRESS => nonce!(1_u8)),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_classes(BlockNumber(3), &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let trace = module
+        .call::<_, TransactionTrace>(
+            "starknet_V0_9_traceTransaction",
+            (tx_hash, [TraceFlag::TraceStateAccesses]),
+        )
+        .await
+        .unwrap();
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        validate_invocation: Some(validate_invocation),
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        ..
+    }) = trace
+    else {
+        panic!("Expected a successful invoke trace with a validation.")
+    };
+    // The nonce change is traced in the validation invocation only.
+    assert_eq!(
+        validate_invocation.state_accesses.unwrap().nonce_changes,
+        vec![NonceChange {
+            contract_address: *ACCOUNT_ADDRESS,
+            old_nonce: nonce!(0_u8),
+            new_nonce: nonce!(1_u8),
+        }]
+    );
+    assert!(execute_invocation.state_accesses.unwrap().nonce_changes.is_empty());
+}
+
+#[test]
+fn state_overrides_conversion() {
+    let state_overrides: StateOverrides = serde_json::from_value(serde_json::json!({
+        "contracts": [{
+            "contract_address": "0x1",
+            "nonce": "0x2",
+            "storage": [{"key": "0x3", "value": "0x4"}],
+            "strk_balance": "0x5"
+        }]
+    }))
+    .unwrap();
+    let expected = ExecutionStateOverrides {
+        contracts: HashMap::from([(
+            contract_address!("0x1"),
+            ExecutionContractOverride {
+                nonce: Some(nonce!(2_u8)),
+                storage: HashMap::from([(storage_key!("0x3"), felt!(4_u8))]),
+                strk_balance: Some(felt!(5_u8)),
+                ..Default::default()
+            },
+        )]),
+        ..Default::default()
+    };
+    assert_eq!(ExecutionStateOverrides::try_from(state_overrides).unwrap(), expected);
+}
+
+#[test]
+fn duplicate_state_overrides_are_rejected() {
+    for state_overrides in [
+        serde_json::json!({
+            "contracts": [
+                {"contract_address": "0x1", "nonce": "0x2"},
+                {"contract_address": "0x1", "strk_balance": "0x5"}
+            ]
+        }),
+        serde_json::json!({
+            "contracts": [{
+                "contract_address": "0x1",
+                "storage": [{"key": "0x3", "value": "0x4"}, {"key": "0x3", "value": "0x5"}]
+            }]
+        }),
+    ] {
+        let state_overrides: StateOverrides = serde_json::from_value(state_overrides).unwrap();
+        let err = ExecutionStateOverrides::try_from(state_overrides).unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+    }
+}
@@ -0,0 +1,219 @@
+//! The history of the gas prices and the tips of recent blocks, for estimating the fees of new
+//! transactions. The prices and the L2 gas usage are read from the stored block headers, and the
+//! tip percentiles are calculated from the V3 transactions of each block, whose bodies are read
+//! only if tip percentiles are requested. The suggested tip is calculated by the mempool from the
+//! transactions that are waiting to be included in a block, so it's served only by nodes that run
+//! a mempool.
+
+use std::sync::Arc;
+
+use apollo_consensus_orchestrator::orchestrator_versioned_constants::VersionedConstants;
+use apollo_mempool_types::communication::SharedMempoolClient;
+use apollo_proc_macros::versioned_rpc;
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::StorageReader;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use papyrus_common::pending_classes::PendingClasses;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHashAndNumber, BlockNumber, GasPricePerToken};
+use starknet_api::core::ChainId;
+use starknet_api::transaction::fields::Tip;
+use starknet_api::transaction::{
+    DeclareTransaction,
+    DeployAccountTransaction,
+    InvokeTransaction,
+    Transaction,
+};
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use super::error::{BLOCK_NOT_FOUND, PAGE_SIZE_TOO_BIG};
+use crate::api::{BlockId, JsonRpcServerTrait};
+use crate::v0_8::block::get_accepted_block_number;
+use crate::{internal_server_error, verify_storage_scope};
+
+/// The maximal number of blocks whose fee history can be requested in a single request.
+pub const MAX_FEE_HISTORY_BLOCKS: u64 = 128;
+
+#[versioned_rpc("V0_9")]
+#[async_trait]
+pub trait FeeHistoryRpc {
+    /// Gets the gas prices, the L2 gas usage and utilization and the given percentiles of the tips
+    /// of up to `block_count` consecutive blocks, ending at the given block.
+    #[method(name = "feeHistory")]
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockId,
+        reward_percentiles: Vec<u8>,
+    ) -> RpcResult<FeeHistory>;
+}
+
+#[versioned_rpc("V0_9")]
+#[async_trait]
+pub trait SuggestedTipRpc {
+    /// Gets a tip for new transactions: the given percentile of the tips of the transactions that
+    /// are waiting in the mempool to be included in a block.
+    #[method(name = "suggestedTip")]
+    async fn suggested_tip(&self, percentile: u8) -> RpcResult<Tip>;
+}
+
+/// The fee history of consecutive blocks. Each list has an entry per block, starting at the oldest
+/// block.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct FeeHistory {
+    pub oldest_block: BlockNumber,
+    pub l1_gas_price: Vec<GasPricePerToken>,
+    pub l1_data_gas_price: Vec<GasPricePerToken>,
+    pub l2_gas_price: Vec<GasPricePerToken>,
+    pub l2_gas_used: Vec<u64>,
+    /// The L2 gas used by each block relative to the L2 gas target of its version, above which
+    /// the L2 gas price of the next block rises.
+    pub l2_gas_utilization: Vec<f64>,
+    /// The requested percentiles of the tips of the V3 transactions of each block. Blocks without
+    /// such transactions have a zero tip in every percentile.
+    pub reward: Vec<Vec<Tip>>,
+}
+
+/// Rpc server of the fee history.
+pub struct FeeHistoryRpcImpl {
+    pub storage_reader: StorageReader,
+}
+
+/// Rpc server of the suggested tip.
+pub struct SuggestedTipRpcImpl {
+    pub mempool_client: SharedMempoolClient,
+}
+
+#[async_trait]
+impl FeeHistoryRpcV0_9Server for FeeHistoryRpcImpl {
+    #[instrument(skip(self), level = "debug", err)]
+    async fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockId,
+        reward_percentiles: Vec<u8>,
+    ) -> RpcResult<FeeHistory> {
+        verify_storage_scope(&self.storage_reader)?;
+        if block_count > MAX_FEE_HISTORY_BLOCKS {
+            return Err(ErrorObjectOwned::from(PAGE_SIZE_TOO_BIG));
+        }
+        if !reward_percentiles.is_sorted() || reward_percentiles.iter().any(|p| *p > 100) {
+            return Err(ErrorObjectOwned::owned(
+                ErrorCode::InvalidParams.code(),
+                "Reward percentiles must be non-decreasing and at most 100.",
+                None::<()>,
+            ));
+        }
+
+        let txn = self.storage_reader.begin_ro_txn().map_err(internal_server_error)?;
+        let newest_block_number = get_accepted_block_number(&txn, newest_block)?;
+        let oldest_block =
+            BlockNumber(newest_block_number.0.saturating_sub(block_count.saturating_sub(1)));
+
+        let mut fee_history = FeeHistory { oldest_block, ..Default::default() };
+        if block_count == 0 {
+            return Ok(fee_history);
+        }
+        for block_number in oldest_block.iter_up_to(newest_block_number.unchecked_next()) {
+            let header = txn
+                .get_block_header(block_number)
+                .map_err(internal_server_error)?
+                .ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?
+                .block_header_without_hash;
+
+            fee_history.l1_gas_price.push(header.l1_gas_price);
+            fee_history.l1_data_gas_price.push(header.l1_data_gas_price);
+            fee_history.l2_gas_price.push(header.l2_gas_price);
+            fee_history.l2_gas_used.push(header.l2_gas_consumed);
+            let l2_gas_target = VersionedConstants::for_block(&header.starknet_version).gas_target;
+            fee_history.l2_gas_utilization.push(ratio(header.l2_gas_consumed, l2_gas_target));
+            if reward_percentiles.is_empty() {
+                fee_history.reward.push(vec![]);
+                continue;
+            }
+            let transactions = txn
+                .get_block_transactions(block_number)
+                .map_err(internal_server_error)?
+                .ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?;
+            let mut tips: Vec<_> = transactions.iter().filter_map(transaction_tip).collect();
+            tips.sort_unstable();
+            fee_history.reward.push(
+                reward_percentiles
+                    .iter()
+                    .map(|percentile| tip_percentile(&tips, *percentile))
+                    .collect(),
+            );
+        }
+        Ok(fee_history)
+    }
+}
+
+#[async_trait]
+impl SuggestedTipRpcV0_9Server for SuggestedTipRpcImpl {
+    #[instrument(skip(self), level = "debug", err)]
+    async fn suggested_tip(&self, percentile: u8) -> RpcResult<Tip> {
+        if percentile > 100 {
+            return Err(ErrorObjectOwned::owned(
+                ErrorCode::InvalidParams.code(),
+                "The percentile must be at most 100.",
+                None::<()>,
+            ));
+        }
+        self.mempool_client.get_suggested_tip(percentile).await.map_err(internal_server_error)
+    }
+}
+
+/// Returns the tip of the transaction, if it's a V3 transaction.
+fn transaction_tip(transaction: &Transaction) -> Option<Tip> {
+    match transaction {
+        Transaction::Declare(DeclareTransaction::V3(tx)) => Some(tx.tip),
+        Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => Some(tx.tip),
+        Transaction::Invoke(InvokeTransaction::V3(tx)) => Some(tx.tip),
+        _ => None,
+    }
+}
+
+#[allow(clippy::as_conversions)] // Gas amounts are far below the precision limit of f64.
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    numerator as f64 / denominator as f64
+}
+
+/// Returns the given percentile of the sorted tips, by the nearest-rank method.
+fn tip_percentile(sorted_tips: &[Tip], percentile: u8) -> Tip {
+    if sorted_tips.is_empty() {
+        return Tip(0);
+    }
+    let rank = (usize::from(percentile) * sorted_tips.len()).div_ceil(100);
+    sorted_tips[rank.saturating_sub(1)]
+}
+
+impl JsonRpcServerTrait for FeeHistoryRpcImpl {
+    fn new(
+        _chain_id: ChainId,
+        _execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        _max_events_chunk_size: usize,
+        _max_events_keys: usize,
+        _starting_block: BlockHashAndNumber,
+        _shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        _pending_data: Arc<RwLock<PendingData>>,
+        _pending_classes: Arc<RwLock<PendingClasses>>,
+        _writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self { storage_reader }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
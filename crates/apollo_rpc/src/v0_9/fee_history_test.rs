@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use apollo_consensus_orchestrator::orchestrator_versioned_constants::VersionedConstants;
+use apollo_mempool_types::communication::MockMempoolClient;
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use assert_matches::assert_matches;
+use jsonrpsee::core::Error;
+use jsonrpsee::types::error::ErrorCode;
+use mockall::predicate::eq;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{
+    BlockBody,
+    BlockHeader,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    GasPrice,
+    GasPricePerToken,
+};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::test_utils::invoke::invoke_tx;
+use starknet_api::transaction::fields::Tip;
+use starknet_api::transaction::{
+    InvokeTransactionOutput,
+    Transaction,
+    TransactionHash,
+    TransactionOutput,
+    TransactionVersion,
+};
+use starknet_api::{felt, invoke_tx_args};
+
+use super::error::{BLOCK_NOT_FOUND, PAGE_SIZE_TOO_BIG};
+use super::fee_history::{
+    FeeHistory,
+    FeeHistoryRpcImpl,
+    SuggestedTipRpcImpl,
+    SuggestedTipRpcV0_9Server,
+    MAX_FEE_HISTORY_BLOCKS,
+};
+use crate::api::{BlockHashOrNumber, BlockId, Tag};
+use crate::test_utils::get_test_rpc_server_and_storage_writer;
+
+const METHOD_NAME: &str = "starknet_V0_9_feeHistory";
+const SUGGESTED_TIP_METHOD_NAME: &str = "starknet_V0_9_suggestedTip";
+
+fn gas_price(price: u8) -> GasPricePerToken {
+    GasPricePerToken {
+        price_in_fri: GasPrice(price.into()),
+        price_in_wei: GasPrice((price + 100).into()),
+    }
+}
+
+// A block with the given V3 tips and an additional V1 transaction, whose tip is ignored.
+fn block(block_number: u8, tips: &[u64]) -> (BlockHeader, BlockBody) {
+    let header = BlockHeader {
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: BlockNumber(block_number.into()),
+            l1_gas_price: gas_price(block_number),
+            l1_data_gas_price: gas_price(block_number + 10),
+            l2_gas_price: gas_price(block_number + 20),
+            l2_gas_consumed: u64::from(block_number) * 1000,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let transactions: Vec<_> = tips
+        .iter()
+        .map(|tip| Transaction::Invoke(invoke_tx(invoke_tx_args!(tip: Tip(*tip)))))
+        .chain([Transaction::Invoke(invoke_tx(invoke_tx_args!(
+            version: TransactionVersion::ONE,
+        )))])
+        .collect();
+    let body = BlockBody {
+        transaction_outputs: vec![
+            TransactionOutput::Invoke(InvokeTransactionOutput::default());
+            transactions.len()
+        ],
+        transaction_hashes: (0..transactions.len())
+            .map(|index| {
+                TransactionHash(felt!(
+                    u64::from(block_number) * 100 + u64::try_from(index).unwrap()
+                ))
+            })
+            .collect(),
+        transactions,
+    };
+    (header, body)
+}
+
+#[tokio::test]
+async fn fee_history() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<FeeHistoryRpcImpl>();
+    let mut txn = storage_writer.begin_rw_txn().unwrap();
+    for (block_number, tips) in [(0, vec![]), (1, vec![1, 2, 3, 4]), (2, vec![7, 5, 6])] {
+        let (header, body) = block(block_number, &tips);
+        let block_number = header.block_header_without_hash.block_number;
+        txn = txn
+            .append_header(block_number, &header)
+            .unwrap()
+            .append_body(block_number, body)
+            .unwrap()
+            .append_state_diff(block_number, ThinStateDiff::default())
+            .unwrap();
+    }
+    txn.commit().unwrap();
+    let l2_gas_target = u32::try_from(VersionedConstants::latest_constants().gas_target).unwrap();
+    let utilization = |l2_gas_used: u32| f64::from(l2_gas_used) / f64::from(l2_gas_target);
+
+    let fee_history = module
+        .call::<_, FeeHistory>(METHOD_NAME, (10, BlockId::Tag(Tag::Latest), [0, 50, 100]))
+        .await
+        .unwrap();
+    assert_eq!(
+        fee_history,
+        FeeHistory {
+            oldest_block: BlockNumber(0),
+            l1_gas_price: vec![gas_price(0), gas_price(1), gas_price(2)],
+            l1_data_gas_price: vec![gas_price(10), gas_price(11), gas_price(12)],
+            l2_gas_price: vec![gas_price(20), gas_price(21), gas_price(22)],
+            l2_gas_used: vec![0, 1000, 2000],
+            l2_gas_utilization: vec![utilization(0), utilization(1000), utilization(2000)],
+            reward: vec![
+                vec![Tip(0), Tip(0), Tip(0)],
+                vec![Tip(1), Tip(2), Tip(4)],
+                vec![Tip(5), Tip(6), Tip(7)],
+            ],
+        }
+    );
+
+    let fee_history = module
+        .call::<_, FeeHistory>(
+            METHOD_NAME,
+            (1, BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1))), [25]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        fee_history,
+        FeeHistory {
+            oldest_block: BlockNumber(1),
+            l1_gas_price: vec![gas_price(1)],
+            l1_data_gas_price: vec![gas_price(11)],
+            l2_gas_price: vec![gas_price(21)],
+            l2_gas_used: vec![1000],
+            l2_gas_utilization: vec![utilization(1000)],
+            reward: vec![vec![Tip(1)]],
+        }
+    );
+
+    // Without reward percentiles, only the headers are read.
+    let fee_history = module
+        .call::<_, FeeHistory>(METHOD_NAME, (2, BlockId::Tag(Tag::Latest), Vec::<u8>::new()))
+        .await
+        .unwrap();
+    assert_eq!(fee_history.l2_gas_used, vec![1000, 2000]);
+    assert_eq!(fee_history.reward, vec![Vec::<Tip>::new(); 2]);
+
+    let err = module
+        .call::<_, FeeHistory>(
+            METHOD_NAME,
+            (1, BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(3))), [50]),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    let err = module
+        .call::<_, FeeHistory>(
+            METHOD_NAME,
+            (MAX_FEE_HISTORY_BLOCKS + 1, BlockId::Tag(Tag::Latest), [50]),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == PAGE_SIZE_TOO_BIG.into());
+
+    for reward_percentiles in [vec![50, 25], vec![101]] {
+        let err = module
+            .call::<_, FeeHistory>(METHOD_NAME, (1, BlockId::Tag(Tag::Latest), reward_percentiles))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+    }
+}
+
+#[tokio::test]
+async fn suggested_tip() {
+    let mut mempool_client = MockMempoolClient::new();
+    mempool_client.expect_get_suggested_tip().with(eq(50)).times(1).returning(|_| Ok(Tip(7)));
+    let module = SuggestedTipRpcImpl { mempool_client: Arc::new(mempool_client) }.into_rpc();
+
+    let tip = module.call::<_, Tip>(SUGGESTED_TIP_METHOD_NAME, [50]).await.unwrap();
+    assert_eq!(tip, Tip(7));
+
+    let err = module.call::<_, Tip>(SUGGESTED_TIP_METHOD_NAME, [101]).await.unwrap_err();
+    assert_matches!(err, Error::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+}
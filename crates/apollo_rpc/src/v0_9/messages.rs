@@ -0,0 +1,131 @@
+//! The status of the messages that were sent from L1 to L2 by an L1 transaction. The messages are
+//! read from the events of the Starknet contract on L1 and joined with the L1 handler transactions
+//! that consumed them on L2.
+
+use std::sync::Arc;
+
+use apollo_proc_macros::versioned_rpc;
+use async_trait::async_trait;
+use ethers::types::H256;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::ErrorObjectOwned;
+use papyrus_base_layer::MessageToL2Cancellation;
+use serde::{Deserialize, Serialize};
+use starknet_api::transaction::{Transaction, TransactionExecutionStatus, TransactionHash};
+use tracing::instrument;
+
+use super::error::TRANSACTION_HASH_NOT_FOUND;
+use crate::base_layer::BaseLayerMessagesSource;
+use crate::internal_server_error;
+use crate::v0_8::api::api_impl::JsonRpcServerImpl as V0_8JsonRpcServerImpl;
+use crate::v0_8::api::JsonRpcV0_8Server;
+use crate::v0_8::transaction::TransactionFinalityStatus;
+
+#[versioned_rpc("V0_9")]
+#[async_trait]
+pub trait MessagesRpc {
+    /// Gets the status of the L1 handler transactions of the messages that were sent to L2 by the
+    /// given L1 transaction, in the order in which they were sent.
+    #[method(name = "getMessagesStatus")]
+    async fn get_messages_status(
+        &self,
+        transaction_hash: H256,
+    ) -> RpcResult<Vec<L1HandlerTransactionStatus>>;
+}
+
+/// The finality status of an L1 handler transaction.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize, PartialOrd, Ord)]
+pub enum MessageFinalityStatus {
+    /// The message was sent on L1, but its transaction wasn't accepted on L2 yet.
+    #[serde(rename = "RECEIVED")]
+    Received,
+    /// The message was canceled on L1, so its transaction will never be accepted on L2.
+    #[serde(rename = "CANCELED")]
+    Canceled,
+    #[serde(rename = "ACCEPTED_ON_L2")]
+    AcceptedOnL2,
+    #[serde(rename = "ACCEPTED_ON_L1")]
+    AcceptedOnL1,
+}
+
+impl From<TransactionFinalityStatus> for MessageFinalityStatus {
+    fn from(status: TransactionFinalityStatus) -> Self {
+        match status {
+            TransactionFinalityStatus::AcceptedOnL2 => Self::AcceptedOnL2,
+            TransactionFinalityStatus::AcceptedOnL1 => Self::AcceptedOnL1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct L1HandlerTransactionStatus {
+    pub transaction_hash: TransactionHash,
+    pub finality_status: MessageFinalityStatus,
+    /// Missing until the transaction is executed.
+    #[serde(flatten)]
+    pub execution_status: Option<TransactionExecutionStatus>,
+}
+
+/// Rpc server of the messages status. Messages whose cancellation was requested on L1 are reported
+/// as received until the cancellation is completed.
+pub struct MessagesRpcImpl {
+    pub rpc: V0_8JsonRpcServerImpl,
+    pub base_layer: Arc<dyn BaseLayerMessagesSource>,
+}
+
+#[async_trait]
+impl MessagesRpcV0_9Server for MessagesRpcImpl {
+    #[instrument(skip(self), level = "debug", err)]
+    async fn get_messages_status(
+        &self,
+        transaction_hash: H256,
+    ) -> RpcResult<Vec<L1HandlerTransactionStatus>> {
+        let l1_handler_transactions = self
+            .base_layer
+            .messages_to_l2(transaction_hash.0)
+            .await
+            .map_err(internal_server_error)?;
+        if l1_handler_transactions.is_empty() {
+            return Err(TRANSACTION_HASH_NOT_FOUND.into());
+        }
+
+        let mut statuses = Vec::with_capacity(l1_handler_transactions.len());
+        for l1_handler_transaction in l1_handler_transactions {
+            let transaction_hash = Transaction::L1Handler(l1_handler_transaction.clone())
+                .calculate_transaction_hash(&self.rpc.chain_id)
+                .map_err(internal_server_error)?;
+            let status = match self.rpc.get_transaction_status(transaction_hash).await {
+                Ok(status) => L1HandlerTransactionStatus {
+                    transaction_hash,
+                    finality_status: status.finality_status.into(),
+                    execution_status: Some(status.execution_status),
+                },
+                Err(err) if err == ErrorObjectOwned::from(TRANSACTION_HASH_NOT_FOUND) => {
+                    // A message that wasn't accepted on L2 can't be consumed on L1, so it's
+                    // canceled if it isn't pending on L1 anymore.
+                    let cancellation = self
+                        .base_layer
+                        .message_to_l2_cancellation(&l1_handler_transaction)
+                        .await
+                        .map_err(internal_server_error)?;
+                    let finality_status = match cancellation {
+                        MessageToL2Cancellation::Canceled => MessageFinalityStatus::Canceled,
+                        MessageToL2Cancellation::NotRequested
+                        | MessageToL2Cancellation::Requested { .. } => {
+                            MessageFinalityStatus::Received
+                        }
+                    };
+                    L1HandlerTransactionStatus {
+                        transaction_hash,
+                        finality_status,
+                        execution_status: None,
+                    }
+                }
+                Err(err) => return Err(err),
+            };
+            statuses.push(status);
+        }
+        Ok(statuses)
+    }
+}
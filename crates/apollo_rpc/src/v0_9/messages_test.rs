@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::StorageWriter;
+use apollo_test_utils::get_test_block;
+use assert_matches::assert_matches;
+use ethers::types::H256;
+use jsonrpsee::core::Error;
+use jsonrpsee::RpcModule;
+use papyrus_base_layer::MessageToL2Cancellation;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockBody;
+use starknet_api::core::{ChainId, Nonce};
+use starknet_api::felt;
+use starknet_api::transaction::{
+    L1HandlerTransaction,
+    L1HandlerTransactionOutput,
+    Transaction,
+    TransactionExecutionStatus,
+    TransactionOutput,
+};
+
+use super::error::TRANSACTION_HASH_NOT_FOUND;
+use super::messages::{
+    L1HandlerTransactionStatus,
+    MessageFinalityStatus,
+    MessagesRpcImpl,
+    MessagesRpcV0_9Server,
+};
+use crate::base_layer::MockBaseLayerMessagesSource;
+use crate::test_utils::{
+    get_test_rpc_config,
+    get_test_rpc_server_impl_and_storage_writer_from_params,
+};
+use crate::v0_8::api::api_impl::JsonRpcServerImpl as V0_8JsonRpcServerImpl;
+
+const METHOD_NAME: &str = "starknet_V0_9_getMessagesStatus";
+const L1_TRANSACTION_HASH: H256 = H256([1; 32]);
+
+fn l1_handler_transaction(nonce: u8) -> L1HandlerTransaction {
+    L1HandlerTransaction { nonce: Nonce(felt!(nonce)), ..Default::default() }
+}
+
+fn get_test_messages_rpc(
+    base_layer: MockBaseLayerMessagesSource,
+) -> (RpcModule<MessagesRpcImpl>, StorageWriter) {
+    let (rpc, storage_writer) = get_test_rpc_server_impl_and_storage_writer_from_params::<
+        V0_8JsonRpcServerImpl,
+    >(None, None, None, None, None);
+    (MessagesRpcImpl { rpc, base_layer: Arc::new(base_layer) }.into_rpc(), storage_writer)
+}
+
+#[tokio::test]
+async fn get_messages_status() {
+    let chain_id: ChainId = get_test_rpc_config().chain_id;
+    let executed_transaction = l1_handler_transaction(0);
+    let executed_transaction_hash = Transaction::L1Handler(executed_transaction.clone())
+        .calculate_transaction_hash(&chain_id)
+        .unwrap();
+    let pending_transaction_hash = Transaction::L1Handler(l1_handler_transaction(1))
+        .calculate_transaction_hash(&chain_id)
+        .unwrap();
+    let canceled_transaction_hash = Transaction::L1Handler(l1_handler_transaction(2))
+        .calculate_transaction_hash(&chain_id)
+        .unwrap();
+
+    let mut base_layer = MockBaseLayerMessagesSource::new();
+    base_layer
+        .expect_messages_to_l2()
+        .withf(|l1_transaction_hash| *l1_transaction_hash == L1_TRANSACTION_HASH.0)
+        .returning(|_| Ok((0..3).map(l1_handler_transaction).collect()));
+    // The cancellation of the pending message was requested, and the third message was canceled.
+    base_layer.expect_message_to_l2_cancellation().times(2).returning(|message| {
+        Ok(if *message == l1_handler_transaction(2) {
+            MessageToL2Cancellation::Canceled
+        } else {
+            MessageToL2Cancellation::Requested { cancellation_request_timestamp: 1 }
+        })
+    });
+    let (module, mut storage_writer) = get_test_messages_rpc(base_layer);
+
+    let header = get_test_block(0, None, None, None).header;
+    let body = BlockBody {
+        transactions: vec![Transaction::L1Handler(executed_transaction)],
+        transaction_outputs: vec![TransactionOutput::L1Handler(
+            L1HandlerTransactionOutput::default(),
+        )],
+        transaction_hashes: vec![executed_transaction_hash],
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(header.block_header_without_hash.block_number, &header)
+        .unwrap()
+        .append_body(header.block_header_without_hash.block_number, body)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let res = module
+        .call::<_, Vec<L1HandlerTransactionStatus>>(METHOD_NAME, [L1_TRANSACTION_HASH])
+        .await
+        .unwrap();
+    assert_eq!(
+        res,
+        vec![
+            L1HandlerTransactionStatus {
+                transaction_hash: executed_transaction_hash,
+                finality_status: MessageFinalityStatus::AcceptedOnL2,
+                execution_status: Some(TransactionExecutionStatus::Succeeded),
+            },
+            L1HandlerTransactionStatus {
+                transaction_hash: pending_transaction_hash,
+                finality_status: MessageFinalityStatus::Received,
+                execution_status: None,
+            },
+            L1HandlerTransactionStatus {
+                transaction_hash: canceled_transaction_hash,
+                finality_status: MessageFinalityStatus::Canceled,
+                execution_status: None,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn get_messages_status_of_unknown_transaction() {
+    let mut base_layer = MockBaseLayerMessagesSource::new();
+    base_layer.expect_messages_to_l2().returning(|_| Ok(vec![]));
+    let (module, _storage_writer) = get_test_messages_rpc(base_layer);
+
+    let err = module
+        .call::<_, Vec<L1HandlerTransactionStatus>>(METHOD_NAME, [L1_TRANSACTION_HASH])
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == TRANSACTION_HASH_NOT_FOUND.into());
+}
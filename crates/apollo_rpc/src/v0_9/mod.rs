@@ -0,0 +1,26 @@
+//! Version 0.9 of the Starknet JSON-RPC API of the node.
+//!
+//! This version doesn't implement the official 0.9 specification. It serves the methods and the
+//! objects of the 0.8 specification with the following additions:
+//! - Block ids can refer to the most recent block that was accepted on L1 (`l1_accepted`).
+//! - Calls and fee estimations accept state overrides.
+//! - Transaction traces accept flags that add data which isn't part of the specification.
+//! - Blocks and transaction statuses of blocks that were accepted on L1 include the base layer
+//!   transaction that proved them (`l1_reference`).
+//! - Methods that are specific to this node, such as the state history and the fee history.
+
+pub mod api;
+pub mod fee_history;
+#[cfg(test)]
+mod fee_history_test;
+pub mod messages;
+#[cfg(test)]
+mod messages_test;
+pub mod reexecution;
+#[cfg(test)]
+mod reexecution_test;
+pub mod state_history;
+#[cfg(test)]
+mod state_history_test;
+
+pub use super::v0_8::error;
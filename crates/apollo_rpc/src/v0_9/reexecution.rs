@@ -0,0 +1,106 @@
+//! Re-execution of stored blocks, for verifying the execution against the blocks that were synced.
+//! These are admin methods that are served under the `apollo` namespace only when enabled in the
+//! config, since re-executing blocks is expensive.
+
+use std::sync::Arc;
+
+use apollo_proc_macros::versioned_rpc;
+use apollo_rpc_execution::reexecution::{reexecute_blocks, BlockReexecutionReport};
+use apollo_rpc_execution::{ExecutionConfig, ExecutionError};
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::StorageReader;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use papyrus_common::pending_classes::PendingClasses;
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+use starknet_api::core::ChainId;
+use tokio::sync::RwLock;
+use tracing::instrument;
+
+use super::error::{BLOCK_NOT_FOUND, PAGE_SIZE_TOO_BIG};
+use crate::api::JsonRpcServerTrait;
+use crate::{internal_server_error, internal_server_error_with_msg};
+
+/// The maximal number of blocks that can be re-executed in a single request.
+pub const MAX_REEXECUTED_BLOCKS: u64 = 100;
+
+#[versioned_rpc("V0_9", "apollo")]
+#[async_trait]
+pub trait ReexecutionRpc {
+    /// Re-executes the blocks in the given inclusive range and reports where the results differ
+    /// from the stored receipts and state diffs.
+    #[method(name = "reexecuteBlocks")]
+    async fn reexecute_blocks(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<BlockReexecutionReport>>;
+}
+
+/// Rpc server of the re-execution methods.
+pub struct ReexecutionRpcImpl {
+    pub chain_id: ChainId,
+    pub execution_config: ExecutionConfig,
+    pub storage_reader: StorageReader,
+}
+
+#[async_trait]
+impl ReexecutionRpcV0_9Server for ReexecutionRpcImpl {
+    #[instrument(skip(self), level = "debug", err)]
+    async fn reexecute_blocks(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> RpcResult<Vec<BlockReexecutionReport>> {
+        if from_block > to_block {
+            return Err(ErrorObjectOwned::owned(
+                ErrorCode::InvalidParams.code(),
+                "The from block must not be after the to block.",
+                None::<()>,
+            ));
+        }
+        // The last block can't exist if it has no next block.
+        let end_block = to_block.next().ok_or_else(|| ErrorObjectOwned::from(BLOCK_NOT_FOUND))?;
+        if end_block.0 - from_block.0 > MAX_REEXECUTED_BLOCKS {
+            return Err(ErrorObjectOwned::from(PAGE_SIZE_TOO_BIG));
+        }
+        let chain_id = self.chain_id.clone();
+        let execution_config = self.execution_config;
+        let storage_reader = self.storage_reader.clone();
+        tokio::task::spawn_blocking(move || {
+            reexecute_blocks(storage_reader, &chain_id, &execution_config, from_block, end_block)
+        })
+        .await
+        .map_err(internal_server_error)?
+        .map_err(|err| match err {
+            ExecutionError::MissingBlock { .. } => BLOCK_NOT_FOUND.into(),
+            err => internal_server_error_with_msg(err),
+        })
+    }
+}
+
+impl JsonRpcServerTrait for ReexecutionRpcImpl {
+    fn new(
+        chain_id: ChainId,
+        execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        _max_events_chunk_size: usize,
+        _max_events_keys: usize,
+        _starting_block: BlockHashAndNumber,
+        _shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        _pending_data: Arc<RwLock<PendingData>>,
+        _pending_classes: Arc<RwLock<PendingClasses>>,
+        _writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self { chain_id, execution_config, storage_reader }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
@@ -0,0 +1,75 @@
+use apollo_rpc_execution::reexecution::BlockReexecutionReport;
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::class::ClassStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use assert_matches::assert_matches;
+use jsonrpsee::core::Error;
+use jsonrpsee::types::error::ErrorCode;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockHeader, BlockNumber};
+use starknet_api::state::ThinStateDiff;
+
+use super::error::{BLOCK_NOT_FOUND, PAGE_SIZE_TOO_BIG};
+use super::reexecution::{ReexecutionRpcImpl, MAX_REEXECUTED_BLOCKS};
+use crate::test_utils::get_test_rpc_server_and_storage_writer;
+
+const METHOD_NAME: &str = "apollo_V0_9_reexecuteBlocks";
+
+#[tokio::test]
+async fn reexecute_blocks() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<ReexecutionRpcImpl>();
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &BlockHeader::default())
+        .unwrap()
+        .append_body(BlockNumber(0), BlockBody::default())
+        .unwrap()
+        .append_state_diff(BlockNumber(0), ThinStateDiff::default())
+        .unwrap()
+        .append_classes(BlockNumber(0), &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let reports = module
+        .call::<_, Vec<BlockReexecutionReport>>(METHOD_NAME, (BlockNumber(0), BlockNumber(0)))
+        .await
+        .unwrap();
+    assert_eq!(
+        reports,
+        vec![BlockReexecutionReport { block_number: BlockNumber(0), mismatches: vec![] }]
+    );
+
+    let err = module
+        .call::<_, Vec<BlockReexecutionReport>>(METHOD_NAME, (BlockNumber(0), BlockNumber(1)))
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+
+    let err = module
+        .call::<_, Vec<BlockReexecutionReport>>(
+            METHOD_NAME,
+            (BlockNumber(0), BlockNumber(MAX_REEXECUTED_BLOCKS)),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == PAGE_SIZE_TOO_BIG.into());
+
+    let err = module
+        .call::<_, Vec<BlockReexecutionReport>>(METHOD_NAME, (BlockNumber(1), BlockNumber(0)))
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+
+    let err = module
+        .call::<_, Vec<BlockReexecutionReport>>(
+            METHOD_NAME,
+            (BlockNumber(u64::MAX), BlockNumber(u64::MAX)),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(err, Error::Call(err) if err == BLOCK_NOT_FOUND.into());
+}
@@ -0,0 +1,177 @@
+//! Queries over the state history of a contract, served under the `apollo` namespace like in the
+//! previous version. The methods are served by the server of the previous version, after
+//! resolving the block ids that are new in this version.
+
+use std::sync::Arc;
+
+use apollo_proc_macros::versioned_rpc;
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_starknet_client::reader::PendingData;
+use apollo_starknet_client::writer::StarknetWriter;
+use apollo_storage::StorageReader;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::RpcModule;
+use papyrus_common::pending_classes::PendingClasses;
+use starknet_api::block::BlockHashAndNumber;
+use starknet_api::core::{ChainId, ContractAddress};
+use tokio::sync::RwLock;
+
+use super::api::api_impl::resolve_block_id;
+use super::api::BlockId;
+use crate::api::JsonRpcServerTrait;
+use crate::v0_8::api::ContinuationToken;
+use crate::v0_8::state_history::{
+    ClassHashChange,
+    NonceChange,
+    StateHistoryRpcImpl as V0_8StateHistoryRpcImpl,
+    StateHistoryRpcV0_8Server,
+    StorageChangesChunk,
+    StorageKeysChunk,
+};
+
+#[versioned_rpc("V0_9", "apollo")]
+#[async_trait]
+pub trait StateHistoryRpc {
+    /// Gets the changes of the storage of a contract in the given blocks, ordered by the storage
+    /// key and then by the block number.
+    #[method(name = "getStorageChanges")]
+    async fn get_storage_changes(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageChangesChunk>;
+
+    /// Gets the storage keys that were ever written in the storage of a contract, in ascending
+    /// order.
+    #[method(name = "getStorageKeys")]
+    async fn get_storage_keys(
+        &self,
+        contract_address: ContractAddress,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageKeysChunk>;
+
+    /// Gets the nonce changes of a contract in the given blocks.
+    #[method(name = "getNonceHistory")]
+    async fn get_nonce_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<NonceChange>>;
+
+    /// Gets the class hash changes of a contract in the given blocks. These are its deployment
+    /// and the replacements of its class.
+    #[method(name = "getClassHashHistory")]
+    async fn get_class_hash_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<ClassHashChange>>;
+}
+
+/// Rpc server of the state history methods.
+pub struct StateHistoryRpcImpl {
+    pub v0_8: V0_8StateHistoryRpcImpl,
+}
+
+#[async_trait]
+impl StateHistoryRpcV0_9Server for StateHistoryRpcImpl {
+    async fn get_storage_changes(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageChangesChunk> {
+        self.v0_8
+            .get_storage_changes(
+                contract_address,
+                resolve_block_id(&self.v0_8.storage_reader, from_block)?,
+                resolve_block_id(&self.v0_8.storage_reader, to_block)?,
+                chunk_size,
+                continuation_token,
+            )
+            .await
+    }
+
+    async fn get_storage_keys(
+        &self,
+        contract_address: ContractAddress,
+        chunk_size: usize,
+        continuation_token: Option<ContinuationToken>,
+    ) -> RpcResult<StorageKeysChunk> {
+        self.v0_8.get_storage_keys(contract_address, chunk_size, continuation_token).await
+    }
+
+    async fn get_nonce_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<NonceChange>> {
+        self.v0_8
+            .get_nonce_history(
+                contract_address,
+                resolve_block_id(&self.v0_8.storage_reader, from_block)?,
+                resolve_block_id(&self.v0_8.storage_reader, to_block)?,
+            )
+            .await
+    }
+
+    async fn get_class_hash_history(
+        &self,
+        contract_address: ContractAddress,
+        from_block: BlockId,
+        to_block: BlockId,
+    ) -> RpcResult<Vec<ClassHashChange>> {
+        self.v0_8
+            .get_class_hash_history(
+                contract_address,
+                resolve_block_id(&self.v0_8.storage_reader, from_block)?,
+                resolve_block_id(&self.v0_8.storage_reader, to_block)?,
+            )
+            .await
+    }
+}
+
+impl JsonRpcServerTrait for StateHistoryRpcImpl {
+    fn new(
+        chain_id: ChainId,
+        execution_config: ExecutionConfig,
+        storage_reader: StorageReader,
+        max_events_chunk_size: usize,
+        max_events_keys: usize,
+        starting_block: BlockHashAndNumber,
+        shared_highest_block: Arc<RwLock<Option<BlockHashAndNumber>>>,
+        pending_data: Arc<RwLock<PendingData>>,
+        pending_classes: Arc<RwLock<PendingClasses>>,
+        writer_client: Arc<dyn StarknetWriter>,
+    ) -> Self {
+        Self {
+            v0_8: V0_8StateHistoryRpcImpl::new(
+                chain_id,
+                execution_config,
+                storage_reader,
+                max_events_chunk_size,
+                max_events_keys,
+                starting_block,
+                shared_highest_block,
+                pending_data,
+                pending_classes,
+                writer_client,
+            ),
+        }
+    }
+
+    fn into_rpc_module(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+}
@@ -0,0 +1,57 @@
+use apollo_storage::base_layer::BaseLayerStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{contract_address, felt};
+
+use super::api::{BlockId, Tag};
+use super::state_history::StateHistoryRpcImpl;
+use crate::api::BlockHashOrNumber;
+use crate::test_utils::get_test_rpc_server_and_storage_writer;
+use crate::v0_8::state_history::NonceChange;
+
+fn contract() -> ContractAddress {
+    contract_address!("0x100")
+}
+
+#[tokio::test]
+async fn get_nonce_history_until_l1_accepted_block() {
+    let (module, mut storage_writer) =
+        get_test_rpc_server_and_storage_writer::<StateHistoryRpcImpl>();
+    for block_number in 0..3_u8 {
+        let diff = ThinStateDiff {
+            nonces: indexmap! { contract() => Nonce(felt!(block_number)) },
+            ..Default::default()
+        };
+        storage_writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number.into()), diff)
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(2))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let res = module
+        .call::<_, Vec<NonceChange>>(
+            "apollo_V0_9_getNonceHistory",
+            (
+                contract(),
+                BlockId::HashOrNumber(BlockHashOrNumber::Number(BlockNumber(1))),
+                BlockId::Tag(Tag::L1Accepted),
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res, vec![NonceChange { block_number: BlockNumber(1), nonce: Nonce(felt!(1_u8)) }]);
+}
@@ -30,5 +30,7 @@ impl std::fmt::Display for VersionId {
 }
 
 /// latest version must be set as supported
-pub const VERSION_CONFIG: &[(VersionId, VersionState)] = &[(VERSION_0_8, VersionState::Supported)];
+pub const VERSION_CONFIG: &[(VersionId, VersionState)] =
+    &[(VERSION_0_8, VersionState::Supported), (VERSION_0_9, VersionState::Supported)];
 pub const VERSION_0_8: VersionId = VersionId { name: "V0_8", patch: 0 };
+pub const VERSION_0_9: VersionId = VersionId { name: "V0_9", patch: 0 };
@@ -0,0 +1,85 @@
+//! Re-executes a range of blocks from the storage of a node and prints where the results differ
+//! from the stored receipts and state diffs. Exits with an error if any difference was found.
+//!
+//! The fee token addresses are those of the default execution config, which are shared by Mainnet
+//! and Sepolia.
+use apollo_rpc_execution::reexecution::reexecute_blocks;
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_storage::db::DbConfig;
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, Command};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+
+pub fn main() {
+    let matches = Command::new("Block re-execution")
+        .about("Re-executes stored blocks and compares the results with the stored blocks")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path prefix of the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .arg(
+            Arg::new("from_block")
+                .short('f')
+                .long("from_block")
+                .required(true)
+                .help("The first block to re-execute"),
+        )
+        .arg(
+            Arg::new("to_block")
+                .short('t')
+                .long("to_block")
+                .required(true)
+                .help("The last block to re-execute"),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id: ChainId =
+        matches.get_one::<String>("chain_id").expect("Missing chain_id").to_string().into();
+    let from_block = parse_block_number(matches.get_one::<String>("from_block"));
+    let to_block = parse_block_number(matches.get_one::<String>("to_block"));
+
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: chain_id.clone(),
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config =
+        StorageConfig { db_config, scope: StorageScope::FullArchive, ..Default::default() };
+    let (reader, _writer) =
+        apollo_storage::open_storage(config).expect("Should be able to open storage");
+
+    let reports = reexecute_blocks(
+        reader,
+        &chain_id,
+        &ExecutionConfig::default(),
+        from_block,
+        to_block.unchecked_next(),
+    )
+    .expect("Should be able to re-execute the blocks");
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&reports).expect("Should be able to serialize the reports")
+    );
+    if reports.iter().any(|report| !report.mismatches.is_empty()) {
+        std::process::exit(1);
+    }
+}
+
+fn parse_block_number(arg: Option<&String>) -> BlockNumber {
+    BlockNumber(
+        arg.expect("Missing block number").parse().expect("Block numbers should be integers"),
+    )
+}
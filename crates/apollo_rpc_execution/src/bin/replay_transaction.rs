@@ -0,0 +1,82 @@
+//! Re-executes a transaction from the storage of a node and writes its trace, with the Cairo steps
+//! run by each of its function invocations, to a file that `vm_trace_viewer` can print.
+//!
+//! The fee token addresses are those of the default execution config, which are shared by Mainnet
+//! and Sepolia. The storage is opened exclusively, so the node must be stopped.
+use std::fs;
+
+use apollo_rpc_execution::reexecution::replay_transaction;
+use apollo_rpc_execution::ExecutionConfig;
+use apollo_storage::db::DbConfig;
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, Command};
+use starknet_api::core::ChainId;
+use starknet_api::transaction::TransactionHash;
+use starknet_types_core::felt::Felt;
+
+pub fn main() {
+    let matches = Command::new("Transaction replay")
+        .about("Re-executes a stored transaction and writes its trace with the Cairo steps")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path prefix of the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .arg(
+            Arg::new("transaction_hash")
+                .short('t')
+                .long("transaction_hash")
+                .required(true)
+                .help("The hash of the transaction to re-execute"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .required(true)
+                .help("The path of the JSON file to write the trace to"),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id: ChainId =
+        matches.get_one::<String>("chain_id").expect("Missing chain_id").to_string().into();
+    let transaction_hash =
+        matches.get_one::<String>("transaction_hash").expect("Missing transaction_hash");
+    let transaction_hash = TransactionHash(
+        Felt::from_hex(transaction_hash)
+            .unwrap_or_else(|_| panic!("{transaction_hash} is not a hex felt")),
+    );
+    let output_path = matches.get_one::<String>("output").expect("Missing output");
+
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id: chain_id.clone(),
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config =
+        StorageConfig { db_config, scope: StorageScope::FullArchive, ..Default::default() };
+    // Opening the storage exclusively fails if a node has it open, so the replay doesn't write to
+    // the storage of a running node.
+    let (reader, _writer) = apollo_storage::open_storage_exclusively(config)
+        .expect("Should be able to open storage exclusively. Is the node still running?");
+
+    let trace =
+        replay_transaction(reader, &chain_id, &ExecutionConfig::default(), transaction_hash)
+            .expect("Should be able to re-execute the transaction");
+    fs::write(
+        output_path,
+        serde_json::to_string_pretty(&trace).expect("Should be able to serialize the trace"),
+    )
+    .expect("Should be able to write the trace file");
+}
@@ -0,0 +1,144 @@
+//! Prints the Cairo steps of a transaction trace that was requested with VM step tracing, e.g. the
+//! result of `starknet_V0_9_traceTransaction` with the `TRACE_VM_STEPS` trace flag, or the output
+//! of `replay_transaction`.
+//!
+//! The steps of Sierra classes are mapped to the Sierra statements they were compiled from if the
+//! class is given with `--sierra_class CLASS_HASH=PATH`, where PATH is a contract class file as
+//! written by the compiler. The statements are named if the file contains debug info, and are
+//! mapped to the Cairo code they were generated from if the debug info contains the statements
+//! code locations.
+use std::collections::HashMap;
+use std::fs;
+
+use apollo_rpc_execution::sierra_statements::SierraStatements;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use clap::{Arg, ArgAction, Command};
+use serde_json::Value;
+use starknet_types_core::felt::Felt;
+
+// The invocations of a transaction trace, in the order in which they run.
+const ROOT_INVOCATIONS: [&str; 5] = [
+    "validate_invocation",
+    "execute_invocation",
+    "constructor_invocation",
+    "function_invocation",
+    "fee_transfer_invocation",
+];
+
+pub fn main() {
+    let matches = Command::new("VM trace viewer")
+        .about("Prints the Cairo steps of a transaction trace")
+        .arg(
+            Arg::new("trace")
+                .short('t')
+                .long("trace")
+                .required(true)
+                .help("The path of the JSON file with the transaction trace"),
+        )
+        .arg(
+            Arg::new("sierra_class")
+                .short('s')
+                .long("sierra_class")
+                .action(ArgAction::Append)
+                .help(
+                    "CLASS_HASH=PATH of a Sierra class whose steps to map to Sierra statements \
+                     and Cairo code",
+                ),
+        )
+        .get_matches();
+
+    let trace_path = matches.get_one::<String>("trace").expect("Missing trace");
+    let trace: Value = serde_json::from_str(
+        &fs::read_to_string(trace_path).expect("Should be able to read the trace file"),
+    )
+    .expect("The trace file should contain JSON");
+    // Accept a full JSON-RPC response as well.
+    let trace = trace.get("result").unwrap_or(&trace);
+
+    let sierra_classes = matches
+        .get_many::<String>("sierra_class")
+        .unwrap_or_default()
+        .map(|arg| {
+            let (class_hash, path) =
+                arg.split_once('=').expect("Sierra classes should be given as CLASS_HASH=PATH");
+            let class: ContractClass = serde_json::from_str(
+                &fs::read_to_string(path).expect("Should be able to read the Sierra class file"),
+            )
+            .expect("The Sierra class file should contain a contract class");
+            let sierra_statements =
+                SierraStatements::new(class).expect("Should be able to compile the Sierra class");
+            (parse_felt(class_hash), sierra_statements)
+        })
+        .collect::<HashMap<_, _>>();
+
+    for name in ROOT_INVOCATIONS {
+        if let Some(invocation) = trace.get(name) {
+            println!("{name}:");
+            print_invocation(invocation, &sierra_classes, 1);
+        }
+    }
+}
+
+fn print_invocation(
+    invocation: &Value,
+    sierra_classes: &HashMap<Felt, SierraStatements>,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    if let Some(revert_reason) = invocation.get("revert_reason") {
+        println!("{indent}reverted: {revert_reason}");
+        return;
+    }
+
+    let field = |name: &str| invocation.get(name).and_then(Value::as_str).unwrap_or("?");
+    println!(
+        "{indent}contract {} selector {} class {}",
+        field("contract_address"),
+        field("entry_point_selector"),
+        field("class_hash")
+    );
+    let sierra_statements = invocation
+        .get("class_hash")
+        .and_then(Value::as_str)
+        .map(parse_felt)
+        .and_then(|class_hash| sierra_classes.get(&class_hash));
+    match invocation.get("vm_trace").and_then(|vm_trace| vm_trace.get("steps")) {
+        Some(Value::Array(steps)) => {
+            for step in steps {
+                let register = |name: &str| {
+                    step.get(name).and_then(Value::as_u64).expect("Steps should contain registers")
+                };
+                let pc = register("pc");
+                let mut line =
+                    format!("{indent}  pc={pc} ap={} fp={}", register("ap"), register("fp"));
+                if let Some(sierra_statements) = sierra_statements {
+                    let statement = usize::try_from(pc)
+                        .ok()
+                        .and_then(|pc| sierra_statements.statement_idx(pc))
+                        .and_then(|idx| {
+                            sierra_statements.describe_statement(idx).map(|desc| (idx, desc))
+                        });
+                    if let Some((idx, description)) = statement {
+                        line.push_str(&format!("  #{idx} {description}"));
+                        let code_locations = sierra_statements.code_locations(idx);
+                        if !code_locations.is_empty() {
+                            line.push_str(&format!("  at {}", code_locations.join(", ")));
+                        }
+                    }
+                }
+                println!("{line}");
+            }
+        }
+        _ => println!("{indent}  (no VM steps)"),
+    }
+
+    if let Some(Value::Array(calls)) = invocation.get("calls") {
+        for call in calls {
+            print_invocation(call, sierra_classes, depth + 1);
+        }
+    }
+}
+
+fn parse_felt(hex: &str) -> Felt {
+    Felt::from_hex(hex).unwrap_or_else(|_| panic!("{hex} is not a hex felt"))
+}
@@ -1,5 +1,6 @@
 // TODO(shahak): Add a test for executing when there's a missing casm that's not required and when
 // there's a missing casm that is required.
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use apollo_storage::test_utils::get_test_storage;
@@ -30,8 +31,10 @@ use crate::objects::{
     TransactionSimulationOutput,
     TransactionTrace,
 };
+use crate::state_overrides::{ContractOverride, InjectedClass, StateOverrides};
 use crate::test_utils::{
     execute_simulate_transactions,
+    get_test_deprecated_contract_class,
     prepare_storage,
     TxsScenarioBuilder,
     ACCOUNT_ADDRESS,
@@ -49,10 +52,12 @@ use crate::testing_instances::get_test_execution_config;
 use crate::{
     estimate_fee,
     execute_call,
+    simulate_transactions,
     ExecutableTransactionInput,
     ExecutionError,
     FeeEstimationResult,
     RevertedTransaction,
+    TraceOptions,
 };
 
 // Test calling entry points of a deprecated class.
@@ -76,6 +81,7 @@ fn execute_call_cairo0() {
         Calldata::default(),
         &get_test_execution_config(),
         true,
+        StateOverrides::default(),
     )
     .unwrap()
     .retdata;
@@ -93,6 +99,7 @@ fn execute_call_cairo0() {
         Calldata(Arc::new(vec![Felt::from(25u128)])),
         &get_test_execution_config(),
         true,
+        StateOverrides::default(),
     )
     .unwrap()
     .retdata;
@@ -110,6 +117,7 @@ fn execute_call_cairo0() {
         Calldata(Arc::new(vec![Felt::from(123u128)])),
         &get_test_execution_config(),
         true,
+        StateOverrides::default(),
     )
     .unwrap()
     .retdata;
@@ -127,6 +135,7 @@ fn execute_call_cairo0() {
         Calldata(Arc::new(vec![Felt::from(123u128), Felt::from(456u128)])),
         &get_test_execution_config(),
         true,
+        StateOverrides::default(),
     )
     .unwrap()
     .retdata;
@@ -155,6 +164,7 @@ fn execute_call_cairo1() {
         calldata,
         &get_test_execution_config(),
         true,
+        StateOverrides::default(),
     )
     .unwrap()
     .retdata;
@@ -162,6 +172,43 @@ fn execute_call_cairo1() {
     assert_eq!(retdata, Retdata(vec![value]));
 }
 
+// Test calling a contract that is deployed only by the state overrides, with a class they inject.
+#[test]
+fn execute_call_with_state_overrides() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let contract_address = contract_address!("0x123");
+    let class_hash = class_hash!("0x1234");
+    let state_overrides = StateOverrides {
+        contracts: HashMap::from([(
+            contract_address,
+            ContractOverride { class_hash: Some(class_hash), ..Default::default() },
+        )]),
+        classes: HashMap::from([(
+            class_hash,
+            InjectedClass::Cairo0(get_test_deprecated_contract_class()),
+        )]),
+    };
+
+    let retdata = execute_call(
+        storage_reader,
+        None,
+        &CHAIN_ID,
+        StateNumber::unchecked_right_after_block(BlockNumber(0)),
+        BlockNumber(0),
+        &contract_address,
+        selector_from_name("return_result"),
+        calldata![felt!(123_u128)],
+        &get_test_execution_config(),
+        true,
+        state_overrides,
+    )
+    .unwrap()
+    .retdata;
+    assert_eq!(retdata, Retdata(vec![felt!(123_u128)]));
+}
+
 // TODO(yair): Compare to the expected fee instead of asserting that it is not zero (all
 // estimate_fee tests).
 #[test]
@@ -252,6 +299,7 @@ fn estimate_fees(txs: Vec<ExecutableTransactionInput>) -> FeeEstimationResult {
         false,
         // TODO(yair): Add test for blob fee estimation.
         true,
+        StateOverrides::default(),
     )
     .unwrap()
 }
@@ -265,6 +313,201 @@ fn serialization_precision() {
     assert_eq!(input, deserialized);
 }
 
+#[test]
+fn simulate_invoke_with_state_accesses() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let tx = TxsScenarioBuilder::default()
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .collect();
+    let simulate = |trace_options| {
+        simulate_transactions(
+            tx.clone(),
+            None,
+            &ChainId::Other(CHAIN_ID.to_string()),
+            storage_reader.clone(),
+            None,
+            StateNumber::unchecked_right_after_block(BlockNumber(0)),
+            BlockNumber(1),
+            &get_test_execution_config(),
+            true,
+            false,
+            true,
+            trace_options,
+            StateOverrides::default(),
+        )
+        .unwrap()
+        .remove(0)
+        .transaction_trace
+    };
+
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        fee_transfer_invocation: Some(fee_transfer_invocation),
+        ..
+    }) = simulate(TraceOptions::default())
+    else {
+        panic!("Expected a successful invoke trace with a fee transfer.")
+    };
+    assert_eq!(execute_invocation.state_accesses, None);
+    assert_eq!(fee_transfer_invocation.state_accesses, None);
+
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        fee_transfer_invocation: Some(fee_transfer_invocation),
+        ..
+    }) = simulate(TraceOptions { state_accesses: true, ..Default::default() })
+    else {
+        panic!("Expected a successful invoke trace with a fee transfer.")
+    };
+    assert!(execute_invocation.state_accesses.is_some());
+    // The fee transfer reads the balances and writes the new ones.
+    let fee_transfer_accesses = fee_transfer_invocation.state_accesses.unwrap();
+    assert!(fee_transfer_accesses
+        .storage_writes
+        .iter()
+        .any(|write| write.old_value != write.new_value));
+}
+
+#[test]
+fn simulate_invoke_with_vm_steps() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let tx = TxsScenarioBuilder::default()
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .collect();
+    let simulate = |trace_options| {
+        simulate_transactions(
+            tx.clone(),
+            None,
+            &ChainId::Other(CHAIN_ID.to_string()),
+            storage_reader.clone(),
+            None,
+            StateNumber::unchecked_right_after_block(BlockNumber(0)),
+            BlockNumber(1),
+            &get_test_execution_config(),
+            true,
+            true,
+            true,
+            trace_options,
+            StateOverrides::default(),
+        )
+        .unwrap()
+        .remove(0)
+        .transaction_trace
+    };
+
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        validate_invocation: Some(validate_invocation),
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        ..
+    }) = simulate(TraceOptions::default())
+    else {
+        panic!("Expected a successful invoke trace with a validation.")
+    };
+    assert_eq!(validate_invocation.vm_trace, None);
+    assert_eq!(execute_invocation.vm_trace, None);
+
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        validate_invocation: Some(validate_invocation),
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        ..
+    }) = simulate(TraceOptions { vm_steps: true, ..Default::default() })
+    else {
+        panic!("Expected a successful invoke trace with a validation.")
+    };
+    assert!(!validate_invocation.vm_trace.unwrap().steps.is_empty());
+    // The account calls the contract, and each of them runs its own steps.
+    assert!(!execute_invocation.vm_trace.unwrap().steps.is_empty());
+    for call in execute_invocation.calls {
+        assert!(!call.vm_trace.unwrap().steps.is_empty());
+    }
+    assert_eq!(execute_invocation.state_accesses, None);
+}
+
+#[test]
+fn simulate_invokes_with_vm_steps_of_last_transaction_only() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let txs = TxsScenarioBuilder::default()
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .collect();
+    let traces = simulate_transactions(
+        txs,
+        None,
+        &ChainId::Other(CHAIN_ID.to_string()),
+        storage_reader,
+        None,
+        StateNumber::unchecked_right_after_block(BlockNumber(0)),
+        BlockNumber(1),
+        &get_test_execution_config(),
+        true,
+        true,
+        true,
+        TraceOptions { vm_steps: true, last_transaction_only: true, ..Default::default() },
+        StateOverrides::default(),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|simulation_output| simulation_output.transaction_trace)
+    .collect::<Vec<_>>();
+
+    let [TransactionTrace::Invoke(InvokeTransactionTrace {
+        execute_invocation: FunctionInvocationResult::Ok(first_execute_invocation),
+        ..
+    }), TransactionTrace::Invoke(InvokeTransactionTrace {
+        execute_invocation: FunctionInvocationResult::Ok(last_execute_invocation),
+        ..
+    })] = traces.as_slice()
+    else {
+        panic!("Expected two successful invoke traces.")
+    };
+    assert_eq!(first_execute_invocation.vm_trace, None);
+    assert!(!last_execute_invocation.vm_trace.as_ref().unwrap().steps.is_empty());
+}
+
+#[test]
+fn simulate_invoke_with_overridden_balance() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let tx = TxsScenarioBuilder::default()
+        .invoke_deprecated(*ACCOUNT_ADDRESS, *DEPRECATED_CONTRACT_ADDRESS, None, false)
+        .collect();
+    let state_overrides = StateOverrides {
+        contracts: HashMap::from([(
+            *ACCOUNT_ADDRESS,
+            ContractOverride { eth_balance: Some(Felt::ZERO), ..Default::default() },
+        )]),
+        ..Default::default()
+    };
+
+    // The account can't pay for the transaction without its balance.
+    let result = simulate_transactions(
+        tx,
+        None,
+        &CHAIN_ID,
+        storage_reader,
+        None,
+        StateNumber::unchecked_right_after_block(BlockNumber(0)),
+        BlockNumber(1),
+        &get_test_execution_config(),
+        true,
+        false,
+        true,
+        TraceOptions::default(),
+        state_overrides,
+    );
+    assert_matches!(
+        result,
+        Err(ExecutionError::TransactionExecutionError { transaction_index: 0, .. })
+    );
+}
+
 #[test]
 fn simulate_invoke() {
     let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
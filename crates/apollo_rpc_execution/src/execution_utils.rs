@@ -12,21 +12,31 @@ use blockifier::execution::contract_class::{
     RunnableCompiledClass,
 };
 use blockifier::state::cached_state::{CachedState, CommitmentStateDiff, MutRefState};
+use blockifier::state::state_api::StateReader;
 use blockifier::transaction::objects::TransactionExecutionInfo;
 use cairo_vm::types::errors::program_errors::ProgramError;
 use indexmap::IndexMap;
+use papyrus_common::deprecated_class_abi::calculate_deprecated_class_abi_length;
 use papyrus_common::state::{DeployedContract, ReplacedClass, StorageEntry};
 // Expose the tool for creating entry point selectors from function names.
 pub use starknet_api::abi::abi_utils::selector_from_name;
 use starknet_api::contract_class::SierraVersion;
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
-use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{DeclareTransaction, Transaction};
 use starknet_types_core::felt::Felt;
 use thiserror::Error;
 
 use crate::objects::TransactionTrace;
-use crate::state_reader::ExecutionStateReader;
-use crate::{ExecutableTransactionInput, ExecutionConfig, ExecutionError, ExecutionResult};
+use crate::{
+    AbiSize,
+    ExecutableTransactionInput,
+    ExecutionConfig,
+    ExecutionError,
+    ExecutionResult,
+};
 
 // An error that can occur during the use of the execution utils.
 #[derive(Debug, Error)]
@@ -82,6 +92,114 @@ pub(crate) fn get_contract_class(
     )))
 }
 
+/// Returns the input for re-executing a stored transaction of the block that starts at the given
+/// state number.
+// TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
+// instead.
+#[allow(clippy::result_large_err)]
+pub fn stored_txn_to_executable_txn(
+    stored_txn: Transaction,
+    storage_txn: &StorageTxn<'_, RO>,
+    state_number: StateNumber,
+) -> ExecutionResult<ExecutableTransactionInput> {
+    match stored_txn {
+        Transaction::Declare(DeclareTransaction::V0(value)) => {
+            let (deprecated_class, abi_length) =
+                get_deprecated_class_for_re_execution(storage_txn, state_number, value.class_hash)?;
+            Ok(ExecutableTransactionInput::DeclareV0(value, deprecated_class, abi_length, false))
+        }
+        Transaction::Declare(DeclareTransaction::V1(value)) => {
+            let (deprecated_class, abi_length) =
+                get_deprecated_class_for_re_execution(storage_txn, state_number, value.class_hash)?;
+            Ok(ExecutableTransactionInput::DeclareV1(value, deprecated_class, abi_length, false))
+        }
+        Transaction::Declare(DeclareTransaction::V2(value)) => {
+            let casm = storage_txn
+                .get_casm(&value.class_hash)?
+                .ok_or(ExecutionError::MissingCompiledClass { class_hash: value.class_hash })?;
+            let class = get_class_for_re_execution(storage_txn, state_number, value.class_hash)?;
+            let sierra_version = SierraVersion::extract_from_program(&class.sierra_program)
+                .map_err(|err| ExecutionError::BadDeclareTransaction {
+                    tx: DeclareTransaction::V2(value.clone()),
+                    err,
+                })?;
+            Ok(ExecutableTransactionInput::DeclareV2(
+                value,
+                casm,
+                class.sierra_program.len(),
+                class.abi.len(),
+                false,
+                sierra_version,
+            ))
+        }
+        Transaction::Declare(DeclareTransaction::V3(value)) => {
+            let casm = storage_txn
+                .get_casm(&value.class_hash)?
+                .ok_or(ExecutionError::MissingCompiledClass { class_hash: value.class_hash })?;
+            let class = get_class_for_re_execution(storage_txn, state_number, value.class_hash)?;
+            let sierra_version = SierraVersion::extract_from_program(&class.sierra_program)
+                .map_err(|err| ExecutionError::BadDeclareTransaction {
+                    tx: DeclareTransaction::V3(value.clone()),
+                    err,
+                })?;
+            Ok(ExecutableTransactionInput::DeclareV3(
+                value,
+                casm,
+                class.sierra_program.len(),
+                class.abi.len(),
+                false,
+                sierra_version,
+            ))
+        }
+        Transaction::Deploy(_) => Err(ExecutionError::DeployTransactionNotSupported),
+        Transaction::DeployAccount(deploy_account_tx) => {
+            Ok(ExecutableTransactionInput::DeployAccount(deploy_account_tx, false))
+        }
+        Transaction::Invoke(value) => Ok(ExecutableTransactionInput::Invoke(value, false)),
+        Transaction::L1Handler(value) => {
+            // todo(yair): This is a temporary solution until we have a better way to get the l1
+            // fee.
+            let paid_fee_on_l1 = Fee(1);
+            Ok(ExecutableTransactionInput::L1Handler(value, paid_fee_on_l1, false))
+        }
+    }
+}
+
+// For re-execution we need to get the class definition of declare transactions from the storage
+// before the execution. They are stored in the state after the block in which they appeared, so we
+// need to get it from the state after given block.
+#[allow(clippy::result_large_err)]
+fn get_deprecated_class_for_re_execution(
+    storage_txn: &StorageTxn<'_, RO>,
+    state_number: StateNumber,
+    class_hash: ClassHash,
+) -> ExecutionResult<(DeprecatedContractClass, AbiSize)> {
+    let state_number_after_block =
+        StateNumber::unchecked_right_after_block(state_number.block_after());
+    let deprecated_class = storage_txn
+        .get_state_reader()?
+        .get_deprecated_class_definition_at(state_number_after_block, &class_hash)?
+        .ok_or(ExecutionError::MissingClassDefinition { class_hash })?;
+    let abi_length = calculate_deprecated_class_abi_length(&deprecated_class)
+        .map_err(|err| ExecutionError::BadDeprecatedClassAbi { class_hash, err })?;
+    Ok((deprecated_class, abi_length))
+}
+
+// Like get_deprecated_class_for_re_execution, for classes that are compiled from Sierra.
+#[allow(clippy::result_large_err)]
+fn get_class_for_re_execution(
+    storage_txn: &StorageTxn<'_, RO>,
+    state_number: StateNumber,
+    class_hash: ClassHash,
+) -> ExecutionResult<SierraContractClass> {
+    let state_number_after_block =
+        StateNumber::unchecked_right_after_block(state_number.block_after());
+    storage_txn
+        .get_state_reader()?
+        .get_class_definition_at(state_number_after_block, &class_hash)?
+        .ok_or(ExecutionError::MissingClassDefinition { class_hash })
+}
+
 /// Given an ExecutableTransactionInput, returns a function that will convert the corresponding
 /// TransactionExecutionInfo into the right TransactionTrace variant.
 pub fn get_trace_constructor(
@@ -118,8 +236,8 @@ pub fn get_trace_constructor(
 // TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
 // instead.
 #[allow(clippy::result_large_err)]
-pub fn induced_state_diff(
-    transactional_state: &mut CachedState<MutRefState<'_, CachedState<ExecutionStateReader>>>,
+pub fn induced_state_diff<S: StateReader>(
+    transactional_state: &mut CachedState<MutRefState<'_, CachedState<S>>>,
     deprecated_declared_class_hash: Option<ClassHash>,
 ) -> ExecutionResult<ThinStateDiff> {
     let blockifier_state_diff =
@@ -18,6 +18,9 @@ mod test_utils;
 pub mod testing_instances;
 
 pub mod objects;
+pub mod reexecution;
+pub mod sierra_statements;
+pub mod state_overrides;
 use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::sync::{Arc, LazyLock};
@@ -64,7 +67,7 @@ use starknet_api::core::{ChainId, ClassHash, ContractAddress, EntryPointSelector
 use starknet_api::data_availability::L1DataAvailabilityMode;
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::execution_resources::GasAmount;
-use starknet_api::state::{StateNumber, ThinStateDiff};
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
 use starknet_api::transaction::fields::{Calldata, Fee};
 use starknet_api::transaction::{
     DeclareTransaction,
@@ -82,6 +85,7 @@ use starknet_api::transaction::{
 use starknet_api::transaction_hash::get_transaction_hash;
 use starknet_api::StarknetApiError;
 use starknet_types_core::felt::Felt;
+use state_overrides::{OverriddenStateReader, StateOverrides};
 use state_reader::ExecutionStateReader;
 use tracing::trace;
 
@@ -172,12 +176,23 @@ pub enum ExecutionError {
         #[source]
         err: StarknetApiError,
     },
+    #[error("Failed to calculate the ABI length of class {class_hash}: {err}")]
+    BadDeprecatedClassAbi {
+        class_hash: ClassHash,
+        #[source]
+        err: serde_json::Error,
+    },
     #[error("Execution config file does not contain a configuration for all blocks")]
     ConfigContentError,
     #[error(transparent)]
     ConfigFileError(#[from] std::io::Error),
     #[error(transparent)]
     ConfigSerdeError(#[from] serde_json::Error),
+    #[error(
+        "The state overrides set the storage of contract {contract_address:?} at key {key:?} more \
+         than once, e.g., both directly and by a balance override."
+    )]
+    ConflictingStateOverrides { contract_address: ContractAddress, key: StorageKey },
     #[error(transparent)]
     ContractError(#[from] BlockifierError),
     #[error(
@@ -185,12 +200,20 @@ pub enum ExecutionError {
          {state_number:?}."
     )]
     ContractNotFound { contract_address: ContractAddress, state_number: StateNumber },
+    #[error("Deploy transactions are not supported in execution.")]
+    DeployTransactionNotSupported,
     #[error("Gas consumed should fit into u64")]
     GasConsumedOutOfRange,
+    #[error("Block {block_number} is not in the storage.")]
+    MissingBlock { block_number: BlockNumber },
+    #[error("Missing the definition of class {class_hash}")]
+    MissingClassDefinition { class_hash: ClassHash },
     #[error("Missing class hash in call info")]
     MissingClassHash,
     #[error("Missing compiled class with hash {class_hash} (The CASM table isn't synced)")]
     MissingCompiledClass { class_hash: ClassHash },
+    #[error("Transaction {transaction_hash} is not in the storage.")]
+    MissingTransaction { transaction_hash: TransactionHash },
     #[error(transparent)]
     StateError(#[from] blockifier::state::errors::StateError),
     #[error(transparent)]
@@ -212,10 +235,24 @@ pub enum ExecutionError {
 /// Whether the only-query bit of the transaction version is on.
 pub type OnlyQuery = bool;
 
+/// Debugging data, which is not part of the specification, to add to the traces of executed
+/// transactions.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct TraceOptions {
+    /// Add the state accesses of each function invocation.
+    pub state_accesses: bool,
+    /// Add the Cairo steps run by each function invocation that runs on the Cairo VM.
+    pub vm_steps: bool,
+    /// Add the above data only to the trace of the last transaction. The preceding transactions
+    /// are executed only to reach its state, so tracing them is wasted work.
+    pub last_transaction_only: bool,
+}
+
 /// Gathers all the possible errors that can be returned from the blockifier.
 type BlockifierError = anyhow::Error;
 
-/// Executes a StarkNet call and returns the execution result.
+/// Executes a StarkNet call on top of the state with the given overrides and returns the execution
+/// result.
 #[allow(clippy::too_many_arguments)]
 // TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
 // instead.
@@ -231,13 +268,17 @@ pub fn execute_call(
     calldata: Calldata,
     execution_config: &ExecutionConfig,
     override_kzg_da_to_false: bool,
+    state_overrides: StateOverrides,
 ) -> ExecutionResult<CallExecution> {
-    verify_contract_exists(
-        *contract_address,
-        &storage_reader,
-        state_number,
-        maybe_pending_data.as_ref(),
-    )?;
+    // A contract whose class is overridden exists even if it isn't deployed.
+    if state_overrides.class_hash_at(*contract_address).is_none() {
+        verify_contract_exists(
+            *contract_address,
+            &storage_reader,
+            state_number,
+            maybe_pending_data.as_ref(),
+        )?;
+    }
 
     // TODO(yair): check if this is the correct value.
     let mut remaining_gas = execution_config.default_initial_gas_cost;
@@ -253,12 +294,16 @@ pub fn execute_call(
         initial_gas: remaining_gas,
     };
 
-    let mut cached_state = CachedState::new(ExecutionStateReader {
-        storage_reader: storage_reader.clone(),
-        state_number,
-        maybe_pending_data: maybe_pending_data.clone(),
-        missing_compiled_class: Cell::new(None),
-    });
+    let mut cached_state = CachedState::new(OverriddenStateReader::new(
+        ExecutionStateReader {
+            storage_reader: storage_reader.clone(),
+            state_number,
+            maybe_pending_data: maybe_pending_data.clone(),
+            missing_compiled_class: Cell::new(None),
+        },
+        state_overrides,
+        &fee_token_addresses(execution_config),
+    )?);
 
     let block_context = create_block_context(
         &mut cached_state,
@@ -282,7 +327,7 @@ pub fn execute_call(
     let res = call_entry_point
         .execute(&mut cached_state, &mut context, &mut remaining_gas)
         .map_err(|error| {
-            if let Some(class_hash) = cached_state.state.missing_compiled_class.get() {
+            if let Some(class_hash) = cached_state.state.state_reader.missing_compiled_class.get() {
                 ExecutionError::MissingCompiledClass { class_hash }
             } else {
                 ExecutionError::ContractError(error.into())
@@ -317,7 +362,7 @@ fn verify_contract_exists(
 // instead.
 #[allow(clippy::result_large_err)]
 fn create_block_context(
-    cached_state: &mut CachedState<ExecutionStateReader>,
+    cached_state: &mut CachedState<OverriddenStateReader<ExecutionStateReader>>,
     block_context_number: BlockNumber,
     chain_id: ChainId,
     storage_reader: &StorageReader,
@@ -387,13 +432,8 @@ fn create_block_context(
             NonzeroGasPrice::new(l2_gas_price.price_in_fri).unwrap_or(NonzeroGasPrice::MIN),
         ),
     };
-    let chain_info = ChainInfo {
-        chain_id,
-        fee_token_addresses: FeeTokenAddresses {
-            strk_fee_token_address: execution_config.strk_fee_contract_address,
-            eth_fee_token_address: execution_config.eth_fee_contract_address,
-        },
-    };
+    let chain_info =
+        ChainInfo { chain_id, fee_token_addresses: fee_token_addresses(execution_config) };
     let starknet_version = storage_reader
         .begin_ro_txn()?
         .get_starknet_version(block_number)?
@@ -613,7 +653,8 @@ pub struct RevertedTransaction {
 /// index and revert reason of the first reverted transaction.
 pub type FeeEstimationResult = Result<Vec<FeeEstimation>, RevertedTransaction>;
 
-/// Returns the fee estimation for a series of transactions.
+/// Returns the fee estimation for a series of transactions executed on top of the state with the
+/// given overrides.
 #[allow(clippy::too_many_arguments)]
 // TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
 // instead.
@@ -628,6 +669,7 @@ pub fn estimate_fee(
     execution_config: &ExecutionConfig,
     validate: bool,
     override_kzg_da_to_false: bool,
+    state_overrides: StateOverrides,
 ) -> ExecutionResult<FeeEstimationResult> {
     let (txs_execution_info, block_context) = execute_transactions(
         txs,
@@ -641,6 +683,8 @@ pub fn estimate_fee(
         false,
         validate,
         override_kzg_da_to_false,
+        TraceOptions::default(),
+        state_overrides,
     )?;
     let mut result = Vec::new();
     for (index, tx_execution_output) in txs_execution_info.into_iter().enumerate() {
@@ -682,16 +726,22 @@ fn execute_transactions(
     charge_fee: bool,
     validate: bool,
     override_kzg_da_to_false: bool,
+    trace_options: TraceOptions,
+    state_overrides: StateOverrides,
 ) -> ExecutionResult<(Vec<TransactionExecutionOutput>, BlockContext)> {
     // The starknet state will be from right before the block in which the transactions should run.
-    let mut cached_state = CachedState::new(ExecutionStateReader {
-        storage_reader: storage_reader.clone(),
-        state_number,
-        maybe_pending_data: maybe_pending_data.clone(),
-        missing_compiled_class: Cell::new(None),
-    });
+    let mut cached_state = CachedState::new(OverriddenStateReader::new(
+        ExecutionStateReader {
+            storage_reader: storage_reader.clone(),
+            state_number,
+            maybe_pending_data: maybe_pending_data.clone(),
+            missing_compiled_class: Cell::new(None),
+        },
+        state_overrides,
+        &fee_token_addresses(execution_config),
+    )?);
 
-    let block_context = create_block_context(
+    let mut block_context = create_block_context(
         &mut cached_state,
         block_context_block_number,
         chain_id.clone(),
@@ -700,7 +750,6 @@ fn execute_transactions(
         execution_config,
         override_kzg_da_to_false,
     )?;
-
     let (txs, tx_hashes) = match tx_hashes {
         Some(tx_hashes) => (txs, tx_hashes),
         None => {
@@ -710,9 +759,13 @@ fn execute_transactions(
         }
     };
 
+    let n_txs = txs.len();
     let mut res = vec![];
     for (transaction_index, (tx, tx_hash)) in txs.into_iter().zip(tx_hashes.into_iter()).enumerate()
     {
+        let trace = !trace_options.last_transaction_only || transaction_index + 1 == n_txs;
+        block_context.set_trace_state_accesses(trace && trace_options.state_accesses);
+        block_context.set_trace_vm_steps(trace && trace_options.vm_steps);
         let transaction_version = tx.transaction_version();
         // TODO(DanB): consider supporting match instead.
         let price_unit = if transaction_version == TransactionVersion::ZERO
@@ -747,7 +800,7 @@ fn execute_transactions(
             induced_state_diff(&mut transactional_state, deprecated_declared_class_hash)?;
         transactional_state.commit();
         let execution_info = tx_execution_info_result.map_err(|error| {
-            if let Some(class_hash) = cached_state.state.missing_compiled_class.get() {
+            if let Some(class_hash) = cached_state.state.state_reader.missing_compiled_class.get() {
                 ExecutionError::MissingCompiledClass { class_hash }
             } else {
                 ExecutionError::from((transaction_index, error))
@@ -763,6 +816,13 @@ fn execute_transactions(
     Ok((res, block_context))
 }
 
+fn fee_token_addresses(execution_config: &ExecutionConfig) -> FeeTokenAddresses {
+    FeeTokenAddresses {
+        strk_fee_token_address: execution_config.strk_fee_contract_address,
+        eth_fee_token_address: execution_config.eth_fee_contract_address,
+    }
+}
+
 /// Converts a transaction index and [BlockifierTransactionExecutionError] to an [ExecutionError].
 // TODO(yair): Remove once blockifier arranges the errors hierarchy.
 impl From<(usize, BlockifierTransactionExecutionError)> for ExecutionError {
@@ -777,14 +837,18 @@ impl From<(usize, BlockifierTransactionExecutionError)> for ExecutionError {
 #[allow(clippy::result_large_err)]
 fn get_10_blocks_ago(
     block_number: &BlockNumber,
-    cached_state: &CachedState<ExecutionStateReader>,
+    cached_state: &CachedState<OverriddenStateReader<ExecutionStateReader>>,
 ) -> ExecutionResult<Option<BlockHashAndNumber>> {
     if block_number.0 < 10 {
         return Ok(None);
     }
     let block_min_10 = BlockNumber(block_number.0 - 10);
-    let Some(header_10_blocks_ago) =
-        cached_state.state.storage_reader.begin_ro_txn()?.get_block_header(block_min_10)?
+    let Some(header_10_blocks_ago) = cached_state
+        .state
+        .state_reader
+        .storage_reader
+        .begin_ro_txn()?
+        .get_block_header(block_min_10)?
     else {
         return Ok(None);
     };
@@ -970,6 +1034,8 @@ fn to_blockifier_tx(
 }
 
 /// Simulates a series of transactions and returns the transaction traces and the fee estimations.
+/// The transactions are executed on top of the state with the given overrides, and their traces
+/// contain the debugging data that `trace_options` requests.
 // TODO(yair): Return structs instead of tuples.
 // TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
 // instead.
@@ -987,6 +1053,8 @@ pub fn simulate_transactions(
     charge_fee: bool,
     validate: bool,
     override_kzg_da_to_false: bool,
+    trace_options: TraceOptions,
+    state_overrides: StateOverrides,
 ) -> ExecutionResult<Vec<TransactionSimulationOutput>> {
     let trace_constructors = txs.iter().map(get_trace_constructor).collect::<Vec<_>>();
     let (execution_results, block_context) = execute_transactions(
@@ -1001,6 +1069,8 @@ pub fn simulate_transactions(
         charge_fee,
         validate,
         override_kzg_da_to_false,
+        trace_options,
+        state_overrides,
     )?;
     execution_results
         .into_iter()
@@ -7,6 +7,8 @@ use blockifier::execution::call_info::{
     OrderedEvent as BlockifierOrderedEvent,
     OrderedL2ToL1Message as BlockifierOrderedL2ToL1Message,
     Retdata as BlockifierRetdata,
+    StateAccessTrace,
+    VmTrace as BlockifierVmTrace,
 };
 use blockifier::execution::entry_point::CallType as BlockifierCallType;
 use blockifier::transaction::objects::TransactionExecutionInfo;
@@ -39,7 +41,7 @@ use starknet_api::execution_resources::{
     GasVector,
     GasVector as StarknetApiGasVector,
 };
-use starknet_api::state::ThinStateDiff;
+use starknet_api::state::{StorageKey, ThinStateDiff};
 use starknet_api::transaction::fields::{Calldata, Fee};
 use starknet_api::transaction::{EventContent, MessageToL1};
 use starknet_types_core::felt::Felt;
@@ -310,6 +312,13 @@ pub struct FunctionInvocation {
     pub messages: Vec<OrderedL2ToL1Message>,
     /// The VM execution resources used by this invocation.
     pub execution_resources: ExecutionResources,
+    /// The state accesses made by this invocation. Only returned if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_accesses: Option<StateAccesses>,
+    /// The Cairo steps run by this invocation. Only returned if requested, and only for
+    /// invocations that run on the Cairo VM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_trace: Option<VmTrace>,
 }
 
 impl TryFrom<(CallInfo, GasVector)> for FunctionInvocation {
@@ -353,10 +362,134 @@ impl TryFrom<(CallInfo, GasVector)> for FunctionInvocation {
                 call_info.resources,
                 gas_vector,
             )?,
+            state_accesses: call_info
+                .storage_access_tracker
+                .state_access_trace
+                .map(StateAccesses::from),
+            vm_trace: call_info.vm_trace.map(VmTrace::from),
         })
     }
 }
 
+/// The state accesses made by a function invocation, excluding the ones made by the calls it made,
+/// in the order in which they were made.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StateAccesses {
+    /// The storage reads of the invoked contract and the values that were read.
+    pub storage_reads: Vec<StorageRead>,
+    /// The storage writes of the invoked contract and the values before and after them.
+    pub storage_writes: Vec<StorageWrite>,
+    /// The contracts whose class was set, by deploying them or by replacing their class.
+    pub class_hash_changes: Vec<ClassHashChange>,
+    /// The nonce change of the account that sent the transaction. Appears in the validation
+    /// invocation, or in the execution invocation if the validation was skipped.
+    pub nonce_changes: Vec<NonceChange>,
+}
+
+impl From<StateAccessTrace> for StateAccesses {
+    fn from(trace: StateAccessTrace) -> Self {
+        Self {
+            storage_reads: trace
+                .storage_reads
+                .into_iter()
+                .map(|read| StorageRead { key: read.key, value: read.value })
+                .collect(),
+            storage_writes: trace
+                .storage_writes
+                .into_iter()
+                .map(|write| StorageWrite {
+                    key: write.key,
+                    old_value: write.old_value,
+                    new_value: write.new_value,
+                })
+                .collect(),
+            class_hash_changes: trace
+                .class_hash_changes
+                .into_iter()
+                .map(|change| ClassHashChange {
+                    contract_address: change.contract_address,
+                    old_class_hash: change.old_class_hash,
+                    new_class_hash: change.new_class_hash,
+                })
+                .collect(),
+            nonce_changes: trace
+                .nonce_changes
+                .into_iter()
+                .map(|change| NonceChange {
+                    contract_address: change.contract_address,
+                    old_nonce: change.old_nonce,
+                    new_nonce: change.new_nonce,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A read of the storage of a contract.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StorageRead {
+    pub key: StorageKey,
+    pub value: Felt,
+}
+
+/// A write to the storage of a contract.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StorageWrite {
+    pub key: StorageKey,
+    pub old_value: Felt,
+    pub new_value: Felt,
+}
+
+/// A change of the class hash of a contract. The old class hash of a deployed contract is zero.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClassHashChange {
+    pub contract_address: ContractAddress,
+    pub old_class_hash: ClassHash,
+    pub new_class_hash: ClassHash,
+}
+
+/// A change of the nonce of an account.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NonceChange {
+    pub contract_address: ContractAddress,
+    pub old_nonce: Nonce,
+    pub new_nonce: Nonce,
+}
+
+/// The Cairo steps run by a function invocation, excluding the steps of the calls it made, in the
+/// order in which they were run.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VmTrace {
+    /// The registers of the VM before running each step.
+    pub steps: Vec<VmStep>,
+}
+
+impl From<BlockifierVmTrace> for VmTrace {
+    fn from(trace: BlockifierVmTrace) -> Self {
+        Self {
+            steps: trace
+                .steps
+                .into_iter()
+                .map(|step| VmStep { pc: step.pc, ap: step.ap, fp: step.fp })
+                .collect(),
+        }
+    }
+}
+
+/// The registers of the VM before running a step. The PC is the offset of the instruction in the
+/// bytecode of the invoked class, and the AP and FP are offsets in the execution segment.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VmStep {
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
+}
+
 // Can't implement `TryFrom` because both types are from external crates.
 // TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
 // instead.
@@ -0,0 +1,456 @@
+//! Re-execution of stored blocks, for verifying that executing their transactions reproduces the
+//! stored receipts and state diffs, and of stored transactions, for debugging them.
+#[cfg(test)]
+#[path = "reexecution_test.rs"]
+mod reexecution_test;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use apollo_storage::body::{BodyStorageReader, TransactionIndex};
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::StorageReader;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{
+    Event,
+    MessageToL1,
+    RevertedTransactionExecutionStatus,
+    TransactionExecutionStatus,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_types_core::felt::Felt;
+
+use crate::execution_utils::stored_txn_to_executable_txn;
+use crate::objects::TransactionTrace;
+use crate::state_overrides::StateOverrides;
+use crate::{
+    execute_transactions,
+    simulate_transactions,
+    ExecutionConfig,
+    ExecutionError,
+    ExecutionResult,
+    TraceOptions,
+    TransactionExecutionOutput,
+};
+
+/// The differences between a stored block and the result of re-executing it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockReexecutionReport {
+    /// The re-executed block.
+    pub block_number: BlockNumber,
+    /// The differences that were found. Empty if the re-execution reproduced the stored block.
+    pub mismatches: Vec<ReexecutionMismatch>,
+}
+
+/// A difference between a stored block and the result of re-executing it. Each variant holds
+/// the stored value and the re-executed one.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReexecutionMismatch {
+    /// The transaction failed to execute, so the rest of the block wasn't compared.
+    TransactionFailed {
+        transaction_hash: TransactionHash,
+        error: String,
+    },
+    /// One of the executions reverted and the other didn't.
+    ExecutionStatus {
+        transaction_hash: TransactionHash,
+        stored: TransactionExecutionStatus,
+        reexecuted: TransactionExecutionStatus,
+    },
+    Fee {
+        transaction_hash: TransactionHash,
+        stored: Fee,
+        reexecuted: Fee,
+    },
+    Events {
+        transaction_hash: TransactionHash,
+        stored: Vec<Event>,
+        reexecuted: Vec<Event>,
+    },
+    MessagesToL1 {
+        transaction_hash: TransactionHash,
+        stored: Vec<MessageToL1>,
+        reexecuted: Vec<MessageToL1>,
+    },
+    Storage {
+        contract_address: ContractAddress,
+        key: StorageKey,
+        stored: Option<Felt>,
+        reexecuted: Option<Felt>,
+    },
+    Nonce {
+        contract_address: ContractAddress,
+        stored: Option<Nonce>,
+        reexecuted: Option<Nonce>,
+    },
+    ClassHash {
+        contract_address: ContractAddress,
+        stored: Option<ClassHash>,
+        reexecuted: Option<ClassHash>,
+    },
+    DeclaredClass {
+        class_hash: ClassHash,
+        stored: Option<CompiledClassHash>,
+        reexecuted: Option<CompiledClassHash>,
+    },
+    DeprecatedDeclaredClass {
+        class_hash: ClassHash,
+        stored: bool,
+        reexecuted: bool,
+    },
+}
+
+/// Re-executes the blocks in the range [from, to) on top of the state before each of them and
+/// compares the results with the stored blocks.
+// TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
+// instead.
+#[allow(clippy::result_large_err)]
+pub fn reexecute_blocks(
+    storage_reader: StorageReader,
+    chain_id: &ChainId,
+    execution_config: &ExecutionConfig,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> ExecutionResult<Vec<BlockReexecutionReport>> {
+    from.iter_up_to(to)
+        .map(|block_number| {
+            reexecute_block(storage_reader.clone(), chain_id, execution_config, block_number)
+        })
+        .collect()
+}
+
+/// Re-executes a block on top of the state before it and compares the results with the stored
+/// block.
+// TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
+// instead.
+#[allow(clippy::result_large_err)]
+pub fn reexecute_block(
+    storage_reader: StorageReader,
+    chain_id: &ChainId,
+    execution_config: &ExecutionConfig,
+    block_number: BlockNumber,
+) -> ExecutionResult<BlockReexecutionReport> {
+    let storage_txn = storage_reader.begin_ro_txn()?;
+    let (Some(txs), Some(tx_hashes), Some(tx_outputs), Some(stored_state_diff)) = (
+        storage_txn.get_block_transactions(block_number)?,
+        storage_txn.get_block_transaction_hashes(block_number)?,
+        storage_txn.get_block_transaction_outputs(block_number)?,
+        storage_txn.get_state_diff(block_number)?,
+    ) else {
+        return Err(ExecutionError::MissingBlock { block_number });
+    };
+    let state_number = StateNumber::right_before_block(block_number);
+    let executable_txs = txs
+        .into_iter()
+        .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
+        .collect::<ExecutionResult<Vec<_>>>()?;
+
+    let execution_outputs = match execute_transactions(
+        executable_txs,
+        Some(tx_hashes.clone()),
+        chain_id,
+        storage_reader,
+        None,
+        state_number,
+        block_number,
+        execution_config,
+        true,
+        true,
+        false,
+        TraceOptions::default(),
+        StateOverrides::default(),
+    ) {
+        Ok((execution_outputs, _block_context)) => execution_outputs,
+        Err(ExecutionError::TransactionExecutionError { transaction_index, execution_error }) => {
+            let mismatch = ReexecutionMismatch::TransactionFailed {
+                transaction_hash: tx_hashes[transaction_index],
+                error: execution_error,
+            };
+            return Ok(BlockReexecutionReport { block_number, mismatches: vec![mismatch] });
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut mismatches = Vec::new();
+    let mut reexecuted_state_diff = ThinStateDiff::default();
+    for ((transaction_hash, stored_output), execution_output) in
+        tx_hashes.into_iter().zip(tx_outputs.iter()).zip(execution_outputs)
+    {
+        compare_transaction_output(
+            transaction_hash,
+            stored_output,
+            &execution_output,
+            &mut mismatches,
+        );
+        merge_state_diff(&mut reexecuted_state_diff, execution_output.induced_state_diff);
+    }
+
+    let state_reader = storage_txn.get_state_reader()?;
+    compare_entries(
+        storage_entries(&stored_state_diff),
+        storage_entries(&reexecuted_state_diff),
+        |(contract_address, key)| {
+            Ok(Some(state_reader.get_storage_at(state_number, contract_address, key)?))
+        },
+        |(contract_address, key), stored, reexecuted| ReexecutionMismatch::Storage {
+            contract_address,
+            key,
+            stored,
+            reexecuted,
+        },
+        &mut mismatches,
+    )?;
+    compare_entries(
+        stored_state_diff.nonces.into_iter().collect(),
+        reexecuted_state_diff.nonces.into_iter().collect(),
+        |contract_address| {
+            Ok(Some(state_reader.get_nonce_at(state_number, contract_address)?.unwrap_or_default()))
+        },
+        |contract_address, stored, reexecuted| ReexecutionMismatch::Nonce {
+            contract_address,
+            stored,
+            reexecuted,
+        },
+        &mut mismatches,
+    )?;
+    compare_entries(
+        stored_state_diff.deployed_contracts.into_iter().collect(),
+        reexecuted_state_diff.deployed_contracts.into_iter().collect(),
+        |contract_address| {
+            Ok(Some(
+                state_reader.get_class_hash_at(state_number, contract_address)?.unwrap_or_default(),
+            ))
+        },
+        |contract_address, stored, reexecuted| ReexecutionMismatch::ClassHash {
+            contract_address,
+            stored,
+            reexecuted,
+        },
+        &mut mismatches,
+    )?;
+    compare_entries(
+        stored_state_diff.declared_classes.into_iter().collect(),
+        reexecuted_state_diff.declared_classes.into_iter().collect(),
+        |_| Ok(None),
+        |class_hash, stored, reexecuted| ReexecutionMismatch::DeclaredClass {
+            class_hash,
+            stored,
+            reexecuted,
+        },
+        &mut mismatches,
+    )?;
+    compare_entries(
+        stored_state_diff.deprecated_declared_classes.into_iter().map(|hash| (hash, ())).collect(),
+        reexecuted_state_diff
+            .deprecated_declared_classes
+            .into_iter()
+            .map(|hash| (hash, ()))
+            .collect(),
+        |_| Ok(None),
+        |class_hash, stored, reexecuted| ReexecutionMismatch::DeprecatedDeclaredClass {
+            class_hash,
+            stored: stored.is_some(),
+            reexecuted: reexecuted.is_some(),
+        },
+        &mut mismatches,
+    )?;
+
+    Ok(BlockReexecutionReport { block_number, mismatches })
+}
+
+/// Re-executes a stored transaction on top of the state before its block, after the transactions
+/// that precede it in the block, and returns its trace with the Cairo steps run by each of its
+/// function invocations.
+// TODO(Dan, Yair): consider box large elements (because of BadDeclareTransaction) or use ID
+// instead.
+#[allow(clippy::result_large_err)]
+pub fn replay_transaction(
+    storage_reader: StorageReader,
+    chain_id: &ChainId,
+    execution_config: &ExecutionConfig,
+    transaction_hash: TransactionHash,
+) -> ExecutionResult<TransactionTrace> {
+    let storage_txn = storage_reader.begin_ro_txn()?;
+    let Some(TransactionIndex(block_number, transaction_offset)) =
+        storage_txn.get_transaction_idx_by_hash(&transaction_hash)?
+    else {
+        return Err(ExecutionError::MissingTransaction { transaction_hash });
+    };
+    let (Some(txs), Some(tx_hashes)) = (
+        storage_txn.get_block_transactions(block_number)?,
+        storage_txn.get_block_transaction_hashes(block_number)?,
+    ) else {
+        return Err(ExecutionError::MissingBlock { block_number });
+    };
+    let n_txs = transaction_offset.0 + 1;
+    let state_number = StateNumber::right_before_block(block_number);
+    let executable_txs = txs
+        .into_iter()
+        .take(n_txs)
+        .map(|tx| stored_txn_to_executable_txn(tx, &storage_txn, state_number))
+        .collect::<ExecutionResult<Vec<_>>>()?;
+
+    let mut simulation_outputs = simulate_transactions(
+        executable_txs,
+        Some(tx_hashes.into_iter().take(n_txs).collect()),
+        chain_id,
+        storage_reader,
+        None,
+        state_number,
+        block_number,
+        execution_config,
+        true,
+        true,
+        false,
+        TraceOptions { vm_steps: true, last_transaction_only: true, ..Default::default() },
+        StateOverrides::default(),
+    )?;
+    Ok(simulation_outputs
+        .pop()
+        .expect("The replayed transaction should be the last executed transaction.")
+        .transaction_trace)
+}
+
+fn compare_transaction_output(
+    transaction_hash: TransactionHash,
+    stored_output: &TransactionOutput,
+    execution_output: &TransactionExecutionOutput,
+    mismatches: &mut Vec<ReexecutionMismatch>,
+) {
+    let execution_info = &execution_output.execution_info;
+    let reexecuted_status = match &execution_info.revert_error {
+        None => TransactionExecutionStatus::Succeeded,
+        Some(revert_error) => {
+            TransactionExecutionStatus::Reverted(RevertedTransactionExecutionStatus {
+                revert_reason: revert_error.to_string(),
+            })
+        }
+    };
+    // The revert reasons aren't compared, since their format changes between versions.
+    if matches!(stored_output.execution_status(), TransactionExecutionStatus::Reverted(_))
+        != execution_info.is_reverted()
+    {
+        mismatches.push(ReexecutionMismatch::ExecutionStatus {
+            transaction_hash,
+            stored: stored_output.execution_status().clone(),
+            reexecuted: reexecuted_status,
+        });
+    }
+
+    if stored_output.actual_fee() != execution_info.receipt.fee {
+        mismatches.push(ReexecutionMismatch::Fee {
+            transaction_hash,
+            stored: stored_output.actual_fee(),
+            reexecuted: execution_info.receipt.fee,
+        });
+    }
+
+    let (events, messages) = emitted_events_and_messages(execution_info);
+    if stored_output.events() != events.as_slice() {
+        mismatches.push(ReexecutionMismatch::Events {
+            transaction_hash,
+            stored: stored_output.events().to_vec(),
+            reexecuted: events,
+        });
+    }
+    if *stored_output.messages_sent() != messages {
+        mismatches.push(ReexecutionMismatch::MessagesToL1 {
+            transaction_hash,
+            stored: stored_output.messages_sent().clone(),
+            reexecuted: messages,
+        });
+    }
+}
+
+// Returns the events and the messages to L1 of a transaction in the order they were emitted. The
+// order of the emitted events and messages restarts in each of the validate, execute and fee
+// transfer calls.
+fn emitted_events_and_messages(
+    execution_info: &TransactionExecutionInfo,
+) -> (Vec<Event>, Vec<MessageToL1>) {
+    let mut events = Vec::new();
+    let mut messages = Vec::new();
+    for root_call_info in execution_info.non_optional_call_infos() {
+        let mut call_events = Vec::new();
+        let mut call_messages = Vec::new();
+        for call_info in root_call_info.iter() {
+            let from_address = call_info.call.storage_address;
+            call_events.extend(call_info.execution.events.iter().map(|ordered_event| {
+                (ordered_event.order, Event { from_address, content: ordered_event.event.clone() })
+            }));
+            call_messages.extend(call_info.execution.l2_to_l1_messages.iter().map(
+                |ordered_message| {
+                    (
+                        ordered_message.order,
+                        MessageToL1 {
+                            from_address,
+                            to_address: ordered_message.message.to_address,
+                            payload: ordered_message.message.payload.clone(),
+                        },
+                    )
+                },
+            ));
+        }
+        call_events.sort_by_key(|(order, _)| *order);
+        call_messages.sort_by_key(|(order, _)| *order);
+        events.extend(call_events.into_iter().map(|(_, event)| event));
+        messages.extend(call_messages.into_iter().map(|(_, message)| message));
+    }
+    (events, messages)
+}
+
+// Applies the state diff of a transaction on top of the state diff of the previous transactions
+// of the block.
+fn merge_state_diff(state_diff: &mut ThinStateDiff, tx_state_diff: ThinStateDiff) {
+    state_diff.deployed_contracts.extend(tx_state_diff.deployed_contracts);
+    for (contract_address, storage_diff) in tx_state_diff.storage_diffs {
+        state_diff.storage_diffs.entry(contract_address).or_default().extend(storage_diff);
+    }
+    state_diff.declared_classes.extend(tx_state_diff.declared_classes);
+    state_diff.deprecated_declared_classes.extend(tx_state_diff.deprecated_declared_classes);
+    state_diff.nonces.extend(tx_state_diff.nonces);
+}
+
+fn storage_entries(state_diff: &ThinStateDiff) -> BTreeMap<(ContractAddress, StorageKey), Felt> {
+    state_diff
+        .storage_diffs
+        .iter()
+        .flat_map(|(contract_address, storage_diff)| {
+            storage_diff.iter().map(|(key, value)| ((*contract_address, *key), *value))
+        })
+        .collect()
+}
+
+// Adds a mismatch for each entry whose stored value differs from the re-executed one. An entry
+// that appears only on one side isn't a mismatch if its value didn't change in the block, since
+// writing the previous value of an entry may or may not appear in a state diff.
+#[allow(clippy::result_large_err)]
+fn compare_entries<K: Copy + Ord, V: Clone + PartialEq>(
+    stored: BTreeMap<K, V>,
+    reexecuted: BTreeMap<K, V>,
+    mut value_before_block: impl FnMut(&K) -> ExecutionResult<Option<V>>,
+    to_mismatch: impl Fn(K, Option<V>, Option<V>) -> ReexecutionMismatch,
+    mismatches: &mut Vec<ReexecutionMismatch>,
+) -> ExecutionResult<()> {
+    let keys = stored.keys().chain(reexecuted.keys()).copied().collect::<BTreeSet<_>>();
+    for key in keys {
+        let (stored_value, reexecuted_value) = (stored.get(&key), reexecuted.get(&key));
+        let is_mismatch = match (stored_value, reexecuted_value) {
+            (Some(stored_value), Some(reexecuted_value)) => stored_value != reexecuted_value,
+            (Some(value), None) | (None, Some(value)) => {
+                value_before_block(&key)?.as_ref() != Some(value)
+            }
+            (None, None) => unreachable!("The key is taken from one of the entries."),
+        };
+        if is_mismatch {
+            mismatches.push(to_mismatch(key, stored_value.cloned(), reexecuted_value.cloned()));
+        }
+    }
+    Ok(())
+}
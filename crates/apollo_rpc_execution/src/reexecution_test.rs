@@ -0,0 +1,204 @@
+use apollo_storage::body::BodyStorageWriter;
+use apollo_storage::class::ClassStorageWriter;
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use apollo_storage::test_utils::get_test_storage;
+use apollo_storage::StorageWriter;
+use assert_matches::assert_matches;
+use starknet_api::block::{BlockBody, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::CompiledClassHash;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::{calldata, class_hash, felt, nonce};
+
+use crate::execution_utils::selector_from_name;
+use crate::objects::{FunctionInvocationResult, InvokeTransactionTrace, TransactionTrace};
+use crate::reexecution::{
+    reexecute_block,
+    reexecute_blocks,
+    replay_transaction,
+    ReexecutionMismatch,
+};
+use crate::test_utils::{
+    prepare_storage,
+    ACCOUNT_ADDRESS,
+    ACCOUNT_CLASS_HASH,
+    BLOCK_TIMESTAMP,
+    CHAIN_ID,
+    CONTRACT_ADDRESS,
+    DEPRECATED_CONTRACT_ADDRESS,
+    GAS_PRICE,
+    MAX_FEE,
+    SEQUENCER_ADDRESS,
+};
+use crate::testing_instances::get_test_execution_config;
+use crate::ExecutionError;
+
+#[test]
+fn reexecute_block_without_transactions() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    let reports = reexecute_blocks(
+        storage_reader,
+        &CHAIN_ID,
+        &get_test_execution_config(),
+        BlockNumber(1),
+        BlockNumber(2),
+    )
+    .unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].block_number, BlockNumber(1));
+    assert!(reports[0].mismatches.is_empty());
+}
+
+#[test]
+fn reexecute_block_reports_state_diff_mismatches() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    prepare_storage(storage_writer);
+
+    // The state diff of the first block isn't induced by its transactions, since it has none.
+    let report =
+        reexecute_block(storage_reader, &CHAIN_ID, &get_test_execution_config(), BlockNumber(0))
+            .unwrap();
+    let mismatches = report.mismatches;
+    assert!(mismatches.contains(&ReexecutionMismatch::ClassHash {
+        contract_address: *CONTRACT_ADDRESS,
+        stored: Some(class_hash!("0x2")),
+        reexecuted: None,
+    }));
+    assert!(mismatches.contains(&ReexecutionMismatch::DeclaredClass {
+        class_hash: class_hash!("0x2"),
+        stored: Some(CompiledClassHash::default()),
+        reexecuted: None,
+    }));
+    assert!(mismatches.contains(&ReexecutionMismatch::DeprecatedDeclaredClass {
+        class_hash: *ACCOUNT_CLASS_HASH,
+        stored: true,
+        reexecuted: false,
+    }));
+    assert!(mismatches
+        .iter()
+        .any(|mismatch| matches!(mismatch, ReexecutionMismatch::Storage { .. })));
+    // The stored nonces are equal to the nonces before the block.
+    assert!(!mismatches
+        .iter()
+        .any(|mismatch| matches!(mismatch, ReexecutionMismatch::Nonce { .. })));
+}
+
+// Appends block 2 with an invoke transaction whose stored output doesn't contain the fee it charges
+// and whose state diff is missing.
+fn append_block_with_invoke(storage_writer: &mut StorageWriter, transaction_hash: TransactionHash) {
+    let tx = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+        calldata: calldata![
+            *DEPRECATED_CONTRACT_ADDRESS.0.key(),
+            selector_from_name("return_result").0,
+            felt!(1_u8),
+            felt!(2_u8)
+        ],
+        max_fee: *MAX_FEE,
+        sender_address: *ACCOUNT_ADDRESS,
+        nonce: nonce!(0_u8),
+        ..Default::default()
+    }));
+    let tx_output = TransactionOutput::Invoke(InvokeTransactionOutput::default());
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(
+            BlockNumber(2),
+            &BlockHeader {
+                block_header_without_hash: BlockHeaderWithoutHash {
+                    block_number: BlockNumber(2),
+                    l1_gas_price: *GAS_PRICE,
+                    sequencer: *SEQUENCER_ADDRESS,
+                    timestamp: *BLOCK_TIMESTAMP,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_body(
+            BlockNumber(2),
+            BlockBody {
+                transactions: vec![tx],
+                transaction_outputs: vec![tx_output],
+                transaction_hashes: vec![transaction_hash],
+            },
+        )
+        .unwrap()
+        .append_state_diff(BlockNumber(2), ThinStateDiff::default())
+        .unwrap()
+        .append_classes(BlockNumber(2), &[], &[])
+        .unwrap()
+        .commit()
+        .unwrap();
+}
+
+#[test]
+fn reexecute_block_reports_receipt_mismatches() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    let mut storage_writer = prepare_storage(storage_writer);
+
+    let transaction_hash = TransactionHash(felt!("0x1234"));
+    append_block_with_invoke(&mut storage_writer, transaction_hash);
+
+    let execution_config = get_test_execution_config();
+    let mismatches =
+        reexecute_block(storage_reader.clone(), &CHAIN_ID, &execution_config, BlockNumber(2))
+            .unwrap()
+            .mismatches;
+    assert_matches!(
+        mismatches.as_slice(),
+        [ReexecutionMismatch::Fee { transaction_hash: hash, stored: Fee(0), reexecuted }, ..]
+        if *hash == transaction_hash && *reexecuted > Fee(0)
+    );
+    // The state diff of the transaction is missing from the stored block.
+    assert!(mismatches.contains(&ReexecutionMismatch::Nonce {
+        contract_address: *ACCOUNT_ADDRESS,
+        stored: None,
+        reexecuted: Some(nonce!(1_u8)),
+    }));
+
+    assert_matches!(
+        reexecute_block(storage_reader, &CHAIN_ID, &execution_config, BlockNumber(3)),
+        Err(ExecutionError::MissingBlock { block_number: BlockNumber(3) })
+    );
+}
+
+#[test]
+fn replay_transaction_traces_vm_steps() {
+    let ((storage_reader, storage_writer), _temp_dir) = get_test_storage();
+    let mut storage_writer = prepare_storage(storage_writer);
+    let transaction_hash = TransactionHash(felt!("0x1234"));
+    append_block_with_invoke(&mut storage_writer, transaction_hash);
+
+    let execution_config = get_test_execution_config();
+    let TransactionTrace::Invoke(InvokeTransactionTrace {
+        validate_invocation: Some(validate_invocation),
+        execute_invocation: FunctionInvocationResult::Ok(execute_invocation),
+        ..
+    }) = replay_transaction(storage_reader.clone(), &CHAIN_ID, &execution_config, transaction_hash)
+        .unwrap()
+    else {
+        panic!("Expected a successful invoke trace with a validation.")
+    };
+    assert!(!validate_invocation.vm_trace.unwrap().steps.is_empty());
+    assert!(!execute_invocation.vm_trace.unwrap().steps.is_empty());
+
+    let missing_transaction_hash = TransactionHash(felt!("0x1235"));
+    assert_matches!(
+        replay_transaction(storage_reader, &CHAIN_ID, &execution_config, missing_transaction_hash),
+        Err(ExecutionError::MissingTransaction { transaction_hash })
+            if transaction_hash == missing_transaction_hash
+    );
+}
@@ -0,0 +1,142 @@
+//! Mapping of the Cairo steps in the VM traces of function invocations to the Sierra statements
+//! they were compiled from, and to the Cairo code the statements were generated from.
+#[cfg(test)]
+#[path = "sierra_statements_test.rs"]
+mod sierra_statements_test;
+
+use std::collections::HashMap;
+
+use cairo_lang_starknet_classes::casm_contract_class::{
+    CasmContractClass,
+    StarknetSierraCompilationError,
+};
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use serde::Deserialize;
+
+// The namespace and the key of the annotation in which the compiler writes the Cairo code
+// locations of the statements to the debug info, when compiling with statements code locations
+// (e.g. `unstable-add-statements-code-locations-debug-info` in Scarb).
+const CODE_LOCATIONS_NAMESPACE: &str = "github.com/software-mansion/cairo-coverage";
+const CODE_LOCATIONS_KEY: &str = "statements_code_locations";
+
+// A span in a Cairo source file, with 0-based lines and columns.
+#[derive(Deserialize)]
+struct CodeSpan {
+    start: CodePosition,
+}
+
+#[derive(Deserialize)]
+struct CodePosition {
+    line: usize,
+    col: usize,
+}
+
+/// The Sierra statements of a class and the offsets of their code in the compiled class.
+///
+/// The stored classes contain no debug info, so the statements are described by the ids of their
+/// functions and libfuncs unless the class is given with its debug info (e.g. as written by the
+/// compiler). The PCs match the statements only if the executed class was compiled by the same
+/// compiler version. Likewise, the Cairo code locations of the statements are known only if the
+/// debug info contains them.
+pub struct SierraStatements {
+    // The offset in the bytecode at which the code of each statement starts.
+    statement_offsets: Vec<usize>,
+    // The statements with the functions they belong to, in a readable form.
+    statement_descriptions: Vec<String>,
+    // The Cairo code locations each statement was generated from, as `PATH:LINE:COLUMN`.
+    statement_code_locations: HashMap<usize, Vec<String>>,
+    bytecode_length: usize,
+}
+
+impl SierraStatements {
+    /// Compiles the given class and records where the code of each of its statements starts.
+    pub fn new(class: ContractClass) -> Result<Self, StarknetSierraCompilationError> {
+        let program = class.extract_sierra_program()?;
+        let statement_code_locations = code_locations(&class);
+        let (casm, debug_info) =
+            CasmContractClass::from_contract_class_with_debug_info(class, false, usize::MAX)?;
+
+        let statement_descriptions = program
+            .statements
+            .iter()
+            .enumerate()
+            .map(|(idx, statement)| {
+                // The function of a statement is the last one that starts before it.
+                let function = program
+                    .funcs
+                    .iter()
+                    .filter(|function| function.entry_point.0 <= idx)
+                    .max_by_key(|function| function.entry_point.0);
+                match function {
+                    Some(function) => format!("{}: {statement}", function.id),
+                    None => statement.to_string(),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            statement_offsets: debug_info
+                .sierra_statement_info
+                .iter()
+                .map(|statement_info| statement_info.start_offset)
+                .collect(),
+            statement_descriptions,
+            statement_code_locations,
+            bytecode_length: casm.bytecode.len(),
+        })
+    }
+
+    /// Returns the index of the statement whose code contains the given PC.
+    pub fn statement_idx(&self, pc: usize) -> Option<usize> {
+        if pc >= self.bytecode_length {
+            return None;
+        }
+        // Statements without code start at the same offset as the statement after them, so the
+        // last statement that starts at or before the PC is the one that contains it.
+        self.statement_offsets.partition_point(|offset| *offset <= pc).checked_sub(1)
+    }
+
+    /// Returns the statement with the given index and the function it belongs to, for example
+    /// `test::foo: felt252_add([0], [1]) -> ([2])`.
+    pub fn describe_statement(&self, idx: usize) -> Option<&str> {
+        self.statement_descriptions.get(idx).map(String::as_str)
+    }
+
+    /// Returns the locations in the Cairo code from which the statement with the given index was
+    /// generated, as `PATH:LINE:COLUMN`. There are several locations if the statement was inlined
+    /// from other functions, and none if the debug info doesn't contain the code locations.
+    pub fn code_locations(&self, idx: usize) -> &[String] {
+        self.statement_code_locations.get(&idx).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+// Reads the Cairo code locations of the statements from the debug info of the class. Malformed
+// locations are ignored like missing ones, since they are only used for debugging.
+fn code_locations(class: &ContractClass) -> HashMap<usize, Vec<String>> {
+    let Some(annotation) = class
+        .sierra_program_debug_info
+        .as_ref()
+        .and_then(|debug_info| debug_info.annotations.get(CODE_LOCATIONS_NAMESPACE))
+        .and_then(|annotation| annotation.get(CODE_LOCATIONS_KEY))
+    else {
+        return HashMap::new();
+    };
+    let Ok(locations) =
+        serde_json::from_value::<HashMap<usize, Vec<(String, CodeSpan)>>>(annotation.clone())
+    else {
+        return HashMap::new();
+    };
+    locations
+        .into_iter()
+        .map(|(idx, locations)| {
+            let locations = locations
+                .into_iter()
+                .map(|(path, span)| {
+                    // Lines and columns are usually displayed 1-based.
+                    format!("{path}:{}:{}", span.start.line + 1, span.start.col + 1)
+                })
+                .collect();
+            (idx, locations)
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+use blockifier_test_utils::cairo_versions::{CairoVersion, RunnableCairo1};
+use blockifier_test_utils::contracts::FeatureContract;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+
+use crate::sierra_statements::SierraStatements;
+
+#[test]
+fn map_pcs_to_statements() {
+    // A class with debug info.
+    let raw_class =
+        FeatureContract::ERC20(CairoVersion::Cairo1(RunnableCairo1::Casm)).get_raw_sierra();
+    let class: ContractClass = serde_json::from_str(&raw_class).unwrap();
+    let casm = CasmContractClass::from_contract_class(class.clone(), false, usize::MAX).unwrap();
+    let sierra_statements = SierraStatements::new(class).unwrap();
+
+    // The code of an entry point starts with the code of its wrapper function.
+    let entry_point = casm.entry_points_by_type.external.first().unwrap();
+    let statement_idx = sierra_statements.statement_idx(entry_point.offset).unwrap();
+    let description = sierra_statements.describe_statement(statement_idx).unwrap();
+    assert!(description.contains("__wrapper__"), "Unexpected statement: {description}");
+
+    // The PCs after the bytecode are not part of any statement.
+    assert_eq!(sierra_statements.statement_idx(casm.bytecode.len()), None);
+}
+
+#[test]
+fn map_statements_to_code_locations() {
+    let raw_class =
+        FeatureContract::ERC20(CairoVersion::Cairo1(RunnableCairo1::Casm)).get_raw_sierra();
+    let mut class: ContractClass = serde_json::from_str(&raw_class).unwrap();
+    // The compiler adds the code locations to the debug info only when asked to.
+    class.sierra_program_debug_info.as_mut().unwrap().annotations.insert(
+        "github.com/software-mansion/cairo-coverage".to_string(),
+        serde_json::json!({
+            "statements_code_locations": {
+                "3": [
+                    [
+                        "src/erc20.cairo",
+                        {"start": {"line": 10, "col": 4}, "end": {"line": 10, "col": 9}}
+                    ],
+                    [
+                        "src/lib.cairo",
+                        {"start": {"line": 0, "col": 0}, "end": {"line": 0, "col": 3}}
+                    ]
+                ]
+            }
+        }),
+    );
+    let sierra_statements = SierraStatements::new(class).unwrap();
+
+    assert_eq!(sierra_statements.code_locations(3), ["src/erc20.cairo:11:5", "src/lib.cairo:1:1"]);
+    assert!(sierra_statements.code_locations(4).is_empty());
+}
@@ -0,0 +1,198 @@
+//! Overrides of the state on top of which transactions and calls are executed, for previewing
+//! their outcome in a state that differs from the stored one.
+#[cfg(test)]
+#[path = "state_overrides_test.rs"]
+mod state_overrides_test;
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use blockifier::context::FeeTokenAddresses;
+use blockifier::execution::contract_class::{
+    CompiledClassV0,
+    CompiledClassV1,
+    RunnableCompiledClass,
+};
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{StateReader as BlockifierStateReader, StateResult};
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use starknet_api::abi::abi_utils::get_fee_token_var_address;
+use starknet_api::contract_class::SierraVersion;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::{ExecutionError, ExecutionResult};
+
+/// Values that replace the stored state during an execution. Everything that isn't overridden is
+/// read from the stored state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateOverrides {
+    /// The overrides of the state of each contract. A storage value may be overridden only once,
+    /// either directly or by a balance override.
+    pub contracts: HashMap<ContractAddress, ContractOverride>,
+    /// Classes to execute as if they were declared. Declared classes with the same hash are
+    /// replaced.
+    pub classes: HashMap<ClassHash, InjectedClass>,
+}
+
+/// Overrides of the state of a single contract.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ContractOverride {
+    /// The class of the contract. Setting it for an address without a contract deploys a contract
+    /// there.
+    pub class_hash: Option<ClassHash>,
+    /// The nonce of the contract.
+    pub nonce: Option<Nonce>,
+    /// Storage values of the contract. Keys that aren't given keep their stored values.
+    pub storage: HashMap<StorageKey, Felt>,
+    /// The STRK balance of the contract. Written to the storage of the STRK fee contract.
+    pub strk_balance: Option<Felt>,
+    /// The ETH balance of the contract. Written to the storage of the ETH fee contract.
+    pub eth_balance: Option<Felt>,
+}
+
+/// A compiled class that is executed as if it was declared.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InjectedClass {
+    /// A Cairo 0 class.
+    Cairo0(DeprecatedContractClass),
+    /// The compiled Sierra class and the version of the Sierra it was compiled from.
+    Cairo1(CasmContractClass, SierraVersion),
+}
+
+impl StateOverrides {
+    // Returns the class hash override of the given contract.
+    pub(crate) fn class_hash_at(&self, contract_address: ContractAddress) -> Option<ClassHash> {
+        self.contracts
+            .get(&contract_address)
+            .and_then(|contract_override| contract_override.class_hash)
+    }
+}
+
+/// A state reader that returns the overridden values of the state and reads everything else from
+/// the wrapped reader.
+pub(crate) struct OverriddenStateReader<S: BlockifierStateReader> {
+    pub(crate) state_reader: S,
+    storage: HashMap<(ContractAddress, StorageKey), Felt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    classes: HashMap<ClassHash, InjectedClass>,
+}
+
+impl<S: BlockifierStateReader> OverriddenStateReader<S> {
+    pub(crate) fn new(
+        state_reader: S,
+        state_overrides: StateOverrides,
+        fee_token_addresses: &FeeTokenAddresses,
+    ) -> ExecutionResult<Self> {
+        let mut storage = HashMap::new();
+        let mut nonces = HashMap::new();
+        let mut class_hashes = HashMap::new();
+        for (contract_address, contract_override) in state_overrides.contracts {
+            for (key, value) in contract_override.storage {
+                insert_storage_override(&mut storage, contract_address, key, value)?;
+            }
+            for (fee_token_address, balance) in [
+                (fee_token_addresses.strk_fee_token_address, contract_override.strk_balance),
+                (fee_token_addresses.eth_fee_token_address, contract_override.eth_balance),
+            ] {
+                let Some(balance) = balance else {
+                    continue;
+                };
+                // The balances are u256 values, whose low and high 128 bits are stored in
+                // consecutive keys.
+                let (high, low) = split_balance(balance);
+                let low_key = get_fee_token_var_address(contract_address);
+                let high_key = low_key.next_storage_key().map_err(StateError::from)?;
+                insert_storage_override(&mut storage, fee_token_address, low_key, low)?;
+                insert_storage_override(&mut storage, fee_token_address, high_key, high)?;
+            }
+            if let Some(nonce) = contract_override.nonce {
+                nonces.insert(contract_address, nonce);
+            }
+            if let Some(class_hash) = contract_override.class_hash {
+                class_hashes.insert(contract_address, class_hash);
+            }
+        }
+        Ok(Self { state_reader, storage, nonces, class_hashes, classes: state_overrides.classes })
+    }
+}
+
+// Adds an override of a storage value. Fails if the value is already overridden, e.g., by both a
+// direct override of the storage of a fee contract and a balance override, since which of them
+// applies would depend on the order of the overrides.
+fn insert_storage_override(
+    storage: &mut HashMap<(ContractAddress, StorageKey), Felt>,
+    contract_address: ContractAddress,
+    key: StorageKey,
+    value: Felt,
+) -> ExecutionResult<()> {
+    match storage.entry((contract_address, key)) {
+        Entry::Occupied(_) => {
+            Err(ExecutionError::ConflictingStateOverrides { contract_address, key })
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(value);
+            Ok(())
+        }
+    }
+}
+
+// Splits a balance to its high and low 128 bits.
+fn split_balance(balance: Felt) -> (Felt, Felt) {
+    let bytes = balance.to_bytes_be();
+    (Felt::from_bytes_be_slice(&bytes[..16]), Felt::from_bytes_be_slice(&bytes[16..]))
+}
+
+impl<S: BlockifierStateReader> BlockifierStateReader for OverriddenStateReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        if let Some(value) = self.storage.get(&(contract_address, key)) {
+            return Ok(*value);
+        }
+        self.state_reader.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.state_reader.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state_reader.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_class(&self, class_hash: ClassHash) -> StateResult<RunnableCompiledClass> {
+        match self.classes.get(&class_hash) {
+            Some(InjectedClass::Cairo0(class)) => Ok(RunnableCompiledClass::V0(
+                CompiledClassV0::try_from(class.clone()).map_err(StateError::ProgramError)?,
+            )),
+            Some(InjectedClass::Cairo1(casm, sierra_version)) => Ok(RunnableCompiledClass::V1(
+                CompiledClassV1::try_from((casm.clone(), sierra_version.clone()))
+                    .map_err(StateError::ProgramError)?,
+            )),
+            None => self.state_reader.get_compiled_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self.classes.get(&class_hash) {
+            // Cairo 0 classes have no compiled class hash.
+            Some(InjectedClass::Cairo0(_)) => Ok(CompiledClassHash::default()),
+            Some(InjectedClass::Cairo1(casm, _)) => {
+                Ok(CompiledClassHash(casm.compiled_class_hash()))
+            }
+            None => self.state_reader.get_compiled_class_hash(class_hash),
+        }
+    }
+}
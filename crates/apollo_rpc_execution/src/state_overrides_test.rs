@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use blockifier::context::FeeTokenAddresses;
+use blockifier::execution::contract_class::RunnableCompiledClass;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::StateReader;
+use blockifier::test_utils::dict_state_reader::DictStateReader;
+use starknet_api::abi::abi_utils::get_fee_token_var_address;
+use starknet_api::contract_class::SierraVersion;
+use starknet_api::core::CompiledClassHash;
+use starknet_api::{class_hash, contract_address, felt, nonce, storage_key};
+use starknet_types_core::felt::Felt;
+
+use crate::state_overrides::{
+    ContractOverride,
+    InjectedClass,
+    OverriddenStateReader,
+    StateOverrides,
+};
+use crate::test_utils::{get_test_casm, get_test_deprecated_contract_class};
+use crate::ExecutionError;
+
+fn fee_token_addresses() -> FeeTokenAddresses {
+    FeeTokenAddresses {
+        strk_fee_token_address: contract_address!("0x1000"),
+        eth_fee_token_address: contract_address!("0x1001"),
+    }
+}
+
+#[test]
+fn overridden_values_replace_stored_ones() {
+    let address = contract_address!("0x1");
+    let other_address = contract_address!("0x2");
+    let key = storage_key!("0x10");
+    let other_key = storage_key!("0x11");
+    let stored_state = DictStateReader {
+        storage_view: HashMap::from([
+            ((address, key), felt!(1_u8)),
+            ((address, other_key), felt!(2_u8)),
+            ((other_address, key), felt!(3_u8)),
+        ]),
+        address_to_nonce: HashMap::from([(address, nonce!(1_u8)), (other_address, nonce!(2_u8))]),
+        address_to_class_hash: HashMap::from([
+            (address, class_hash!("0x100")),
+            (other_address, class_hash!("0x200")),
+        ]),
+        ..Default::default()
+    };
+    let state_overrides = StateOverrides {
+        contracts: HashMap::from([(
+            address,
+            ContractOverride {
+                class_hash: Some(class_hash!("0x101")),
+                nonce: Some(nonce!(7_u8)),
+                storage: HashMap::from([(key, felt!(4_u8))]),
+                ..Default::default()
+            },
+        )]),
+        ..Default::default()
+    };
+    let reader =
+        OverriddenStateReader::new(stored_state, state_overrides, &fee_token_addresses()).unwrap();
+
+    assert_eq!(reader.get_storage_at(address, key).unwrap(), felt!(4_u8));
+    assert_eq!(reader.get_nonce_at(address).unwrap(), nonce!(7_u8));
+    assert_eq!(reader.get_class_hash_at(address).unwrap(), class_hash!("0x101"));
+
+    // Values that aren't overridden are read from the stored state.
+    assert_eq!(reader.get_storage_at(address, other_key).unwrap(), felt!(2_u8));
+    assert_eq!(reader.get_storage_at(other_address, key).unwrap(), felt!(3_u8));
+    assert_eq!(reader.get_nonce_at(other_address).unwrap(), nonce!(2_u8));
+    assert_eq!(reader.get_class_hash_at(other_address).unwrap(), class_hash!("0x200"));
+}
+
+#[test]
+fn balances_are_written_to_the_fee_contracts() {
+    let address = contract_address!("0x1");
+    // 2^128 + 5.
+    let strk_balance = Felt::from(u128::MAX) + felt!(6_u8);
+    let state_overrides = StateOverrides {
+        contracts: HashMap::from([(
+            address,
+            ContractOverride {
+                strk_balance: Some(strk_balance),
+                eth_balance: Some(felt!(9_u8)),
+                ..Default::default()
+            },
+        )]),
+        ..Default::default()
+    };
+    let reader = OverriddenStateReader::new(
+        DictStateReader::default(),
+        state_overrides,
+        &fee_token_addresses(),
+    )
+    .unwrap();
+
+    let fee_token_addresses = fee_token_addresses();
+    assert_eq!(
+        reader.get_fee_token_balance(address, fee_token_addresses.strk_fee_token_address).unwrap(),
+        (felt!(5_u8), felt!(1_u8))
+    );
+    assert_eq!(
+        reader.get_fee_token_balance(address, fee_token_addresses.eth_fee_token_address).unwrap(),
+        (felt!(9_u8), Felt::ZERO)
+    );
+    // The balance is stored only in the fee contracts.
+    assert_eq!(
+        reader.get_storage_at(address, get_fee_token_var_address(address)).unwrap(),
+        Felt::ZERO
+    );
+}
+
+#[test]
+fn conflicting_storage_and_balance_overrides_are_rejected() {
+    let address = contract_address!("0x1");
+    let fee_token_addresses = fee_token_addresses();
+    let state_overrides = StateOverrides {
+        contracts: HashMap::from([
+            (address, ContractOverride { strk_balance: Some(felt!(9_u8)), ..Default::default() }),
+            (
+                fee_token_addresses.strk_fee_token_address,
+                ContractOverride {
+                    storage: HashMap::from([(get_fee_token_var_address(address), felt!(1_u8))]),
+                    ..Default::default()
+                },
+            ),
+        ]),
+        ..Default::default()
+    };
+
+    assert_matches!(
+        OverriddenStateReader::new(DictStateReader::default(), state_overrides, &fee_token_addresses),
+        Err(ExecutionError::ConflictingStateOverrides { contract_address, key })
+            if contract_address == fee_token_addresses.strk_fee_token_address
+                && key == get_fee_token_var_address(address)
+    );
+}
+
+#[test]
+fn injected_classes_are_declared() {
+    let cairo0_class_hash = class_hash!("0x10");
+    let cairo1_class_hash = class_hash!("0x11");
+    let casm = get_test_casm();
+    let state_overrides = StateOverrides {
+        classes: HashMap::from([
+            (cairo0_class_hash, InjectedClass::Cairo0(get_test_deprecated_contract_class())),
+            (cairo1_class_hash, InjectedClass::Cairo1(casm.clone(), SierraVersion::LATEST)),
+        ]),
+        ..Default::default()
+    };
+    let reader = OverriddenStateReader::new(
+        DictStateReader::default(),
+        state_overrides,
+        &fee_token_addresses(),
+    )
+    .unwrap();
+
+    assert_matches!(reader.get_compiled_class(cairo0_class_hash), Ok(RunnableCompiledClass::V0(_)));
+    assert_matches!(reader.get_compiled_class(cairo1_class_hash), Ok(RunnableCompiledClass::V1(_)));
+    assert_eq!(
+        reader.get_compiled_class_hash(cairo1_class_hash).unwrap(),
+        CompiledClassHash(casm.compiled_class_hash())
+    );
+    assert_matches!(
+        reader.get_compiled_class(class_hash!("0x12")),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+}
@@ -48,8 +48,15 @@ use starknet_types_core::felt::Felt;
 
 use crate::execution_utils::selector_from_name;
 use crate::objects::{PendingData, TransactionSimulationOutput};
+use crate::state_overrides::StateOverrides;
 use crate::testing_instances::get_test_execution_config;
-use crate::{simulate_transactions, ExecutableTransactionInput, OnlyQuery, SierraSize};
+use crate::{
+    simulate_transactions,
+    ExecutableTransactionInput,
+    OnlyQuery,
+    SierraSize,
+    TraceOptions,
+};
 
 lazy_static! {
     pub static ref CHAIN_ID: ChainId = ChainId::Other(String::from("TEST_CHAIN_ID"));
@@ -96,7 +103,8 @@ pub fn get_test_account_class() -> DeprecatedContractClass {
     get_test_instance("account_class.json")
 }
 
-pub fn prepare_storage(mut storage_writer: StorageWriter) {
+// Returns the storage writer, for appending more blocks.
+pub fn prepare_storage(mut storage_writer: StorageWriter) -> StorageWriter {
     let class_hash0 = class_hash!("0x2");
     let class_hash1 = class_hash!("0x1");
 
@@ -196,6 +204,7 @@ pub fn prepare_storage(mut storage_writer: StorageWriter) {
         .unwrap()
         .commit()
         .unwrap();
+    storage_writer
 }
 
 pub fn execute_simulate_transactions(
@@ -221,6 +230,8 @@ pub fn execute_simulate_transactions(
         validate,
         // TODO(DanB): Consider testing without overriding DA (It's already tested in the RPC)
         true,
+        TraceOptions::default(),
+        StateOverrides::default(),
     )
     .unwrap()
 }
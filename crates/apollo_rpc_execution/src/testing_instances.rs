@@ -119,6 +119,8 @@ impl GetTestInstance for FunctionInvocation {
             events: Vec::<OrderedEvent>::get_test_instance(rng),
             messages: Vec::<OrderedL2ToL1Message>::get_test_instance(rng),
             execution_resources: ExecutionResources::get_test_instance(rng),
+            state_accesses: None,
+            vm_trace: None,
         }
     }
 }
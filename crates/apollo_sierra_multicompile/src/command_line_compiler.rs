@@ -75,10 +75,20 @@ impl SierraToNativeCompiler for CommandLineCompiler {
         &self,
         contract_class: ContractClass,
     ) -> Result<AotContractExecutor, CompilationUtilError> {
+        let output_file = NamedTempFile::new()?;
+        self.compile_to_native_library(contract_class, output_file.path())?;
+
+        Ok(AotContractExecutor::from_path(output_file.path())?.unwrap())
+    }
+
+    fn compile_to_native_library(
+        &self,
+        contract_class: ContractClass,
+        output_path: &Path,
+    ) -> Result<(), CompilationUtilError> {
         let compiler_binary_path = &self.path_to_starknet_native_compile_binary;
 
-        let output_file = NamedTempFile::new()?;
-        let output_file_path = output_file.path().to_str().ok_or(
+        let output_file_path = output_path.to_str().ok_or(
             CompilationUtilError::UnexpectedError("Failed to get output file path".to_owned()),
         )?;
         let optimization_level = self.config.optimization_level.to_string();
@@ -95,7 +105,7 @@ impl SierraToNativeCompiler for CommandLineCompiler {
             resource_limits,
         )?;
 
-        Ok(AotContractExecutor::from_path(Path::new(&output_file_path))?.unwrap())
+        Ok(())
     }
 
     fn panic_on_compilation_failure(&self) -> bool {
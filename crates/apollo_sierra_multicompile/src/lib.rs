@@ -1,4 +1,7 @@
 //! A lib for compiling Sierra into Casm.
+#[cfg(feature = "cairo_native")]
+use std::path::Path;
+
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_sierra_multicompile_types::{RawClass, RawExecutableClass, RawExecutableHashedClass};
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
@@ -53,6 +56,14 @@ pub trait SierraToNativeCompiler: Send + Sync {
         contract_class: CairoLangContractClass,
     ) -> Result<AotContractExecutor, CompilationUtilError>;
 
+    /// Compiles the contract class to a shared library at the given path, which can be loaded with
+    /// [`AotContractExecutor::from_path`].
+    fn compile_to_native_library(
+        &self,
+        contract_class: CairoLangContractClass,
+        output_path: &Path,
+    ) -> Result<(), CompilationUtilError>;
+
     fn panic_on_compilation_failure(&self) -> bool;
 }
 
@@ -41,14 +41,36 @@ mod base_layer_test;
 
 use starknet_api::block::BlockNumber;
 
-use crate::db::table_types::Table;
+use crate::db::table_types::{DbCursorTrait, Table};
 use crate::db::{TransactionKind, RW};
 use crate::{MarkerKind, StorageResult, StorageTxn};
 
+/// The base layer transaction of a state update, which proved blocks on the base layer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BaseLayerProof {
+    /// The first block that the state update proved. The last block it proved is the key of the
+    /// proof.
+    pub first_proved_block: BlockNumber,
+    /// The number of the base layer block that contains the transaction.
+    pub l1_block_number: u64,
+    /// The hash of the base layer block that contains the transaction.
+    pub l1_block_hash: [u8; 32],
+    /// The hash of the transaction.
+    pub l1_transaction_hash: [u8; 32],
+}
+
 /// Interface for reading data related to the base layer.
 pub trait BaseLayerStorageReader {
     /// The block number marker is the first block number that doesn't exist yet in the base layer.
     fn get_base_layer_block_marker(&self) -> StorageResult<BlockNumber>;
+
+    /// Returns the proof of a block that exists in the base layer, i.e., the stored state update
+    /// whose proved blocks contain it. Returns `None` if the block doesn't exist in the base layer
+    /// or if the state update that proved it isn't stored.
+    fn get_base_layer_proof(
+        &self,
+        block_number: BlockNumber,
+    ) -> StorageResult<Option<BaseLayerProof>>;
 }
 
 /// Interface for writing data related to the base layer.
@@ -60,8 +82,16 @@ where
     // To enforce that no commit happen after a failure, we consume and return Self on success.
     fn update_base_layer_block_marker(self, block_number: &BlockNumber) -> StorageResult<Self>;
 
+    /// Stores the proof of the state update that proved the blocks from
+    /// [`BaseLayerProof::first_proved_block`] up to and including the given block.
+    fn update_base_layer_proof(
+        self,
+        proved_block_number: BlockNumber,
+        proof: &BaseLayerProof,
+    ) -> StorageResult<Self>;
+
     /// When reverting a block, if the base layer marker points to the block afterward, revert the
-    /// marker.
+    /// marker. The proof of a state update that proved up to the block is deleted as well.
     fn try_revert_base_layer_marker(
         self,
         reverted_block_number: BlockNumber,
@@ -73,6 +103,21 @@ impl<Mode: TransactionKind> BaseLayerStorageReader for StorageTxn<'_, Mode> {
         let markers_table = self.open_table(&self.tables.markers)?;
         Ok(markers_table.get(&self.txn, &MarkerKind::BaseLayerBlock)?.unwrap_or_default())
     }
+
+    fn get_base_layer_proof(
+        &self,
+        block_number: BlockNumber,
+    ) -> StorageResult<Option<BaseLayerProof>> {
+        if block_number >= self.get_base_layer_block_marker()? {
+            return Ok(None);
+        }
+        let base_layer_proofs_table = self.open_table(&self.tables.base_layer_proofs)?;
+        let mut cursor = base_layer_proofs_table.cursor(&self.txn)?;
+        Ok(cursor
+            .lower_bound(&block_number)?
+            .map(|(_, proof)| proof)
+            .filter(|proof| proof.first_proved_block <= block_number))
+    }
 }
 
 impl BaseLayerStorageWriter for StorageTxn<'_, RW> {
@@ -82,10 +127,22 @@ impl BaseLayerStorageWriter for StorageTxn<'_, RW> {
         Ok(self)
     }
 
+    fn update_base_layer_proof(
+        self,
+        proved_block_number: BlockNumber,
+        proof: &BaseLayerProof,
+    ) -> StorageResult<Self> {
+        let base_layer_proofs_table = self.open_table(&self.tables.base_layer_proofs)?;
+        base_layer_proofs_table.upsert(&self.txn, &proved_block_number, proof)?;
+        Ok(self)
+    }
+
     fn try_revert_base_layer_marker(
         self,
         reverted_block_number: BlockNumber,
     ) -> StorageResult<Self> {
+        let base_layer_proofs_table = self.open_table(&self.tables.base_layer_proofs)?;
+        base_layer_proofs_table.delete(&self.txn, &reverted_block_number)?;
         let cur_marker = self.get_base_layer_block_marker()?;
         // Revert only if we revert a block that is the last block in the base layer we know about.
         if cur_marker == reverted_block_number.unchecked_next() {
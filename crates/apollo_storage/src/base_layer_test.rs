@@ -1,6 +1,6 @@
 use starknet_api::block::BlockNumber;
 
-use crate::base_layer::{BaseLayerStorageReader, BaseLayerStorageWriter};
+use crate::base_layer::{BaseLayerProof, BaseLayerStorageReader, BaseLayerStorageWriter};
 use crate::test_utils::get_test_storage;
 
 #[tokio::test]
@@ -50,3 +50,52 @@ fn try_revert_base_layer_marker() {
     let cur_marker = reader.begin_ro_txn().unwrap().get_base_layer_block_marker().unwrap();
     assert_eq!(cur_marker, BlockNumber(1));
 }
+
+fn proof(first_proved_block: u64, l1_block_number: u64) -> BaseLayerProof {
+    BaseLayerProof {
+        first_proved_block: BlockNumber(first_proved_block),
+        l1_block_number,
+        l1_transaction_hash: [1; 32],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rw_base_layer_proofs() {
+    let (reader, mut writer) = get_test_storage().0;
+
+    // Block 0 was proved by a state update that isn't stored, the next state update proved blocks
+    // 1-2 and the one after it proved blocks 3-4.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .update_base_layer_proof(BlockNumber(2), &proof(1, 10))
+        .unwrap()
+        .update_base_layer_proof(BlockNumber(4), &proof(3, 20))
+        .unwrap()
+        .update_base_layer_block_marker(&BlockNumber(5))
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(0)).unwrap(), None);
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(1)).unwrap(), Some(proof(1, 10)));
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(2)).unwrap(), Some(proof(1, 10)));
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(3)).unwrap(), Some(proof(3, 20)));
+    // Not in the base layer.
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(5)).unwrap(), None);
+    drop(txn);
+
+    // Reverting the last proved block deletes its proof.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .try_revert_base_layer_marker(BlockNumber(4))
+        .unwrap()
+        .commit()
+        .unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(3)).unwrap(), None);
+    assert_eq!(txn.get_base_layer_proof(BlockNumber(2)).unwrap(), Some(proof(1, 10)));
+}
@@ -0,0 +1,75 @@
+use apollo_storage::db::DbConfig;
+use apollo_storage::integrity::{check_integrity, roll_back_storage};
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, ArgAction, Command};
+use starknet_api::core::ChainId;
+
+pub fn main() {
+    let matches = Command::new("Storage integrity")
+        .about(
+            "Checks the consistency of the storage. With --repair, rolls back the storage to the \
+             first inconsistent block",
+        )
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path prefix of the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .arg(
+            Arg::new("state_only")
+                .long("state_only")
+                .action(ArgAction::SetTrue)
+                .help("Whether the storage was created with the state-only scope"),
+        )
+        .arg(
+            Arg::new("repair")
+                .long("repair")
+                .action(ArgAction::SetTrue)
+                .help("Delete the data from the first inconsistent block onwards"),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id: ChainId =
+        matches.get_one::<String>("chain_id").expect("Missing chain_id").to_string().into();
+    let scope = if matches.get_flag("state_only") {
+        StorageScope::StateOnly
+    } else {
+        StorageScope::FullArchive
+    };
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id,
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config = StorageConfig { db_config, scope, ..Default::default() };
+    let (reader, mut writer) =
+        apollo_storage::open_storage(config).expect("Should be able to open storage");
+
+    let mut report = check_integrity(&reader).expect("Should be able to check the storage");
+    if matches.get_flag("repair") {
+        if let Some(block_number) = report.first_inconsistent_block() {
+            println!("Rolling back the storage to block {block_number}");
+            roll_back_storage(&mut writer, block_number)
+                .expect("Should be able to roll back the storage");
+            report = check_integrity(&reader).expect("Should be able to check the storage");
+        }
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("Should be able to serialize the report")
+    );
+    if !report.is_consistent() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,76 @@
+use apollo_storage::db::DbConfig;
+use apollo_storage::migration::{
+    apply_migrations,
+    list_migrations,
+    open_storage_for_migration,
+    MIGRATIONS,
+};
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, ArgAction, Command};
+use starknet_api::core::ChainId;
+
+pub fn main() {
+    let matches = Command::new("Storage migration")
+        .about("Inspects and applies the pending migrations of the storage")
+        .arg(
+            Arg::new("db_path")
+                .short('d')
+                .long("db_path")
+                .required(true)
+                .help("The path prefix of the database"),
+        )
+        .arg(
+            Arg::new("chain_id")
+                .short('c')
+                .long("chain_id")
+                .required(true)
+                .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+        )
+        .arg(
+            Arg::new("state_only")
+                .long("state_only")
+                .action(ArgAction::SetTrue)
+                .help("Whether the storage was created with the state-only scope"),
+        )
+        .subcommand_required(true)
+        .subcommand(Command::new("list").about("Lists the migrations and their status"))
+        .subcommand(
+            Command::new("apply").about("Applies the pending migrations").arg(
+                Arg::new("dry_run")
+                    .long("dry_run")
+                    .action(ArgAction::SetTrue)
+                    .help("Run the migrations without committing their changes"),
+            ),
+        )
+        .get_matches();
+
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id: ChainId =
+        matches.get_one::<String>("chain_id").expect("Missing chain_id").to_string().into();
+    let scope = if matches.get_flag("state_only") {
+        StorageScope::StateOnly
+    } else {
+        StorageScope::FullArchive
+    };
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id,
+        enforce_file_exists: true,
+        ..Default::default()
+    };
+    let config = StorageConfig { db_config, scope, ..Default::default() };
+    let (reader, mut writer) =
+        open_storage_for_migration(config).expect("Should be able to open storage");
+
+    let output = match matches.subcommand() {
+        Some(("list", _)) => serde_json::to_string_pretty(
+            &list_migrations(&reader, MIGRATIONS).expect("Should be able to list the migrations"),
+        ),
+        Some(("apply", apply_matches)) => serde_json::to_string_pretty(
+            &apply_migrations(&reader, &mut writer, MIGRATIONS, apply_matches.get_flag("dry_run"))
+                .expect("Should be able to apply the migrations"),
+        ),
+        _ => unreachable!("A subcommand is required"),
+    };
+    println!("{}", output.expect("Should be able to serialize the output"));
+}
@@ -0,0 +1,114 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+use apollo_storage::db::DbConfig;
+use apollo_storage::snapshot::{export_snapshot, import_snapshot, SnapshotSummary};
+use apollo_storage::{StorageConfig, StorageScope};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use starknet_api::core::ChainId;
+
+pub fn main() {
+    let matches = Command::new("Storage snapshot")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("export")
+                .about(
+                    "Writes a consistent snapshot of the storage into an archive file. The node \
+                     must be stopped, since the storage is opened exclusively",
+                )
+                .args(common_args())
+                .arg(
+                    Arg::new("output_file_path")
+                        .short('o')
+                        .long("output_file_path")
+                        .required(true)
+                        .help("The path of the snapshot archive to create"),
+                )
+                .arg(
+                    Arg::new("state_only")
+                        .long("state_only")
+                        .action(ArgAction::SetTrue)
+                        .help("Whether the storage was created with the state-only scope"),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Restores a snapshot archive into a new storage directory")
+                .args(common_args())
+                .arg(
+                    Arg::new("input_file_path")
+                        .short('i')
+                        .long("input_file_path")
+                        .required(true)
+                        .help("The path of the snapshot archive to restore"),
+                ),
+        )
+        .get_matches();
+
+    let summary = match matches.subcommand() {
+        Some(("export", sub_matches)) => export(sub_matches),
+        Some(("import", sub_matches)) => import(sub_matches),
+        _ => unreachable!("A subcommand is required"),
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&summary).expect("Should be able to serialize the summary")
+    );
+}
+
+fn export(matches: &ArgMatches) -> SnapshotSummary {
+    let mut config = storage_config(matches, true);
+    if matches.get_flag("state_only") {
+        config.scope = StorageScope::StateOnly;
+    }
+    let chain_id = config.db_config.chain_id.clone();
+    // Opening the storage exclusively fails if a node has it open, so the export doesn't write to
+    // the storage of a running node.
+    let (reader, _writer) = apollo_storage::open_storage_exclusively(config)
+        .expect("Should be able to open storage exclusively. Is the node still running?");
+    let output_file_path =
+        matches.get_one::<String>("output_file_path").expect("Missing output_file_path");
+    let output = BufWriter::new(
+        File::create(output_file_path).expect("Should be able to create the output file"),
+    );
+    println!("Exporting snapshot to {output_file_path}");
+    export_snapshot(&reader, chain_id, output).expect("Should be able to export the snapshot")
+}
+
+fn import(matches: &ArgMatches) -> SnapshotSummary {
+    let config = storage_config(matches, false);
+    let input_file_path =
+        matches.get_one::<String>("input_file_path").expect("Missing input_file_path");
+    let input =
+        BufReader::new(File::open(input_file_path).expect("Should be able to open the input file"));
+    println!("Importing snapshot from {input_file_path}");
+    import_snapshot(input, config).expect("Should be able to import the snapshot")
+}
+
+fn common_args() -> [Arg; 2] {
+    [
+        Arg::new("db_path")
+            .short('d')
+            .long("db_path")
+            .required(true)
+            .help("The path prefix of the database"),
+        Arg::new("chain_id")
+            .short('c')
+            .long("chain_id")
+            .required(true)
+            .help("The chain id SN_MAIN/SN_SEPOLIA for example"),
+    ]
+}
+
+fn storage_config(matches: &ArgMatches, enforce_file_exists: bool) -> StorageConfig {
+    let db_path = matches.get_one::<String>("db_path").expect("Missing db_path").to_string();
+    let chain_id: ChainId =
+        matches.get_one::<String>("chain_id").expect("Missing chain_id").to_string().into();
+    let db_config = DbConfig {
+        path_prefix: db_path.into(),
+        chain_id,
+        enforce_file_exists,
+        ..Default::default()
+    };
+    StorageConfig { db_config, ..Default::default() }
+}
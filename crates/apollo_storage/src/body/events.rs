@@ -57,6 +57,7 @@ use starknet_api::transaction::{
     Event,
     EventContent,
     EventIndexInTransactionOutput,
+    EventKey,
     TransactionOutput,
 };
 
@@ -91,6 +92,27 @@ pub trait EventsReader<'txn, 'env> {
         event_index: EventIndex,
         to_block_number: BlockNumber,
     ) -> StorageResult<EventIter<'txn, 'env>>;
+
+    /// Returns an iterator over the events of the transactions that emitted an event whose first
+    /// key is one of the given keys, by the order of the event index. All the events of these
+    /// transactions are returned, so the caller should filter them.
+    ///
+    /// The iterator uses the events index (see [`events_index`](super::events_index)), so it
+    /// returns only events of blocks below the event index marker.
+    ///
+    /// # Arguments
+    /// * first_keys - the first keys of the events to search for.
+    /// * event_index - event index to start iterate from it.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_first_keys(
+        &'env self,
+        first_keys: Vec<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIter<'txn, 'env>>;
 }
 
 // TODO(DanB): support all read transactions (including RW).
@@ -109,20 +131,35 @@ impl<'txn, 'env> EventsReader<'txn, 'env> for StorageTxn<'env, RO> {
 
         Ok(EventIter::ByEventIndex(self.iter_events_by_event_index(event_index, to_block_number)?))
     }
+
+    fn iter_events_by_first_keys(
+        &'env self,
+        first_keys: Vec<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIter<'txn, 'env>> {
+        Ok(EventIter::ByFirstKeys(self.iter_events_by_event_keys_index(
+            first_keys,
+            event_index,
+            to_block_number,
+        )?))
+    }
 }
 
 // TODO(dvir): add transaction hash to the return value. In the RPC when returning events this is
 // with the transaction hash. We can do it efficiently here because we anyway read the relevant
 // entry in the transaction_metadata table..
 #[allow(missing_docs)]
-/// A wrapper of two iterators [`EventIterByContractAddress`] and [`EventIterByEventIndex`].
+/// A wrapper of the iterators [`EventIterByContractAddress`], [`EventIterByEventIndex`] and
+/// [`EventIterByFirstKeys`].
 pub enum EventIter<'txn, 'env> {
     ByContractAddress(EventIterByContractAddress<'env, 'txn>),
     ByEventIndex(EventIterByEventIndex<'txn>),
+    ByFirstKeys(EventIterByFirstKeys<'env, 'txn>),
 }
 
-/// This iterator is a wrapper of two iterators [`EventIterByContractAddress`]
-/// and [`EventIterByEventIndex`].
+/// This iterator is a wrapper of the iterators [`EventIterByContractAddress`],
+/// [`EventIterByEventIndex`] and [`EventIterByFirstKeys`].
 /// With this wrapper we can execute the same code, regardless the
 /// type of iteration used.
 impl Iterator for EventIter<'_, '_> {
@@ -132,6 +169,7 @@ impl Iterator for EventIter<'_, '_> {
         match self {
             EventIter::ByContractAddress(it) => it.next(),
             EventIter::ByEventIndex(it) => it.next(),
+            EventIter::ByFirstKeys(it) => it.next(),
         }
         .unwrap_or(None)
     }
@@ -249,6 +287,90 @@ impl EventIterByEventIndex<'_> {
     }
 }
 
+/// This iterator goes over the events of the transactions that emitted an event with one of the
+/// given first keys, by the order of the event index. The transactions are found by merging the
+/// entries of the keys in the event keys index.
+pub struct EventIterByFirstKeys<'env, 'txn> {
+    txn: &'txn DbTransaction<'env, RO>,
+    file_handlers: &'txn FileHandlers<RO>,
+    transaction_metadata_table: TransactionMetadataTable<'env>,
+    // A cursor of the event keys table for each of the keys.
+    key_cursors: Vec<EventKeyCursor<'txn>>,
+    tx_current: Option<(TransactionIndex, TransactionOutput)>,
+    event_index_in_tx_current: EventIndexInTransactionOutput,
+    to_block_number: BlockNumber,
+}
+
+// A cursor over the transactions that emitted an event with the given first key.
+struct EventKeyCursor<'txn> {
+    key: EventKey,
+    cursor: EventKeysTableCursor<'txn>,
+    // The transaction the cursor points to, None if there are no more relevant transactions.
+    current: Option<TransactionIndex>,
+}
+
+impl EventKeyCursor<'_> {
+    fn set_current(
+        &mut self,
+        entry: Option<((EventKey, TransactionIndex), NoValue)>,
+        to_block_number: BlockNumber,
+    ) {
+        self.current = entry.and_then(|((key, tx_index), _)| {
+            (key == self.key && tx_index.0 <= to_block_number).then_some(tx_index)
+        });
+    }
+}
+
+impl EventIterByFirstKeys<'_, '_> {
+    /// Returns the next event. If there are no more events, returns None.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn next(&mut self) -> StorageResult<Option<((ContractAddress, EventIndex), EventContent)>> {
+        loop {
+            let Some((tx_index, tx_output)) = &self.tx_current else { return Ok(None) };
+            if let Some(Event { from_address, content }) =
+                tx_output.events().get(self.event_index_in_tx_current.0)
+            {
+                let key = (*from_address, EventIndex(*tx_index, self.event_index_in_tx_current));
+                // TODO(dvir): don't clone here the event content.
+                let content = content.clone();
+                self.event_index_in_tx_current.0 += 1;
+                return Ok(Some((key, content)));
+            }
+            self.load_next_transaction()?;
+        }
+    }
+
+    /// Sets the current transaction to the first transaction that one of the cursors points to,
+    /// and advances these cursors.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn load_next_transaction(&mut self) -> StorageResult<()> {
+        let Some(tx_index) = self.key_cursors.iter().filter_map(|cursor| cursor.current).min()
+        else {
+            self.tx_current = None;
+            return Ok(());
+        };
+        for key_cursor in &mut self.key_cursors {
+            if key_cursor.current == Some(tx_index) {
+                let entry = key_cursor.cursor.next()?;
+                key_cursor.set_current(entry, self.to_block_number);
+            }
+        }
+        let tx_metadata =
+            self.transaction_metadata_table.get(self.txn, &tx_index)?.unwrap_or_else(|| {
+                panic!("Transaction metadata not found for transaction index: {tx_index:?}")
+            });
+        let tx_output =
+            self.file_handlers.get_transaction_output_unchecked(tx_metadata.tx_output_location)?;
+        self.tx_current = Some((tx_index, tx_output));
+        self.event_index_in_tx_current = EventIndexInTransactionOutput(0);
+        Ok(())
+    }
+}
+
 impl<'txn, 'env> StorageTxn<'env, RO>
 where
     'env: 'txn,
@@ -339,6 +461,51 @@ where
         it.find_next_event_by_event_index()?;
         Ok(it)
     }
+
+    /// Returns an events iterator that iterates the events of the transactions that emitted an
+    /// event with one of the given first keys, from the given event index.
+    ///
+    /// # Arguments
+    /// * first_keys - the first keys of the events to search for.
+    /// * event_index - event index to start from the first event with an index greater or equals
+    ///   to.
+    /// * to_block_number - block number to stop iterate at it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error.
+    fn iter_events_by_event_keys_index(
+        &'env self,
+        first_keys: Vec<EventKey>,
+        event_index: EventIndex,
+        to_block_number: BlockNumber,
+    ) -> StorageResult<EventIterByFirstKeys<'env, 'txn>> {
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        let mut key_cursors = Vec::with_capacity(first_keys.len());
+        for key in first_keys {
+            let mut cursor = event_keys_table.cursor(&self.txn)?;
+            let entry = cursor.lower_bound(&(key.clone(), event_index.0))?;
+            let mut key_cursor = EventKeyCursor { key, cursor, current: None };
+            key_cursor.set_current(entry, to_block_number);
+            key_cursors.push(key_cursor);
+        }
+
+        let mut it = EventIterByFirstKeys {
+            txn: &self.txn,
+            file_handlers: &self.file_handlers,
+            transaction_metadata_table,
+            key_cursors,
+            tx_current: None,
+            event_index_in_tx_current: EventIndexInTransactionOutput(0),
+            to_block_number,
+        };
+        it.load_next_transaction()?;
+        // The first event index may point to the middle of the first transaction.
+        if it.tx_current.as_ref().is_some_and(|(tx_index, _)| *tx_index == event_index.0) {
+            it.event_index_in_tx_current = event_index.1;
+        }
+        Ok(it)
+    }
 }
 
 fn get_events_from_tx(
@@ -360,6 +527,9 @@ fn get_events_from_tx(
 /// A cursor of the events table.
 type EventsTableCursor<'txn> =
     DbCursor<'txn, RO, EventsTableKey, NoVersionValueWrapper<NoValue>, CommonPrefix>;
+/// A cursor of the event keys table.
+type EventKeysTableCursor<'txn> =
+    DbCursor<'txn, RO, (EventKey, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>;
 /// A cursor of the transaction outputs table.
 type TransactionMetadataTableCursor<'txn> =
     DbCursor<'txn, RO, TransactionIndex, VersionZeroWrapper<TransactionMetadata>, SimpleTable>;
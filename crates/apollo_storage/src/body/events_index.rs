@@ -0,0 +1,208 @@
+//! Secondary indexes of the events.
+//!
+//! The events table is keyed by the emitting contract address, so finding events by their keys
+//! requires scanning the transactions. When [`StorageConfig::index_events`] is set, appending a
+//! block body also maintains:
+//! - The `event_keys` table, which maps the first key of an event to the transactions that emitted
+//!   an event with this key. See
+//!   [`iter_events_by_first_keys`](super::events::EventsReader::iter_events_by_first_keys).
+//! - The `events_bloom` table, which holds an [`EventsBloom`] of the addresses and keys of the
+//!   events of each block.
+//!
+//! The blocks are indexed by their order. The event index marker is the first block that is not
+//! indexed. When the indexing is enabled on a storage that already has bodies, every appended body
+//! also indexes a few of the bodies that were stored before, until the indexes catch up.
+//!
+//! [`StorageConfig::index_events`]: crate::StorageConfig::index_events
+
+#[cfg(test)]
+#[path = "events_index_test.rs"]
+mod events_index_test;
+
+use std::collections::{BTreeSet, HashSet};
+
+use sha2::{Digest, Sha256};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ContractAddress;
+use starknet_api::transaction::{Event, EventKey, TransactionOffsetInBlock, TransactionOutput};
+use starknet_types_core::felt::Felt;
+use tracing::debug;
+
+use crate::body::{BodyStorageReader, TransactionIndex};
+use crate::db::serialization::{StorageSerde, StorageSerdeError};
+use crate::db::table_types::{NoValue, Table};
+use crate::db::{TransactionKind, RW};
+use crate::{MarkerKind, StorageError, StorageResult, StorageTxn};
+
+/// The size of an [`EventsBloom`] in bytes.
+pub const EVENTS_BLOOM_SIZE: usize = 256;
+const EVENTS_BLOOM_BITS: usize = EVENTS_BLOOM_SIZE * 8;
+// The number of bits that are set for every inserted element.
+const EVENTS_BLOOM_HASHES: usize = 3;
+// The maximal number of blocks that are indexed when a block body is appended.
+const MAX_INDEXED_BLOCKS_PER_APPEND: usize = 10;
+
+/// A bloom filter of the contract addresses and the keys of the events of a block.
+/// A negative answer is certain; a positive answer may be wrong.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventsBloom([u8; EVENTS_BLOOM_SIZE]);
+
+impl Default for EventsBloom {
+    fn default() -> Self {
+        Self([0; EVENTS_BLOOM_SIZE])
+    }
+}
+
+impl EventsBloom {
+    /// Inserts the address and the keys of the event to the filter.
+    pub fn insert_event(&mut self, event: &Event) {
+        self.insert(&Felt::from(event.from_address));
+        for key in &event.content.keys {
+            self.insert(&key.0);
+        }
+    }
+
+    /// Returns false if no event of the block was emitted by the address.
+    pub fn may_contain_address(&self, address: &ContractAddress) -> bool {
+        self.may_contain(&Felt::from(*address))
+    }
+
+    /// Returns false if no event of the block has the key, in any position.
+    pub fn may_contain_key(&self, key: &EventKey) -> bool {
+        self.may_contain(&key.0)
+    }
+
+    /// Returns false if no event of the block can match the filter. The filter is of the form of
+    /// the starknet_getEvents filter: for every position, the set of the allowed keys, where an
+    /// empty set allows any key.
+    pub fn may_match(&self, address: Option<&ContractAddress>, keys: &[HashSet<EventKey>]) -> bool {
+        address.is_none_or(|address| self.may_contain_address(address))
+            && keys.iter().all(|allowed_keys| {
+                allowed_keys.is_empty() || allowed_keys.iter().any(|key| self.may_contain_key(key))
+            })
+    }
+
+    fn insert(&mut self, felt: &Felt) {
+        for bit in bloom_bits(felt) {
+            self.0[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    fn may_contain(&self, felt: &Felt) -> bool {
+        bloom_bits(felt).into_iter().all(|bit| self.0[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
+
+// The bits are taken from a SHA-256 digest, since they are persisted and must be stable.
+fn bloom_bits(felt: &Felt) -> [usize; EVENTS_BLOOM_HASHES] {
+    let digest = Sha256::digest(felt.to_bytes_be());
+    std::array::from_fn(|i| {
+        usize::from(u16::from_be_bytes([digest[2 * i], digest[2 * i + 1]])) % EVENTS_BLOOM_BITS
+    })
+}
+
+impl StorageSerde for EventsBloom {
+    fn serialize_into(&self, res: &mut impl std::io::Write) -> Result<(), StorageSerdeError> {
+        res.write_all(&self.0)?;
+        Ok(())
+    }
+
+    fn deserialize_from(bytes: &mut impl std::io::Read) -> Option<Self> {
+        let mut bloom = [0; EVENTS_BLOOM_SIZE];
+        bytes.read_exact(&mut bloom).ok()?;
+        Some(Self(bloom))
+    }
+}
+
+/// Interface for reading the events indexes.
+pub trait EventsIndexReader {
+    /// The event index marker is the first block number whose events are not indexed.
+    fn get_event_index_marker(&self) -> StorageResult<BlockNumber>;
+
+    /// Returns the bloom filter of the events of the given block, or None if the block is not
+    /// indexed.
+    fn get_events_bloom(&self, block_number: BlockNumber) -> StorageResult<Option<EventsBloom>>;
+}
+
+impl<Mode: TransactionKind> EventsIndexReader for StorageTxn<'_, Mode> {
+    fn get_event_index_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::EventIndex)?.unwrap_or_default())
+    }
+
+    fn get_events_bloom(&self, block_number: BlockNumber) -> StorageResult<Option<EventsBloom>> {
+        let events_bloom_table = self.open_table(&self.tables.events_bloom)?;
+        Ok(events_bloom_table.get(&self.txn, &block_number)?)
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    // Indexes the stored bodies from the event index marker up to the given block, at most
+    // MAX_INDEXED_BLOCKS_PER_APPEND of them.
+    pub(crate) fn update_events_index(&self, last_block_number: BlockNumber) -> StorageResult<()> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        let events_bloom_table = self.open_table(&self.tables.events_bloom)?;
+
+        let mut marker = self.get_event_index_marker()?;
+        let mut indexed_blocks = 0;
+        while marker <= last_block_number && indexed_blocks < MAX_INDEXED_BLOCKS_PER_APPEND {
+            let transaction_outputs = self.get_block_transaction_outputs(marker)?.ok_or(
+                StorageError::DBInconsistency {
+                    msg: format!("Missing transaction outputs for block {marker}."),
+                },
+            )?;
+            let mut bloom = EventsBloom::default();
+            for (offset, tx_output) in transaction_outputs.iter().enumerate() {
+                let tx_index = TransactionIndex(marker, TransactionOffsetInBlock(offset));
+                for event in tx_output.events() {
+                    bloom.insert_event(event);
+                }
+                for key in first_keys(tx_output) {
+                    event_keys_table.append_greater_sub_key(
+                        &self.txn,
+                        &(key.clone(), tx_index),
+                        &NoValue,
+                    )?;
+                }
+            }
+            events_bloom_table.append(&self.txn, &marker, &bloom)?;
+            marker = marker.unchecked_next();
+            indexed_blocks += 1;
+        }
+        if marker <= last_block_number {
+            debug!("Events are indexed up to block {marker}, behind block {last_block_number}.");
+        }
+        markers_table.upsert(&self.txn, &MarkerKind::EventIndex, &marker)?;
+        Ok(())
+    }
+
+    // Removes the given block from the events indexes if it is the last indexed block.
+    pub(crate) fn revert_events_index(
+        &self,
+        block_number: BlockNumber,
+        transaction_outputs: &[TransactionOutput],
+    ) -> StorageResult<()> {
+        if self.get_event_index_marker()? != block_number.unchecked_next() {
+            return Ok(());
+        }
+        let markers_table = self.open_table(&self.tables.markers)?;
+        let event_keys_table = self.open_table(&self.tables.event_keys)?;
+        let events_bloom_table = self.open_table(&self.tables.events_bloom)?;
+
+        for (offset, tx_output) in transaction_outputs.iter().enumerate() {
+            let tx_index = TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+            for key in first_keys(tx_output) {
+                event_keys_table.delete(&self.txn, &(key.clone(), tx_index))?;
+            }
+        }
+        events_bloom_table.delete(&self.txn, &block_number)?;
+        markers_table.upsert(&self.txn, &MarkerKind::EventIndex, &block_number)?;
+        Ok(())
+    }
+}
+
+// Returns the distinct first keys of the events of the transaction, sorted.
+fn first_keys(tx_output: &TransactionOutput) -> BTreeSet<&EventKey> {
+    tx_output.events().iter().filter_map(|event| event.content.keys.first()).collect()
+}
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use apollo_test_utils::get_test_block;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{Block, BlockHash, BlockNumber};
+use starknet_api::core::ContractAddress;
+use starknet_api::felt;
+use starknet_api::transaction::{
+    Event,
+    EventContent,
+    EventIndexInTransactionOutput,
+    EventKey,
+    TransactionOffsetInBlock,
+};
+
+use crate::body::events::{EventIndex, EventsReader};
+use crate::body::events_index::{EventsBloom, EventsIndexReader};
+use crate::body::{BodyStorageWriter, TransactionIndex};
+use crate::header::HeaderStorageWriter;
+use crate::test_utils::TestStorageBuilder;
+use crate::{open_storage, StorageWriter};
+
+const KEY_1: u8 = 0x11;
+const KEY_2: u8 = 0x22;
+
+fn key(value: u8) -> EventKey {
+    EventKey(felt!(value))
+}
+
+// Returns blocks whose events have a single key, chosen out of KEY_1 and KEY_2.
+fn test_blocks(n_blocks: u64) -> Vec<Block> {
+    (0..n_blocks)
+        .map(|block_number| {
+            let mut block =
+                get_test_block(3, Some(2), None, Some(vec![vec![key(KEY_1), key(KEY_2)]]));
+            block.header.block_hash = BlockHash(felt!(block_number + 1));
+            block.header.block_header_without_hash.block_number = BlockNumber(block_number);
+            block
+        })
+        .collect()
+}
+
+fn append_blocks(writer: &mut StorageWriter, blocks: &[Block]) {
+    for block in blocks {
+        let block_number = block.header.block_header_without_hash.block_number;
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(block_number, &block.header)
+            .unwrap()
+            .append_body(block_number, block.body.clone())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+// Returns the events of the blocks whose first key is one of the given keys, in index order.
+fn expected_events(
+    blocks: &[Block],
+    first_keys: &[EventKey],
+) -> Vec<((ContractAddress, EventIndex), EventContent)> {
+    let mut events = vec![];
+    for block in blocks {
+        let block_number = block.header.block_header_without_hash.block_number;
+        for (tx_i, tx_output) in block.body.transaction_outputs.iter().enumerate() {
+            for (event_i, event) in tx_output.events().iter().enumerate() {
+                if !event.content.keys.first().is_some_and(|key| first_keys.contains(key)) {
+                    continue;
+                }
+                let event_index = EventIndex(
+                    TransactionIndex(block_number, TransactionOffsetInBlock(tx_i)),
+                    EventIndexInTransactionOutput(event_i),
+                );
+                events.push(((event.from_address, event_index), event.content.clone()));
+            }
+        }
+    }
+    events
+}
+
+fn start_index(block_number: u64) -> EventIndex {
+    EventIndex(
+        TransactionIndex(BlockNumber(block_number), TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    )
+}
+
+#[test]
+fn events_bloom() {
+    let address = ContractAddress::from(7_u8);
+    let mut bloom = EventsBloom::default();
+    bloom.insert_event(&Event {
+        from_address: address,
+        content: EventContent { keys: vec![key(KEY_1), key(KEY_2)], ..Default::default() },
+    });
+
+    assert!(bloom.may_contain_address(&address));
+    assert!(bloom.may_contain_key(&key(KEY_1)));
+    assert!(bloom.may_contain_key(&key(KEY_2)));
+    assert!(!bloom.may_contain_key(&key(0x33)));
+
+    let keys = [HashSet::from([key(0x33), key(KEY_2)]), HashSet::new()];
+    assert!(bloom.may_match(Some(&address), &keys));
+    assert!(!bloom.may_match(Some(&ContractAddress::from(8_u8)), &keys));
+    assert!(!bloom.may_match(None, &[HashSet::from([key(0x33)])]));
+    assert!(EventsBloom::default().may_match(None, &[]));
+}
+
+#[test]
+fn iter_events_by_first_keys() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::new(None).index_events(true).build();
+    let blocks = test_blocks(3);
+    append_blocks(&mut writer, &blocks);
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_event_index_marker().unwrap(), BlockNumber(3));
+    assert!(txn.get_events_bloom(BlockNumber(2)).unwrap().is_some());
+    assert!(txn.get_events_bloom(BlockNumber(3)).unwrap().is_none());
+
+    for first_keys in [vec![key(KEY_1)], vec![key(KEY_2)], vec![key(KEY_2), key(KEY_1)]] {
+        let events = txn
+            .iter_events_by_first_keys(first_keys.clone(), start_index(0), BlockNumber(2))
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(events, expected_events(&blocks, &first_keys));
+    }
+
+    // Start from the second block and stop before the last one.
+    let events = txn
+        .iter_events_by_first_keys(vec![key(KEY_1)], start_index(1), BlockNumber(1))
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(events, expected_events(&blocks[1..2], &[key(KEY_1)]));
+
+    let events = txn
+        .iter_events_by_first_keys(vec![key(0x33)], start_index(0), BlockNumber(2))
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(events, vec![]);
+}
+
+#[test]
+fn revert_body_reverts_events_index() {
+    let ((reader, mut writer), _config, _temp_dir) =
+        TestStorageBuilder::new(None).index_events(true).build();
+    let blocks = test_blocks(2);
+    append_blocks(&mut writer, &blocks);
+
+    let (txn, _) = writer.begin_rw_txn().unwrap().revert_body(BlockNumber(1)).unwrap();
+    txn.commit().unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_event_index_marker().unwrap(), BlockNumber(1));
+    assert!(txn.get_events_bloom(BlockNumber(1)).unwrap().is_none());
+    let first_keys = vec![key(KEY_1), key(KEY_2)];
+    let events = txn
+        .iter_events_by_first_keys(first_keys.clone(), start_index(0), BlockNumber(1))
+        .unwrap()
+        .collect::<Vec<_>>();
+    assert_eq!(events, expected_events(&blocks[..1], &first_keys));
+}
+
+#[test]
+fn events_index_catches_up() {
+    let ((reader, mut writer), mut config, _temp_dir) = TestStorageBuilder::new(None).build();
+    let blocks = test_blocks(13);
+    append_blocks(&mut writer, &blocks[..12]);
+    assert_eq!(reader.begin_ro_txn().unwrap().get_event_index_marker().unwrap(), BlockNumber(0));
+    drop((reader, writer));
+
+    config.index_events = true;
+    let (reader, mut writer) = open_storage(config).unwrap();
+    // Every appended body indexes a bounded number of the stored bodies.
+    append_blocks(&mut writer, &blocks[12..]);
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_event_index_marker().unwrap(), BlockNumber(10));
+    assert!(txn.get_events_bloom(BlockNumber(9)).unwrap().is_some());
+    assert!(txn.get_events_bloom(BlockNumber(10)).unwrap().is_none());
+}
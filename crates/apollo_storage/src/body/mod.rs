@@ -5,7 +5,8 @@
 //! Import [`BodyStorageReader`] and [`BodyStorageWriter`] to read and write data related
 //! to the block bodies using a [`StorageTxn`].
 //!
-//! See [`events`] module for the interface for handling events.
+//! See [`events`] module for the interface for handling events, and [`events_index`] for the
+//! optional secondary indexes of the events.
 //!
 //!  # Example
 //! ```
@@ -41,6 +42,7 @@
 #[cfg(test)]
 mod body_test;
 pub mod events;
+pub mod events_index;
 
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -364,6 +366,9 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
                 &events_table,
                 block_number,
             )?;
+            if self.index_events {
+                self.update_events_index(block_number)?;
+            }
         }
 
         Ok(self)
@@ -421,6 +426,7 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
                 transaction_hash_to_idx_table.delete(&self.txn, tx_hash)?;
                 transaction_metadata_table.delete(&self.txn, &tx_index)?;
             }
+            self.revert_events_index(block_number, &transaction_outputs)?;
             Some((transactions, transaction_outputs, transaction_hashes))
         };
 
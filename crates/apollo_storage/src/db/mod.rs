@@ -33,7 +33,7 @@ use apollo_config::dumping::{ser_param, SerializeConfig};
 use apollo_config::validators::validate_ascii;
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_proc_macros::latency_histogram;
-use libmdbx::{DatabaseFlags, Geometry, PageSize, WriteMap};
+use libmdbx::{DatabaseFlags, Geometry, PageSize, WriteFlags, WriteMap};
 use serde::{Deserialize, Serialize};
 use starknet_api::core::ChainId;
 use validator::Validate;
@@ -43,7 +43,7 @@ use self::table_types::{DbCursor, DbCursorTrait};
 use crate::db::table_types::TableType;
 
 // Maximum number of Sub-Databases.
-const MAX_DBS: usize = 19;
+const MAX_DBS: usize = 23;
 
 // Note that NO_TLS mode is used by default.
 type EnvironmentKind = WriteMap;
@@ -191,6 +191,16 @@ impl KeyAlreadyExistsError {
 /// There is a single non clonable writer instance, to make sure there is only one write transaction
 ///  at any given moment.
 pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
+    open_env_with_exclusivity(config, false)
+}
+
+// Opens the environment in exclusive mode: fails if another process has the environment open, and
+// keeps other processes from opening it until it is closed.
+pub(crate) fn open_env_exclusively(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
+    open_env_with_exclusivity(config, true)
+}
+
+fn open_env_with_exclusivity(config: &DbConfig, exclusive: bool) -> DbResult<(DbReader, DbWriter)> {
     let db_file_path = config.path().join("mdbx.dat");
     // Checks if path exists if enforce_file_exists is true.
     if config.enforce_file_exists && !db_file_path.exists() {
@@ -208,6 +218,7 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
             .set_max_tables(MAX_DBS)
             .set_max_readers(MAX_READERS)
             .set_flags(DatabaseFlags {
+                exclusive,
                 // There is no locality of pages in the database almost at all, so readahead will
                 // fill the RAM with garbage.
                 no_rdahead: true,
@@ -267,6 +278,15 @@ impl DbWriteTransaction<'_> {
         self.txn.commit()?;
         Ok(())
     }
+
+    // Writes serialized key-value pair to a table without any checks. Existing keys are
+    // overwritten, unless the table uses the libmdbx `DUP_SORT` feature, in which case the value is
+    // added as a duplicate item.
+    pub(crate) fn put_raw(&self, table_name: &str, key: &[u8], value: &[u8]) -> DbResult<()> {
+        let database = self.txn.open_table(Some(table_name))?;
+        self.txn.put(&database, key, value, WriteFlags::UPSERT)?;
+        Ok(())
+    }
 }
 
 #[doc(hidden)]
@@ -293,6 +313,19 @@ impl<Mode: TransactionKind> DbTransaction<'_, Mode> {
             _table_type: PhantomData {},
         })
     }
+
+    // Returns an iterator over the serialized key-value pairs of a table, in the table order.
+    // For tables using the libmdbx `DUP_SORT` feature, each duplicate item is a separate pair.
+    pub(crate) fn raw_table_iter(
+        &self,
+        table_name: &str,
+    ) -> DbResult<impl Iterator<Item = DbResult<(DbKeyType<'_>, DbValueType<'_>)>> + '_> {
+        let database = self.txn.open_table(Some(table_name))?;
+        let cursor = self.txn.cursor(&database)?;
+        Ok(cursor
+            .into_iter_start::<DbKeyType<'_>, DbValueType<'_>>()
+            .map(|res| res.map_err(DbError::from)))
+    }
 }
 pub(crate) struct TableIdentifier<K: Key + Debug, V: ValueSerde + Debug, T: TableType> {
     pub(crate) name: &'static str,
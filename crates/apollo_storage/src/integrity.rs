@@ -0,0 +1,584 @@
+//! Integrity checks and repair of the storage.
+//!
+//! [`check_integrity`] scans the storage in a single read transaction and reports
+//! inconsistencies between the tables, the markers and the memory mapped files:
+//! - Markers that break their order invariants.
+//! - Missing data below a marker and data at or above it.
+//! - Locations that point beyond the written part of a memory mapped file.
+//! - Mismatches between the `block_hash_to_number` and `transaction_hash_to_idx` indices and the
+//!   entries they point to.
+//! - Events indexes entries of blocks that are not indexed yet.
+//!
+//! [`roll_back_storage`] repairs the storage by deleting the data of all the blocks starting from a
+//! given block and lowering the markers to it, so the node can sync these blocks again.
+
+#[cfg(test)]
+#[path = "integrity_test.rs"]
+mod integrity_test;
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use tracing::{debug, info};
+
+use crate::db::serialization::{Key, ValueSerde};
+use crate::db::table_types::{DbCursor, DbCursorTrait, Table, TableType};
+use crate::db::{DbIter, DbTransaction, TableHandle, RO, RW};
+use crate::mmap_file::LocationInFile;
+use crate::{
+    MarkerKind,
+    OffsetKind,
+    StorageReader,
+    StorageResult,
+    StorageScope,
+    StorageTxn,
+    StorageWriter,
+};
+
+// Pairs of markers (marker, bound) where marker <= bound should hold.
+const MARKERS_ORDER: [(MarkerKind, MarkerKind); 6] = [
+    (MarkerKind::CompiledClass, MarkerKind::Class),
+    (MarkerKind::Class, MarkerKind::State),
+    (MarkerKind::State, MarkerKind::Header),
+    (MarkerKind::Body, MarkerKind::Header),
+    (MarkerKind::BaseLayerBlock, MarkerKind::Header),
+    (MarkerKind::EventIndex, MarkerKind::Body),
+];
+
+// Markers that are lowered when rolling back the storage. The compiler backward compatibility
+// marker is not a sync progress marker, so it is kept as is.
+const ROLLED_BACK_MARKERS: [MarkerKind; 9] = [
+    MarkerKind::Header,
+    MarkerKind::Body,
+    MarkerKind::Event,
+    MarkerKind::State,
+    MarkerKind::Class,
+    MarkerKind::CompiledClass,
+    MarkerKind::BaseLayerBlock,
+    MarkerKind::ClassManagerBlock,
+    MarkerKind::EventIndex,
+];
+
+/// The result of an integrity check of the storage.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// The markers of the storage by their names.
+    pub markers: BTreeMap<String, BlockNumber>,
+    /// The offsets of the memory mapped files.
+    pub file_offsets: BTreeMap<String, usize>,
+    /// The inconsistencies that were found.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Returns true if no inconsistencies were found.
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the first block that is affected by an inconsistency, if there is one. Rolling
+    /// back the storage to this block removes all the inconsistencies that are tied to a block.
+    pub fn first_inconsistent_block(&self) -> Option<BlockNumber> {
+        self.issues.iter().filter_map(IntegrityIssue::block_number).min()
+    }
+}
+
+/// An inconsistency in the storage.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// A marker is greater than a marker that should bound it.
+    MarkerOrder { marker: String, value: BlockNumber, bound_marker: String, bound: BlockNumber },
+    /// The data of a block below the marker is missing.
+    MissingData { table: String, block_number: BlockNumber },
+    /// The table contains data of a block at or above the marker.
+    DataBeyondMarker { table: String, block_number: BlockNumber, marker: BlockNumber },
+    /// A location points beyond the written part of its memory mapped file.
+    LocationOutOfBounds {
+        table: String,
+        key: String,
+        block_number: Option<BlockNumber>,
+        location_end: usize,
+        file_offset: usize,
+    },
+    /// An entry doesn't match the entry it points to in another table.
+    IndexMismatch { table: String, key: String, block_number: Option<BlockNumber>, details: String },
+}
+
+impl IntegrityIssue {
+    /// Returns the first block that is affected by the issue, if it is known.
+    pub fn block_number(&self) -> Option<BlockNumber> {
+        match self {
+            IntegrityIssue::MarkerOrder { bound, .. } => Some(*bound),
+            IntegrityIssue::MissingData { block_number, .. } => Some(*block_number),
+            IntegrityIssue::DataBeyondMarker { marker, .. } => Some(*marker),
+            IntegrityIssue::LocationOutOfBounds { block_number, .. }
+            | IntegrityIssue::IndexMismatch { block_number, .. } => *block_number,
+        }
+    }
+}
+
+/// Checks the integrity of the storage. Entries that can't be deserialized fail the check with an
+/// error.
+pub fn check_integrity(reader: &StorageReader) -> StorageResult<IntegrityReport> {
+    let txn = reader.begin_ro_txn()?;
+    let markers = get_markers(&txn)?;
+    let file_offsets = get_file_offsets(&txn)?;
+    let marker = |kind: MarkerKind| markers.get(&kind).copied().unwrap_or_default();
+    let file_offset = |kind: OffsetKind| file_offsets.get(&kind).copied().unwrap_or_default();
+
+    let mut issues = Vec::new();
+    for (kind, bound_kind) in MARKERS_ORDER {
+        if marker(kind) > marker(bound_kind) {
+            issues.push(IntegrityIssue::MarkerOrder {
+                marker: format!("{kind:?}"),
+                value: marker(kind),
+                bound_marker: format!("{bound_kind:?}"),
+                bound: marker(bound_kind),
+            });
+        }
+    }
+
+    debug!("Checking the headers.");
+    check_headers(&txn, marker(MarkerKind::Header), &mut issues)?;
+    debug!("Checking the state.");
+    check_state(
+        &txn,
+        marker(MarkerKind::State),
+        marker(MarkerKind::Class),
+        &file_offset,
+        &mut issues,
+    )?;
+    if txn.scope == StorageScope::FullArchive {
+        debug!("Checking the bodies.");
+        check_bodies(&txn, marker(MarkerKind::Body), &file_offset, &mut issues)?;
+        check_events_index(&txn, marker(MarkerKind::EventIndex), &mut issues)?;
+    }
+
+    Ok(IntegrityReport {
+        markers: markers.into_iter().map(|(kind, block)| (format!("{kind:?}"), block)).collect(),
+        file_offsets: file_offsets
+            .into_iter()
+            .map(|(kind, offset)| (kind.file_name().to_owned(), offset))
+            .collect(),
+        issues,
+    })
+}
+
+/// Rolls back the storage to `block_number`: deletes the data of all the blocks starting from
+/// `block_number` and lowers the markers that are above it. The memory mapped files are not
+/// truncated, the deleted objects remain there unreferenced.
+pub fn roll_back_storage(
+    writer: &mut StorageWriter,
+    block_number: BlockNumber,
+) -> StorageResult<()> {
+    info!("Rolling back the storage to block {block_number}.");
+    let txn = writer.begin_rw_txn()?;
+    let is_deleted = |block: &BlockNumber| *block >= block_number;
+
+    let headers_table = txn.open_table(&txn.tables.headers)?;
+    delete_entries(&txn.txn, &headers_table, |block, _| is_deleted(block))?;
+    let block_hash_to_number_table = txn.open_table(&txn.tables.block_hash_to_number)?;
+    delete_entries(&txn.txn, &block_hash_to_number_table, |_, block| is_deleted(block))?;
+    let block_signatures_table = txn.open_table(&txn.tables.block_signatures)?;
+    delete_entries(&txn.txn, &block_signatures_table, |block, _| is_deleted(block))?;
+    let starknet_version_table = txn.open_table(&txn.tables.starknet_version)?;
+    delete_entries(&txn.txn, &starknet_version_table, |block, _| is_deleted(block))?;
+    let base_layer_proofs_table = txn.open_table(&txn.tables.base_layer_proofs)?;
+    delete_entries(&txn.txn, &base_layer_proofs_table, |block, _| is_deleted(block))?;
+
+    if txn.scope == StorageScope::FullArchive {
+        let transaction_metadata_table = txn.open_table(&txn.tables.transaction_metadata)?;
+        delete_entries(&txn.txn, &transaction_metadata_table, |tx_index, _| {
+            is_deleted(&tx_index.0)
+        })?;
+        let transaction_hash_to_idx_table = txn.open_table(&txn.tables.transaction_hash_to_idx)?;
+        delete_entries(&txn.txn, &transaction_hash_to_idx_table, |_, tx_index| {
+            is_deleted(&tx_index.0)
+        })?;
+        let events_table = txn.open_table(&txn.tables.events)?;
+        delete_entries(&txn.txn, &events_table, |(_, tx_index), _| is_deleted(&tx_index.0))?;
+        let event_keys_table = txn.open_table(&txn.tables.event_keys)?;
+        delete_entries(&txn.txn, &event_keys_table, |(_, tx_index), _| is_deleted(&tx_index.0))?;
+        let events_bloom_table = txn.open_table(&txn.tables.events_bloom)?;
+        delete_entries(&txn.txn, &events_bloom_table, |block, _| is_deleted(block))?;
+    }
+
+    let state_diffs_table = txn.open_table(&txn.tables.state_diffs)?;
+    delete_entries(&txn.txn, &state_diffs_table, |block, _| is_deleted(block))?;
+    let contract_storage_table = txn.open_table(&txn.tables.contract_storage)?;
+    delete_entries(&txn.txn, &contract_storage_table, |(_, block), _| is_deleted(block))?;
+    let nonces_table = txn.open_table(&txn.tables.nonces)?;
+    delete_entries(&txn.txn, &nonces_table, |(_, block), _| is_deleted(block))?;
+    let deployed_contracts_table = txn.open_table(&txn.tables.deployed_contracts)?;
+    delete_entries(&txn.txn, &deployed_contracts_table, |(_, block), _| is_deleted(block))?;
+    let deprecated_declared_classes_table =
+        txn.open_table(&txn.tables.deprecated_declared_classes)?;
+    delete_entries(&txn.txn, &deprecated_declared_classes_table, |_, value| {
+        is_deleted(&value.block_number)
+    })?;
+
+    let declared_classes_block_table = txn.open_table(&txn.tables.declared_classes_block)?;
+    let deleted_classes =
+        delete_entries(&txn.txn, &declared_classes_block_table, |_, block| is_deleted(block))?;
+    let declared_classes_table = txn.open_table(&txn.tables.declared_classes)?;
+    let casms_table = txn.open_table(&txn.tables.casms)?;
+    let class_hash_to_executable_class_hash_table =
+        txn.open_table(&txn.tables.class_hash_to_executable_class_hash)?;
+    for class_hash in &deleted_classes {
+        declared_classes_table.delete(&txn.txn, class_hash)?;
+        casms_table.delete(&txn.txn, class_hash)?;
+        class_hash_to_executable_class_hash_table.delete(&txn.txn, class_hash)?;
+    }
+
+    let markers_table = txn.open_table(&txn.tables.markers)?;
+    for kind in ROLLED_BACK_MARKERS {
+        if markers_table.get(&txn.txn, &kind)?.is_some_and(|marker| marker > block_number) {
+            debug!("Lowering the {kind:?} marker to {block_number}.");
+            markers_table.upsert(&txn.txn, &kind, &block_number)?;
+        }
+    }
+    txn.commit()
+}
+
+fn get_markers(txn: &StorageTxn<'_, RO>) -> StorageResult<BTreeMap<MarkerKind, BlockNumber>> {
+    let markers_table = txn.open_table(&txn.tables.markers)?;
+    let mut cursor = markers_table.cursor(&txn.txn)?;
+    Ok(DbIter::new(&mut cursor).collect::<Result<_, _>>()?)
+}
+
+fn get_file_offsets(txn: &StorageTxn<'_, RO>) -> StorageResult<BTreeMap<OffsetKind, usize>> {
+    let file_offsets_table = txn.open_table(&txn.tables.file_offsets)?;
+    let mut cursor = file_offsets_table.cursor(&txn.txn)?;
+    Ok(DbIter::new(&mut cursor).collect::<Result<_, _>>()?)
+}
+
+fn check_headers(
+    txn: &StorageTxn<'_, RO>,
+    header_marker: BlockNumber,
+    issues: &mut Vec<IntegrityIssue>,
+) -> StorageResult<()> {
+    let headers_table = txn.open_table(&txn.tables.headers)?;
+    let block_hash_to_number_table = txn.open_table(&txn.tables.block_hash_to_number)?;
+
+    let mut blocks = BlocksSequence::new("headers", header_marker);
+    let mut cursor = headers_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (block_number, header) = entry?;
+        blocks.visit(block_number, issues);
+        if header.block_number != block_number {
+            issues.push(IntegrityIssue::IndexMismatch {
+                table: "headers".to_owned(),
+                key: block_number.to_string(),
+                block_number: Some(block_number),
+                details: format!("The header belongs to block {}.", header.block_number),
+            });
+        }
+        let indexed_block_number = block_hash_to_number_table.get(&txn.txn, &header.block_hash)?;
+        if indexed_block_number != Some(block_number) {
+            issues.push(IntegrityIssue::IndexMismatch {
+                table: "block_hash_to_number".to_owned(),
+                key: format!("{:?}", header.block_hash),
+                block_number: Some(block_number),
+                details: format!("The hash is indexed to block {indexed_block_number:?}."),
+            });
+        }
+    }
+    blocks.finish(issues);
+
+    let mut cursor = block_hash_to_number_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (block_hash, block_number) = entry?;
+        let header_block_hash =
+            headers_table.get(&txn.txn, &block_number)?.map(|header| header.block_hash);
+        if header_block_hash != Some(block_hash) {
+            issues.push(IntegrityIssue::IndexMismatch {
+                table: "block_hash_to_number".to_owned(),
+                key: format!("{block_hash:?}"),
+                block_number: Some(block_number),
+                details: format!("The hash of the header is {header_block_hash:?}."),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_state(
+    txn: &StorageTxn<'_, RO>,
+    state_marker: BlockNumber,
+    class_marker: BlockNumber,
+    file_offset: &impl Fn(OffsetKind) -> usize,
+    issues: &mut Vec<IntegrityIssue>,
+) -> StorageResult<()> {
+    let state_diffs_table = txn.open_table(&txn.tables.state_diffs)?;
+    let mut blocks = BlocksSequence::new("state_diffs", state_marker);
+    let mut cursor = state_diffs_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (block_number, location) = entry?;
+        blocks.visit(block_number, issues);
+        check_location(
+            "state_diffs",
+            &block_number,
+            Some(block_number),
+            location,
+            file_offset(OffsetKind::ThinStateDiff),
+            issues,
+        );
+    }
+    blocks.finish(issues);
+
+    let declared_classes_block_table = txn.open_table(&txn.tables.declared_classes_block)?;
+    let declared_classes_table = txn.open_table(&txn.tables.declared_classes)?;
+    let mut cursor = declared_classes_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (class_hash, location) = entry?;
+        let block_number = declared_classes_block_table.get(&txn.txn, &class_hash)?;
+        match block_number {
+            None => issues.push(IntegrityIssue::IndexMismatch {
+                table: "declared_classes_block".to_owned(),
+                key: format!("{class_hash:?}"),
+                block_number: None,
+                details: "The declaration block of the class is missing.".to_owned(),
+            }),
+            Some(block_number) if block_number >= class_marker => {
+                issues.push(IntegrityIssue::DataBeyondMarker {
+                    table: "declared_classes".to_owned(),
+                    block_number,
+                    marker: class_marker,
+                })
+            }
+            Some(_) => {}
+        }
+        check_location(
+            "declared_classes",
+            &class_hash,
+            block_number,
+            location,
+            file_offset(OffsetKind::ContractClass),
+            issues,
+        );
+    }
+
+    let casms_table = txn.open_table(&txn.tables.casms)?;
+    let mut cursor = casms_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (class_hash, location) = entry?;
+        let block_number = declared_classes_block_table.get(&txn.txn, &class_hash)?;
+        check_location(
+            "casms",
+            &class_hash,
+            block_number,
+            location,
+            file_offset(OffsetKind::Casm),
+            issues,
+        );
+    }
+
+    let deprecated_declared_classes_table =
+        txn.open_table(&txn.tables.deprecated_declared_classes)?;
+    let mut cursor = deprecated_declared_classes_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (class_hash, value) = entry?;
+        if value.block_number >= class_marker {
+            issues.push(IntegrityIssue::DataBeyondMarker {
+                table: "deprecated_declared_classes".to_owned(),
+                block_number: value.block_number,
+                marker: class_marker,
+            });
+        }
+        check_location(
+            "deprecated_declared_classes",
+            &class_hash,
+            Some(value.block_number),
+            value.location_in_file,
+            file_offset(OffsetKind::DeprecatedContractClass),
+            issues,
+        );
+    }
+    Ok(())
+}
+
+fn check_bodies(
+    txn: &StorageTxn<'_, RO>,
+    body_marker: BlockNumber,
+    file_offset: &impl Fn(OffsetKind) -> usize,
+    issues: &mut Vec<IntegrityIssue>,
+) -> StorageResult<()> {
+    let transaction_metadata_table = txn.open_table(&txn.tables.transaction_metadata)?;
+    let transaction_hash_to_idx_table = txn.open_table(&txn.tables.transaction_hash_to_idx)?;
+
+    let mut cursor = transaction_metadata_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (tx_index, metadata) = entry?;
+        let block_number = tx_index.0;
+        if block_number >= body_marker {
+            issues.push(IntegrityIssue::DataBeyondMarker {
+                table: "transaction_metadata".to_owned(),
+                block_number,
+                marker: body_marker,
+            });
+        }
+        check_location(
+            "transaction_metadata",
+            &tx_index,
+            Some(block_number),
+            metadata.tx_location,
+            file_offset(OffsetKind::Transaction),
+            issues,
+        );
+        check_location(
+            "transaction_metadata",
+            &tx_index,
+            Some(block_number),
+            metadata.tx_output_location,
+            file_offset(OffsetKind::TransactionOutput),
+            issues,
+        );
+        let indexed_tx_index = transaction_hash_to_idx_table.get(&txn.txn, &metadata.tx_hash)?;
+        if indexed_tx_index != Some(tx_index) {
+            issues.push(IntegrityIssue::IndexMismatch {
+                table: "transaction_hash_to_idx".to_owned(),
+                key: format!("{:?}", metadata.tx_hash),
+                block_number: Some(block_number),
+                details: format!("The hash is indexed to {indexed_tx_index:?}."),
+            });
+        }
+    }
+
+    let mut cursor = transaction_hash_to_idx_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (tx_hash, tx_index) = entry?;
+        let metadata_tx_hash =
+            transaction_metadata_table.get(&txn.txn, &tx_index)?.map(|metadata| metadata.tx_hash);
+        if metadata_tx_hash != Some(tx_hash) {
+            issues.push(IntegrityIssue::IndexMismatch {
+                table: "transaction_hash_to_idx".to_owned(),
+                key: format!("{tx_hash:?}"),
+                block_number: Some(tx_index.0),
+                details: format!("The hash of the transaction is {metadata_tx_hash:?}."),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_events_index(
+    txn: &StorageTxn<'_, RO>,
+    event_index_marker: BlockNumber,
+    issues: &mut Vec<IntegrityIssue>,
+) -> StorageResult<()> {
+    let events_bloom_table = txn.open_table(&txn.tables.events_bloom)?;
+    let mut blocks = BlocksSequence::new("events_bloom", event_index_marker);
+    let mut cursor = events_bloom_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (block_number, _bloom) = entry?;
+        blocks.visit(block_number, issues);
+    }
+    blocks.finish(issues);
+
+    let event_keys_table = txn.open_table(&txn.tables.event_keys)?;
+    let mut cursor = event_keys_table.cursor(&txn.txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let ((_key, tx_index), _) = entry?;
+        if tx_index.0 >= event_index_marker {
+            issues.push(IntegrityIssue::DataBeyondMarker {
+                table: "event_keys".to_owned(),
+                block_number: tx_index.0,
+                marker: event_index_marker,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_location(
+    table: &str,
+    key: &impl Debug,
+    block_number: Option<BlockNumber>,
+    location: LocationInFile,
+    file_offset: usize,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    if location.next_offset() > file_offset {
+        issues.push(IntegrityIssue::LocationOutOfBounds {
+            table: table.to_owned(),
+            key: format!("{key:?}"),
+            block_number,
+            location_end: location.next_offset(),
+            file_offset,
+        });
+    }
+}
+
+// Checks that the keys of a table that is keyed by block number are exactly the blocks below the
+// marker. The keys should be visited in ascending order.
+struct BlocksSequence {
+    table: &'static str,
+    marker: BlockNumber,
+    next_block: BlockNumber,
+}
+
+impl BlocksSequence {
+    fn new(table: &'static str, marker: BlockNumber) -> Self {
+        Self { table, marker, next_block: BlockNumber(0) }
+    }
+
+    fn visit(&mut self, block_number: BlockNumber, issues: &mut Vec<IntegrityIssue>) {
+        if block_number >= self.marker {
+            issues.push(IntegrityIssue::DataBeyondMarker {
+                table: self.table.to_owned(),
+                block_number,
+                marker: self.marker,
+            });
+            return;
+        }
+        // Only the first block of every gap is reported.
+        if block_number > self.next_block {
+            self.report_missing(issues);
+        }
+        self.next_block = block_number.unchecked_next();
+    }
+
+    fn finish(self, issues: &mut Vec<IntegrityIssue>) {
+        if self.next_block < self.marker {
+            self.report_missing(issues);
+        }
+    }
+
+    fn report_missing(&self, issues: &mut Vec<IntegrityIssue>) {
+        issues.push(IntegrityIssue::MissingData {
+            table: self.table.to_owned(),
+            block_number: self.next_block,
+        });
+    }
+}
+
+// Deletes the entries of the table that satisfy the predicate and returns their keys.
+fn delete_entries<'env, K, V, T>(
+    txn: &'env DbTransaction<'env, RW>,
+    table: &'env TableHandle<'env, K, V, T>,
+    should_delete: impl Fn(&K, &V::Value) -> bool,
+) -> StorageResult<Vec<K>>
+where
+    K: Key + Debug,
+    V: ValueSerde + Debug,
+    T: TableType,
+    TableHandle<'env, K, V, T>: Table<'env, Key = K, Value = V, TableVariant = T>,
+    DbCursor<'env, RW, K, V, T>: DbCursorTrait<Key = K, Value = V>,
+{
+    let mut keys = Vec::new();
+    let mut cursor = table.cursor(txn)?;
+    for entry in DbIter::new(&mut cursor) {
+        let (key, value) = entry?;
+        if should_delete(&key, &value) {
+            keys.push(key);
+        }
+    }
+    for key in &keys {
+        table.delete(txn, key)?;
+    }
+    Ok(keys)
+}
@@ -0,0 +1,133 @@
+use apollo_test_utils::get_test_block;
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::felt;
+use starknet_api::state::ThinStateDiff;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::db::table_types::Table;
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::integrity::{check_integrity, roll_back_storage, IntegrityIssue};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::get_test_storage;
+use crate::{MarkerKind, OffsetKind, StorageWriter};
+
+fn header(block_number: u64) -> BlockHeader {
+    BlockHeader {
+        block_hash: BlockHash(felt!(block_number + 1)),
+        block_header_without_hash: BlockHeaderWithoutHash {
+            block_number: BlockNumber(block_number),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+// Appends the given number of blocks. Only the first block contains transactions.
+fn append_blocks(writer: &mut StorageWriter, n_blocks: u64) {
+    for block_number in 0..n_blocks {
+        let body = if block_number == 0 {
+            get_test_block(2, None, None, None).body
+        } else {
+            BlockBody::default()
+        };
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_header(BlockNumber(block_number), &header(block_number))
+            .unwrap()
+            .append_body(BlockNumber(block_number), body)
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number), ThinStateDiff::default())
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+}
+
+#[test]
+fn consistent_storage() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, 3);
+
+    let report = check_integrity(&reader).unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+    assert_eq!(report.first_inconsistent_block(), None);
+    assert_eq!(report.markers.get("Header"), Some(&BlockNumber(3)));
+    assert_eq!(report.markers.get("State"), Some(&BlockNumber(3)));
+}
+
+#[test]
+fn repair_missing_index_entry() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, 3);
+
+    let txn = writer.begin_rw_txn().unwrap();
+    let block_hash_to_number_table = txn.open_table(&txn.tables.block_hash_to_number).unwrap();
+    block_hash_to_number_table.delete(&txn.txn, &header(1).block_hash).unwrap();
+    txn.commit().unwrap();
+
+    let report = check_integrity(&reader).unwrap();
+    assert_matches!(
+        report.issues.as_slice(),
+        [IntegrityIssue::IndexMismatch { table, block_number: Some(BlockNumber(1)), .. }]
+        if table == "block_hash_to_number"
+    );
+    assert_eq!(report.first_inconsistent_block(), Some(BlockNumber(1)));
+
+    roll_back_storage(&mut writer, BlockNumber(1)).unwrap();
+    let report = check_integrity(&reader).unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_body_marker().unwrap(), BlockNumber(1));
+    assert_eq!(txn.get_state_marker().unwrap(), BlockNumber(1));
+    assert!(txn.get_block_header(BlockNumber(0)).unwrap().is_some());
+    assert!(txn.get_block_header(BlockNumber(2)).unwrap().is_none());
+    drop(txn);
+
+    // The rolled back blocks can be synced again.
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(1), &header(1))
+        .unwrap()
+        .append_body(BlockNumber(1), BlockBody::default())
+        .unwrap()
+        .append_state_diff(BlockNumber(1), ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert!(check_integrity(&reader).unwrap().is_consistent());
+}
+
+#[test]
+fn markers_and_locations_issues() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    append_blocks(&mut writer, 2);
+
+    let txn = writer.begin_rw_txn().unwrap();
+    let markers_table = txn.open_table(&txn.tables.markers).unwrap();
+    markers_table.upsert(&txn.txn, &MarkerKind::State, &BlockNumber(4)).unwrap();
+    let file_offsets_table = txn.open_table(&txn.tables.file_offsets).unwrap();
+    file_offsets_table.upsert(&txn.txn, &OffsetKind::TransactionOutput, &0).unwrap();
+    txn.commit().unwrap();
+
+    let report = check_integrity(&reader).unwrap();
+    assert!(report.issues.contains(&IntegrityIssue::MarkerOrder {
+        marker: "State".to_owned(),
+        value: BlockNumber(4),
+        bound_marker: "Header".to_owned(),
+        bound: BlockNumber(2),
+    }));
+    assert!(report.issues.contains(&IntegrityIssue::MissingData {
+        table: "state_diffs".to_owned(),
+        block_number: BlockNumber(2),
+    }));
+    assert!(report.issues.iter().any(|issue| matches!(
+        issue,
+        IntegrityIssue::LocationOutOfBounds { block_number: Some(BlockNumber(0)), .. }
+    )));
+    assert_eq!(report.first_inconsistent_block(), Some(BlockNumber(0)));
+}
@@ -72,6 +72,10 @@
 //! - Code: {major: 0, minor: 1}, Database: {major: 0, minor: 0} will succeed since the major
 //!   versions match and the code's minor version is higher.
 //!
+//! Before the minor version of an existing database is raised, the registered migrations of the
+//! [`migration`] module that target a higher minor version are applied to it. A migration that was
+//! interrupted continues from its last committed step when the storage is opened again.
+//!
 //! [`Starknet`]: https://starknet.io/
 //! [`libmdbx`]: https://docs.rs/libmdbx/latest/libmdbx/
 
@@ -89,8 +93,11 @@ pub mod storage_metrics;
 pub mod compression_utils;
 pub mod db;
 pub mod header;
+pub mod integrity;
+pub mod migration;
 pub mod mmap_file;
 mod serialization;
+pub mod snapshot;
 pub mod state;
 mod version;
 
@@ -111,6 +118,7 @@ use apollo_config::dumping::{append_sub_config_name, ser_param, SerializeConfig}
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_proc_macros::latency_histogram;
 use body::events::EventIndex;
+use body::events_index::EventsBloom;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use db::db_stats::{DbTableStats, DbWholeStats};
 use db::serialization::{Key, NoVersionValueWrapper, ValueSerde, VersionZeroWrapper};
@@ -129,16 +137,20 @@ use starknet_api::block::{BlockHash, BlockNumber, BlockSignature, StarknetVersio
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
-use starknet_api::transaction::{Transaction, TransactionHash, TransactionOutput};
+use starknet_api::transaction::{EventKey, Transaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
+use tokio::sync::watch;
 use tracing::{debug, info, warn};
 use validator::Validate;
-use version::{StorageVersionError, Version};
+use version::StorageVersionError;
+pub use version::Version;
 
+use crate::base_layer::BaseLayerProof;
 use crate::body::TransactionIndex;
 use crate::db::table_types::SimpleTable;
 use crate::db::{
     open_env,
+    open_env_exclusively,
     DbConfig,
     DbError,
     DbReader,
@@ -157,13 +169,40 @@ use crate::version::{VersionStorageReader, VersionStorageWriter};
 
 // For more details on the storage version, see the module documentation.
 /// The current version of the storage state code.
-pub const STORAGE_VERSION_STATE: Version = Version { major: 5, minor: 0 };
+pub const STORAGE_VERSION_STATE: Version = Version { major: 5, minor: 1 };
 /// The current version of the storage blocks code.
 pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 5, minor: 0 };
 
 /// Opens a storage and returns a [`StorageReader`] and a [`StorageWriter`].
 pub fn open_storage(
     storage_config: StorageConfig,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    open_storage_with_exclusivity(storage_config, false)
+}
+
+/// Opens a storage like [`open_storage`], but fails if another process (e.g., a running node) has
+/// the storage open, and keeps other processes from opening it until the returned reader and
+/// writer are dropped.
+pub fn open_storage_exclusively(
+    storage_config: StorageConfig,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    open_storage_with_exclusivity(storage_config, true)
+}
+
+fn open_storage_with_exclusivity(
+    storage_config: StorageConfig,
+    exclusive: bool,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    let (reader, writer) = open_storage_without_version_check(storage_config, exclusive)?;
+    let writer = set_version_if_needed(reader.clone(), writer)?;
+    verify_storage_version(reader.clone())?;
+    Ok((reader, writer))
+}
+
+// Opens the storage without initializing, migrating or verifying its version.
+pub(crate) fn open_storage_without_version_check(
+    storage_config: StorageConfig,
+    exclusive: bool,
 ) -> StorageResult<(StorageReader, StorageWriter)> {
     info!("Opening storage: {}", storage_config.db_config.path_prefix.display());
     if !storage_config.db_config.path_prefix.exists()
@@ -173,8 +212,13 @@ pub fn open_storage(
         info!("Created storage directory: {}", storage_config.db_config.path_prefix.display());
     }
 
-    let (db_reader, mut db_writer) = open_env(&storage_config.db_config)?;
+    let (db_reader, mut db_writer) = if exclusive {
+        open_env_exclusively(&storage_config.db_config)?
+    } else {
+        open_env(&storage_config.db_config)?
+    };
     let tables = Arc::new(Tables {
+        base_layer_proofs: db_writer.create_simple_table("base_layer_proofs")?,
         block_hash_to_number: db_writer.create_simple_table("block_hash_to_number")?,
         block_signatures: db_writer.create_simple_table("block_signatures")?,
         casms: db_writer.create_simple_table("casms")?,
@@ -185,6 +229,8 @@ pub fn open_storage(
             .create_simple_table("deprecated_declared_classes")?,
         deployed_contracts: db_writer.create_simple_table("deployed_contracts")?,
         events: db_writer.create_common_prefix_table("events")?,
+        event_keys: db_writer.create_common_prefix_table("event_keys")?,
+        events_bloom: db_writer.create_simple_table("events_bloom")?,
         headers: db_writer.create_simple_table("headers")?,
         markers: db_writer.create_simple_table("markers")?,
         nonces: db_writer.create_common_prefix_table("nonces")?,
@@ -196,6 +242,7 @@ pub fn open_storage(
         // Version tables.
         starknet_version: db_writer.create_simple_table("starknet_version")?,
         storage_version: db_writer.create_simple_table("storage_version")?,
+        migrations: db_writer.create_simple_table("migrations")?,
 
         // Class hashes.
         class_hash_to_executable_class_hash: db_writer
@@ -208,16 +255,22 @@ pub fn open_storage(
         &tables.file_offsets,
     )?;
 
+    let commits = Arc::new(watch::Sender::new(()));
     let reader = StorageReader {
         db_reader,
         tables: tables.clone(),
         scope: storage_config.scope,
         file_readers,
+        commits: commits.clone(),
+    };
+    let writer = StorageWriter {
+        db_writer,
+        tables,
+        scope: storage_config.scope,
+        index_events: storage_config.index_events,
+        file_writers,
+        commits,
     };
-    let writer = StorageWriter { db_writer, tables, scope: storage_config.scope, file_writers };
-
-    let writer = set_version_if_needed(reader.clone(), writer)?;
-    verify_storage_version(reader.clone())?;
     Ok((reader, writer))
 }
 
@@ -228,7 +281,7 @@ fn set_version_if_needed(
     reader: StorageReader,
     mut writer: StorageWriter,
 ) -> StorageResult<StorageWriter> {
-    let Some(existing_storage_version) = get_storage_version(reader)? else {
+    let Some(existing_storage_version) = get_storage_version(reader.clone())? else {
         // Initialize the storage version.
         writer.begin_rw_txn()?.set_state_version(&STORAGE_VERSION_STATE)?.commit()?;
         // If in full-archive mode, also set the block version.
@@ -264,7 +317,12 @@ fn set_version_if_needed(
             }
         }
     }
-    // Update the version if it's lower than the crate version.
+    // Apply the registered migrations, and then update the version if it's still lower than the
+    // crate version.
+    migration::apply_migrations(&reader, &mut writer, migration::MIGRATIONS, false)?;
+    let Some(existing_storage_version) = get_storage_version(reader)? else {
+        unreachable!("The storage version exists, it was read above.");
+    };
     let mut wtxn = writer.begin_rw_txn()?;
     match existing_storage_version {
         StorageVersion::FullArchive(FullArchiveVersion { state_version, blocks_version }) => {
@@ -420,6 +478,7 @@ pub struct StorageReader {
     file_readers: FileHandlers<RO>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    commits: Arc<watch::Sender<()>>,
 }
 
 impl StorageReader {
@@ -431,9 +490,17 @@ impl StorageReader {
             file_handlers: self.file_readers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            index_events: false,
+            commits: self.commits.clone(),
         })
     }
 
+    /// Returns a receiver that is notified whenever a RW transaction is committed to the storage,
+    /// for waiting on new data instead of polling the storage.
+    pub fn subscribe_to_commits(&self) -> watch::Receiver<()> {
+        self.commits.subscribe()
+    }
+
     /// Returns metadata about the tables in the storage.
     pub fn db_tables_stats(&self) -> StorageResult<DbStats> {
         let mut tables_stats = BTreeMap::new();
@@ -462,6 +529,8 @@ pub struct StorageWriter {
     file_writers: FileHandlers<RW>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    index_events: bool,
+    commits: Arc<watch::Sender<()>>,
 }
 
 impl StorageWriter {
@@ -473,6 +542,8 @@ impl StorageWriter {
             file_handlers: self.file_writers.clone(),
             tables: self.tables.clone(),
             scope: self.scope,
+            index_events: self.index_events,
+            commits: self.commits.clone(),
         })
     }
 }
@@ -484,6 +555,10 @@ pub struct StorageTxn<'env, Mode: TransactionKind> {
     file_handlers: FileHandlers<Mode>,
     tables: Arc<Tables>,
     scope: StorageScope,
+    // Whether to maintain the events indexes when appending block bodies.
+    index_events: bool,
+    // Notifies the subscribers of the storage reader about the commits.
+    commits: Arc<watch::Sender<()>>,
 }
 
 impl StorageTxn<'_, RW> {
@@ -491,7 +566,9 @@ impl StorageTxn<'_, RW> {
     #[latency_histogram("storage_commit_latency_seconds", false)]
     pub fn commit(self) -> StorageResult<()> {
         self.file_handlers.flush();
-        Ok(self.txn.commit()?)
+        self.txn.commit()?;
+        self.commits.send_replace(());
+        Ok(())
     }
 }
 
@@ -503,6 +580,8 @@ impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
         if self.scope == StorageScope::StateOnly {
             let unused_tables = [
                 self.tables.events.name,
+                self.tables.event_keys.name,
+                self.tables.events_bloom.name,
                 self.tables.transaction_hash_to_idx.name,
                 self.tables.transaction_metadata.name,
             ];
@@ -524,6 +603,8 @@ pub fn table_names() -> &'static [&'static str] {
 
 struct_field_names! {
     struct Tables {
+        // The proofs of the blocks on the base layer, by the last block that each of them proved.
+        base_layer_proofs: TableIdentifier<BlockNumber, VersionZeroWrapper<BaseLayerProof>, SimpleTable>,
         block_hash_to_number: TableIdentifier<BlockHash, NoVersionValueWrapper<BlockNumber>, SimpleTable>,
         block_signatures: TableIdentifier<BlockNumber, VersionZeroWrapper<BlockSignature>, SimpleTable>,
         casms: TableIdentifier<ClassHash, VersionZeroWrapper<LocationInFile>, SimpleTable>,
@@ -536,6 +617,9 @@ struct_field_names! {
         // TODO(dvir): consider use here also the CommonPrefix table type.
         deployed_contracts: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<ClassHash>, SimpleTable>,
         events: TableIdentifier<(ContractAddress, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
+        // Secondary indexes of the events, see the body::events_index module.
+        event_keys: TableIdentifier<(EventKey, TransactionIndex), NoVersionValueWrapper<NoValue>, CommonPrefix>,
+        events_bloom: TableIdentifier<BlockNumber, NoVersionValueWrapper<EventsBloom>, SimpleTable>,
         headers: TableIdentifier<BlockNumber, VersionZeroWrapper<StorageBlockHeader>, SimpleTable>,
         markers: TableIdentifier<MarkerKind, VersionZeroWrapper<BlockNumber>, SimpleTable>,
         nonces: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<Nonce>, CommonPrefix>,
@@ -548,6 +632,8 @@ struct_field_names! {
         // Version tables
         starknet_version: TableIdentifier<BlockNumber, VersionZeroWrapper<StarknetVersion>, SimpleTable>,
         storage_version: TableIdentifier<String, NoVersionValueWrapper<Version>, SimpleTable>,
+        // The positions of the migrations that are in progress, see the migration module.
+        migrations: TableIdentifier<String, NoVersionValueWrapper<Vec<u8>>, SimpleTable>,
 
         // Class hashes.
         class_hash_to_executable_class_hash: TableIdentifier<ClassHash, NoVersionValueWrapper<CompiledClassHash>, SimpleTable>
@@ -634,16 +720,26 @@ pub struct StorageConfig {
     #[validate]
     pub mmap_file_config: MmapFileConfig,
     pub scope: StorageScope,
+    pub index_events: bool,
 }
 
 impl SerializeConfig for StorageConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let mut dumped_config = BTreeMap::from_iter([ser_param(
-            "scope",
-            &self.scope,
-            "The categories of data saved in storage.",
-            ParamPrivacyInput::Public,
-        )]);
+        let mut dumped_config = BTreeMap::from_iter([
+            ser_param(
+                "scope",
+                &self.scope,
+                "The categories of data saved in storage.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "index_events",
+                &self.index_events,
+                "Whether to maintain secondary indexes of the events by their first key and by \
+                 block bloom filters. Applies only to the full-archive scope.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
         dumped_config
             .extend(append_sub_config_name(self.mmap_file_config.dump(), "mmap_file_config"));
         dumped_config.extend(append_sub_config_name(self.db_config.dump(), "db_config"));
@@ -666,6 +762,7 @@ pub struct DbStats {
 // - CompiledClass <= Class <= State <= Header
 // - Body <= Header
 // - BaseLayerBlock <= Header
+// - EventIndex <= Body
 // Event is currently unsupported.
 pub(crate) enum MarkerKind {
     Header,
@@ -679,6 +776,8 @@ pub(crate) enum MarkerKind {
     /// Marks the block beyond the last block that its classes can't be compiled with the current
     /// compiler version used in the class manager. Determined by starknet version.
     CompilerBackwardCompatibility,
+    /// The first block whose events are not in the events indexes.
+    EventIndex,
 }
 
 pub(crate) type MarkersTable<'env> =
@@ -755,6 +854,20 @@ impl<Mode: TransactionKind> FileHandlers<Mode> {
         ])
     }
 
+    // Returns the first `len` raw bytes of the file of the given kind.
+    fn get_raw_bytes(&self, kind: OffsetKind, len: usize) -> &[u8] {
+        match kind {
+            OffsetKind::ThinStateDiff => self.thin_state_diff.get_raw_bytes(len),
+            OffsetKind::ContractClass => self.contract_class.get_raw_bytes(len),
+            OffsetKind::Casm => self.casm.get_raw_bytes(len),
+            OffsetKind::DeprecatedContractClass => {
+                self.deprecated_contract_class.get_raw_bytes(len)
+            }
+            OffsetKind::TransactionOutput => self.transaction_output.get_raw_bytes(len),
+            OffsetKind::Transaction => self.transaction.get_raw_bytes(len),
+        }
+    }
+
     // Returns the thin state diff at the given location or an error in case it doesn't exist.
     fn get_thin_state_diff_unchecked(
         &self,
@@ -826,7 +939,7 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::ThinStateDiff)?.unwrap_or_default();
     let (thin_state_diff_writer, thin_state_diff_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("thin_state_diff.dat"),
+        db_config.path().join(OffsetKind::ThinStateDiff.file_name()),
         thin_state_diff_offset,
     )?;
 
@@ -834,19 +947,22 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::ContractClass)?.unwrap_or_default();
     let (contract_class_writer, contract_class_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("contract_class.dat"),
+        db_config.path().join(OffsetKind::ContractClass.file_name()),
         contract_class_offset,
     )?;
 
     let casm_offset = table.get(&db_transaction, &OffsetKind::Casm)?.unwrap_or_default();
-    let (casm_writer, casm_reader) =
-        open_file(mmap_file_config.clone(), db_config.path().join("casm.dat"), casm_offset)?;
+    let (casm_writer, casm_reader) = open_file(
+        mmap_file_config.clone(),
+        db_config.path().join(OffsetKind::Casm.file_name()),
+        casm_offset,
+    )?;
 
     let deprecated_contract_class_offset =
         table.get(&db_transaction, &OffsetKind::DeprecatedContractClass)?.unwrap_or_default();
     let (deprecated_contract_class_writer, deprecated_contract_class_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("deprecated_contract_class.dat"),
+        db_config.path().join(OffsetKind::DeprecatedContractClass.file_name()),
         deprecated_contract_class_offset,
     )?;
 
@@ -854,14 +970,17 @@ fn open_storage_files(
         table.get(&db_transaction, &OffsetKind::TransactionOutput)?.unwrap_or_default();
     let (transaction_output_writer, transaction_output_reader) = open_file(
         mmap_file_config.clone(),
-        db_config.path().join("transaction_output.dat"),
+        db_config.path().join(OffsetKind::TransactionOutput.file_name()),
         transaction_output_offset,
     )?;
 
     let transaction_offset =
         table.get(&db_transaction, &OffsetKind::Transaction)?.unwrap_or_default();
-    let (transaction_writer, transaction_reader) =
-        open_file(mmap_file_config, db_config.path().join("transaction.dat"), transaction_offset)?;
+    let (transaction_writer, transaction_reader) = open_file(
+        mmap_file_config,
+        db_config.path().join(OffsetKind::Transaction.file_name()),
+        transaction_offset,
+    )?;
 
     Ok((
         FileHandlers {
@@ -900,6 +1019,30 @@ pub enum OffsetKind {
     Transaction,
 }
 
+impl OffsetKind {
+    /// All the kinds of mmap files.
+    pub const ALL: [OffsetKind; 6] = [
+        OffsetKind::ThinStateDiff,
+        OffsetKind::ContractClass,
+        OffsetKind::Casm,
+        OffsetKind::DeprecatedContractClass,
+        OffsetKind::TransactionOutput,
+        OffsetKind::Transaction,
+    ];
+
+    /// Returns the name of the mmap file inside the storage directory.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            OffsetKind::ThinStateDiff => "thin_state_diff.dat",
+            OffsetKind::ContractClass => "contract_class.dat",
+            OffsetKind::Casm => "casm.dat",
+            OffsetKind::DeprecatedContractClass => "deprecated_contract_class.dat",
+            OffsetKind::TransactionOutput => "transaction_output.dat",
+            OffsetKind::Transaction => "transaction.dat",
+        }
+    }
+}
+
 /// A storage query. Used for benchmarking in the storage_benchmark binary.
 // TODO(dvir): add more queries (especially get casm).
 // TODO(dvir): consider move this, maybe to test_utils.
@@ -0,0 +1,235 @@
+//! A framework for migrating the storage schema.
+//!
+//! A [`Migration`] upgrades the data of some tables so that it matches a newer minor version of
+//! one of the components of the storage version (see the crate documentation). The migrations are
+//! registered in [`MIGRATIONS`], ordered by the version they produce, and a storage applies the
+//! migrations whose target version is higher than its own version.
+//!
+//! A migration runs in steps. Each step migrates a bounded batch of entries in its own write
+//! transaction and returns the position to continue from. The position is committed together with
+//! the step, so a long migration that is interrupted continues from the last committed step the
+//! next time it is applied. When the last step is done, the same transaction sets the storage
+//! version to the target version of the migration.
+//!
+//! [`open_storage`](crate::open_storage) applies the pending migrations. The `storage_migration`
+//! binary lists them and applies them ahead of time, optionally as a dry run in which the steps
+//! are executed and then aborted.
+
+#[cfg(test)]
+#[path = "migration_test.rs"]
+mod migration_test;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::db::table_types::Table;
+use crate::db::{TransactionKind, RW};
+use crate::version::{Version, VersionStorageReader, VersionStorageWriter};
+use crate::{StorageConfig, StorageReader, StorageResult, StorageTxn, StorageWriter};
+
+/// The registered migrations, ordered by their target versions. The target version of the last
+/// migration of each component must not be higher than the crate's version of the component.
+pub const MIGRATIONS: &[&dyn Migration] = &[];
+
+/// The components of the storage version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionComponent {
+    /// See [`STORAGE_VERSION_STATE`](crate::STORAGE_VERSION_STATE).
+    State,
+    /// See [`STORAGE_VERSION_BLOCKS`](crate::STORAGE_VERSION_BLOCKS).
+    Blocks,
+}
+
+/// A migration of the storage schema.
+pub trait Migration: Sync {
+    /// The unique name of the migration. The progress of the migration is stored under this name.
+    fn name(&self) -> &'static str;
+
+    /// The component of the storage version that the migration upgrades.
+    fn component(&self) -> VersionComponent;
+
+    /// The version of the component after the migration. It has the same major version as the
+    /// version before the migration.
+    fn target_version(&self) -> Version;
+
+    /// The names of the tables that the migration changes.
+    fn tables(&self) -> &'static [&'static str];
+
+    /// Migrates a bounded batch of entries, starting from the given position, which is None in
+    /// the first step. Returns the position to continue from, or None if the migration is done.
+    ///
+    /// A step should depend only on its position and on the data that was not migrated yet, since
+    /// the steps of a dry run are not committed.
+    fn up_step(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        position: Option<&[u8]>,
+    ) -> StorageResult<Option<Vec<u8>>>;
+}
+
+/// The status of a migration in a storage.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    /// The storage version is at least the target version of the migration.
+    Applied,
+    /// The migration should be applied and it didn't start yet.
+    Pending,
+    /// The migration should be applied and some of its steps were committed.
+    InProgress,
+    /// The storage doesn't have the component of the migration, or has a different major version.
+    NotApplicable,
+}
+
+/// A description of a migration and its status in a storage.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MigrationInfo {
+    pub name: String,
+    pub component: VersionComponent,
+    pub target_version: Version,
+    pub tables: Vec<String>,
+    pub status: MigrationStatus,
+}
+
+/// The result of applying a migration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MigrationOutcome {
+    /// The name of the migration.
+    pub name: String,
+    /// The number of steps that were executed.
+    pub steps: usize,
+    /// Whether the steps were aborted instead of committed.
+    pub dry_run: bool,
+}
+
+/// Opens a storage without setting or verifying its version, so that its migrations can be
+/// inspected and applied.
+pub fn open_storage_for_migration(
+    storage_config: StorageConfig,
+) -> StorageResult<(StorageReader, StorageWriter)> {
+    crate::open_storage_without_version_check(storage_config, false)
+}
+
+/// Returns the given migrations with their status in the storage.
+pub fn list_migrations(
+    reader: &StorageReader,
+    migrations: &[&dyn Migration],
+) -> StorageResult<Vec<MigrationInfo>> {
+    let txn = reader.begin_ro_txn()?;
+    migrations
+        .iter()
+        .map(|migration| {
+            Ok(MigrationInfo {
+                name: migration.name().to_owned(),
+                component: migration.component(),
+                target_version: migration.target_version(),
+                tables: migration.tables().iter().map(|table| table.to_string()).collect(),
+                status: migration_status(&txn, *migration)?,
+            })
+        })
+        .collect()
+}
+
+/// Applies the pending migrations out of the given ones, in their order. Each migration resumes
+/// from its last committed step. If `dry_run` is set, the steps are executed and aborted, so the
+/// storage is not changed.
+pub fn apply_migrations(
+    reader: &StorageReader,
+    writer: &mut StorageWriter,
+    migrations: &[&dyn Migration],
+    dry_run: bool,
+) -> StorageResult<Vec<MigrationOutcome>> {
+    let mut outcomes = Vec::new();
+    for migration in migrations {
+        let status = migration_status(&reader.begin_ro_txn()?, *migration)?;
+        if !matches!(status, MigrationStatus::Pending | MigrationStatus::InProgress) {
+            continue;
+        }
+        info!(
+            "Applying the storage migration {} to version {}{}.",
+            migration.name(),
+            migration.target_version(),
+            if dry_run { " (dry run)" } else { "" }
+        );
+        let steps = apply_migration(reader, writer, *migration, dry_run)?;
+        outcomes.push(MigrationOutcome { name: migration.name().to_owned(), steps, dry_run });
+    }
+    Ok(outcomes)
+}
+
+fn apply_migration(
+    reader: &StorageReader,
+    writer: &mut StorageWriter,
+    migration: &dyn Migration,
+    dry_run: bool,
+) -> StorageResult<usize> {
+    let mut position = reader.begin_ro_txn()?.get_migration_position(migration.name())?;
+    let mut steps = 0;
+    loop {
+        let txn = writer.begin_rw_txn()?;
+        let next_position = migration.up_step(&txn, position.as_deref())?;
+        steps += 1;
+        let Some(next_position) = next_position else {
+            if !dry_run {
+                txn.finish_migration(migration)?.commit()?;
+            }
+            debug!("The storage migration {} finished after {steps} steps.", migration.name());
+            return Ok(steps);
+        };
+        if !dry_run {
+            txn.set_migration_position(migration.name(), &next_position)?;
+            txn.commit()?;
+        }
+        position = Some(next_position);
+    }
+}
+
+fn migration_status<Mode: TransactionKind>(
+    txn: &StorageTxn<'_, Mode>,
+    migration: &dyn Migration,
+) -> StorageResult<MigrationStatus> {
+    let version = match migration.component() {
+        VersionComponent::State => txn.get_state_version()?,
+        VersionComponent::Blocks => txn.get_blocks_version()?,
+    };
+    let target_version = migration.target_version();
+    Ok(match version {
+        Some(version) if version.major == target_version.major => {
+            if version.minor >= target_version.minor {
+                MigrationStatus::Applied
+            } else if txn.get_migration_position(migration.name())?.is_some() {
+                MigrationStatus::InProgress
+            } else {
+                MigrationStatus::Pending
+            }
+        }
+        _ => MigrationStatus::NotApplicable,
+    })
+}
+
+impl<Mode: TransactionKind> StorageTxn<'_, Mode> {
+    fn get_migration_position(&self, name: &str) -> StorageResult<Option<Vec<u8>>> {
+        let migrations_table = self.open_table(&self.tables.migrations)?;
+        Ok(migrations_table.get(&self.txn, &name.to_owned())?)
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    fn set_migration_position(&self, name: &str, position: &[u8]) -> StorageResult<()> {
+        let migrations_table = self.open_table(&self.tables.migrations)?;
+        migrations_table.upsert(&self.txn, &name.to_owned(), &position.to_vec())?;
+        Ok(())
+    }
+
+    // Removes the position of the migration and sets the storage version to its target version.
+    fn finish_migration(self, migration: &dyn Migration) -> StorageResult<Self> {
+        let migrations_table = self.open_table(&self.tables.migrations)?;
+        migrations_table.delete(&self.txn, &migration.name().to_owned())?;
+        match migration.component() {
+            VersionComponent::State => self.set_state_version(&migration.target_version()),
+            VersionComponent::Blocks => self.set_blocks_version(&migration.target_version()),
+        }
+    }
+}
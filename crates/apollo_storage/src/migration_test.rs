@@ -0,0 +1,282 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::state::{StateNumber, ThinStateDiff};
+use starknet_api::{contract_address, felt};
+
+use crate::db::serialization::StorageSerdeEx;
+use crate::db::table_types::{DbCursorTrait, Table};
+use crate::db::RW;
+use crate::migration::{
+    apply_migrations,
+    list_migrations,
+    Migration,
+    MigrationInfo,
+    MigrationOutcome,
+    MigrationStatus,
+    VersionComponent,
+    MIGRATIONS,
+};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::get_test_storage;
+use crate::version::{Version, VersionStorageReader};
+use crate::{
+    StorageError,
+    StorageReader,
+    StorageResult,
+    StorageTxn,
+    StorageWriter,
+    STORAGE_VERSION_BLOCKS,
+    STORAGE_VERSION_STATE,
+};
+
+const N_CONTRACTS: u8 = 5;
+const BATCH_SIZE: usize = 2;
+
+// Increments all the nonces in batches, and fails on its second step if `fail` is set.
+struct IncrementNonces {
+    name: &'static str,
+    target_version: Version,
+    fail: AtomicBool,
+}
+
+impl IncrementNonces {
+    fn new(name: &'static str, target_version: Version) -> Self {
+        Self { name, target_version, fail: AtomicBool::new(false) }
+    }
+}
+
+fn state_minor_version(minor: u32) -> Version {
+    Version { major: STORAGE_VERSION_STATE.major, minor }
+}
+
+impl Migration for IncrementNonces {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn component(&self) -> VersionComponent {
+        VersionComponent::State
+    }
+
+    fn target_version(&self) -> Version {
+        self.target_version.clone()
+    }
+
+    fn tables(&self) -> &'static [&'static str] {
+        &["nonces"]
+    }
+
+    fn up_step(
+        &self,
+        txn: &StorageTxn<'_, RW>,
+        position: Option<&[u8]>,
+    ) -> StorageResult<Option<Vec<u8>>> {
+        if position.is_some() && self.fail.load(Ordering::SeqCst) {
+            return Err(StorageError::DBInconsistency { msg: "Injected failure.".to_owned() });
+        }
+        let start = match position {
+            Some(mut position) => <(ContractAddress, BlockNumber)>::deserialize(&mut position)
+                .expect("Invalid position."),
+            None => (ContractAddress::default(), BlockNumber(0)),
+        };
+        let nonces_table = txn.open_table(&txn.tables.nonces)?;
+        let mut cursor = nonces_table.cursor(&txn.txn)?;
+        let mut current = cursor.lower_bound(&start)?;
+        let mut batch = vec![];
+        while let Some((key, nonce)) = current {
+            if batch.len() == BATCH_SIZE {
+                for (key, nonce) in batch {
+                    nonces_table.upsert(&txn.txn, &key, &nonce)?;
+                }
+                return Ok(Some(key.serialize()?));
+            }
+            batch.push((key, Nonce(nonce.0 + felt!(1_u8))));
+            current = cursor.next()?;
+        }
+        for (key, nonce) in batch {
+            nonces_table.upsert(&txn.txn, &key, &nonce)?;
+        }
+        Ok(None)
+    }
+}
+
+fn contract(index: u8) -> ContractAddress {
+    contract_address!(0x100 + u128::from(index))
+}
+
+// Returns a storage in which the nonce of every contract is its index.
+fn storage_with_nonces() -> ((StorageReader, StorageWriter), tempfile::TempDir) {
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    let diff = ThinStateDiff {
+        nonces: (0..N_CONTRACTS).map(|index| (contract(index), Nonce(felt!(index)))).collect(),
+        ..Default::default()
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_state_diff(BlockNumber(0), diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+    ((reader, writer), temp_dir)
+}
+
+fn get_nonces(reader: &StorageReader) -> Vec<Nonce> {
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+    (0..N_CONTRACTS)
+        .map(|index| {
+            state_reader
+                .get_nonce_at(
+                    StateNumber::unchecked_right_after_block(BlockNumber(0)),
+                    &contract(index),
+                )
+                .unwrap()
+                .unwrap()
+        })
+        .collect()
+}
+
+fn expected_nonces(increment: u8) -> Vec<Nonce> {
+    (0..N_CONTRACTS).map(|index| Nonce(felt!(index + increment))).collect()
+}
+
+fn state_version(reader: &StorageReader) -> Version {
+    reader.begin_ro_txn().unwrap().get_state_version().unwrap().unwrap()
+}
+
+fn statuses(reader: &StorageReader, migrations: &[&dyn Migration]) -> Vec<MigrationStatus> {
+    list_migrations(reader, migrations).unwrap().into_iter().map(|info| info.status).collect()
+}
+
+#[test]
+fn apply_and_list_migrations() {
+    let ((reader, mut writer), _temp_dir) = storage_with_nonces();
+    let first = IncrementNonces::new("first", state_minor_version(STORAGE_VERSION_STATE.minor + 1));
+    let second =
+        IncrementNonces::new("second", state_minor_version(STORAGE_VERSION_STATE.minor + 2));
+    let migrations: [&dyn Migration; 2] = [&first, &second];
+
+    assert_eq!(
+        list_migrations(&reader, &migrations).unwrap()[0],
+        MigrationInfo {
+            name: "first".to_owned(),
+            component: VersionComponent::State,
+            target_version: first.target_version(),
+            tables: vec!["nonces".to_owned()],
+            status: MigrationStatus::Pending,
+        }
+    );
+
+    let outcomes = apply_migrations(&reader, &mut writer, &migrations, false).unwrap();
+    // 5 nonces in batches of 2.
+    let steps = 3;
+    assert_eq!(
+        outcomes,
+        vec![
+            MigrationOutcome { name: "first".to_owned(), steps, dry_run: false },
+            MigrationOutcome { name: "second".to_owned(), steps, dry_run: false },
+        ]
+    );
+    assert_eq!(get_nonces(&reader), expected_nonces(2));
+    assert_eq!(state_version(&reader), second.target_version());
+    assert_eq!(statuses(&reader, &migrations), vec![MigrationStatus::Applied; 2]);
+
+    // Applied migrations are not applied again.
+    assert_eq!(apply_migrations(&reader, &mut writer, &migrations, false).unwrap(), vec![]);
+    assert_eq!(get_nonces(&reader), expected_nonces(2));
+}
+
+#[test]
+fn resume_interrupted_migration() {
+    let ((reader, mut writer), _temp_dir) = storage_with_nonces();
+    let migration =
+        IncrementNonces::new("migration", state_minor_version(STORAGE_VERSION_STATE.minor + 1));
+    migration.fail.store(true, Ordering::SeqCst);
+    let migrations: [&dyn Migration; 1] = [&migration];
+
+    apply_migrations(&reader, &mut writer, &migrations, false).unwrap_err();
+    // Only the first batch was migrated.
+    let mut nonces = expected_nonces(0);
+    for nonce in &mut nonces[..BATCH_SIZE] {
+        *nonce = Nonce(nonce.0 + felt!(1_u8));
+    }
+    assert_eq!(get_nonces(&reader), nonces);
+    assert_eq!(state_version(&reader), STORAGE_VERSION_STATE);
+    assert_eq!(statuses(&reader, &migrations), vec![MigrationStatus::InProgress]);
+
+    migration.fail.store(false, Ordering::SeqCst);
+    let outcomes = apply_migrations(&reader, &mut writer, &migrations, false).unwrap();
+    assert_eq!(outcomes[0].steps, 2);
+    assert_eq!(get_nonces(&reader), expected_nonces(1));
+    assert_eq!(state_version(&reader), migration.target_version());
+    assert_eq!(statuses(&reader, &migrations), vec![MigrationStatus::Applied]);
+}
+
+#[test]
+fn dry_run_does_not_change_storage() {
+    let ((reader, mut writer), _temp_dir) = storage_with_nonces();
+    let migration =
+        IncrementNonces::new("migration", state_minor_version(STORAGE_VERSION_STATE.minor + 1));
+    let migrations: [&dyn Migration; 1] = [&migration];
+
+    let outcomes = apply_migrations(&reader, &mut writer, &migrations, true).unwrap();
+    assert_eq!(
+        outcomes,
+        vec![MigrationOutcome { name: "migration".to_owned(), steps: 3, dry_run: true }]
+    );
+    assert_eq!(get_nonces(&reader), expected_nonces(0));
+    assert_eq!(state_version(&reader), STORAGE_VERSION_STATE);
+    assert_eq!(statuses(&reader, &migrations), vec![MigrationStatus::Pending]);
+}
+
+#[test]
+fn migration_of_another_major_version_is_not_applicable() {
+    let ((reader, mut writer), _temp_dir) = storage_with_nonces();
+    let migration = IncrementNonces::new(
+        "migration",
+        Version { major: STORAGE_VERSION_STATE.major + 1, minor: 1 },
+    );
+    let migrations: [&dyn Migration; 1] = [&migration];
+
+    assert_eq!(statuses(&reader, &migrations), vec![MigrationStatus::NotApplicable]);
+    assert_eq!(apply_migrations(&reader, &mut writer, &migrations, false).unwrap(), vec![]);
+    assert_eq!(get_nonces(&reader), expected_nonces(0));
+}
+
+#[test]
+fn registered_migrations_are_ordered() {
+    for (component, crate_version) in [
+        (VersionComponent::State, STORAGE_VERSION_STATE),
+        (VersionComponent::Blocks, STORAGE_VERSION_BLOCKS),
+    ] {
+        let versions = MIGRATIONS
+            .iter()
+            .filter(|migration| migration.component() == component)
+            .map(|migration| migration.target_version())
+            .collect::<Vec<_>>();
+        for pair in versions.windows(2) {
+            assert!(
+                pair[0].major < pair[1].major
+                    || (pair[0].major == pair[1].major && pair[0].minor < pair[1].minor),
+                "The {component:?} migrations are not ordered: {} before {}.",
+                pair[0],
+                pair[1]
+            );
+        }
+        if let Some(last) = versions.last() {
+            assert!(
+                last.major < crate_version.major
+                    || (last.major == crate_version.major && last.minor <= crate_version.minor),
+                "The {component:?} migration to {last} is ahead of the crate version."
+            );
+        }
+    }
+    let mut names = MIGRATIONS.iter().map(|migration| migration.name()).collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), MIGRATIONS.len(), "The migration names are not unique.");
+}
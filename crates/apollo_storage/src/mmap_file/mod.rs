@@ -284,6 +284,14 @@ impl<V: ValueSerde, Mode: TransactionKind> FileHandler<V, Mode> {
         let mmap_file = self.mmap_file.lock().expect("Lock should not be poisoned");
         MMapFileStats { size: mmap_file.size, offset: mmap_file.offset }
     }
+
+    /// Returns the raw bytes of the file in the range `[0, len)`.
+    /// The caller must ensure that `len` is not greater than an offset that was already written
+    /// and flushed.
+    pub(crate) fn get_raw_bytes(&self, len: usize) -> &[u8] {
+        trace!("Reading the first {} raw bytes of the file", len);
+        unsafe { std::slice::from_raw_parts(self.memory_ptr, len) }
+    }
 }
 
 // This serialization writes the offset as 6 bytes and the length as 4 bytes.
@@ -126,6 +126,7 @@ use starknet_api::transaction::{
 use starknet_types_core::felt::Felt;
 use tracing::warn;
 
+use crate::base_layer::BaseLayerProof;
 use crate::body::events::EventIndex;
 use crate::body::TransactionIndex;
 use crate::compression_utils::{
@@ -155,6 +156,12 @@ auto_storage_serde! {
         pub l2_gas: ResourceBounds,
         pub l1_data_gas: ResourceBounds,
     }
+    pub struct BaseLayerProof {
+        pub first_proved_block: BlockNumber,
+        pub l1_block_number: u64,
+        pub l1_block_hash: [u8; 32],
+        pub l1_transaction_hash: [u8; 32],
+    }
     pub struct BlockHash(pub StarkHash);
     pub struct StorageBlockHeader {
         pub block_hash: BlockHash,
@@ -329,6 +336,7 @@ auto_storage_serde! {
         BaseLayerBlock = 6,
         ClassManagerBlock = 7,
         CompilerBackwardCompatibility = 8,
+        EventIndex = 9,
     }
     pub struct MessageToL1 {
         pub to_address: EthAddress,
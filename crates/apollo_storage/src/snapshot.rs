@@ -0,0 +1,413 @@
+//! Snapshots of the storage.
+//!
+//! A snapshot is taken from a single read transaction ([`StorageReader::begin_ro_txn`]), so it is
+//! consistent even while the process that owns the storage keeps writing to it through its
+//! [`crate::StorageWriter`]. The snapshot contains the raw content of all the tables and the
+//! prefixes of the memory mapped files that were committed when the transaction started.
+//!
+//! Another process must not open the storage of a running node, since opening the storage writes
+//! to it. The `storage_snapshot` binary therefore opens the storage with
+//! [`crate::open_storage_exclusively`], and the node must be stopped while it exports.
+//!
+//! # Archive format
+//! The archive starts with [`SNAPSHOT_MAGIC`] and the big-endian [`SNAPSHOT_FORMAT_VERSION`],
+//! followed by a zstd stream of:
+//! - A length prefixed JSON [`SnapshotHeader`].
+//! - A section for each memory mapped file: the file name and the file content.
+//! - A section for each table: the table name and its serialized key-value pairs.
+//! - An end section followed by the SHA-256 digest of all the preceding bytes of the stream.
+//!
+//! The archive can be restored only by a crate with the same storage version as the one that
+//! created it, since the data is copied without being deserialized.
+
+#[cfg(test)]
+#[path = "snapshot_test.rs"]
+mod snapshot_test;
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+use tracing::{debug, info};
+
+use crate::db::table_types::Table;
+use crate::header::HeaderStorageReader;
+use crate::state::StateStorageReader;
+use crate::version::{Version, VersionStorageReader};
+use crate::{
+    open_storage,
+    table_names,
+    OffsetKind,
+    StorageConfig,
+    StorageError,
+    StorageReader,
+    StorageScope,
+    STORAGE_VERSION_BLOCKS,
+    STORAGE_VERSION_STATE,
+};
+
+/// The first bytes of every snapshot archive.
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"APSNAPSH";
+/// The version of the archive format. Should be bumped on every change in the format.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+// The number of entries written in a single write transaction during import.
+const IMPORT_BATCH_SIZE: u64 = 100_000;
+
+const END_SECTION_TAG: u8 = 0;
+const FILE_SECTION_TAG: u8 = 1;
+const TABLE_SECTION_TAG: u8 = 2;
+
+const TABLE_ENTRY_FLAG: u8 = 1;
+const TABLE_END_FLAG: u8 = 0;
+
+/// Errors that may be returned when exporting or importing a snapshot.
+#[allow(missing_docs)]
+#[derive(thiserror::Error, Debug)]
+pub enum SnapshotError {
+    /// Errors related to the storage.
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    /// Errors related to reading or writing the archive.
+    #[error(transparent)]
+    IO(#[from] io::Error),
+    /// Errors related to the snapshot header.
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("The input is not a storage snapshot.")]
+    /// The archive doesn't start with [`SNAPSHOT_MAGIC`].
+    InvalidMagic,
+    /// The archive was created with an unsupported format.
+    #[error("Unsupported snapshot format version {found} (expected {expected}).")]
+    UnsupportedFormatVersion { found: u32, expected: u32 },
+    /// The archive was created by a crate with a different storage version.
+    #[error(
+        "Snapshot storage version {snapshot_version} is incompatible with the crate version \
+         {crate_version}."
+    )]
+    IncompatibleStorageVersion { crate_version: Version, snapshot_version: Version },
+    /// The archive belongs to another chain.
+    #[error(
+        "Snapshot chain id {snapshot_chain_id} does not match the target chain id {chain_id}."
+    )]
+    ChainIdMismatch { chain_id: ChainId, snapshot_chain_id: ChainId },
+    /// The import target already contains a database.
+    #[error("The target directory {0} already contains a database.")]
+    TargetNotEmpty(PathBuf),
+    /// The content of the archive doesn't match its checksum.
+    #[error("Snapshot checksum mismatch, the archive is corrupted.")]
+    ChecksumMismatch,
+    /// The archive content is malformed.
+    #[error("Malformed snapshot: {0}.")]
+    Malformed(String),
+}
+
+/// A type alias that maps to std::result::Result<T, SnapshotError>.
+pub type SnapshotResult<V> = std::result::Result<V, SnapshotError>;
+
+/// Metadata describing the content of a snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotHeader {
+    /// The chain of the snapshotted storage.
+    pub chain_id: ChainId,
+    /// The scope of the snapshotted storage.
+    pub scope: StorageScope,
+    /// The state version of the snapshotted storage.
+    pub state_version: Version,
+    /// The blocks version of the snapshotted storage. None in state-only storage.
+    pub blocks_version: Option<Version>,
+    /// The first block whose header is not in the snapshot.
+    pub header_marker: BlockNumber,
+    /// The first block whose state diff is not in the snapshot.
+    pub state_marker: BlockNumber,
+}
+
+/// A summary of an exported or imported snapshot.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotSummary {
+    /// The header of the snapshot.
+    pub header: SnapshotHeader,
+    /// The number of entries of each table.
+    pub table_entries: BTreeMap<String, u64>,
+    /// The number of bytes of each memory mapped file.
+    pub file_bytes: BTreeMap<String, u64>,
+}
+
+/// Writes a consistent snapshot of the storage of the chain `chain_id` into `output`.
+pub fn export_snapshot(
+    reader: &StorageReader,
+    chain_id: ChainId,
+    output: impl Write,
+) -> SnapshotResult<SnapshotSummary> {
+    let txn = reader.begin_ro_txn()?;
+    let header = SnapshotHeader {
+        chain_id,
+        scope: reader.scope,
+        state_version: txn.get_state_version()?.ok_or_else(|| StorageError::DBInconsistency {
+            msg: "Storage state version is missing.".to_string(),
+        })?,
+        blocks_version: txn.get_blocks_version()?,
+        header_marker: txn.get_header_marker()?,
+        state_marker: txn.get_state_marker()?,
+    };
+    info!("Exporting storage snapshot: {:?}", header);
+
+    let mut output = output;
+    output.write_all(SNAPSHOT_MAGIC)?;
+    output.write_u32::<BigEndian>(SNAPSHOT_FORMAT_VERSION)?;
+    let mut writer = HashingWriter::new(zstd::Encoder::new(output, ZSTD_COMPRESSION_LEVEL)?);
+
+    let serialized_header = serde_json::to_vec(&header)?;
+    write_bytes(&mut writer, &serialized_header)?;
+
+    let mut summary =
+        SnapshotSummary { header, table_entries: BTreeMap::new(), file_bytes: BTreeMap::new() };
+
+    let file_offsets_table = txn.open_table(&txn.tables.file_offsets)?;
+    for kind in OffsetKind::ALL {
+        // Only data that was committed up to this transaction is written. The files are append
+        // only, so this prefix can't change while exporting.
+        let offset = file_offsets_table
+            .get(&txn.txn, &kind)
+            .map_err(StorageError::from)?
+            .unwrap_or_default();
+        debug!("Exporting {} bytes of file {}.", offset, kind.file_name());
+        let len = u64::try_from(offset).expect("usize should fit in u64");
+        writer.write_u8(FILE_SECTION_TAG)?;
+        write_bytes(&mut writer, kind.file_name().as_bytes())?;
+        writer.write_u64::<BigEndian>(len)?;
+        writer.write_all(txn.file_handlers.get_raw_bytes(kind, offset))?;
+        summary.file_bytes.insert(kind.file_name().to_string(), len);
+    }
+
+    for table_name in table_names() {
+        debug!("Exporting table {}.", table_name);
+        writer.write_u8(TABLE_SECTION_TAG)?;
+        write_bytes(&mut writer, table_name.as_bytes())?;
+        let mut entries = 0;
+        for entry in txn.txn.raw_table_iter(table_name).map_err(StorageError::from)? {
+            let (key, value) = entry.map_err(StorageError::from)?;
+            writer.write_u8(TABLE_ENTRY_FLAG)?;
+            write_bytes(&mut writer, &key)?;
+            write_bytes(&mut writer, &value)?;
+            entries += 1;
+        }
+        writer.write_u8(TABLE_END_FLAG)?;
+        summary.table_entries.insert(table_name.to_string(), entries);
+    }
+
+    writer.write_u8(END_SECTION_TAG)?;
+    let (mut encoder, checksum) = writer.finalize();
+    encoder.write_all(&checksum)?;
+    encoder.finish()?.flush()?;
+    info!("Finished exporting storage snapshot.");
+    Ok(summary)
+}
+
+/// Restores a snapshot created by [`export_snapshot`] into a new storage, according to
+/// `storage_config`. The scope of the new storage is taken from the snapshot.
+///
+/// The target path must not contain a database. In case of an error, the content of the target
+/// path is undefined and it should be deleted before retrying.
+pub fn import_snapshot(
+    input: impl Read,
+    storage_config: StorageConfig,
+) -> SnapshotResult<SnapshotSummary> {
+    let mut input = input;
+    let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+    input.read_exact(&mut magic).map_err(|_| SnapshotError::InvalidMagic)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(SnapshotError::InvalidMagic);
+    }
+    let format_version = input.read_u32::<BigEndian>()?;
+    if format_version != SNAPSHOT_FORMAT_VERSION {
+        return Err(SnapshotError::UnsupportedFormatVersion {
+            found: format_version,
+            expected: SNAPSHOT_FORMAT_VERSION,
+        });
+    }
+    let mut reader = HashingReader::new(zstd::Decoder::new(input)?);
+
+    let header: SnapshotHeader = serde_json::from_slice(&read_bytes(&mut reader)?)?;
+    info!("Importing storage snapshot: {:?}", header);
+    verify_header(&header, &storage_config)?;
+
+    let db_path = storage_config.db_config.path();
+    if db_path.join("mdbx.dat").exists() {
+        return Err(SnapshotError::TargetNotEmpty(db_path));
+    }
+    fs::create_dir_all(&db_path)?;
+
+    let storage_config = StorageConfig { scope: header.scope, ..storage_config };
+    let mut summary =
+        SnapshotSummary { header, table_entries: BTreeMap::new(), file_bytes: BTreeMap::new() };
+    // The storage is opened only after the files are restored.
+    let mut storage = None;
+    loop {
+        match reader.read_u8()? {
+            FILE_SECTION_TAG => {
+                let file_name = read_string(&mut reader)?;
+                if !OffsetKind::ALL.iter().any(|kind| kind.file_name() == file_name) {
+                    return Err(SnapshotError::Malformed(format!("unknown file {file_name}")));
+                }
+                let len = reader.read_u64::<BigEndian>()?;
+                debug!("Importing {} bytes of file {}.", len, file_name);
+                let mut file = BufWriter::new(File::create(db_path.join(&file_name))?);
+                let copied = io::copy(&mut (&mut reader).take(len), &mut file)?;
+                if copied != len {
+                    return Err(SnapshotError::Malformed(format!("truncated file {file_name}")));
+                }
+                file.flush()?;
+                summary.file_bytes.insert(file_name, len);
+            }
+            TABLE_SECTION_TAG => {
+                let table_name = read_string(&mut reader)?;
+                if !table_names().contains(&table_name.as_str()) {
+                    return Err(SnapshotError::Malformed(format!("unknown table {table_name}")));
+                }
+                debug!("Importing table {}.", table_name);
+                if storage.is_none() {
+                    storage = Some(open_storage(storage_config.clone())?);
+                }
+                let (_, writer) = storage.as_mut().expect("Storage should be open");
+                let mut entries = 0;
+                let mut txn = writer.begin_rw_txn()?;
+                loop {
+                    match reader.read_u8()? {
+                        TABLE_ENTRY_FLAG => {}
+                        TABLE_END_FLAG => break,
+                        flag => {
+                            return Err(SnapshotError::Malformed(format!(
+                                "unknown entry flag {flag} in table {table_name}"
+                            )));
+                        }
+                    }
+                    let key = read_bytes(&mut reader)?;
+                    let value = read_bytes(&mut reader)?;
+                    txn.txn.put_raw(&table_name, &key, &value).map_err(StorageError::from)?;
+                    entries += 1;
+                    if entries % IMPORT_BATCH_SIZE == 0 {
+                        txn.commit()?;
+                        txn = writer.begin_rw_txn()?;
+                    }
+                }
+                txn.commit()?;
+                summary.table_entries.insert(table_name, entries);
+            }
+            END_SECTION_TAG => break,
+            tag => return Err(SnapshotError::Malformed(format!("unknown section tag {tag}"))),
+        }
+    }
+
+    let (mut decoder, expected_checksum) = reader.finalize();
+    let mut checksum = [0u8; 32];
+    decoder.read_exact(&mut checksum)?;
+    if checksum != expected_checksum {
+        return Err(SnapshotError::ChecksumMismatch);
+    }
+    if storage.is_none() {
+        return Err(SnapshotError::Malformed("no tables in snapshot".to_string()));
+    }
+    info!("Finished importing storage snapshot.");
+    Ok(summary)
+}
+
+fn verify_header(header: &SnapshotHeader, storage_config: &StorageConfig) -> SnapshotResult<()> {
+    if header.chain_id != storage_config.db_config.chain_id {
+        return Err(SnapshotError::ChainIdMismatch {
+            chain_id: storage_config.db_config.chain_id.clone(),
+            snapshot_chain_id: header.chain_id.clone(),
+        });
+    }
+    if header.state_version != STORAGE_VERSION_STATE {
+        return Err(SnapshotError::IncompatibleStorageVersion {
+            crate_version: STORAGE_VERSION_STATE,
+            snapshot_version: header.state_version.clone(),
+        });
+    }
+    if let Some(blocks_version) = &header.blocks_version {
+        if *blocks_version != STORAGE_VERSION_BLOCKS {
+            return Err(SnapshotError::IncompatibleStorageVersion {
+                crate_version: STORAGE_VERSION_BLOCKS,
+                snapshot_version: blocks_version.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn write_bytes(writer: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    writer.write_u32::<BigEndian>(bytes.len().try_into().expect("Item should fit in u32"))?;
+    writer.write_all(bytes)
+}
+
+fn read_bytes(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = reader.read_u32::<BigEndian>()?;
+    let mut bytes = vec![0u8; len.try_into().expect("u32 should fit in usize")];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_string(reader: &mut impl Read) -> SnapshotResult<String> {
+    String::from_utf8(read_bytes(reader)?)
+        .map_err(|_| SnapshotError::Malformed("invalid name".to_string()))
+}
+
+// Computes the checksum of all the bytes written through it.
+struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new() }
+    }
+
+    fn finalize(self) -> (W, [u8; 32]) {
+        (self.inner, self.hasher.finalize().into())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// Computes the checksum of all the bytes read through it.
+struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self { inner, hasher: Sha256::new() }
+    }
+
+    fn finalize(self) -> (R, [u8; 32]) {
+        (self.inner, self.hasher.finalize().into())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
@@ -0,0 +1,131 @@
+use std::io::Cursor;
+
+use apollo_test_utils::get_test_block;
+use assert_matches::assert_matches;
+use byteorder::{BigEndian, ReadBytesExt};
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::test_utils::CHAIN_ID_FOR_TESTS;
+use tempfile::TempDir;
+
+use crate::body::{BodyStorageReader, BodyStorageWriter};
+use crate::header::{HeaderStorageReader, HeaderStorageWriter};
+use crate::snapshot::{
+    export_snapshot,
+    import_snapshot,
+    SnapshotError,
+    FILE_SECTION_TAG,
+    SNAPSHOT_MAGIC,
+};
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::{get_test_config, get_test_storage};
+use crate::{open_storage, table_names, StorageReader, StorageScope};
+
+// Returns a reader of a storage with a single block, a snapshot of it and the storage directory.
+fn get_test_snapshot() -> (StorageReader, Vec<u8>, TempDir) {
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    let block = get_test_block(4, Some(2), None, None);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(BlockNumber(0), &block.header)
+        .unwrap()
+        .append_body(BlockNumber(0), block.body)
+        .unwrap()
+        .append_state_diff(BlockNumber(0), ThinStateDiff::default())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let mut archive = Vec::new();
+    let summary = export_snapshot(&reader, CHAIN_ID_FOR_TESTS.clone(), &mut archive).unwrap();
+    assert_eq!(summary.header.header_marker, BlockNumber(1));
+    assert_eq!(summary.table_entries.len(), table_names().len());
+    (reader, archive, temp_dir)
+}
+
+#[test]
+fn export_and_import_snapshot() {
+    let (reader, archive, _storage_dir) = get_test_snapshot();
+    let (config, _temp_dir) = get_test_config(None);
+    let summary = import_snapshot(archive.as_slice(), config.clone()).unwrap();
+    assert_eq!(summary.header.scope, StorageScope::FullArchive);
+
+    let (imported_reader, _) = open_storage(config).unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    let imported_txn = imported_reader.begin_ro_txn().unwrap();
+    assert_eq!(imported_txn.get_header_marker().unwrap(), BlockNumber(1));
+    assert_eq!(imported_txn.get_body_marker().unwrap(), BlockNumber(1));
+    assert_eq!(imported_txn.get_state_marker().unwrap(), BlockNumber(1));
+    assert_eq!(
+        imported_txn.get_block_header(BlockNumber(0)).unwrap(),
+        txn.get_block_header(BlockNumber(0)).unwrap()
+    );
+    assert_eq!(
+        imported_txn.get_block_transactions(BlockNumber(0)).unwrap(),
+        txn.get_block_transactions(BlockNumber(0)).unwrap()
+    );
+    assert_eq!(
+        imported_txn.get_block_transaction_outputs(BlockNumber(0)).unwrap(),
+        txn.get_block_transaction_outputs(BlockNumber(0)).unwrap()
+    );
+    assert_eq!(
+        imported_txn.get_state_diff(BlockNumber(0)).unwrap(),
+        txn.get_state_diff(BlockNumber(0)).unwrap()
+    );
+}
+
+#[test]
+fn import_snapshot_into_existing_storage() {
+    let (_reader, archive, _storage_dir) = get_test_snapshot();
+    let (config, _temp_dir) = get_test_config(None);
+    open_storage(config.clone()).unwrap();
+    assert_matches!(
+        import_snapshot(archive.as_slice(), config),
+        Err(SnapshotError::TargetNotEmpty(_))
+    );
+}
+
+#[test]
+fn import_corrupted_snapshot() {
+    let (_reader, archive, _storage_dir) = get_test_snapshot();
+
+    let mut wrong_magic = archive.clone();
+    wrong_magic[0] ^= 1;
+    let (config, _temp_dir) = get_test_config(None);
+    assert_matches!(
+        import_snapshot(wrong_magic.as_slice(), config),
+        Err(SnapshotError::InvalidMagic)
+    );
+
+    // Recompress the content with a flipped byte in the data of a file, which is copied as is, so
+    // only the checksum can detect it.
+    let header_len = SNAPSHOT_MAGIC.len() + 4;
+    let mut content = zstd::decode_all(&archive[header_len..]).unwrap();
+    content[first_file_byte_position(&content)] ^= 1;
+    let mut corrupted = archive[..header_len].to_vec();
+    corrupted.extend(zstd::encode_all(content.as_slice(), 0).unwrap());
+    let (config, _temp_dir) = get_test_config(None);
+    assert_matches!(
+        import_snapshot(corrupted.as_slice(), config),
+        Err(SnapshotError::ChecksumMismatch)
+    );
+}
+
+// Returns the position of the first byte of the first non-empty file in the decompressed content
+// of a snapshot.
+fn first_file_byte_position(content: &[u8]) -> usize {
+    let mut cursor = Cursor::new(content);
+    let header_len = cursor.read_u32::<BigEndian>().unwrap();
+    cursor.set_position(cursor.position() + u64::from(header_len));
+    loop {
+        assert_eq!(cursor.read_u8().unwrap(), FILE_SECTION_TAG);
+        let file_name_len = cursor.read_u32::<BigEndian>().unwrap();
+        cursor.set_position(cursor.position() + u64::from(file_name_len));
+        let file_len = cursor.read_u64::<BigEndian>().unwrap();
+        if file_len > 0 {
+            return usize::try_from(cursor.position()).unwrap();
+        }
+    }
+}
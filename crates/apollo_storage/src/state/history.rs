@@ -0,0 +1,258 @@
+//! Range queries over the state history of a single contract.
+//!
+//! The state tables are keyed by the contract address first and by the block number last, so the
+//! history of a contract is stored contiguously. The functions here are methods of
+//! [`StateReader`], and they complement its point queries.
+//!
+//! # Example
+//! ```
+//! # use apollo_storage::{db::DbConfig, StorageConfig};
+//! use apollo_storage::open_storage;
+//! use apollo_storage::state::{StateStorageReader, StateStorageWriter};
+//! use indexmap::indexmap;
+//! use starknet_api::block::BlockNumber;
+//! use starknet_api::core::ContractAddress;
+//! use starknet_api::state::{StorageKey, ThinStateDiff};
+//! use starknet_types_core::felt::Felt;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir_handle.path().to_path_buf(),
+//! #     chain_id: starknet_api::core::ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! let address = ContractAddress::from(1_u8);
+//! let key = StorageKey::from(2_u8);
+//! for (block_number, value) in [(0, 10_u8), (1, 11), (2, 12)] {
+//!     let state_diff = ThinStateDiff {
+//!         storage_diffs: indexmap! { address => indexmap! { key => Felt::from(value) } },
+//!         ..Default::default()
+//!     };
+//!     writer
+//!         .begin_rw_txn()?
+//!         .append_state_diff(BlockNumber(block_number), state_diff)?
+//!         .commit()?;
+//! }
+//!
+//! let txn = reader.begin_ro_txn()?;
+//! let state_reader = txn.get_state_reader()?;
+//! let changes = state_reader
+//!     .iter_storage_changes(address, BlockNumber(1)..=BlockNumber(2), None)?
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(changes.len(), 2);
+//! assert_eq!(changes[0].value, Felt::from(11_u8));
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "history_test.rs"]
+mod history_test;
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::db::serialization::{NoVersionValueWrapper, ValueSerde};
+use crate::db::table_types::{CommonPrefix, DbCursor, DbCursorTrait, Table, TableType};
+use crate::db::{DbTransaction, TableHandle, TransactionKind};
+use crate::state::StateReader;
+use crate::StorageResult;
+
+// Block numbers are stored as u32, so this is greater than the block number of every entry.
+const AFTER_LAST_BLOCK_NUMBER: BlockNumber = BlockNumber(u32::MAX as u64);
+
+type ContractStorageKey = ((ContractAddress, StorageKey), BlockNumber);
+type ContractStorageCursor<'txn, Mode> =
+    DbCursor<'txn, Mode, ContractStorageKey, NoVersionValueWrapper<Felt>, CommonPrefix>;
+
+/// A change of a value in the storage of a contract.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StorageChange {
+    /// The storage key whose value was changed.
+    pub key: StorageKey,
+    /// The block in which the value was changed.
+    pub block_number: BlockNumber,
+    /// The value after the change.
+    pub value: Felt,
+}
+
+impl<Mode: TransactionKind> StateReader<'_, Mode> {
+    /// Returns an iterator over the storage changes of the contract in the given blocks, ordered by
+    /// the storage key and then by the block number.
+    ///
+    /// # Arguments
+    /// * address - the contract whose storage changes are returned.
+    /// * blocks - the blocks of the changes, inclusive.
+    /// * start - if given, the iteration starts from the first change whose (key, block number) is
+    ///   not smaller than it. Used for resuming a previous iteration.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error searching the table.
+    pub fn iter_storage_changes(
+        &self,
+        address: ContractAddress,
+        blocks: RangeInclusive<BlockNumber>,
+        start: Option<(StorageKey, BlockNumber)>,
+    ) -> StorageResult<StorageChangesIter<'_, Mode>> {
+        let mut cursor = self.storage_table.cursor(self.txn)?;
+        let (start_key, start_block) = start.unwrap_or((StorageKey::default(), *blocks.start()));
+        let start_block = start_block.max(*blocks.start()).min(AFTER_LAST_BLOCK_NUMBER);
+        let current = cursor.lower_bound(&((address, start_key), start_block))?;
+        Ok(StorageChangesIter { cursor, address, blocks, current })
+    }
+
+    /// Returns an iterator over the storage keys that were ever written in the storage of the
+    /// contract, in ascending order.
+    ///
+    /// # Arguments
+    /// * address - the contract whose storage keys are returned.
+    /// * start_key - if given, the iteration starts from the first key that is not smaller than it.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error searching the table.
+    pub fn iter_storage_keys(
+        &self,
+        address: ContractAddress,
+        start_key: Option<StorageKey>,
+    ) -> StorageResult<StorageKeysIter<'_, Mode>> {
+        let mut cursor = self.storage_table.cursor(self.txn)?;
+        let current =
+            cursor.lower_bound(&((address, start_key.unwrap_or_default()), BlockNumber(0)))?;
+        Ok(StorageKeysIter { cursor, address, current })
+    }
+
+    /// Returns the nonce changes of the contract in the given blocks, ordered by the block number.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error searching the table.
+    pub fn get_nonce_history(
+        &self,
+        address: ContractAddress,
+        blocks: RangeInclusive<BlockNumber>,
+    ) -> StorageResult<Vec<(BlockNumber, Nonce)>> {
+        get_contract_history(self.txn, &self.nonces_table, address, blocks)
+    }
+
+    /// Returns the class hash changes of the contract in the given blocks, ordered by the block
+    /// number. These are the deployment of the contract and the replacements of its class.
+    ///
+    /// # Errors
+    /// Returns [`StorageError`](crate::StorageError) if there was an error searching the table.
+    pub fn get_class_hash_history(
+        &self,
+        address: ContractAddress,
+        blocks: RangeInclusive<BlockNumber>,
+    ) -> StorageResult<Vec<(BlockNumber, ClassHash)>> {
+        get_contract_history(self.txn, &self.deployed_contracts_table, address, blocks)
+    }
+}
+
+/// An iterator over the storage changes of a contract. See
+/// [`StateReader::iter_storage_changes`].
+pub struct StorageChangesIter<'txn, Mode: TransactionKind> {
+    cursor: ContractStorageCursor<'txn, Mode>,
+    address: ContractAddress,
+    blocks: RangeInclusive<BlockNumber>,
+    // The entry the cursor points to. None if there are no more entries.
+    current: Option<(ContractStorageKey, Felt)>,
+}
+
+impl<Mode: TransactionKind> StorageChangesIter<'_, Mode> {
+    fn next_change(&mut self) -> StorageResult<Option<StorageChange>> {
+        while let Some((((address, key), block_number), value)) = self.current.take() {
+            if address != self.address {
+                return Ok(None);
+            }
+            if block_number < *self.blocks.start() {
+                // Skip to the first relevant change of this key.
+                self.current = self.cursor.lower_bound(&((address, key), *self.blocks.start()))?;
+            } else if block_number > *self.blocks.end() {
+                // Skip to the first change of the next key.
+                self.current =
+                    self.cursor.lower_bound(&((address, key), AFTER_LAST_BLOCK_NUMBER))?;
+            } else {
+                self.current = self.cursor.next()?;
+                return Ok(Some(StorageChange { key, block_number, value }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<Mode: TransactionKind> Iterator for StorageChangesIter<'_, Mode> {
+    type Item = StorageResult<StorageChange>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_change().transpose()
+    }
+}
+
+/// An iterator over the storage keys of a contract. See [`StateReader::iter_storage_keys`].
+pub struct StorageKeysIter<'txn, Mode: TransactionKind> {
+    cursor: ContractStorageCursor<'txn, Mode>,
+    address: ContractAddress,
+    // The entry the cursor points to. None if there are no more entries.
+    current: Option<(ContractStorageKey, Felt)>,
+}
+
+impl<Mode: TransactionKind> StorageKeysIter<'_, Mode> {
+    fn next_key(&mut self) -> StorageResult<Option<StorageKey>> {
+        let Some((((address, key), _block_number), _value)) = self.current.take() else {
+            return Ok(None);
+        };
+        if address != self.address {
+            return Ok(None);
+        }
+        self.current = self.cursor.lower_bound(&((address, key), AFTER_LAST_BLOCK_NUMBER))?;
+        Ok(Some(key))
+    }
+}
+
+impl<Mode: TransactionKind> Iterator for StorageKeysIter<'_, Mode> {
+    type Item = StorageResult<StorageKey>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_key().transpose()
+    }
+}
+
+// Returns the values of the contract in the given blocks, from a table that is keyed by the
+// contract address and the block number.
+fn get_contract_history<'env, Mode, V, T>(
+    txn: &'env DbTransaction<'env, Mode>,
+    table: &'env TableHandle<'env, (ContractAddress, BlockNumber), V, T>,
+    address: ContractAddress,
+    blocks: RangeInclusive<BlockNumber>,
+) -> StorageResult<Vec<(BlockNumber, V::Value)>>
+where
+    Mode: TransactionKind,
+    V: ValueSerde + Debug,
+    T: TableType,
+    TableHandle<'env, (ContractAddress, BlockNumber), V, T>:
+        Table<'env, Key = (ContractAddress, BlockNumber), Value = V, TableVariant = T>,
+    DbCursor<'env, Mode, (ContractAddress, BlockNumber), V, T>:
+        DbCursorTrait<Key = (ContractAddress, BlockNumber), Value = V>,
+{
+    let mut cursor = table.cursor(txn)?;
+    let mut history = vec![];
+    let mut current =
+        cursor.lower_bound(&(address, (*blocks.start()).min(AFTER_LAST_BLOCK_NUMBER)))?;
+    while let Some(((got_address, block_number), value)) = current {
+        if got_address != address || block_number > *blocks.end() {
+            break;
+        }
+        history.push((block_number, value));
+        current = cursor.next()?;
+    }
+    Ok(history)
+}
@@ -0,0 +1,159 @@
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::state::{StorageKey, ThinStateDiff};
+use starknet_api::{class_hash, contract_address, felt, storage_key};
+
+use crate::state::history::StorageChange;
+use crate::state::{StateStorageReader, StateStorageWriter};
+use crate::test_utils::get_test_storage;
+use crate::StorageReader;
+
+fn contract_0() -> ContractAddress {
+    contract_address!("0x100")
+}
+
+fn contract_1() -> ContractAddress {
+    contract_address!("0x101")
+}
+
+fn key_0() -> StorageKey {
+    storage_key!("0x10")
+}
+
+fn key_1() -> StorageKey {
+    storage_key!("0x11")
+}
+
+// Block 0 deploys both contracts, blocks 1 and 2 update the storage of the first contract, and
+// block 2 replaces its class.
+fn storage_with_history() -> (StorageReader, tempfile::TempDir) {
+    let diffs = [
+        ThinStateDiff {
+            deployed_contracts: indexmap! {
+                contract_0() => class_hash!("0x1000"),
+                contract_1() => class_hash!("0x1000"),
+            },
+            storage_diffs: indexmap! {
+                contract_0() => indexmap! { key_0() => felt!(1_u8), key_1() => felt!(2_u8) },
+                contract_1() => indexmap! { key_0() => felt!(3_u8) },
+            },
+            nonces: indexmap! { contract_0() => Nonce(felt!(1_u8)) },
+            ..Default::default()
+        },
+        ThinStateDiff {
+            storage_diffs: indexmap! { contract_0() => indexmap! { key_0() => felt!(4_u8) } },
+            nonces: indexmap! { contract_0() => Nonce(felt!(2_u8)) },
+            ..Default::default()
+        },
+        ThinStateDiff {
+            deployed_contracts: indexmap! { contract_0() => class_hash!("0x1001") },
+            storage_diffs: indexmap! { contract_0() => indexmap! { key_0() => felt!(5_u8) } },
+            ..Default::default()
+        },
+    ];
+    let ((reader, mut writer), temp_dir) = get_test_storage();
+    for (block_number, diff) in diffs.into_iter().enumerate() {
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .append_state_diff(BlockNumber(block_number.try_into().unwrap()), diff)
+            .unwrap()
+            .commit()
+            .unwrap();
+    }
+    (reader, temp_dir)
+}
+
+fn change(key: StorageKey, block_number: u64, value: u8) -> StorageChange {
+    StorageChange { key, block_number: BlockNumber(block_number), value: felt!(value) }
+}
+
+#[test]
+fn iter_storage_changes() {
+    let (reader, _temp_dir) = storage_with_history();
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+    let get_changes = |address, blocks, start| {
+        state_reader
+            .iter_storage_changes(address, blocks, start)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+
+    assert_eq!(
+        get_changes(contract_0(), BlockNumber(0)..=BlockNumber(2), None),
+        vec![
+            change(key_0(), 0, 1),
+            change(key_0(), 1, 4),
+            change(key_0(), 2, 5),
+            change(key_1(), 0, 2)
+        ]
+    );
+    assert_eq!(
+        get_changes(contract_0(), BlockNumber(1)..=BlockNumber(1), None),
+        vec![change(key_0(), 1, 4)]
+    );
+    assert_eq!(
+        get_changes(contract_0(), BlockNumber(0)..=BlockNumber(2), Some((key_0(), BlockNumber(2)))),
+        vec![change(key_0(), 2, 5), change(key_1(), 0, 2)]
+    );
+    assert_eq!(
+        get_changes(contract_1(), BlockNumber(0)..=BlockNumber(5), None),
+        vec![change(key_0(), 0, 3)]
+    );
+    assert_eq!(get_changes(contract_1(), BlockNumber(1)..=BlockNumber(2), None), vec![]);
+    assert_eq!(
+        get_changes(contract_address!("0x102"), BlockNumber(0)..=BlockNumber(2), None),
+        vec![]
+    );
+}
+
+#[test]
+fn iter_storage_keys() {
+    let (reader, _temp_dir) = storage_with_history();
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+    let get_keys = |address, start_key| {
+        state_reader
+            .iter_storage_keys(address, start_key)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+
+    assert_eq!(get_keys(contract_0(), None), vec![key_0(), key_1()]);
+    assert_eq!(get_keys(contract_0(), Some(key_1())), vec![key_1()]);
+    assert_eq!(get_keys(contract_1(), None), vec![key_0()]);
+    assert_eq!(get_keys(contract_address!("0x99"), None), vec![]);
+}
+
+#[test]
+fn nonce_and_class_hash_history() {
+    let (reader, _temp_dir) = storage_with_history();
+    let txn = reader.begin_ro_txn().unwrap();
+    let state_reader = txn.get_state_reader().unwrap();
+
+    assert_eq!(
+        state_reader.get_nonce_history(contract_0(), BlockNumber(0)..=BlockNumber(2)).unwrap(),
+        vec![(BlockNumber(0), Nonce(felt!(1_u8))), (BlockNumber(1), Nonce(felt!(2_u8)))]
+    );
+    assert_eq!(
+        state_reader.get_nonce_history(contract_0(), BlockNumber(1)..=BlockNumber(2)).unwrap(),
+        vec![(BlockNumber(1), Nonce(felt!(2_u8)))]
+    );
+    assert_eq!(
+        state_reader.get_nonce_history(contract_1(), BlockNumber(0)..=BlockNumber(2)).unwrap(),
+        vec![]
+    );
+    assert_eq!(
+        state_reader.get_class_hash_history(contract_0(), BlockNumber(0)..=BlockNumber(2)).unwrap(),
+        vec![(BlockNumber(0), class_hash!("0x1000")), (BlockNumber(2), class_hash!("0x1001"))]
+    );
+    assert_eq!(
+        state_reader.get_class_hash_history(contract_0(), BlockNumber(0)..=BlockNumber(1)).unwrap(),
+        vec![(BlockNumber(0), class_hash!("0x1000"))]
+    );
+}
@@ -3,7 +3,8 @@
 //! Import [`StateStorageReader`] and [`StateStorageWriter`] to read and write data related to state
 //! diffs using a [`StorageTxn`].
 //!
-//! See [`StateReader`] struct for querying specific data from the state.
+//! See [`StateReader`] struct for querying specific data from the state, and the [`history`]
+//! module for querying the state history of a contract.
 //!
 //! # Example
 //! ```
@@ -51,6 +52,7 @@
 
 #[doc(hidden)]
 pub mod data;
+pub mod history;
 #[cfg(test)]
 mod state_test;
 
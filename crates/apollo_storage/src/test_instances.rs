@@ -15,6 +15,7 @@ use starknet_api::transaction::{
     TransactionOffsetInBlock,
 };
 
+use crate::base_layer::BaseLayerProof;
 use crate::body::TransactionIndex;
 use crate::compression_utils::IsCompressed;
 use crate::header::StorageBlockHeader;
@@ -24,6 +25,12 @@ use crate::version::Version;
 use crate::{EventIndex, MarkerKind, OffsetKind, TransactionMetadata};
 
 auto_impl_get_test_instance! {
+    pub struct BaseLayerProof {
+        pub first_proved_block: BlockNumber,
+        pub l1_block_number: u64,
+        pub l1_block_hash: [u8; 32],
+        pub l1_transaction_hash: [u8; 32],
+    }
     pub struct StorageBlockHeader {
         pub block_hash: BlockHash,
         pub parent_hash: BlockHash,
@@ -65,6 +72,7 @@ auto_impl_get_test_instance! {
         BaseLayerBlock = 6,
         ClassManagerBlock = 7,
         CompilerBackwardCompatibility = 8,
+        EventIndex = 9,
     }
     pub enum OffsetKind {
         ThinStateDiff = 0,
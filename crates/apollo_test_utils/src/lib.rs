@@ -1069,6 +1069,11 @@ impl<T: GetTestInstance> GetTestInstance for Vec<T> {
         vec![T::get_test_instance(rng)]
     }
 }
+impl<T: GetTestInstance, const N: usize> GetTestInstance for [T; N] {
+    fn get_test_instance(rng: &mut ChaCha8Rng) -> Self {
+        std::array::from_fn(|_| T::get_test_instance(rng))
+    }
+}
 impl<K: GetTestInstance + Eq + Hash, V: GetTestInstance> GetTestInstance for HashMap<K, V> {
     fn get_test_instance(rng: &mut ChaCha8Rng) -> Self {
         let mut res = HashMap::with_capacity(1);
@@ -1,6 +1,12 @@
 use std::collections::BTreeMap;
-
-use apollo_config::dumping::{append_sub_config_name, ser_param, SerializeConfig};
+use std::path::PathBuf;
+
+use apollo_config::dumping::{
+    append_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_sierra_multicompile::config::SierraCompilationConfig;
 use serde::{Deserialize, Serialize};
@@ -9,6 +15,7 @@ use starknet_api::core::ClassHash;
 use crate::blockifier::transaction_executor::DEFAULT_STACK_SIZE;
 use crate::state::contract_class_manager::DEFAULT_COMPILATION_REQUEST_CHANNEL_SIZE;
 use crate::state::global_cache::GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST;
+use crate::state::native_artifact_cache::DEFAULT_NATIVE_ARTIFACT_CACHE_MAX_SIZE;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct TransactionExecutorConfig {
@@ -90,6 +97,7 @@ pub struct ContractClassManagerConfig {
     pub cairo_native_run_config: CairoNativeRunConfig,
     pub contract_cache_size: usize,
     pub native_compiler_config: SierraCompilationConfig,
+    pub native_artifact_cache_config: NativeArtifactCacheConfig,
 }
 
 impl Default for ContractClassManagerConfig {
@@ -98,6 +106,7 @@ impl Default for ContractClassManagerConfig {
             cairo_native_run_config: CairoNativeRunConfig::default(),
             contract_cache_size: GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
             native_compiler_config: SierraCompilationConfig::default(),
+            native_artifact_cache_config: NativeArtifactCacheConfig::default(),
         }
     }
 }
@@ -130,6 +139,46 @@ impl SerializeConfig for ContractClassManagerConfig {
             self.native_compiler_config.dump(),
             "native_compiler_config",
         ));
+        dump.append(&mut append_sub_config_name(
+            self.native_artifact_cache_config.dump(),
+            "native_artifact_cache_config",
+        ));
+        dump
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct NativeArtifactCacheConfig {
+    /// The directory of the on-disk cache. The cache is disabled if not set.
+    pub directory: Option<PathBuf>,
+    /// The total size of the cached artifacts (in bytes), above which the least recently used
+    /// artifacts are evicted.
+    pub max_size: u64,
+}
+
+impl Default for NativeArtifactCacheConfig {
+    fn default() -> Self {
+        Self { directory: None, max_size: DEFAULT_NATIVE_ARTIFACT_CACHE_MAX_SIZE }
+    }
+}
+
+impl SerializeConfig for NativeArtifactCacheConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        let mut dump = BTreeMap::from_iter([ser_param(
+            "max_size",
+            &self.max_size,
+            "The total size of the cached Cairo native artifacts (bytes), above which the least \
+             recently used artifacts are evicted.",
+            ParamPrivacyInput::Public,
+        )]);
+        dump.extend(ser_optional_param(
+            &self.directory,
+            "".into(),
+            "directory",
+            "The directory of the on-disk cache of Cairo native artifacts. The cache is disabled \
+             if not set.",
+            ParamPrivacyInput::Public,
+        ));
         dump
     }
 }
@@ -145,6 +194,7 @@ pub struct CairoNativeRunConfig {
     pub run_cairo_native: bool,
     pub wait_on_native_compilation: bool,
     pub channel_size: usize,
+    pub differential_execution: bool,
     // TODO(AvivG): implement `native_classes_whitelist` logic.
     pub native_classes_whitelist: NativeClassesWhitelist,
 }
@@ -155,6 +205,7 @@ impl Default for CairoNativeRunConfig {
             run_cairo_native: false,
             wait_on_native_compilation: false,
             channel_size: DEFAULT_COMPILATION_REQUEST_CHANNEL_SIZE,
+            differential_execution: false,
             native_classes_whitelist: NativeClassesWhitelist::All,
         }
     }
@@ -181,6 +232,14 @@ impl SerializeConfig for CairoNativeRunConfig {
                 "The size of the compilation request channel.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "differential_execution",
+                &self.differential_execution,
+                "Executes each entry point of a natively compiled class with both Cairo native \
+                 and the Cairo VM, records any mismatch between the results, and uses the Cairo \
+                 VM result.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "native_classes_whitelist",
                 &self.native_classes_whitelist,
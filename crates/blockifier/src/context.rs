@@ -111,6 +111,10 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) bouncer_config: BouncerConfig,
+    // Whether to record the state accesses of each call, for debugging.
+    pub(crate) trace_state_accesses: bool,
+    // Whether to record the Cairo steps of each call, for debugging.
+    pub(crate) trace_vm_steps: bool,
 }
 
 impl BlockContext {
@@ -120,7 +124,14 @@ impl BlockContext {
         versioned_constants: VersionedConstants,
         bouncer_config: BouncerConfig,
     ) -> Self {
-        BlockContext { block_info, chain_info, versioned_constants, bouncer_config }
+        BlockContext {
+            block_info,
+            chain_info,
+            versioned_constants,
+            bouncer_config,
+            trace_state_accesses: false,
+            trace_vm_steps: false,
+        }
     }
 
     pub fn block_info(&self) -> &BlockInfo {
@@ -135,6 +146,27 @@ impl BlockContext {
         &self.versioned_constants
     }
 
+    pub fn trace_state_accesses(&self) -> bool {
+        self.trace_state_accesses
+    }
+
+    /// Sets whether the calls executed in this block record their storage reads and writes, their
+    /// class hash changes and the nonce changes of the transactions, see
+    /// [StateAccessTrace](crate::execution::call_info::StateAccessTrace).
+    pub fn set_trace_state_accesses(&mut self, trace_state_accesses: bool) {
+        self.trace_state_accesses = trace_state_accesses;
+    }
+
+    pub fn trace_vm_steps(&self) -> bool {
+        self.trace_vm_steps
+    }
+
+    /// Sets whether the calls executed in this block by the Cairo VM record the registers of each
+    /// step they run, see [VmTrace](crate::execution::call_info::VmTrace).
+    pub fn set_trace_vm_steps(&mut self, trace_vm_steps: bool) {
+        self.trace_vm_steps = trace_vm_steps;
+    }
+
     pub fn to_tx_context(
         &self,
         tx_info_creator: &impl TransactionInfoCreator,
@@ -5,7 +5,7 @@ use std::ops::{Add, AddAssign};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use serde::Serialize;
 use starknet_api::block::{BlockHash, BlockNumber};
-use starknet_api::core::{ClassHash, ContractAddress, EthAddress};
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress, Nonce};
 use starknet_api::execution_resources::{GasAmount, GasVector};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::fields::GasVectorComputationMode;
@@ -14,7 +14,7 @@ use starknet_types_core::felt::Felt;
 
 use crate::blockifier_versioned_constants::VersionedConstants;
 use crate::execution::contract_class::TrackedResource;
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
 use crate::state::cached_state::StorageEntry;
 use crate::utils::u64_from_usize;
 
@@ -208,6 +208,65 @@ pub struct StorageAccessTracker {
     // TODO(Aner): add tests for storage tracking of contract 0x1
     pub read_block_hash_values: Vec<BlockHash>,
     pub accessed_blocks: HashSet<BlockNumber>,
+    // Recorded only if enabled in the block context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_access_trace: Option<StateAccessTrace>,
+}
+
+/// The state accesses made by a call, excluding the ones made by its inner calls, in the order in
+/// which they were made. Accesses of calls that were later reverted are included.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StateAccessTrace {
+    pub storage_reads: Vec<StorageRead>,
+    pub storage_writes: Vec<StorageWrite>,
+    pub class_hash_changes: Vec<ClassHashChange>,
+    pub nonce_changes: Vec<NonceChange>,
+}
+
+/// A read of the storage of the called contract.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StorageRead {
+    pub key: StorageKey,
+    pub value: Felt,
+}
+
+/// A write to the storage of the called contract.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StorageWrite {
+    pub key: StorageKey,
+    pub old_value: Felt,
+    pub new_value: Felt,
+}
+
+/// A change of the class hash of a contract, either by deploying it or by replacing its class.
+/// The old class hash of a deployed contract is zero.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ClassHashChange {
+    pub contract_address: ContractAddress,
+    pub old_class_hash: ClassHash,
+    pub new_class_hash: ClassHash,
+}
+
+/// A change of the nonce of an account. Nonces aren't changed by calls, so the nonce change of the
+/// account that sent a transaction is recorded in the trace of the validation call of the
+/// transaction, or in the trace of its execution call if the validation was skipped.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct NonceChange {
+    pub contract_address: ContractAddress,
+    pub old_nonce: Nonce,
+    pub new_nonce: Nonce,
+}
+
+impl StateAccessTrace {
+    /// Returns an empty trace if the block context enables tracing, and `None` otherwise.
+    pub fn new_if_enabled(context: &EntryPointExecutionContext) -> Option<Self> {
+        context.tx_context.block_context.trace_state_accesses.then(Self::default)
+    }
 }
 
 /// Represents the full effects of executing an entry point, including the inner calls it invoked.
@@ -223,6 +282,27 @@ pub struct CallInfo {
 
     // Additional information gathered during execution.
     pub storage_access_tracker: StorageAccessTracker,
+    // Recorded only if enabled in the block context, and only for calls run by the Cairo VM.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_trace: Option<VmTrace>,
+}
+
+/// The Cairo steps run by a call, excluding the steps of its inner calls, in the order in which
+/// they were run.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct VmTrace {
+    pub steps: Vec<VmStep>,
+}
+
+/// The registers of the VM before running a step. The PC is the offset of the instruction in the
+/// bytecode of the class, and the AP and FP are offsets in the execution segment.
+#[cfg_attr(feature = "transaction_serde", derive(serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct VmStep {
+    pub pc: usize,
+    pub ap: usize,
+    pub fp: usize,
 }
 
 impl CallInfo {
@@ -22,7 +22,12 @@ use crate::execution::entry_point::{
     ExecutableCallEntryPoint,
 };
 use crate::execution::errors::{PostExecutionError, PreExecutionError};
-use crate::execution::execution_utils::{read_execution_retdata, Args, ReadOnlySegments};
+use crate::execution::execution_utils::{
+    extract_vm_trace,
+    read_execution_retdata,
+    Args,
+    ReadOnlySegments,
+};
 use crate::state::state_api::State;
 
 pub struct VmExecutionContext<'a> {
@@ -86,7 +91,7 @@ pub fn initialize_execution_context<'a>(
     let entry_point_pc = resolve_entry_point_pc(call, &compiled_class)?;
     // Instantiate Cairo runner.
     let proof_mode = false;
-    let trace_enabled = false;
+    let trace_enabled = context.tx_context.block_context.trace_vm_steps();
     let allow_missing_builtins = false;
     let program_base = None;
     let mut runner =
@@ -257,6 +262,7 @@ pub fn finalize_execution(
 
     let vm_resources = &vm_resources_without_inner_calls
         + &CallInfo::summarize_vm_resources(syscall_handler.inner_calls.iter());
+    let vm_trace = extract_vm_trace(&mut runner, syscall_handler.context)?;
 
     Ok(CallInfo {
         call: call.into(),
@@ -273,8 +279,10 @@ pub fn finalize_execution(
         storage_access_tracker: StorageAccessTracker {
             storage_read_values: syscall_handler.read_values,
             accessed_storage_keys: syscall_handler.accessed_keys,
+            state_access_trace: syscall_handler.state_access_trace,
             ..Default::default()
         },
+        vm_trace,
     })
 }
 
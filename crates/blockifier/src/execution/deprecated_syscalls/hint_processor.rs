@@ -28,7 +28,14 @@ use starknet_types_core::felt::{Felt, FromStrError};
 use thiserror::Error;
 
 use crate::context::TransactionContext;
-use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::execution::call_info::{
+    CallInfo,
+    OrderedEvent,
+    OrderedL2ToL1Message,
+    StateAccessTrace,
+    StorageRead,
+    StorageWrite,
+};
 use crate::execution::common_hints::{
     extended_builtin_hint_processor,
     ExecutionMode,
@@ -182,6 +189,7 @@ pub struct DeprecatedSyscallHintProcessor<'a> {
     // Additional information gathered during execution.
     pub read_values: Vec<Felt>,
     pub accessed_keys: HashSet<StorageKey>,
+    pub state_access_trace: Option<StateAccessTrace>,
 
     // Additional fields.
     // Invariant: must only contain allowed hints.
@@ -200,6 +208,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         caller_address: ContractAddress,
         class_hash: ClassHash,
     ) -> Self {
+        let state_access_trace = StateAccessTrace::new_if_enabled(context);
         DeprecatedSyscallHintProcessor {
             state,
             context,
@@ -214,6 +223,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
             accessed_keys: HashSet::new(),
+            state_access_trace,
             builtin_hint_processor: extended_builtin_hint_processor(),
             tx_signature_start_ptr: None,
             tx_info_start_ptr: None,
@@ -437,6 +447,9 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address, key)?;
         self.read_values.push(value);
+        if let Some(trace) = &mut self.state_access_trace {
+            trace.storage_reads.push(StorageRead { key, value });
+        }
 
         Ok(StorageReadResponse { value })
     }
@@ -446,6 +459,10 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         key: StorageKey,
         value: Felt,
     ) -> DeprecatedSyscallResult<StorageWriteResponse> {
+        if let Some(trace) = &mut self.state_access_trace {
+            let old_value = self.state.get_storage_at(self.storage_address, key)?;
+            trace.storage_writes.push(StorageWrite { key, old_value, new_value: value });
+        }
         self.accessed_keys.insert(key);
         self.state.set_storage_at(self.storage_address, key, value)?;
 
@@ -27,7 +27,12 @@ use self::hint_processor::{
     DeprecatedSyscallHintProcessor,
 };
 use super::syscalls::exceeds_event_size_limit;
-use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
+use crate::execution::call_info::{
+    ClassHashChange,
+    MessageToL1,
+    OrderedEvent,
+    OrderedL2ToL1Message,
+};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
 use crate::execution::execution_utils::{
@@ -381,6 +386,13 @@ pub fn deploy(
         &mut remaining_gas,
     )?;
     syscall_handler.inner_calls.push(call_info);
+    if let Some(trace) = &mut syscall_handler.state_access_trace {
+        trace.class_hash_changes.push(ClassHashChange {
+            contract_address: deployed_contract_address,
+            old_class_hash: ClassHash::default(),
+            new_class_hash: request.class_hash,
+        });
+    }
 
     Ok(DeployResponse { contract_address: deployed_contract_address })
 }
@@ -682,6 +694,15 @@ pub fn replace_class(
 ) -> DeprecatedSyscallResult<ReplaceClassResponse> {
     // Ensure the class is declared (by reading it).
     syscall_handler.state.get_compiled_class(request.class_hash)?;
+    if let Some(trace) = &mut syscall_handler.state_access_trace {
+        trace.class_hash_changes.push(ClassHashChange {
+            contract_address: syscall_handler.storage_address,
+            old_class_hash: syscall_handler
+                .state
+                .get_class_hash_at(syscall_handler.storage_address)?,
+            new_class_hash: request.class_hash,
+        });
+    }
     syscall_handler.state.set_class_hash_at(syscall_handler.storage_address, request.class_hash)?;
 
     Ok(ReplaceClassResponse {})
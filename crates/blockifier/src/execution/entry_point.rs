@@ -33,6 +33,8 @@ use crate::execution::errors::{
     PreExecutionError,
 };
 use crate::execution::execution_utils::execute_entry_point_call_wrapper;
+#[cfg(feature = "cairo_native")]
+use crate::execution::native::differential_execution::DifferentialExecutionBackend;
 use crate::execution::stack_trace::{extract_trailing_cairo1_revert_trace, Cairo1RevertHeader};
 use crate::state::state_api::{State, StateResult};
 use crate::transaction::objects::{HasRelatedFeeType, TransactionInfo};
@@ -270,7 +272,7 @@ pub struct ConstructorContext {
     pub caller_address: ContractAddress,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SierraGasRevertTracker {
     initial_remaining_gas: GasAmount,
     last_seen_remaining_gas: GasAmount,
@@ -326,6 +328,11 @@ pub struct EntryPointExecutionContext {
 
     // Used to support charging for gas consumed in blockifier revert flow.
     pub sierra_gas_revert_tracker: SierraGasRevertTracker,
+
+    // Set while an entry point is executed in differential execution mode; determines the backend
+    // that executes the natively compiled classes it calls.
+    #[cfg(feature = "cairo_native")]
+    pub(crate) differential_execution_backend: Option<DifferentialExecutionBackend>,
 }
 
 impl EntryPointExecutionContext {
@@ -346,6 +353,36 @@ impl EntryPointExecutionContext {
             tracked_resource_stack: vec![],
             revert_infos: ExecutionRevertInfo(vec![]),
             sierra_gas_revert_tracker,
+            #[cfg(feature = "cairo_native")]
+            differential_execution_backend: None,
+        }
+    }
+
+    /// Returns a context for executing the current entry point again with the given backend,
+    /// without affecting this context.
+    #[cfg(feature = "cairo_native")]
+    pub(crate) fn new_for_differential_execution(
+        &self,
+        backend: DifferentialExecutionBackend,
+    ) -> Self {
+        let current_revert_info =
+            self.revert_infos.0.last().expect("Missing contract revert info.");
+        Self {
+            tx_context: self.tx_context.clone(),
+            vm_run_resources: self.vm_run_resources.clone(),
+            n_emitted_events: self.n_emitted_events,
+            n_sent_messages_to_l1: self.n_sent_messages_to_l1,
+            current_recursion_depth: Arc::new(RefCell::new(*self.current_recursion_depth.borrow())),
+            execution_mode: self.execution_mode,
+            tracked_resource_stack: self.tracked_resource_stack.clone(),
+            revert_infos: ExecutionRevertInfo(vec![EntryPointRevertInfo::new(
+                current_revert_info.contract_address,
+                current_revert_info.original_class_hash,
+                self.n_emitted_events,
+                self.n_sent_messages_to_l1,
+            )]),
+            sierra_gas_revert_tracker: self.sierra_gas_revert_tracker.clone(),
+            differential_execution_backend: Some(backend),
         }
     }
 
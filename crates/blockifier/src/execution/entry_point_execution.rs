@@ -20,6 +20,7 @@ use crate::execution::entry_point::{
 };
 use crate::execution::errors::{EntryPointExecutionError, PostExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{
+    extract_vm_trace,
     read_execution_retdata,
     write_felt,
     write_maybe_relocatable,
@@ -130,7 +131,8 @@ pub fn initialize_execution_context_with_runner_mode<'a>(
 
     // Instantiate Cairo runner.
     let proof_mode = execution_runner_mode.proof_mode();
-    let trace_enabled = execution_runner_mode.trace_enabled();
+    let trace_enabled = execution_runner_mode.trace_enabled()
+        || context.tx_context.block_context.trace_vm_steps();
     let mut runner = CairoRunner::new(
         &compiled_class.0.program,
         LayoutName::starknet,
@@ -411,6 +413,7 @@ pub fn finalize_execution(
     };
 
     syscall_handler.finalize();
+    let vm_trace = extract_vm_trace(&mut runner, syscall_handler.base.context)?;
 
     let vm_resources = &vm_resources_without_inner_calls
         + &CallInfo::summarize_vm_resources(syscall_handler.base.inner_calls.iter());
@@ -428,6 +431,7 @@ pub fn finalize_execution(
         tracked_resource,
         resources: vm_resources,
         storage_access_tracker: syscall_handler_base.storage_access_tracker,
+        vm_trace,
     })
 }
 
@@ -7,8 +7,9 @@ use rstest::rstest;
 use starknet_api::abi::abi_utils::selector_from_name;
 use starknet_api::execution_resources::GasAmount;
 use starknet_api::transaction::fields::Calldata;
+use starknet_api::{calldata, felt};
 
-use crate::context::ChainInfo;
+use crate::context::{BlockContext, ChainInfo};
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::TrackedResource;
 use crate::execution::entry_point::CallEntryPoint;
@@ -95,3 +96,33 @@ fn test_charged_resources_computation(
 
     assert_charged_resource_as_expected_rec(&call_info);
 }
+
+#[rstest]
+#[case::vm(CairoVersion::Cairo1(RunnableCairo1::Casm))]
+#[case::cairo0(CairoVersion::Cairo0)]
+fn test_vm_trace(#[case] cairo_version: CairoVersion) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Not traced by default.
+    let call_info = entry_point_call.clone().execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.vm_trace, None);
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.set_trace_vm_steps(true);
+    let traced_call_info = entry_point_call
+        .execute_directly_given_block_context(&mut state, block_context)
+        .unwrap();
+    assert_eq!(traced_call_info.execution, call_info.execution);
+    let steps = traced_call_info.vm_trace.expect("The call should be traced.").steps;
+    // The run starts and ends in the frame of the entry point.
+    let first_step = steps.first().unwrap();
+    assert_eq!(first_step.ap, first_step.fp);
+    assert_eq!(steps.last().unwrap().fp, first_step.fp);
+}
@@ -74,6 +74,8 @@ pub enum PostExecutionError {
     #[error("Validation failed: {0}.")]
     SecurityValidationError(String),
     #[error(transparent)]
+    TraceError(#[from] TraceError),
+    #[error(transparent)]
     VirtualMachineError(#[from] VirtualMachineError),
     #[error("Malformed return data : {error_message}.")]
     MalformedReturnData { error_message: String },
@@ -21,7 +21,7 @@ use starknet_api::deprecated_contract_class::Program as DeprecatedProgram;
 use starknet_api::transaction::fields::Calldata;
 use starknet_types_core::felt::Felt;
 
-use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata, VmStep, VmTrace};
 use crate::execution::contract_class::{RunnableCompiledClass, TrackedResource};
 use crate::execution::entry_point::{
     execute_constructor_entry_point,
@@ -38,6 +38,8 @@ use crate::execution::errors::{
     PreExecutionError,
 };
 #[cfg(feature = "cairo_native")]
+use crate::execution::native::differential_execution::{self, DifferentialExecutionBackend};
+#[cfg(feature = "cairo_native")]
 use crate::execution::native::entry_point_execution as native_entry_point_execution;
 use crate::execution::stack_trace::{extract_trailing_cairo1_revert_trace, Cairo1RevertHeader};
 use crate::execution::syscalls::hint_processor::{ENTRYPOINT_NOT_FOUND_ERROR, OUT_OF_GAS_ERROR};
@@ -129,7 +131,10 @@ pub fn execute_entry_point_call(
         }
         #[cfg(feature = "cairo_native")]
         RunnableCompiledClass::V1Native(compiled_class) => {
-            if context.tracked_resource_stack.last() == Some(&TrackedResource::CairoSteps) {
+            if context.tracked_resource_stack.last() == Some(&TrackedResource::CairoSteps)
+                || context.differential_execution_backend
+                    == Some(DifferentialExecutionBackend::CairoVm)
+            {
                 // We cannot run native with cairo steps as the tracked resources (it's a vm
                 // resouorce).
                 entry_point_execution::execute_entry_point_call(
@@ -138,6 +143,17 @@ pub fn execute_entry_point_call(
                     state,
                     context,
                 )
+            } else if let (Some(mismatches), None) = (
+                compiled_class.differential_execution_mismatches().cloned(),
+                context.differential_execution_backend,
+            ) {
+                differential_execution::execute_entry_point_call(
+                    call,
+                    compiled_class,
+                    &mismatches,
+                    state,
+                    context,
+                )
             } else {
                 native_entry_point_execution::execute_entry_point_call(
                     call,
@@ -170,6 +186,41 @@ pub fn read_execution_retdata(
     Ok(Retdata(felt_range_from_ptr(&runner.vm, Relocatable::try_from(retdata_ptr)?, retdata_size)?))
 }
 
+/// Returns the steps run by the given runner, or `None` if the block context does not enable VM
+/// step tracing. Must be called after the run ended.
+pub fn extract_vm_trace(
+    runner: &mut CairoRunner,
+    context: &EntryPointExecutionContext,
+) -> Result<Option<VmTrace>, PostExecutionError> {
+    if !context.tx_context.block_context.trace_vm_steps {
+        return Ok(None);
+    }
+
+    runner.vm.segments.compute_effective_sizes();
+    let relocation_table = runner.vm.segments.relocate_segments()?;
+    runner.relocate_trace(&relocation_table)?;
+
+    // Translate the relocated addresses back to offsets in the program and execution segments.
+    let program_base =
+        runner.program_base.expect("The `program_base` field should be initialized after the run.");
+    let program_start = relocation_table[usize::try_from(program_base.segment_index)
+        .expect("The program segment should not be a temporary segment.")]
+        + program_base.offset;
+    let execution_start = relocation_table[1];
+    let steps = runner
+        .relocated_trace
+        .take()
+        .expect("The trace should be relocated.")
+        .into_iter()
+        .map(|entry| VmStep {
+            pc: entry.pc - program_start,
+            ap: entry.ap - execution_start,
+            fp: entry.fp - execution_start,
+        })
+        .collect();
+    Ok(Some(VmTrace { steps }))
+}
+
 pub fn felt_from_ptr(
     vm: &VirtualMachine,
     ptr: &mut Relocatable,
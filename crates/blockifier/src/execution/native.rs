@@ -1,7 +1,10 @@
 pub mod contract_class;
+pub mod differential_execution;
 pub mod entry_point_execution;
 pub mod syscall_handler;
 pub mod utils;
 
+#[cfg(test)]
+pub mod differential_execution_test;
 #[cfg(test)]
 pub mod utils_test;
@@ -1,4 +1,5 @@
 use std::ops::Deref;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 
 use cairo_native::executor::AotContractExecutor;
@@ -7,16 +8,31 @@ use starknet_api::core::EntryPointSelector;
 use crate::execution::contract_class::{CompiledClassV1, EntryPointV1};
 use crate::execution::entry_point::EntryPointTypeAndSelector;
 use crate::execution::errors::PreExecutionError;
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct NativeCompiledClassV1(pub Arc<NativeCompiledClassV1Inner>);
+#[derive(Clone, Debug)]
+pub struct NativeCompiledClassV1 {
+    inner: Arc<NativeCompiledClassV1Inner>,
+    /// If set, the class is executed with both Cairo native and the Cairo VM, and the mismatches
+    /// between the results are counted here.
+    differential_execution_mismatches: Option<Arc<AtomicU64>>,
+}
 impl Deref for NativeCompiledClassV1 {
     type Target = NativeCompiledClassV1Inner;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.inner
+    }
+}
+
+// The differential execution mode is a property of the class manager that loaded the class, and
+// not of the class itself, therefore we exclude it from the comparison.
+impl PartialEq for NativeCompiledClassV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
     }
 }
 
+impl Eq for NativeCompiledClassV1 {}
+
 impl NativeCompiledClassV1 {
     pub(crate) fn constructor_selector(&self) -> Option<EntryPointSelector> {
         self.casm.constructor_selector()
@@ -29,7 +45,17 @@ impl NativeCompiledClassV1 {
     pub fn new(executor: AotContractExecutor, casm: CompiledClassV1) -> NativeCompiledClassV1 {
         let contract = NativeCompiledClassV1Inner::new(executor, casm);
 
-        Self(Arc::new(contract))
+        Self { inner: Arc::new(contract), differential_execution_mismatches: None }
+    }
+
+    /// Returns the class, set to be executed with both Cairo native and the Cairo VM. Mismatches
+    /// between the results are added to the given counter.
+    pub fn with_differential_execution(self, mismatches: Arc<AtomicU64>) -> NativeCompiledClassV1 {
+        Self { differential_execution_mismatches: Some(mismatches), ..self }
+    }
+
+    pub(crate) fn differential_execution_mismatches(&self) -> Option<&Arc<AtomicU64>> {
+        self.differential_execution_mismatches.as_ref()
     }
 
     pub fn get_entry_point(
@@ -0,0 +1,129 @@
+//! Differential execution of natively compiled classes.
+//!
+//! Each entry point of a class in differential execution mode is executed twice: first with Cairo
+//! native, against a throwaway state and context, and then with the Cairo VM. The call infos and
+//! the state writes of both executions are compared, and the Cairo VM result is used.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::execution::call_info::{CallExecution, CallInfo};
+use crate::execution::entry_point::{
+    EntryPointExecutionContext,
+    EntryPointExecutionResult,
+    ExecutableCallEntryPoint,
+};
+use crate::execution::entry_point_execution;
+use crate::execution::native::contract_class::NativeCompiledClassV1;
+use crate::execution::native::entry_point_execution as native_entry_point_execution;
+use crate::state::cached_state::{CachedState, MutRefState, StateMaps};
+use crate::state::state_api::{State, StateResult};
+
+/// The backend that executes the natively compiled classes called during a differential
+/// execution.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DifferentialExecutionBackend {
+    CairoNative,
+    CairoVm,
+}
+
+/// Executes the entry point with both Cairo native and the Cairo VM, and returns the Cairo VM
+/// result. A mismatch between the results is logged and added to the given counter.
+pub fn execute_entry_point_call(
+    call: ExecutableCallEntryPoint,
+    compiled_class: NativeCompiledClassV1,
+    mismatches: &AtomicU64,
+    state: &mut dyn State,
+    context: &mut EntryPointExecutionContext,
+) -> EntryPointExecutionResult<CallInfo> {
+    let (native_result, native_writes) = {
+        let mut native_context =
+            context.new_for_differential_execution(DifferentialExecutionBackend::CairoNative);
+        let mut native_state = CachedState::new(MutRefState::new(&mut *state));
+        let native_result = native_entry_point_execution::execute_entry_point_call(
+            call.clone(),
+            compiled_class.clone(),
+            &mut native_state,
+            &mut native_context,
+        );
+        (native_result, native_state.cache.into_inner().writes)
+    };
+
+    let (vm_result, vm_writes) = {
+        context.differential_execution_backend = Some(DifferentialExecutionBackend::CairoVm);
+        let mut vm_state = CachedState::new(MutRefState::new(&mut *state));
+        let vm_result = entry_point_execution::execute_entry_point_call(
+            call.clone(),
+            compiled_class.casm(),
+            &mut vm_state,
+            context,
+        );
+        context.differential_execution_backend = None;
+        (vm_result, vm_state.cache.into_inner().writes)
+    };
+
+    if let Some(mismatch) = find_mismatch(&vm_result, &native_result, &vm_writes, &native_writes) {
+        mismatches.fetch_add(1, Ordering::Relaxed);
+        log::warn!(
+            "Cairo native and Cairo VM executions of entry point {} of class {} differ: {}.",
+            call.entry_point_selector.0,
+            call.class_hash,
+            mismatch
+        );
+        log::debug!("Cairo VM result: {vm_result:?}, Cairo native result: {native_result:?}.");
+    }
+
+    apply_writes(state, vm_writes)?;
+    vm_result
+}
+
+/// Returns a description of the first difference between the executions, if any.
+fn find_mismatch(
+    vm_result: &EntryPointExecutionResult<CallInfo>,
+    native_result: &EntryPointExecutionResult<CallInfo>,
+    vm_writes: &StateMaps,
+    native_writes: &StateMaps,
+) -> Option<String> {
+    match (vm_result, native_result) {
+        (Ok(vm_call_info), Ok(native_call_info)) => {
+            let vm_executions: Vec<&CallExecution> =
+                vm_call_info.iter().map(|call_info| &call_info.execution).collect();
+            let native_executions: Vec<&CallExecution> =
+                native_call_info.iter().map(|call_info| &call_info.execution).collect();
+            if vm_executions.len() != native_executions.len() {
+                return Some(format!(
+                    "the number of calls differs ({} vs {})",
+                    vm_executions.len(),
+                    native_executions.len()
+                ));
+            }
+            if let Some(call_index) = vm_executions
+                .iter()
+                .zip(&native_executions)
+                .position(|(vm_execution, native_execution)| vm_execution != native_execution)
+            {
+                return Some(format!("the execution of call {call_index} differs"));
+            }
+            if vm_writes != native_writes {
+                return Some("the state writes differ".to_string());
+            }
+            None
+        }
+        (Err(_), Err(_)) => None,
+        (Ok(_), Err(native_error)) => {
+            Some(format!("only Cairo native failed to execute: {native_error}"))
+        }
+        (Err(vm_error), Ok(_)) => Some(format!("only the Cairo VM failed to execute: {vm_error}")),
+    }
+}
+
+/// Applies the writes of an entry point execution to the state. Entry points don't change nonces
+/// or declare classes, so only the storage and the class hashes are written.
+fn apply_writes(state: &mut dyn State, writes: StateMaps) -> StateResult<()> {
+    for ((contract_address, key), value) in writes.storage {
+        state.set_storage_at(contract_address, key, value)?;
+    }
+    for (contract_address, class_hash) in writes.class_hashes {
+        state.set_class_hash_at(contract_address, class_hash)?;
+    }
+    Ok(())
+}
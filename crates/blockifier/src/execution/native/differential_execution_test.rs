@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use blockifier_test_utils::cairo_versions::{CairoVersion, RunnableCairo1};
+use blockifier_test_utils::contracts::FeatureContract;
+use starknet_api::abi::abi_utils::selector_from_name;
+use starknet_api::state::StorageKey;
+use starknet_api::{calldata, felt};
+
+use crate::context::ChainInfo;
+use crate::execution::contract_class::RunnableCompiledClass;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::retdata;
+use crate::state::state_api::{State, StateReader};
+use crate::test_utils::contracts::FeatureContractTrait;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, BALANCE};
+
+#[test]
+fn test_differential_execution_uses_cairo_vm_result() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1(RunnableCairo1::Native));
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let RunnableCompiledClass::V1Native(native_class) = test_contract.get_runnable_class() else {
+        panic!("Expected a native compiled class.");
+    };
+    let mismatches = Arc::new(AtomicU64::new(0));
+    state
+        .set_contract_class(
+            test_contract.get_class_hash(),
+            RunnableCompiledClass::V1Native(
+                native_class.with_differential_execution(mismatches.clone()),
+            ),
+        )
+        .unwrap();
+
+    let key = felt!(1234_u16);
+    let value = felt!(18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let storage_address = entry_point_call.storage_address;
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+
+    assert_eq!(call_info.execution.retdata, retdata![value]);
+    assert_eq!(
+        state.get_storage_at(storage_address, StorageKey::try_from(key).unwrap()).unwrap(),
+        value
+    );
+    assert_eq!(mismatches.load(Ordering::Relaxed), 0);
+}
@@ -98,6 +98,7 @@ fn create_callinfo(
         resources: vm_resources,
         inner_calls: syscall_handler.base.inner_calls,
         storage_access_tracker: syscall_handler.base.storage_access_tracker,
+        vm_trace: None,
         tracked_resource: TrackedResource::SierraGas,
     })
 }
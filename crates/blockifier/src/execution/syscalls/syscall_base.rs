@@ -18,10 +18,14 @@ use super::exceeds_event_size_limit;
 use crate::abi::constants;
 use crate::execution::call_info::{
     CallInfo,
+    ClassHashChange,
     MessageToL1,
     OrderedEvent,
     OrderedL2ToL1Message,
+    StateAccessTrace,
     StorageAccessTracker,
+    StorageRead,
+    StorageWrite,
 };
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::entry_point::{
@@ -81,6 +85,10 @@ impl<'state> SyscallHandlerBase<'state> {
                 .expect("Missing contract revert info.")
                 .original_values,
         );
+        let storage_access_tracker = StorageAccessTracker {
+            state_access_trace: StateAccessTrace::new_if_enabled(context),
+            ..Default::default()
+        };
         SyscallHandlerBase {
             state,
             call,
@@ -88,7 +96,7 @@ impl<'state> SyscallHandlerBase<'state> {
             events: Vec::new(),
             l2_to_l1_messages: Vec::new(),
             inner_calls: Vec::new(),
-            storage_access_tracker: StorageAccessTracker::default(),
+            storage_access_tracker,
             original_values,
             revert_info_idx,
         }
@@ -148,6 +156,9 @@ impl<'state> SyscallHandlerBase<'state> {
         self.storage_access_tracker.accessed_storage_keys.insert(key);
         let value = self.state.get_storage_at(self.call.storage_address, key)?;
         self.storage_access_tracker.storage_read_values.push(value);
+        if let Some(trace) = &mut self.storage_access_tracker.state_access_trace {
+            trace.storage_reads.push(StorageRead { key, value });
+        }
         Ok(value)
     }
 
@@ -161,6 +172,10 @@ impl<'state> SyscallHandlerBase<'state> {
             hash_map::Entry::Occupied(_) => {}
         }
 
+        if let Some(trace) = &mut self.storage_access_tracker.state_access_trace {
+            let old_value = self.state.get_storage_at(contract_address, key)?;
+            trace.storage_writes.push(StorageWrite { key, old_value, new_value: value });
+        }
         self.storage_access_tracker.accessed_storage_keys.insert(key);
         self.state.set_storage_at(contract_address, key, value)?;
 
@@ -240,6 +255,13 @@ impl<'state> SyscallHandlerBase<'state> {
         if !is_cairo1(&compiled_class) {
             return Err(SyscallExecutionError::ForbiddenClassReplacement { class_hash });
         }
+        if let Some(trace) = &mut self.storage_access_tracker.state_access_trace {
+            trace.class_hash_changes.push(ClassHashChange {
+                contract_address: self.call.storage_address,
+                old_class_hash: self.state.get_class_hash_at(self.call.storage_address)?,
+                new_class_hash: class_hash,
+            });
+        }
         self.state.set_class_hash_at(self.call.storage_address, class_hash)?;
         Ok(())
     }
@@ -277,6 +299,13 @@ impl<'state> SyscallHandlerBase<'state> {
             constructor_calldata,
             remaining_gas,
         )?;
+        if let Some(trace) = &mut self.storage_access_tracker.state_access_trace {
+            trace.class_hash_changes.push(ClassHashChange {
+                contract_address: deployed_contract_address,
+                old_class_hash: ClassHash::default(),
+                new_class_hash: class_hash,
+            });
+        }
         Ok((deployed_contract_address, call_info))
     }
 
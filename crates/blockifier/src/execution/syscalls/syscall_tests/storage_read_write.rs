@@ -5,8 +5,8 @@ use starknet_api::state::StorageKey;
 use starknet_api::{calldata, felt};
 use test_case::test_case;
 
-use crate::context::ChainInfo;
-use crate::execution::call_info::CallExecution;
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::call_info::{CallExecution, StateAccessTrace, StorageRead, StorageWrite};
 use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::syscalls::syscall_tests::constants::REQUIRED_GAS_STORAGE_READ_WRITE_TEST;
 use crate::retdata;
@@ -44,3 +44,45 @@ fn test_storage_read_write(runnable_version: RunnableCairo1) {
         state.get_storage_at(storage_address, StorageKey::try_from(key).unwrap()).unwrap();
     assert_eq!(value_from_state, value);
 }
+
+#[cfg_attr(
+    feature = "cairo_native",
+    test_case(CairoVersion::Cairo1(RunnableCairo1::Native); "Native")
+)]
+#[test_case(CairoVersion::Cairo1(RunnableCairo1::Casm); "VM")]
+#[test_case(CairoVersion::Cairo0; "Cairo0")]
+fn test_storage_read_write_trace(cairo_version: CairoVersion) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let key = felt!(1234_u16);
+    let value = felt!(18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Not traced by default.
+    let call_info = entry_point_call.clone().execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.storage_access_tracker.state_access_trace, None);
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.set_trace_state_accesses(true);
+    let new_value = felt!(19_u8);
+    let entry_point_call =
+        CallEntryPoint { calldata: calldata![key, new_value], ..entry_point_call };
+    let call_info =
+        entry_point_call.execute_directly_given_block_context(&mut state, block_context).unwrap();
+    let key = StorageKey::try_from(key).unwrap();
+    assert_eq!(
+        call_info.storage_access_tracker.state_access_trace,
+        Some(StateAccessTrace {
+            storage_reads: vec![StorageRead { key, value: new_value }],
+            storage_writes: vec![StorageWrite { key, old_value: value, new_value }],
+            class_hash_changes: vec![],
+            nonce_changes: vec![],
+        })
+    );
+}
@@ -4,6 +4,7 @@ pub mod contract_class_manager;
 pub mod error_format_test;
 pub mod errors;
 pub mod global_cache;
+pub mod native_artifact_cache;
 #[cfg(feature = "cairo_native")]
 pub mod native_class_manager;
 pub mod state_api;
@@ -53,6 +53,10 @@ pub mod trivial_class_manager {
         pub fn take_cache_hit_counter(&self) -> u64 {
             0
         }
+
+        pub fn take_native_execution_mismatch_counter(&self) -> u64 {
+            0
+        }
     }
 }
 
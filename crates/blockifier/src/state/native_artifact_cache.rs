@@ -0,0 +1,281 @@
+//! An on-disk cache of Cairo native compiled artifacts.
+//!
+//! Artifacts are keyed by the class hash and the compiler version. Each artifact is stored in its
+//! own directory, named by its key, so that the artifact may consist of several files (e.g., a
+//! shared library and its metadata). An artifact is written to a staging directory first and is
+//! then renamed into place, so that a crash never leaves a partial artifact under a valid key.
+//!
+//! The total size of the artifacts is bounded; when it's exceeded, the least recently used
+//! artifacts are evicted. The last access time of an artifact is its directory's modification time,
+//! so that the eviction order survives restarts.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+use starknet_api::core::ClassHash;
+
+use crate::blockifier::config::NativeArtifactCacheConfig;
+
+#[cfg(test)]
+#[path = "native_artifact_cache_test.rs"]
+mod native_artifact_cache_test;
+
+pub const DEFAULT_NATIVE_ARTIFACT_CACHE_MAX_SIZE: u64 = 10 * 1024 * 1024 * 1024;
+const STAGING_DIRECTORY_PREFIX: &str = ".staging";
+/// Staging directories that weren't modified for this long are leftovers of crashed processes.
+const STALE_STAGING_DIRECTORY_AGE: Duration = Duration::from_secs(60 * 60);
+/// Names the staging directories of this process.
+static STAGING_DIRECTORY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the key of the artifact of the given class, compiled by the given compiler version.
+pub fn native_artifact_key(class_hash: ClassHash, compiler_version: &str) -> String {
+    format!("{:#066x}-{compiler_version}", class_hash.0)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct ArtifactEntry {
+    size: u64,
+    last_access: SystemTime,
+}
+
+#[derive(Debug, Default)]
+struct ArtifactIndex {
+    entries: HashMap<String, ArtifactEntry>,
+    total_size: u64,
+}
+
+impl ArtifactIndex {
+    fn insert(&mut self, key: String, entry: ArtifactEntry) {
+        if let Some(old_entry) = self.entries.insert(key, entry) {
+            self.total_size -= old_entry.size;
+        }
+        self.total_size += entry.size;
+    }
+
+    fn remove(&mut self, key: &str) -> Option<ArtifactEntry> {
+        let entry = self.entries.remove(key)?;
+        self.total_size -= entry.size;
+        Some(entry)
+    }
+
+    /// Returns the least recently used key.
+    fn least_recently_used(&self) -> Option<String> {
+        self.entries.iter().min_by_key(|(_, entry)| entry.last_access).map(|(key, _)| key.clone())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NativeArtifactCache {
+    directory: PathBuf,
+    max_size: u64,
+    /// The version of the compiler that produces the artifacts; part of the artifact keys.
+    compiler_version: String,
+    /// The artifacts in the cache directory.
+    index: Arc<Mutex<ArtifactIndex>>,
+}
+
+impl NativeArtifactCache {
+    /// Returns the cache, or `None` if the config doesn't set a cache directory or the cache
+    /// directory can't be read. The cache directory is scanned here, rather than on the first use
+    /// of the cache, so that execution doesn't wait on the scan.
+    pub fn new(config: &NativeArtifactCacheConfig, compiler_version: String) -> Option<Self> {
+        let directory = config.directory.clone()?;
+        let index = match load_index(&directory, config.max_size) {
+            Ok(index) => index,
+            Err(error) => {
+                log::warn!(
+                    "Failed to read the Cairo native artifact cache directory {}: {error}.",
+                    directory.display()
+                );
+                return None;
+            }
+        };
+        Some(Self {
+            directory,
+            max_size: config.max_size,
+            compiler_version,
+            index: Arc::new(Mutex::new(index)),
+        })
+    }
+
+    /// Loads the artifact of the given class, if it's in the cache. An artifact that fails to load
+    /// is removed from the cache.
+    pub fn get<T, E: std::fmt::Display>(
+        &self,
+        class_hash: ClassHash,
+        load: impl FnOnce(&Path) -> Result<T, E>,
+    ) -> Option<T> {
+        let key = &self.key(class_hash);
+        let mut index = self.lock_index();
+        let entry = index.entries.get_mut(key)?;
+        let artifact_path = self.directory.join(key);
+        match load(&artifact_path) {
+            Ok(artifact) => {
+                entry.last_access = SystemTime::now();
+                // Persist the access time, so that it's restored on restart. Best effort.
+                let _ = fs::File::open(&artifact_path)
+                    .and_then(|directory| directory.set_modified(entry.last_access));
+                Some(artifact)
+            }
+            Err(error) => {
+                log::warn!("Failed to load the cached Cairo native artifact {key}: {error}.");
+                index.remove(key);
+                remove_directory(&artifact_path);
+                None
+            }
+        }
+    }
+
+    /// Writes the artifact of the given class to the cache, and loads it. `write` is given an empty
+    /// directory to write the artifact to.
+    ///
+    /// The least recently used artifacts are then evicted, to keep the cache within its size
+    /// limit. This may evict the new artifact, if it alone exceeds the limit; the loaded artifact
+    /// is returned regardless.
+    pub fn insert<T, E: From<io::Error>>(
+        &self,
+        class_hash: ClassHash,
+        write: impl FnOnce(&Path) -> Result<(), E>,
+        load: impl FnOnce(&Path) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let staging_path = self.directory.join(format!(
+            "{}{}",
+            staging_directory_prefix(),
+            STAGING_DIRECTORY_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&staging_path)?;
+        if let Err(error) = write(&staging_path) {
+            remove_directory(&staging_path);
+            return Err(error);
+        }
+        let size = directory_size(&staging_path)?;
+
+        let key = &self.key(class_hash);
+        let mut index = self.lock_index();
+        let artifact_path = self.directory.join(key);
+        if index.remove(key).is_some() {
+            remove_directory(&artifact_path);
+        }
+        if let Err(error) = fs::rename(&staging_path, &artifact_path) {
+            remove_directory(&staging_path);
+            return Err(error.into());
+        }
+        index.insert(key.to_string(), ArtifactEntry { size, last_access: SystemTime::now() });
+        let artifact = load(&artifact_path);
+        if artifact.is_err() {
+            index.remove(key);
+            remove_directory(&artifact_path);
+        }
+        evict(&self.directory, self.max_size, &mut index);
+        artifact
+    }
+
+    /// Returns whether the artifact of the given class is in the cache.
+    pub fn contains(&self, class_hash: ClassHash) -> bool {
+        self.lock_index().entries.contains_key(&self.key(class_hash))
+    }
+
+    /// Returns the total size of the cached artifacts.
+    pub fn size(&self) -> u64 {
+        self.lock_index().total_size
+    }
+
+    fn key(&self, class_hash: ClassHash) -> String {
+        native_artifact_key(class_hash, &self.compiler_version)
+    }
+
+    fn lock_index(&self) -> MutexGuard<'_, ArtifactIndex> {
+        self.index.lock().expect("Native artifact cache index is poisoned.")
+    }
+}
+
+/// Scans the cache directory, and evicts artifacts if the cache exceeds its size limit. Leftover
+/// staging directories of crashed processes are removed.
+fn load_index(directory: &Path, max_size: u64) -> io::Result<ArtifactIndex> {
+    fs::create_dir_all(directory)?;
+    let mut index = ArtifactIndex::default();
+    for dir_entry in fs::read_dir(directory)? {
+        let dir_entry = dir_entry?;
+        let Ok(key) = dir_entry.file_name().into_string() else { continue };
+        if !dir_entry.file_type()?.is_dir() {
+            continue;
+        }
+        let metadata = dir_entry.metadata()?;
+        if key.starts_with(STAGING_DIRECTORY_PREFIX) {
+            if is_stale_staging_directory(&key, &metadata) {
+                remove_directory(&dir_entry.path());
+            }
+            continue;
+        }
+        let entry = ArtifactEntry {
+            size: directory_size(&dir_entry.path())?,
+            last_access: metadata.modified()?,
+        };
+        index.insert(key, entry);
+    }
+    evict(directory, max_size, &mut index);
+    Ok(index)
+}
+
+/// Evicts the least recently used artifacts until the cache is within its size limit.
+fn evict(directory: &Path, max_size: u64, index: &mut ArtifactIndex) {
+    while index.total_size > max_size {
+        let Some(key) = index.least_recently_used() else { break };
+        index.remove(&key);
+        remove_directory(&directory.join(&key));
+    }
+}
+
+/// Returns the prefix of the staging directories of this process.
+fn staging_directory_prefix() -> String {
+    format!("{STAGING_DIRECTORY_PREFIX}-{}-", std::process::id())
+}
+
+/// Returns whether the staging directory with the given name is a leftover: its process is no
+/// longer running, or it wasn't modified for [`STALE_STAGING_DIRECTORY_AGE`] (which also covers a
+/// new process that reused the id of a crashed one).
+fn is_stale_staging_directory(name: &str, metadata: &fs::Metadata) -> bool {
+    let process_id = name
+        .strip_prefix(STAGING_DIRECTORY_PREFIX)
+        .and_then(|suffix| suffix.split('-').nth(1))
+        .and_then(|process_id| process_id.parse::<u32>().ok());
+    if process_id.is_some_and(|process_id| {
+        process_id != std::process::id() && !is_process_running(process_id)
+    }) {
+        return true;
+    }
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_STAGING_DIRECTORY_AGE)
+}
+
+/// Returns whether a process with the given id is running. Where this can't be checked, the
+/// process is assumed to be running.
+fn is_process_running(process_id: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(process_id.to_string()).exists()
+    } else {
+        true
+    }
+}
+
+/// Returns the total size of the files in the directory (non-recursive).
+fn directory_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for dir_entry in fs::read_dir(path)? {
+        size += dir_entry?.metadata()?.len();
+    }
+    Ok(size)
+}
+
+fn remove_directory(path: &Path) {
+    if let Err(error) = fs::remove_dir_all(path) {
+        log::warn!("Failed to remove {}: {error}.", path.display());
+    }
+}
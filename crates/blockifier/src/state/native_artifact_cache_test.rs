@@ -0,0 +1,169 @@
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+use pretty_assertions::assert_eq;
+use starknet_api::class_hash;
+use starknet_api::core::ClassHash;
+use tempfile::tempdir;
+
+use crate::blockifier::config::NativeArtifactCacheConfig;
+use crate::state::native_artifact_cache::{native_artifact_key, NativeArtifactCache};
+
+const ARTIFACT_FILE_NAME: &str = "artifact";
+const COMPILER_VERSION: &str = "0.3.4-O2";
+
+fn cache(directory: &Path, max_size: u64) -> NativeArtifactCache {
+    NativeArtifactCache::new(
+        &NativeArtifactCacheConfig { directory: Some(directory.to_path_buf()), max_size },
+        COMPILER_VERSION.to_string(),
+    )
+    .unwrap()
+}
+
+fn artifact_path(directory: &Path, class_hash: ClassHash) -> std::path::PathBuf {
+    directory.join(native_artifact_key(class_hash, COMPILER_VERSION))
+}
+
+fn write_artifact(content: &str) -> impl FnOnce(&Path) -> io::Result<()> + '_ {
+    move |path| fs::write(path.join(ARTIFACT_FILE_NAME), content)
+}
+
+fn load_artifact(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path.join(ARTIFACT_FILE_NAME))
+}
+
+#[test]
+fn disabled_without_directory() {
+    assert!(NativeArtifactCache::new(
+        &NativeArtifactCacheConfig::default(),
+        COMPILER_VERSION.to_string()
+    )
+    .is_none());
+}
+
+#[test]
+fn disabled_if_directory_cannot_be_read() {
+    let directory = tempdir().unwrap();
+    let file_path = directory.path().join("file");
+    fs::write(&file_path, "").unwrap();
+    assert!(NativeArtifactCache::new(
+        &NativeArtifactCacheConfig { directory: Some(file_path), max_size: 100 },
+        COMPILER_VERSION.to_string()
+    )
+    .is_none());
+}
+
+#[test]
+fn artifact_key() {
+    assert_eq!(
+        native_artifact_key(class_hash!("0x1234"), COMPILER_VERSION),
+        "0x0000000000000000000000000000000000000000000000000000000000001234-0.3.4-O2"
+    );
+}
+
+#[test]
+fn insert_and_get() {
+    let directory = tempdir().unwrap();
+    let cache = cache(directory.path(), 100);
+    let class_hash = class_hash!("0x1");
+
+    assert_eq!(cache.get(class_hash, load_artifact), None);
+    assert_eq!(cache.insert(class_hash, write_artifact("aaaa"), load_artifact).unwrap(), "aaaa");
+    assert_eq!(cache.get(class_hash, load_artifact), Some("aaaa".to_string()));
+    assert_eq!(cache.size(), 4);
+
+    // Replacing an artifact.
+    assert_eq!(cache.insert(class_hash, write_artifact("aa"), load_artifact).unwrap(), "aa");
+    assert_eq!(cache.get(class_hash, load_artifact), Some("aa".to_string()));
+    assert_eq!(cache.size(), 2);
+
+    // Artifacts of other compiler versions are not used.
+    let other_version_cache = NativeArtifactCache::new(
+        &NativeArtifactCacheConfig {
+            directory: Some(directory.path().to_path_buf()),
+            max_size: 100,
+        },
+        "0.3.5-O2".to_string(),
+    )
+    .unwrap();
+    assert_eq!(other_version_cache.get(class_hash, load_artifact), None);
+}
+
+#[test]
+fn artifacts_persist_across_instances() {
+    let directory = tempdir().unwrap();
+    let class_hash = class_hash!("0x1");
+    cache(directory.path(), 100).insert(class_hash, write_artifact("aaaa"), load_artifact).unwrap();
+    // A leftover staging directory of a crashed process.
+    fs::create_dir(directory.path().join(".staging-0-0")).unwrap();
+
+    let cache = cache(directory.path(), 100);
+    assert_eq!(cache.get(class_hash, load_artifact), Some("aaaa".to_string()));
+    assert_eq!(cache.size(), 4);
+    assert!(!directory.path().join(".staging-0-0").exists());
+}
+
+#[test]
+fn removes_only_stale_staging_directories() {
+    let directory = tempdir().unwrap();
+    // Staging directories of a running process: one that is in use, and an old one.
+    let in_use_path = directory.path().join(format!(".staging-{}-0", std::process::id()));
+    let old_path = directory.path().join(format!(".staging-{}-1", std::process::id()));
+    fs::create_dir(&in_use_path).unwrap();
+    fs::create_dir(&old_path).unwrap();
+    fs::File::open(&old_path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(2 * 60 * 60))
+        .unwrap();
+
+    cache(directory.path(), 100);
+    assert!(in_use_path.exists());
+    assert!(!old_path.exists());
+}
+
+#[test]
+fn evicts_least_recently_used_artifacts() {
+    let directory = tempdir().unwrap();
+    let cache = cache(directory.path(), 12);
+    let [a, b, c, d, e] = [1_u8, 2, 3, 4, 5].map(|n| ClassHash(n.into()));
+
+    cache.insert(a, write_artifact("aaaa"), load_artifact).unwrap();
+    cache.insert(b, write_artifact("bbbb"), load_artifact).unwrap();
+    cache.insert(c, write_artifact("cccc"), load_artifact).unwrap();
+    assert_eq!(cache.get(a, load_artifact), Some("aaaa".to_string()));
+
+    // `b` is the least recently used artifact.
+    cache.insert(d, write_artifact("dddd"), load_artifact).unwrap();
+    assert!(!cache.contains(b));
+    assert!(!artifact_path(directory.path(), b).exists());
+    assert!(cache.contains(a) && cache.contains(c) && cache.contains(d));
+    assert_eq!(cache.size(), 12);
+
+    // An artifact that exceeds the limit on its own is loaded, but not kept.
+    assert_eq!(
+        cache.insert(e, write_artifact("eeeeeeeeeeeeeeee"), load_artifact).unwrap(),
+        "eeeeeeeeeeeeeeee"
+    );
+    assert!(!cache.contains(e));
+    assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn removes_artifacts_that_fail_to_load() {
+    let directory = tempdir().unwrap();
+    let cache = cache(directory.path(), 100);
+    let class_hash = class_hash!("0x1");
+
+    cache.insert(class_hash, write_artifact("aaaa"), load_artifact).unwrap();
+    fs::remove_file(artifact_path(directory.path(), class_hash).join(ARTIFACT_FILE_NAME)).unwrap();
+    assert_eq!(cache.get(class_hash, load_artifact), None);
+    assert!(!cache.contains(class_hash));
+    assert!(!artifact_path(directory.path(), class_hash).exists());
+
+    // A failed write leaves nothing behind.
+    let write_error = |_: &Path| Err(io::Error::other("compilation failed"));
+    assert!(cache.insert(class_hash, write_error, load_artifact).is_err());
+    assert!(!cache.contains(class_hash));
+    assert_eq!(fs::read_dir(directory.path()).unwrap().count(), 0);
+}
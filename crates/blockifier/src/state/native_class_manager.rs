@@ -1,13 +1,17 @@
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 use std::sync::Arc;
 
 use apollo_sierra_multicompile::command_line_compiler::CommandLineCompiler;
+use apollo_sierra_multicompile::config::SierraCompilationConfig;
+use apollo_sierra_multicompile::constants::REQUIRED_CAIRO_NATIVE_VERSION;
 use apollo_sierra_multicompile::errors::CompilationUtilError;
 use apollo_sierra_multicompile::utils::into_contract_class_for_compilation;
 use apollo_sierra_multicompile::SierraToNativeCompiler;
 #[cfg(any(feature = "testing", test))]
 use cached::Cached;
+use cairo_native::executor::AotContractExecutor;
 use log;
 use starknet_api::core::ClassHash;
 use starknet_api::state::SierraContractClass;
@@ -21,6 +25,10 @@ use crate::blockifier::config::{
 use crate::execution::contract_class::{CompiledClassV1, RunnableCompiledClass};
 use crate::execution::native::contract_class::NativeCompiledClassV1;
 use crate::state::global_cache::{CachedCairoNative, CachedClass, RawClassCache};
+use crate::state::native_artifact_cache::NativeArtifactCache;
+
+/// The name of the native shared library in a cached artifact.
+const NATIVE_LIBRARY_FILE_NAME: &str = "contract.so";
 
 #[cfg(test)]
 #[path = "native_class_manager_test.rs"]
@@ -54,8 +62,13 @@ pub struct NativeClassManager {
     sender: Option<SyncSender<CompilationRequest>>,
     /// The sierra-to-native compiler.
     compiler: Option<Arc<dyn SierraToNativeCompiler>>,
+    /// The on-disk cache of the native compiled artifacts. Set to `None` if disabled.
+    artifact_cache: Option<NativeArtifactCache>,
     /// cache_miss_rate
     cache_metrics: Arc<CacheMetrics>,
+    /// The number of entry point executions whose Cairo native and Cairo VM results differ, in
+    /// differential execution mode.
+    native_execution_mismatches: Arc<AtomicU64>,
 }
 
 #[derive(Default)]
@@ -97,10 +110,16 @@ impl NativeClassManager {
                 cache,
                 sender: None,
                 compiler: None,
+                artifact_cache: None,
                 cache_metrics: Arc::new(CacheMetrics::new()),
+                native_execution_mismatches: Arc::new(AtomicU64::new(0)),
             };
         }
 
+        let artifact_cache = NativeArtifactCache::new(
+            &config.native_artifact_cache_config,
+            native_compiler_version(&config.native_compiler_config),
+        );
         let compiler_config = config.native_compiler_config.clone();
         let compiler = Arc::new(CommandLineCompiler::new(compiler_config));
         if cairo_native_run_config.wait_on_native_compilation {
@@ -110,7 +129,9 @@ impl NativeClassManager {
                 cache,
                 sender: None,
                 compiler: Some(compiler),
+                artifact_cache,
                 cache_metrics: Arc::new(CacheMetrics::new()),
+                native_execution_mismatches: Arc::new(AtomicU64::new(0)),
             };
         }
 
@@ -118,7 +139,8 @@ impl NativeClassManager {
 
         std::thread::spawn({
             let cache = cache.clone();
-            move || run_compilation_worker(cache, receiver, compiler)
+            let artifact_cache = artifact_cache.clone();
+            move || run_compilation_worker(cache, receiver, compiler, artifact_cache)
         });
 
         // TODO(AVIV): Add private constructor with default values.
@@ -127,7 +149,9 @@ impl NativeClassManager {
             cache,
             sender: Some(sender),
             compiler: None,
+            artifact_cache,
             cache_metrics: Arc::new(CacheMetrics::new()),
+            native_execution_mismatches: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -159,6 +183,13 @@ impl NativeClassManager {
             {
                 CachedClass::V1(native.casm(), Arc::new(SierraContractClass::default()))
             }
+            CachedClass::V1Native(CachedCairoNative::Compiled(native))
+                if self.differential_execution() =>
+            {
+                return Some(RunnableCompiledClass::V1Native(
+                    native.with_differential_execution(self.native_execution_mismatches.clone()),
+                ));
+            }
             _ => cached_class,
         };
 
@@ -173,6 +204,17 @@ impl NativeClassManager {
         match compiled_class {
             CachedClass::V0(_) => self.cache.set(class_hash, compiled_class),
             CachedClass::V1(compiled_class_v1, sierra_contract_class) => {
+                if let Some(native_compiled_class) =
+                    self.load_cached_artifact(class_hash, &compiled_class_v1)
+                {
+                    // The class was compiled before, e.g., before a restart.
+                    self.cache.set(
+                        class_hash,
+                        CachedClass::V1Native(CachedCairoNative::Compiled(native_compiled_class)),
+                    );
+                    return;
+                }
+
                 // TODO(Yoni): instead of these two flag, use an enum.
                 if self.wait_on_native_compilation() {
                     assert!(self.run_cairo_native(), "Native compilation is disabled.");
@@ -183,6 +225,7 @@ impl NativeClassManager {
                     process_compilation_request(
                         self.cache.clone(),
                         compiler.clone(),
+                        self.artifact_cache.as_ref(),
                         (class_hash, sierra_contract_class, compiled_class_v1),
                     )
                     .unwrap_or(());
@@ -210,6 +253,16 @@ impl NativeClassManager {
         }
     }
 
+    /// Loads the native compiled class from the on-disk artifact cache, if it's there.
+    fn load_cached_artifact(
+        &self,
+        class_hash: ClassHash,
+        casm: &CompiledClassV1,
+    ) -> Option<NativeCompiledClassV1> {
+        let executor = self.artifact_cache.as_ref()?.get(class_hash, load_native_executor)?;
+        Some(NativeCompiledClassV1::new(executor, casm.clone()))
+    }
+
     /// Sends a compilation request to the compilation worker. Does not block the sender. Logs an
     /// error if the channel is full.
     fn send_compilation_request(
@@ -242,6 +295,10 @@ impl NativeClassManager {
         self.cairo_native_run_config.wait_on_native_compilation
     }
 
+    fn differential_execution(&self) -> bool {
+        self.cairo_native_run_config.differential_execution
+    }
+
     /// Determines if a contract should run with cairo native based on the whitelist.
     pub fn run_class_with_cairo_native(&self, class_hash: &ClassHash) -> bool {
         match &self.cairo_native_run_config.native_classes_whitelist {
@@ -269,6 +326,12 @@ impl NativeClassManager {
     pub fn take_cache_hit_counter(&self) -> u64 {
         self.cache_metrics.cache_hits.swap(0, Ordering::Relaxed)
     }
+
+    /// Retrieves the current counter of mismatches between Cairo native and the Cairo VM in
+    /// differential execution mode, and resets it to zero.
+    pub fn take_native_execution_mismatch_counter(&self) -> u64 {
+        self.native_execution_mismatches.swap(0, Ordering::Relaxed)
+    }
 }
 
 /// Handles compilation requests from the channel, holding the receiver end of the channel.
@@ -278,19 +341,27 @@ fn run_compilation_worker(
     cache: RawClassCache,
     receiver: Receiver<CompilationRequest>,
     compiler: Arc<dyn SierraToNativeCompiler>,
+    artifact_cache: Option<NativeArtifactCache>,
 ) {
     log::info!("Compilation worker started.");
     for compilation_request in receiver.iter() {
-        process_compilation_request(cache.clone(), compiler.clone(), compilation_request)
-            .unwrap_or(());
+        process_compilation_request(
+            cache.clone(),
+            compiler.clone(),
+            artifact_cache.as_ref(),
+            compilation_request,
+        )
+        .unwrap_or(());
     }
     log::info!("Compilation worker terminated.");
 }
 
-/// Processes a compilation request and caches the result.
+/// Processes a compilation request and caches the result, in memory and, if given, in the on-disk
+/// artifact cache.
 fn process_compilation_request(
     cache: RawClassCache,
     compiler: Arc<dyn SierraToNativeCompiler>,
+    artifact_cache: Option<&NativeArtifactCache>,
     compilation_request: CompilationRequest,
 ) -> Result<(), CompilationUtilError> {
     let (class_hash, sierra, casm) = compilation_request;
@@ -299,7 +370,19 @@ fn process_compilation_request(
         return Ok(());
     }
     let sierra_for_compilation = into_contract_class_for_compilation(sierra.as_ref());
-    let compilation_result = compiler.compile_to_native(sierra_for_compilation);
+    let compilation_result = match artifact_cache {
+        Some(artifact_cache) => artifact_cache.insert(
+            class_hash,
+            |artifact_path| {
+                compiler.compile_to_native_library(
+                    sierra_for_compilation,
+                    &artifact_path.join(NATIVE_LIBRARY_FILE_NAME),
+                )
+            },
+            load_native_executor,
+        ),
+        None => compiler.compile_to_native(sierra_for_compilation),
+    };
     match compilation_result {
         Ok(executor) => {
             let native_compiled_class = NativeCompiledClassV1::new(executor, casm);
@@ -320,3 +403,15 @@ fn process_compilation_request(
         }
     }
 }
+
+/// Loads the executor of a native compiled artifact.
+fn load_native_executor(artifact_path: &Path) -> Result<AotContractExecutor, CompilationUtilError> {
+    AotContractExecutor::from_path(&artifact_path.join(NATIVE_LIBRARY_FILE_NAME))?.ok_or_else(
+        || CompilationUtilError::UnexpectedError("The native library is locked.".to_owned()),
+    )
+}
+
+/// Returns the version of the compiler that produces the native artifacts with the given config.
+fn native_compiler_version(config: &SierraCompilationConfig) -> String {
+    format!("{REQUIRED_CAIRO_NATIVE_VERSION}-O{}", config.optimization_level)
+}
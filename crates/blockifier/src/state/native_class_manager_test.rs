@@ -1,3 +1,4 @@
+use std::sync::atomic::AtomicU64;
 use std::sync::mpsc::{sync_channel, TrySendError};
 use std::sync::Arc;
 use std::thread::sleep;
@@ -9,8 +10,14 @@ use blockifier_test_utils::cairo_versions::{CairoVersion, RunnableCairo1};
 use blockifier_test_utils::contracts::FeatureContract;
 use rstest::rstest;
 use starknet_api::core::ClassHash;
+use tempfile::tempdir;
 
-use crate::blockifier::config::{CairoNativeRunConfig, NativeClassesWhitelist};
+use crate::blockifier::config::{
+    CairoNativeRunConfig,
+    ContractClassManagerConfig,
+    NativeArtifactCacheConfig,
+    NativeClassesWhitelist,
+};
 use crate::execution::contract_class::{CompiledClassV1, RunnableCompiledClass};
 use crate::state::global_cache::{
     CachedCairoNative,
@@ -164,7 +171,9 @@ fn test_send_compilation_request_channel_disconnected() {
         cache: RawClassCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
         sender: Some(sender),
         compiler: None,
+        artifact_cache: None,
         cache_metrics: Arc::new(CacheMetrics::new()),
+        native_execution_mismatches: Arc::new(AtomicU64::new(0)),
     };
     // Disconnect the channel by dropping the receiver.
     drop(receiver);
@@ -211,6 +220,7 @@ fn test_process_compilation_request(
     let res = process_compilation_request(
         manager.clone().cache,
         manager.clone().compiler.unwrap(),
+        None,
         request.clone(),
     );
 
@@ -246,6 +256,7 @@ fn test_native_classes_whitelist(
         run_cairo_native: true,
         wait_on_native_compilation: true,
         channel_size: TEST_CHANNEL_SIZE,
+        differential_execution: false,
         native_classes_whitelist: whitelist,
     };
     let manager = NativeClassManager::create_for_testing(native_config);
@@ -267,3 +278,40 @@ fn test_native_classes_whitelist(
         }
     }
 }
+
+#[test]
+fn test_native_artifact_cache() {
+    let artifact_cache_directory = tempdir().unwrap();
+    let start_manager = |wait_on_native_compilation| {
+        NativeClassManager::start(ContractClassManagerConfig {
+            cairo_native_run_config: CairoNativeRunConfig {
+                run_cairo_native: true,
+                wait_on_native_compilation,
+                ..CairoNativeRunConfig::default()
+            },
+            native_artifact_cache_config: NativeArtifactCacheConfig {
+                directory: Some(artifact_cache_directory.path().to_path_buf()),
+                ..NativeArtifactCacheConfig::default()
+            },
+            ..ContractClassManagerConfig::default()
+        })
+    };
+    let (class_hash, sierra, casm) = create_test_request();
+
+    let manager = start_manager(true);
+    manager.set_and_compile(class_hash, CachedClass::V1(casm.clone(), sierra.clone()));
+    assert_matches!(
+        manager.cache.get(&class_hash).unwrap(),
+        CachedClass::V1Native(CachedCairoNative::Compiled(_))
+    );
+    assert!(manager.artifact_cache.as_ref().unwrap().contains(class_hash));
+
+    // A new manager (e.g., after a restart) loads the artifact from the disk instead of compiling
+    // the class in the background.
+    let manager = start_manager(false);
+    manager.set_and_compile(class_hash, CachedClass::V1(casm, sierra));
+    assert_matches!(
+        manager.cache.get(&class_hash).unwrap(),
+        CachedClass::V1Native(CachedCairoNative::Compiled(_))
+    );
+}
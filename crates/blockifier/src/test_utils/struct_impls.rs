@@ -177,6 +177,8 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
             bouncer_config: BouncerConfig::max(),
+            trace_state_accesses: false,
+            trace_vm_steps: false,
         }
     }
 
@@ -186,6 +188,8 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
             bouncer_config: BouncerConfig::max(),
+            trace_state_accesses: false,
+            trace_vm_steps: false,
         }
     }
 
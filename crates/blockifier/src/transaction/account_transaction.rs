@@ -24,7 +24,7 @@ use starknet_types_core::felt::Felt;
 
 use super::errors::ResourceBoundsError;
 use crate::context::{BlockContext, GasCounter, TransactionContext};
-use crate::execution::call_info::CallInfo;
+use crate::execution::call_info::{CallInfo, NonceChange};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::RunnableCompiledClass;
 use crate::execution::entry_point::{
@@ -247,14 +247,15 @@ impl AccountTransaction {
     }
 
     // Performs static checks before executing validation entry point.
-    // Note that nonce is incremented during these checks.
+    // Note that nonce is incremented during these checks, and the change of the nonce is returned.
     pub fn perform_pre_validation_stage<S: State + StateReader>(
         &self,
         state: &mut S,
         tx_context: &TransactionContext,
-    ) -> TransactionPreValidationResult<()> {
+    ) -> TransactionPreValidationResult<Option<NonceChange>> {
         let tx_info = &tx_context.tx_info;
-        Self::handle_nonce(state, tx_info, self.execution_flags.strict_nonce_check)?;
+        let nonce_change =
+            Self::handle_nonce(state, tx_info, self.execution_flags.strict_nonce_check)?;
 
         if self.execution_flags.charge_fee {
             self.check_fee_bounds(tx_context)?;
@@ -262,7 +263,7 @@ impl AccountTransaction {
             verify_can_pay_committed_bounds(state, tx_context)?;
         }
 
-        Ok(())
+        Ok(nonce_change)
     }
 
     fn check_fee_bounds(
@@ -373,9 +374,9 @@ impl AccountTransaction {
         state: &mut dyn State,
         tx_info: &TransactionInfo,
         strict: bool,
-    ) -> TransactionPreValidationResult<()> {
+    ) -> TransactionPreValidationResult<Option<NonceChange>> {
         if tx_info.is_v0() {
-            return Ok(());
+            return Ok(None);
         }
 
         let address = tx_info.sender_address();
@@ -387,7 +388,12 @@ impl AccountTransaction {
             account_nonce <= incoming_tx_nonce
         };
         if valid_nonce {
-            return Ok(state.increment_nonce(address)?);
+            state.increment_nonce(address)?;
+            return Ok(Some(NonceChange {
+                contract_address: address,
+                old_nonce: account_nonce,
+                new_nonce: state.get_nonce_at(address)?,
+            }));
         }
         Err(TransactionPreValidationError::InvalidNonce {
             address,
@@ -803,13 +809,13 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
         self.verify_tx_version(tx_context.tx_info.version())?;
 
         // Nonce and fee check should be done before running user code.
-        self.perform_pre_validation_stage(state, &tx_context)?;
+        let nonce_change = self.perform_pre_validation_stage(state, &tx_context)?;
 
         // Run validation and execution.
         let initial_gas = tx_context.initial_sierra_gas();
         let ValidateExecuteCallInfo {
-            validate_call_info,
-            execute_call_info,
+            mut validate_call_info,
+            mut execute_call_info,
             revert_error,
             final_cost:
                 TransactionReceipt {
@@ -819,6 +825,15 @@ impl<U: UpdatableState> ExecutableTransaction<U> for AccountTransaction {
                     gas: total_gas,
                 },
         } = self.run_or_revert(state, &mut GasCounter::new(initial_gas), tx_context.clone())?;
+        // The nonce isn't changed by a call, so its change is traced in the validation call, or in
+        // the execution call if the validation was skipped.
+        if let (Some(nonce_change), Some(call_info)) =
+            (nonce_change, validate_call_info.as_mut().or(execute_call_info.as_mut()))
+        {
+            if let Some(trace) = &mut call_info.storage_access_tracker.state_access_trace {
+                trace.nonce_changes.push(nonce_change);
+            }
+        }
         let fee_transfer_call_info = Self::handle_fee(
             state,
             tx_context,
@@ -70,7 +70,7 @@ use starknet_types_core::felt::Felt;
 
 use crate::check_tx_execution_error_for_invalid_scenario;
 use crate::context::{BlockContext, TransactionContext};
-use crate::execution::call_info::CallInfo;
+use crate::execution::call_info::{CallInfo, NonceChange};
 use crate::execution::contract_class::TrackedResource;
 use crate::execution::entry_point::{EntryPointExecutionContext, SierraGasRevertTracker};
 use crate::execution::syscalls::SyscallSelector;
@@ -354,6 +354,41 @@ fn test_account_flow_test(
     .unwrap();
 }
 
+#[rstest]
+fn test_nonce_change_trace(
+    mut block_context: BlockContext,
+    default_all_resource_bounds: ValidResourceBounds,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    block_context.set_trace_state_accesses(true);
+
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_trivial_calldata(contract_address),
+            resource_bounds: default_all_resource_bounds,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+    let state_access_trace = |call_info: Option<CallInfo>| {
+        call_info.unwrap().storage_access_tracker.state_access_trace.unwrap()
+    };
+    // The nonce change is traced in the validation call only.
+    assert_eq!(
+        state_access_trace(tx_execution_info.validate_call_info).nonce_changes,
+        vec![NonceChange {
+            contract_address: account_address,
+            old_nonce: nonce!(0_u8),
+            new_nonce: nonce!(1_u8),
+        }]
+    );
+    assert!(state_access_trace(tx_execution_info.execute_call_info).nonce_changes.is_empty());
+}
+
 #[rstest]
 #[case(TransactionVersion::ZERO)]
 #[case(TransactionVersion::ONE)]
@@ -2,10 +2,15 @@ use std::fs;
 use std::path::Path;
 
 use apollo_gateway::config::RpcStateReaderConfig;
+use apollo_storage::db::DbConfig;
+use apollo_storage::{StorageConfig, StorageScope};
+use blockifier_reexecution::state_reader::corpus::{replay_corpus, Corpus};
 use blockifier_reexecution::state_reader::offline_state_reader::OfflineConsecutiveStateReaders;
 use blockifier_reexecution::state_reader::test_state_reader::ConsecutiveTestStateReaders;
 use blockifier_reexecution::state_reader::utils::{
     get_block_numbers_for_reexecution,
+    get_block_reexecution_data_from_rpc,
+    get_block_reexecution_data_from_storage,
     guess_chain_id_from_node_url,
     reexecute_and_verify_correctness,
     write_block_reexecution_data_to_file,
@@ -72,6 +77,34 @@ impl RpcArgs {
     }
 }
 
+#[derive(Clone, Debug, Args)]
+struct CorpusSourceArgs {
+    /// Node url to capture the blocks from.
+    #[clap(long, short = 'n', required_unless_present = "db_path", conflicts_with = "db_path")]
+    node_url: Option<String>,
+
+    /// Path prefix of the database of a (stopped) node to capture the blocks from.
+    #[clap(long)]
+    db_path: Option<String>,
+
+    /// Optional chain ID (if not provided, it will be guessed from the node url). Required when
+    /// capturing the blocks from a database.
+    #[clap(long, short = 'c', required_unless_present = "node_url")]
+    chain_id: Option<SupportedChainId>,
+}
+
+impl CorpusSourceArgs {
+    fn parse_chain_id(&self) -> ChainId {
+        match &self.node_url {
+            Some(node_url) => {
+                RpcArgs { node_url: node_url.clone(), chain_id: self.chain_id.clone() }
+                    .parse_chain_id()
+            }
+            None => self.chain_id.clone().expect("Missing chain ID.").into(),
+        }
+    }
+}
+
 #[derive(Debug, Subcommand)]
 enum Command {
     /// Runs the RPC test.
@@ -140,6 +173,34 @@ enum Command {
         #[clap(long, short = 'd', default_value = None)]
         directory_path: Option<String>,
     },
+
+    /// Captures the reexecution data of a range of blocks, from an RPC node or from the storage of
+    /// a node, into a compressed corpus directory.
+    CaptureCorpus {
+        #[clap(flatten)]
+        source_args: CorpusSourceArgs,
+
+        /// First block to capture.
+        #[clap(long, short = 'f')]
+        from_block: u64,
+
+        /// Last block to capture (inclusive).
+        #[clap(long, short = 't')]
+        to_block: u64,
+
+        /// Directory path of the corpus.
+        #[clap(long, short = 'd')]
+        directory_path: String,
+    },
+
+    /// Replays all the blocks of a corpus under the Cairo VM and, if built with the
+    /// `cairo_native` feature, under Cairo native. Fails on any divergence in the receipts or the
+    /// state diffs.
+    ReplayCorpus {
+        /// Directory path of the corpus.
+        #[clap(long, short = 'd')]
+        directory_path: String,
+    },
 }
 
 fn parse_block_numbers_args(block_numbers: Option<Vec<u64>>) -> Vec<BlockNumber> {
@@ -346,5 +407,80 @@ async fn main() {
 
             println!("All blocks downloaded successfully to {directory_path}.");
         }
+
+        Command::CaptureCorpus { source_args, from_block, to_block, directory_path } => {
+            let chain_id = source_args.parse_chain_id();
+            let corpus = Corpus::new(directory_path.clone());
+            // Keep the storage writer alive while the storage is read.
+            let (storage_reader, _storage_writer) = match source_args.db_path {
+                Some(db_path) => {
+                    let db_config = DbConfig {
+                        path_prefix: db_path.into(),
+                        chain_id: chain_id.clone(),
+                        enforce_file_exists: true,
+                        ..Default::default()
+                    };
+                    let config = StorageConfig {
+                        db_config,
+                        scope: StorageScope::FullArchive,
+                        ..Default::default()
+                    };
+                    let (reader, writer) =
+                        apollo_storage::open_storage(config).expect("Failed to open storage.");
+                    (Some(reader), Some(writer))
+                }
+                None => (None, None),
+            };
+
+            println!("Capturing blocks {from_block} to {to_block} into {directory_path}.");
+            for block_number in (from_block..=to_block).map(BlockNumber) {
+                let (corpus, chain_id) = (corpus.clone(), chain_id.clone());
+                let (storage_reader, node_url) =
+                    (storage_reader.clone(), source_args.node_url.clone());
+                // RPC calls and storage reads are synchronous IO, so should be executed in a
+                // blocking thread.
+                tokio::task::spawn_blocking(move || {
+                    println!("Capturing block {block_number}.");
+                    let block_data = match storage_reader {
+                        Some(storage_reader) => get_block_reexecution_data_from_storage(
+                            block_number,
+                            storage_reader,
+                            chain_id,
+                        ),
+                        None => get_block_reexecution_data_from_rpc(
+                            block_number,
+                            node_url.expect("Missing node url."),
+                            chain_id,
+                        ),
+                    };
+                    corpus.add_block(&block_data).unwrap();
+                })
+                .await
+                .unwrap();
+            }
+
+            println!("All blocks captured successfully to {directory_path}.");
+        }
+
+        Command::ReplayCorpus { directory_path } => {
+            println!("Replaying the corpus in {directory_path}.");
+            let reports =
+                tokio::task::spawn_blocking(move || replay_corpus(&Corpus::new(directory_path)))
+                    .await
+                    .unwrap()
+                    .unwrap();
+
+            let mut diverged_blocks = vec![];
+            for report in reports {
+                if report.divergences.is_empty() {
+                    println!("Block {} replayed without divergences.", report.block_number);
+                } else {
+                    println!("Block {} diverged: {:#?}", report.block_number, report.divergences);
+                    diverged_blocks.push(report.block_number);
+                }
+            }
+            assert!(diverged_blocks.is_empty(), "Blocks {diverged_blocks:?} diverged.");
+            println!("Corpus replayed successfully.");
+        }
     }
 }
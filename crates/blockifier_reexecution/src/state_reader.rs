@@ -1,4 +1,7 @@
 pub mod compile;
+pub mod corpus;
+#[cfg(test)]
+pub mod corpus_test;
 mod errors;
 pub mod offline_state_reader;
 #[cfg(test)]
@@ -9,5 +12,6 @@ pub mod reexecution_test;
 #[cfg(all(test, feature = "blockifier_regression_https_testing"))]
 pub mod rpc_https_test;
 pub mod serde_utils;
+pub mod storage_state_reader;
 pub mod test_state_reader;
 pub mod utils;
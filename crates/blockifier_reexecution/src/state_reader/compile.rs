@@ -3,13 +3,14 @@
 // `<starknet-replay: https://github.com/lambdaclass/starknet-replay>`
 
 use std::collections::HashMap;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 
 use apollo_gateway::errors::serde_err_to_state_err;
 use blockifier::state::state_api::StateResult;
 use cairo_lang_starknet_classes::contract_class::ContractEntryPoints;
 use cairo_lang_utils::bigint::BigUintAsHex;
-use flate2::bufread;
+use flate2::write::GzEncoder;
+use flate2::{bufread, Compression};
 use serde::Deserialize;
 use starknet_api::contract_class::{ContractClass, EntryPointType, SierraVersion};
 use starknet_api::core::EntryPointSelector;
@@ -20,11 +21,15 @@ use starknet_api::deprecated_contract_class::{
     Program,
 };
 use starknet_api::hash::StarkHash;
+use starknet_api::rpc_transaction::EntryPointByType;
+use starknet_api::state::{EntryPoint, FunctionIndex, SierraContractClass};
 use starknet_core::types::{
     CompressedLegacyContractClass,
+    EntryPointsByType,
     FlattenedSierraClass,
     LegacyContractEntryPoint,
     LegacyEntryPointsByType,
+    SierraEntryPoint,
 };
 
 #[derive(Debug, Deserialize)]
@@ -72,6 +77,102 @@ pub fn decode_reader(bytes: Vec<u8>) -> io::Result<String> {
     Ok(s)
 }
 
+/// Gz encodes a string, the inverse of `decode_reader`.
+pub fn encode_to_bytes(s: &str) -> io::Result<Vec<u8>> {
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    gz.write_all(s.as_bytes())?;
+    gz.finish()
+}
+
+/// Converts a FlattenedSierraClass to the Sierra class that is compiled to Cairo native.
+pub fn flattened_sierra_to_sierra_contract_class(
+    sierra: &FlattenedSierraClass,
+) -> SierraContractClass {
+    let to_entry_points = |entry_points: &Vec<SierraEntryPoint>| -> Vec<EntryPoint> {
+        entry_points
+            .iter()
+            .map(|entry_point| EntryPoint {
+                function_idx: FunctionIndex(usize::try_from(entry_point.function_idx).unwrap()),
+                selector: EntryPointSelector(entry_point.selector),
+            })
+            .collect()
+    };
+    SierraContractClass {
+        sierra_program: sierra.sierra_program.clone(),
+        contract_class_version: sierra.contract_class_version.clone(),
+        entry_points_by_type: EntryPointByType {
+            constructor: to_entry_points(&sierra.entry_points_by_type.constructor),
+            external: to_entry_points(&sierra.entry_points_by_type.external),
+            l1handler: to_entry_points(&sierra.entry_points_by_type.l1_handler),
+        },
+        abi: sierra.abi.clone(),
+    }
+}
+
+/// Converts a Sierra class, as stored by a node, to a FlattenedSierraClass.
+pub fn sierra_contract_class_to_flattened_sierra(
+    sierra: SierraContractClass,
+) -> FlattenedSierraClass {
+    let to_sierra_entry_points = |entry_points: Vec<EntryPoint>| -> Vec<SierraEntryPoint> {
+        entry_points
+            .into_iter()
+            .map(|entry_point| SierraEntryPoint {
+                selector: entry_point.selector.0,
+                function_idx: u64::try_from(entry_point.function_idx.0).unwrap(),
+            })
+            .collect()
+    };
+    FlattenedSierraClass {
+        sierra_program: sierra.sierra_program,
+        contract_class_version: sierra.contract_class_version,
+        entry_points_by_type: EntryPointsByType {
+            constructor: to_sierra_entry_points(sierra.entry_points_by_type.constructor),
+            external: to_sierra_entry_points(sierra.entry_points_by_type.external),
+            l1_handler: to_sierra_entry_points(sierra.entry_points_by_type.l1handler),
+        },
+        abi: sierra.abi,
+    }
+}
+
+/// Converts a deprecated class, as stored by a node, to a CompressedLegacyContractClass.
+pub fn deprecated_contract_class_to_compressed_legacy(
+    deprecated_class: DeprecatedContractClass,
+) -> StateResult<CompressedLegacyContractClass> {
+    let program =
+        serde_json::to_string(&deprecated_class.program).map_err(serde_err_to_state_err)?;
+    let mut entry_points_by_type = deprecated_class.entry_points_by_type;
+    let mut take_entry_points = |entry_point_type| -> Vec<LegacyContractEntryPoint> {
+        entry_points_by_type
+            .remove(&entry_point_type)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry_point| LegacyContractEntryPoint {
+                offset: u64::try_from(entry_point.offset.0).unwrap(),
+                selector: entry_point.selector.0,
+            })
+            .collect()
+    };
+    let entry_points_by_type = LegacyEntryPointsByType {
+        constructor: take_entry_points(EntryPointType::Constructor),
+        external: take_entry_points(EntryPointType::External),
+        l1_handler: take_entry_points(EntryPointType::L1Handler),
+    };
+    // Both ABI types follow the same JSON format.
+    let abi = deprecated_class
+        .abi
+        .map(|abi| {
+            serde_json::to_value(abi)
+                .and_then(serde_json::from_value)
+                .map_err(serde_err_to_state_err)
+        })
+        .transpose()?;
+    Ok(CompressedLegacyContractClass {
+        program: encode_to_bytes(&program).unwrap(),
+        entry_points_by_type,
+        abi,
+    })
+}
+
 /// Compile a FlattenedSierraClass to a versioned ContractClass V1 (casm) using
 /// cairo_lang_starknet_classes.
 pub fn sierra_to_versioned_contract_class_v1(
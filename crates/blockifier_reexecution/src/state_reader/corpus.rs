@@ -0,0 +1,308 @@
+//! A regression corpus of blocks for reexecution.
+//!
+//! A corpus is a directory with the offline reexecution data of each of its blocks, in a
+//! gzip-compressed JSON file, and a manifest that lists the blocks. Replaying the corpus
+//! reexecutes each block under the Cairo VM and, when the `cairo_native` feature is enabled, under
+//! Cairo native, and reports any divergence in the receipts or the state diffs.
+
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+use std::iter::zip;
+use std::path::PathBuf;
+
+#[cfg(feature = "cairo_native")]
+use blockifier::blockifier::config::{CairoNativeRunConfig, ContractClassManagerConfig};
+use blockifier::execution::call_info::MessageToL1;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::contract_class_manager::ContractClassManager;
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
+use starknet_api::execution_resources::GasVector;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::EventContent;
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::offline_state_reader::{
+    OfflineConsecutiveStateReaders,
+    SerializableOfflineReexecutionData,
+};
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+use crate::state_reader::utils::ComparableStateDiff;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Lists the blocks of a corpus, in ascending order.
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct CorpusManifest {
+    pub chain_id: ChainId,
+    pub block_numbers: Vec<BlockNumber>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Corpus {
+    directory_path: PathBuf,
+}
+
+impl Corpus {
+    pub fn new(directory_path: impl Into<PathBuf>) -> Self {
+        Self { directory_path: directory_path.into() }
+    }
+
+    pub fn read_manifest(&self) -> ReexecutionResult<CorpusManifest> {
+        let manifest = fs::read_to_string(self.directory_path.join(MANIFEST_FILE_NAME))?;
+        Ok(serde_json::from_str(&manifest)?)
+    }
+
+    pub fn block_file_path(&self, block_number: BlockNumber) -> PathBuf {
+        self.directory_path.join(format!("block_{block_number}.json.gz"))
+    }
+
+    /// Adds the block to the corpus, or replaces it if it's already in the corpus.
+    pub fn add_block(&self, data: &SerializableOfflineReexecutionData) -> ReexecutionResult<()> {
+        let block_number = data.serializable_data_next_block.block_info_next_block.block_number;
+        let mut manifest = match self.read_manifest() {
+            Ok(manifest) => manifest,
+            Err(ReexecutionError::Io(err)) if err.kind() == ErrorKind::NotFound => {
+                CorpusManifest { chain_id: data.chain_id.clone(), block_numbers: vec![] }
+            }
+            Err(err) => return Err(err),
+        };
+        if manifest.chain_id != data.chain_id {
+            return Err(ReexecutionError::CorpusChainIdMismatch {
+                corpus_chain_id: manifest.chain_id,
+                block_chain_id: data.chain_id.clone(),
+            });
+        }
+
+        fs::create_dir_all(&self.directory_path)?;
+        let mut encoder =
+            GzEncoder::new(File::create(self.block_file_path(block_number))?, Compression::best());
+        serde_json::to_writer(&mut encoder, data)?;
+        encoder.finish()?;
+
+        // Update the manifest only after the block is written, so that it never lists a missing
+        // block.
+        if let Err(index) = manifest.block_numbers.binary_search(&block_number) {
+            manifest.block_numbers.insert(index, block_number);
+            fs::write(
+                self.directory_path.join(MANIFEST_FILE_NAME),
+                serde_json::to_string_pretty(&manifest)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn read_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> ReexecutionResult<SerializableOfflineReexecutionData> {
+        let decoder = GzDecoder::new(File::open(self.block_file_path(block_number))?);
+        Ok(serde_json::from_reader(BufReader::new(decoder))?)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExecutionMode {
+    CairoVm,
+    CairoNative,
+}
+
+/// The parts of a transaction receipt that are compared between the execution modes. The VM
+/// resources are omitted, since Cairo native doesn't count them.
+#[derive(Debug, PartialEq)]
+pub struct ReplayedReceipt {
+    pub fee: Fee,
+    pub gas: GasVector,
+    pub da_gas: GasVector,
+    pub is_reverted: bool,
+    pub events: Vec<EventContent>,
+    pub messages_to_l1: Vec<MessageToL1>,
+}
+
+impl From<&TransactionExecutionInfo> for ReplayedReceipt {
+    fn from(execution_info: &TransactionExecutionInfo) -> Self {
+        let call_infos =
+            || execution_info.non_optional_call_infos().flat_map(|call_info| call_info.iter());
+        Self {
+            fee: execution_info.receipt.fee,
+            gas: execution_info.receipt.gas,
+            da_gas: execution_info.receipt.da_gas,
+            is_reverted: execution_info.is_reverted(),
+            events: call_infos()
+                .flat_map(|call_info| &call_info.execution.events)
+                .map(|ordered_event| ordered_event.event.clone())
+                .collect(),
+            messages_to_l1: call_infos()
+                .flat_map(|call_info| &call_info.execution.l2_to_l1_messages)
+                .map(|ordered_message| MessageToL1 {
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayDivergence {
+    /// The transaction failed to execute, so it couldn't be included in the block.
+    TransactionFailed { execution_mode: ExecutionMode, transaction_index: usize, error: String },
+    /// The receipts of the transaction under the Cairo VM and under Cairo native differ.
+    Receipt {
+        transaction_index: usize,
+        cairo_vm: Box<ReplayedReceipt>,
+        cairo_native: Box<ReplayedReceipt>,
+    },
+    /// The state diff of the block differs from the state diff of the captured block.
+    StateDiff {
+        execution_mode: ExecutionMode,
+        expected: Box<ComparableStateDiff>,
+        actual: Box<ComparableStateDiff>,
+    },
+}
+
+#[derive(Debug)]
+pub struct BlockReplayReport {
+    pub block_number: BlockNumber,
+    pub divergences: Vec<ReplayDivergence>,
+}
+
+struct ReplayedBlock {
+    execution_mode: ExecutionMode,
+    receipts: Vec<Result<ReplayedReceipt, String>>,
+    expected_state_diff: CommitmentStateDiff,
+    state_diff: CommitmentStateDiff,
+}
+
+impl ReplayedBlock {
+    fn divergences_from_captured_block(&self) -> Vec<ReplayDivergence> {
+        let mut divergences: Vec<_> = self
+            .receipts
+            .iter()
+            .enumerate()
+            .filter_map(|(transaction_index, receipt)| {
+                let error = receipt.as_ref().err()?;
+                Some(ReplayDivergence::TransactionFailed {
+                    execution_mode: self.execution_mode,
+                    transaction_index,
+                    error: error.clone(),
+                })
+            })
+            .collect();
+        let expected = ComparableStateDiff::from(self.expected_state_diff.clone());
+        let actual = ComparableStateDiff::from(self.state_diff.clone());
+        if expected != actual {
+            divergences.push(ReplayDivergence::StateDiff {
+                execution_mode: self.execution_mode,
+                expected: Box::new(expected),
+                actual: Box::new(actual),
+            });
+        }
+        divergences
+    }
+}
+
+/// Reexecutes the block. The classes are executed with Cairo native if the given manager compiles
+/// them to Cairo native, and with the Cairo VM otherwise.
+fn replay_block(
+    corpus: &Corpus,
+    block_number: BlockNumber,
+    execution_mode: ExecutionMode,
+    contract_class_manager: Option<ContractClassManager>,
+) -> ReexecutionResult<ReplayedBlock> {
+    let mut consecutive_state_readers =
+        OfflineConsecutiveStateReaders::new(corpus.read_block(block_number)?.into());
+    if let Some(contract_class_manager) = contract_class_manager {
+        consecutive_state_readers =
+            consecutive_state_readers.with_contract_class_manager(contract_class_manager);
+    }
+    let expected_state_diff = consecutive_state_readers.get_next_block_state_diff()?;
+    let all_txs_in_next_block = consecutive_state_readers.get_next_block_txs()?;
+
+    let mut transaction_executor =
+        consecutive_state_readers.pre_process_and_create_executor(None)?;
+    let receipts = transaction_executor
+        .execute_txs(&all_txs_in_next_block)
+        .into_iter()
+        .map(|result| match result {
+            Ok((execution_info, _)) => Ok(ReplayedReceipt::from(&execution_info)),
+            Err(err) => Err(err.to_string()),
+        })
+        .collect();
+    let state_diff = transaction_executor.non_consuming_finalize()?.state_diff;
+
+    Ok(ReplayedBlock { execution_mode, receipts, expected_state_diff, state_diff })
+}
+
+/// Replays the block under the Cairo VM and, if a manager that compiles the classes to Cairo
+/// native is given, under Cairo native.
+pub fn replay_corpus_block(
+    corpus: &Corpus,
+    block_number: BlockNumber,
+    native_contract_class_manager: Option<ContractClassManager>,
+) -> ReexecutionResult<BlockReplayReport> {
+    let cairo_vm_block = replay_block(corpus, block_number, ExecutionMode::CairoVm, None)?;
+    let mut divergences = cairo_vm_block.divergences_from_captured_block();
+
+    if let Some(native_contract_class_manager) = native_contract_class_manager {
+        let cairo_native_block = replay_block(
+            corpus,
+            block_number,
+            ExecutionMode::CairoNative,
+            Some(native_contract_class_manager),
+        )?;
+        divergences.extend(cairo_native_block.divergences_from_captured_block());
+        for (transaction_index, receipts) in
+            zip(cairo_vm_block.receipts, cairo_native_block.receipts).enumerate()
+        {
+            if let (Ok(cairo_vm), Ok(cairo_native)) = receipts {
+                if cairo_vm != cairo_native {
+                    divergences.push(ReplayDivergence::Receipt {
+                        transaction_index,
+                        cairo_vm: Box::new(cairo_vm),
+                        cairo_native: Box::new(cairo_native),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(BlockReplayReport { block_number, divergences })
+}
+
+/// Replays all the blocks of the corpus under the Cairo VM and, when the `cairo_native` feature is
+/// enabled, under Cairo native.
+pub fn replay_corpus(corpus: &Corpus) -> ReexecutionResult<Vec<BlockReplayReport>> {
+    let native_contract_class_manager = native_contract_class_manager();
+    corpus
+        .read_manifest()?
+        .block_numbers
+        .into_iter()
+        .map(|block_number| {
+            replay_corpus_block(corpus, block_number, native_contract_class_manager.clone())
+        })
+        .collect()
+}
+
+/// Returns a manager that compiles the classes to Cairo native before they're executed.
+#[cfg(feature = "cairo_native")]
+fn native_contract_class_manager() -> Option<ContractClassManager> {
+    Some(ContractClassManager::start(ContractClassManagerConfig {
+        cairo_native_run_config: CairoNativeRunConfig {
+            run_cairo_native: true,
+            wait_on_native_compilation: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }))
+}
+
+#[cfg(not(feature = "cairo_native"))]
+fn native_contract_class_manager() -> Option<ContractClassManager> {
+    None
+}
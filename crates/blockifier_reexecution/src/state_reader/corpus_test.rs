@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use blockifier::state::cached_state::{CommitmentStateDiff, StateMaps};
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockInfo, BlockNumber, StarknetVersion};
+use starknet_api::core::ChainId;
+use starknet_api::{contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+use tempfile::tempdir;
+
+use crate::state_reader::corpus::{
+    replay_corpus,
+    Corpus,
+    CorpusManifest,
+    ExecutionMode,
+    ReplayDivergence,
+};
+use crate::state_reader::errors::ReexecutionError;
+use crate::state_reader::offline_state_reader::{
+    SerializableDataNextBlock,
+    SerializableDataPrevBlock,
+    SerializableOfflineReexecutionData,
+};
+
+/// Returns the data of a block without transactions, whose state diff is the write of the old
+/// block hash to the block hash contract.
+fn empty_block_data(
+    block_number: BlockNumber,
+    chain_id: ChainId,
+) -> SerializableOfflineReexecutionData {
+    let old_block_hash = felt!("0x1234");
+    let old_block_number = storage_key!(block_number.0 - 10);
+    SerializableOfflineReexecutionData {
+        serializable_data_prev_block: SerializableDataPrevBlock {
+            // The block hash contract storage is read when the block is finalized.
+            state_maps: StateMaps {
+                storage: HashMap::from([((contract_address!(1_u8), old_block_number), Felt::ZERO)]),
+                ..Default::default()
+            }
+            .into(),
+            contract_class_mapping: HashMap::new(),
+        },
+        serializable_data_next_block: SerializableDataNextBlock {
+            block_info_next_block: BlockInfo { block_number, ..Default::default() },
+            starknet_version: StarknetVersion::V0_13_1,
+            transactions_next_block: vec![],
+            state_diff_next_block: CommitmentStateDiff {
+                storage_updates: IndexMap::from([(
+                    contract_address!(1_u8),
+                    IndexMap::from([(old_block_number, old_block_hash)]),
+                )]),
+                ..Default::default()
+            },
+            declared_classes: HashMap::new(),
+        },
+        chain_id,
+        old_block_hash: BlockHash(old_block_hash),
+    }
+}
+
+#[test]
+fn add_and_read_blocks() {
+    let corpus_dir = tempdir().unwrap();
+    let corpus = Corpus::new(corpus_dir.path());
+
+    for block_number in [BlockNumber(101), BlockNumber(100), BlockNumber(101)] {
+        corpus.add_block(&empty_block_data(block_number, ChainId::Mainnet)).unwrap();
+    }
+    assert_eq!(
+        corpus.read_manifest().unwrap(),
+        CorpusManifest {
+            chain_id: ChainId::Mainnet,
+            block_numbers: vec![BlockNumber(100), BlockNumber(101)],
+        }
+    );
+    assert_eq!(
+        serde_json::to_value(corpus.read_block(BlockNumber(100)).unwrap()).unwrap(),
+        serde_json::to_value(empty_block_data(BlockNumber(100), ChainId::Mainnet)).unwrap()
+    );
+
+    assert_matches!(
+        corpus.add_block(&empty_block_data(BlockNumber(102), ChainId::Sepolia)),
+        Err(ReexecutionError::CorpusChainIdMismatch { .. })
+    );
+}
+
+#[test]
+fn replay_corpus_reports_state_diff_divergences() {
+    let corpus_dir = tempdir().unwrap();
+    let corpus = Corpus::new(corpus_dir.path());
+
+    corpus.add_block(&empty_block_data(BlockNumber(100), ChainId::Mainnet)).unwrap();
+    // The captured state diff is missing the write of the old block hash.
+    let mut block_data = empty_block_data(BlockNumber(101), ChainId::Mainnet);
+    block_data.serializable_data_next_block.state_diff_next_block = CommitmentStateDiff::default();
+    corpus.add_block(&block_data).unwrap();
+
+    let reports = replay_corpus(&corpus).unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].block_number, BlockNumber(100));
+    assert_eq!(reports[0].divergences, vec![]);
+    assert_eq!(reports[1].block_number, BlockNumber(101));
+    assert_matches!(
+        reports[1].divergences.as_slice(),
+        [ReplayDivergence::StateDiff { execution_mode: ExecutionMode::CairoVm, .. }, ..]
+    );
+}
@@ -1,8 +1,12 @@
 use apollo_gateway::errors::RPCStateReaderError;
+use apollo_storage::StorageError;
+use blockifier::blockifier::transaction_executor::TransactionExecutorError;
 use blockifier::blockifier_versioned_constants::VersionedConstantsError;
 use blockifier::state::errors::StateError;
 use blockifier::transaction::errors::TransactionExecutionError;
 use serde_json::Error as SerdeError;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::ChainId;
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
@@ -11,6 +15,14 @@ use thiserror::Error;
 pub enum ReexecutionError {
     #[error("Cannot discern chain ID from URL: {0}")]
     AmbiguousChainIdFromUrl(String),
+    #[error(
+        "The corpus is of chain {corpus_chain_id}, but the block is of chain {block_chain_id}."
+    )]
+    CorpusChainIdMismatch { corpus_chain_id: ChainId, block_chain_id: ChainId },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Block {0} is missing from the storage.")]
+    MissingBlockInStorage(BlockNumber),
     #[error(transparent)]
     Rpc(#[from] RPCStateReaderError),
     #[error(transparent)]
@@ -20,8 +32,12 @@ pub enum ReexecutionError {
     #[error(transparent)]
     State(#[from] StateError),
     #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
     TransactionExecutionError(#[from] TransactionExecutionError),
     #[error(transparent)]
+    TransactionExecutorError(#[from] TransactionExecutorError),
+    #[error(transparent)]
     VersionedConstants(#[from] VersionedConstantsError),
 }
 
@@ -1,4 +1,5 @@
 use std::fs;
+use std::sync::Arc;
 
 use blockifier::abi::constants;
 use blockifier::blockifier::config::TransactionExecutorConfig;
@@ -8,7 +9,9 @@ use blockifier::bouncer::BouncerConfig;
 use blockifier::context::BlockContext;
 use blockifier::execution::contract_class::RunnableCompiledClass;
 use blockifier::state::cached_state::{CommitmentStateDiff, StateMaps};
+use blockifier::state::contract_class_manager::ContractClassManager;
 use blockifier::state::errors::StateError;
+use blockifier::state::global_cache::CachedClass;
 use blockifier::state::state_api::{StateReader, StateResult};
 use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
 use serde::{Deserialize, Serialize};
@@ -20,6 +23,7 @@ use starknet_core::types::ContractClass as StarknetContractClass;
 use starknet_types_core::felt::Felt;
 
 use crate::state_reader::compile::{
+    flattened_sierra_to_sierra_contract_class,
     legacy_to_contract_class_v0,
     sierra_to_versioned_contract_class_v1,
 };
@@ -100,6 +104,7 @@ impl From<SerializableOfflineReexecutionData> for OfflineReexecutionData {
             state_maps: state_maps.try_into().expect("Failed to deserialize state maps."),
             contract_class_mapping,
             old_block_hash,
+            contract_class_manager: None,
         };
 
         // Use the declared classes from the next block to allow retrieving the class info.
@@ -127,6 +132,9 @@ pub struct OfflineStateReader {
     pub state_maps: StateMaps,
     pub contract_class_mapping: StarknetContractClassMapping,
     pub old_block_hash: BlockHash,
+    /// If set, compiled classes are cached by (and, if configured, compiled to Cairo native by)
+    /// this manager. Otherwise, they are compiled to CASM on each access.
+    pub contract_class_manager: Option<ContractClassManager>,
 }
 
 impl StateReader for OfflineStateReader {
@@ -158,6 +166,9 @@ impl StateReader for OfflineStateReader {
     }
 
     fn get_compiled_class(&self, class_hash: ClassHash) -> StateResult<RunnableCompiledClass> {
+        if let Some(contract_class_manager) = &self.contract_class_manager {
+            return self.get_compiled_class_from_manager(contract_class_manager, class_hash);
+        }
         match self.get_contract_class(&class_hash)? {
             StarknetContractClass::Sierra(sierra) => {
                 let (casm, _) = sierra_to_versioned_contract_class_v1(sierra).unwrap();
@@ -193,6 +204,41 @@ impl ReexecutionStateReader for OfflineStateReader {
 }
 
 impl OfflineStateReader {
+    fn get_compiled_class_from_manager(
+        &self,
+        contract_class_manager: &ContractClassManager,
+        class_hash: ClassHash,
+    ) -> StateResult<RunnableCompiledClass> {
+        if let Some(runnable_class) = contract_class_manager.get_runnable(&class_hash) {
+            return Ok(runnable_class);
+        }
+        let cached_class = match self.get_contract_class(&class_hash)? {
+            StarknetContractClass::Sierra(sierra) => {
+                let sierra_contract_class = flattened_sierra_to_sierra_contract_class(&sierra);
+                let (casm, _) = sierra_to_versioned_contract_class_v1(sierra)?;
+                let RunnableCompiledClass::V1(compiled_class_v1) =
+                    RunnableCompiledClass::try_from(casm)?
+                else {
+                    unreachable!("A Sierra class is compiled to a V1 class.");
+                };
+                CachedClass::V1(compiled_class_v1, Arc::new(sierra_contract_class))
+            }
+            StarknetContractClass::Legacy(legacy) => {
+                let RunnableCompiledClass::V0(compiled_class_v0) =
+                    RunnableCompiledClass::try_from(legacy_to_contract_class_v0(legacy)?)?
+                else {
+                    unreachable!("A legacy class is compiled to a V0 class.");
+                };
+                CachedClass::V0(compiled_class_v0)
+            }
+        };
+        // Compiles the class to Cairo native if the manager is configured to wait on compilation.
+        contract_class_manager.set_and_compile(class_hash, cached_class.clone());
+        Ok(contract_class_manager
+            .get_runnable(&class_hash)
+            .unwrap_or_else(|| cached_class.to_runnable()))
+    }
+
     pub fn get_transaction_executor(
         self,
         block_context_next_block: BlockContext,
@@ -241,6 +287,16 @@ impl OfflineConsecutiveStateReaders {
             state_diff_next_block,
         }
     }
+
+    /// Runs the classes of the block through the given manager, e.g., to execute them with Cairo
+    /// native.
+    pub fn with_contract_class_manager(
+        mut self,
+        contract_class_manager: ContractClassManager,
+    ) -> Self {
+        self.offline_state_reader_prev_block.contract_class_manager = Some(contract_class_manager);
+        self
+    }
 }
 
 impl ConsecutiveReexecutionStateReaders<OfflineStateReader> for OfflineConsecutiveStateReaders {
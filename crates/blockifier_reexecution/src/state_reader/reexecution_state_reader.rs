@@ -17,6 +17,8 @@ use crate::state_reader::compile::{
     sierra_to_versioned_contract_class_v1,
 };
 use crate::state_reader::errors::ReexecutionResult;
+use crate::state_reader::offline_state_reader::SerializableDataNextBlock;
+use crate::state_reader::test_state_reader::StarknetContractClassMapping;
 
 pub trait ReexecutionStateReader {
     fn get_contract_class(&self, class_hash: &ClassHash) -> StateResult<StarknetContractClass>;
@@ -105,3 +107,16 @@ pub trait ConsecutiveReexecutionStateReaders<S: StateReader> {
 
     fn get_next_block_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff>;
 }
+
+/// Consecutive state readers that record the data they read, which is required for reexecuting
+/// the next block offline.
+pub trait DumpingConsecutiveStateReaders<S: StateReader>:
+    ConsecutiveReexecutionStateReaders<S>
+{
+    fn get_serializable_data_next_block(&self) -> ReexecutionResult<SerializableDataNextBlock>;
+
+    fn get_old_block_hash(&self) -> ReexecutionResult<BlockHash>;
+
+    /// Returns the contract classes that the given state reader read.
+    fn get_contract_class_mapping_dumper(state_reader: &S) -> Option<StarknetContractClassMapping>;
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::{StorageError, StorageReader};
+use assert_matches::assert_matches;
+use blockifier::abi::constants;
+use blockifier::blockifier::block::validated_gas_prices;
+use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::blockifier_versioned_constants::VersionedConstants;
+use blockifier::bouncer::BouncerConfig;
+use blockifier::context::BlockContext;
+use blockifier::execution::contract_class::RunnableCompiledClass;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{StateReader, StateResult};
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use starknet_api::block::{
+    BlockHash,
+    BlockHashAndNumber,
+    BlockInfo,
+    BlockNumber,
+    NonzeroGasPrice,
+    StarknetVersion,
+};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::state::{StateNumber, StorageKey};
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_core::types::ContractClass as StarknetContractClass;
+use starknet_types_core::felt::Felt;
+
+use crate::state_reader::compile::{
+    deprecated_contract_class_to_compressed_legacy,
+    legacy_to_contract_class_v0,
+    sierra_contract_class_to_flattened_sierra,
+    sierra_to_versioned_contract_class_v1,
+};
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::offline_state_reader::SerializableDataNextBlock;
+use crate::state_reader::reexecution_state_reader::{
+    ConsecutiveReexecutionStateReaders,
+    DumpingConsecutiveStateReaders,
+    ReexecutionStateReader,
+};
+use crate::state_reader::test_state_reader::StarknetContractClassMapping;
+use crate::state_reader::utils::get_chain_info;
+
+/// A state reader of the state right after the given block, read from the storage of a node.
+#[derive(Clone)]
+pub struct StorageStateReader {
+    pub(crate) storage_reader: StorageReader,
+    pub(crate) block_number: BlockNumber,
+    pub(crate) chain_id: ChainId,
+    pub(crate) contract_class_mapping_dumper: Arc<Mutex<Option<StarknetContractClassMapping>>>,
+}
+
+impl StateReader for StorageStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.storage_reader
+            .begin_ro_txn()
+            .and_then(|txn| {
+                txn.get_state_reader()?.get_storage_at(self.state_number(), &contract_address, &key)
+            })
+            .map_err(storage_err_to_state_err)
+    }
+
+    // Returns the default value if the contract address is not found.
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(self
+            .storage_reader
+            .begin_ro_txn()
+            .and_then(|txn| {
+                txn.get_state_reader()?.get_nonce_at(self.state_number(), &contract_address)
+            })
+            .map_err(storage_err_to_state_err)?
+            .unwrap_or_default())
+    }
+
+    // Returns the default value if the contract address is not found.
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(self
+            .storage_reader
+            .begin_ro_txn()
+            .and_then(|txn| {
+                txn.get_state_reader()?.get_class_hash_at(self.state_number(), &contract_address)
+            })
+            .map_err(storage_err_to_state_err)?
+            .unwrap_or_default())
+    }
+
+    /// Returns the contract class of the given class hash.
+    /// Compile the contract class if it is Sierra.
+    fn get_compiled_class(&self, class_hash: ClassHash) -> StateResult<RunnableCompiledClass> {
+        match self.get_contract_class(&class_hash)? {
+            StarknetContractClass::Sierra(sierra) => {
+                let (casm, _) = sierra_to_versioned_contract_class_v1(sierra)?;
+                Ok(RunnableCompiledClass::try_from(casm)?)
+            }
+            StarknetContractClass::Legacy(legacy) => {
+                Ok(legacy_to_contract_class_v0(legacy)?.try_into()?)
+            }
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(storage_err_to_state_err)?;
+        let declaration_block_number = txn
+            .get_state_reader()
+            .and_then(|state_reader| state_reader.get_class_definition_block_number(&class_hash))
+            .map_err(storage_err_to_state_err)?
+            .filter(|declaration_block_number| *declaration_block_number <= self.block_number)
+            .ok_or(StateError::UndeclaredClassHash(class_hash))?;
+        let state_diff = txn
+            .get_state_diff(declaration_block_number)
+            .map_err(storage_err_to_state_err)?
+            .ok_or(StateError::StateReadError(format!(
+                "Missing state diff at block {declaration_block_number}."
+            )))?;
+        state_diff.declared_classes.get(&class_hash).copied().ok_or(StateError::StateReadError(
+            format!(
+                "Missing class declaration at block {declaration_block_number}, class \
+                 {class_hash}."
+            ),
+        ))
+    }
+}
+
+impl StorageStateReader {
+    pub fn new(
+        storage_reader: StorageReader,
+        chain_id: ChainId,
+        block_number: BlockNumber,
+        dump_mode: bool,
+    ) -> Self {
+        let contract_class_mapping_dumper = Arc::new(Mutex::new(match dump_mode {
+            true => Some(HashMap::new()),
+            false => None,
+        }));
+        Self { storage_reader, block_number, chain_id, contract_class_mapping_dumper }
+    }
+
+    fn state_number(&self) -> StateNumber {
+        StateNumber::unchecked_right_after_block(self.block_number)
+    }
+
+    /// Get the block info of the current block.
+    /// Gas prices that are zero in old blocks are set to the minimal gas price.
+    pub fn get_block_info(&self) -> ReexecutionResult<BlockInfo> {
+        let header = self
+            .storage_reader
+            .begin_ro_txn()?
+            .get_block_header(self.block_number)?
+            .ok_or(ReexecutionError::MissingBlockInStorage(self.block_number))?
+            .block_header_without_hash;
+        let gas_price = |price| NonzeroGasPrice::new(price).unwrap_or(NonzeroGasPrice::MIN);
+        Ok(BlockInfo {
+            block_number: header.block_number,
+            block_timestamp: header.timestamp,
+            sequencer_address: header.sequencer.0,
+            gas_prices: validated_gas_prices(
+                gas_price(header.l1_gas_price.price_in_wei),
+                gas_price(header.l1_gas_price.price_in_fri),
+                gas_price(header.l1_data_gas_price.price_in_wei),
+                gas_price(header.l1_data_gas_price.price_in_fri),
+                gas_price(header.l2_gas_price.price_in_wei),
+                gas_price(header.l2_gas_price.price_in_fri),
+            ),
+            use_kzg_da: header.l1_da_mode == L1DataAvailabilityMode::Blob,
+        })
+    }
+
+    pub fn get_starknet_version(&self) -> ReexecutionResult<StarknetVersion> {
+        self.storage_reader
+            .begin_ro_txn()?
+            .get_starknet_version(self.block_number)?
+            .ok_or(ReexecutionError::MissingBlockInStorage(self.block_number))
+    }
+
+    pub fn get_all_txs_in_block(&self) -> ReexecutionResult<Vec<(Transaction, TransactionHash)>> {
+        let txn = self.storage_reader.begin_ro_txn()?;
+        let missing_block = || ReexecutionError::MissingBlockInStorage(self.block_number);
+        let txs = txn.get_block_transactions(self.block_number)?.ok_or_else(missing_block)?;
+        let tx_hashes =
+            txn.get_block_transaction_hashes(self.block_number)?.ok_or_else(missing_block)?;
+        Ok(txs.into_iter().zip(tx_hashes).collect())
+    }
+
+    pub fn get_versioned_constants(&self) -> ReexecutionResult<&'static VersionedConstants> {
+        Ok(VersionedConstants::get(&self.get_starknet_version()?)?)
+    }
+
+    pub fn get_block_context(&self) -> ReexecutionResult<BlockContext> {
+        Ok(BlockContext::new(
+            self.get_block_info()?,
+            get_chain_info(&self.chain_id),
+            self.get_versioned_constants()?.clone(),
+            BouncerConfig::max(),
+        ))
+    }
+
+    pub fn get_transaction_executor(
+        self,
+        block_context_next_block: BlockContext,
+        transaction_executor_config: Option<TransactionExecutorConfig>,
+    ) -> ReexecutionResult<TransactionExecutor<StorageStateReader>> {
+        let old_block_number = BlockNumber(
+            block_context_next_block.block_info().block_number.0
+                - constants::STORED_BLOCK_HASH_BUFFER,
+        );
+        let old_block_hash = self.get_old_block_hash(old_block_number)?;
+        Ok(TransactionExecutor::<StorageStateReader>::pre_process_and_create(
+            self,
+            block_context_next_block,
+            Some(BlockHashAndNumber { number: old_block_number, hash: old_block_hash }),
+            transaction_executor_config.unwrap_or_default(),
+        )?)
+    }
+
+    pub fn get_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff> {
+        let state_diff = self
+            .storage_reader
+            .begin_ro_txn()?
+            .get_state_diff(self.block_number)?
+            .ok_or(ReexecutionError::MissingBlockInStorage(self.block_number))?;
+        // The deployed contracts of a stored state diff include the replaced classes.
+        Ok(CommitmentStateDiff {
+            address_to_class_hash: state_diff.deployed_contracts,
+            address_to_nonce: state_diff.nonces,
+            storage_updates: state_diff.storage_diffs,
+            class_hash_to_compiled_class_hash: state_diff.declared_classes,
+        })
+    }
+
+    pub fn get_contract_class_mapping_dumper(&self) -> Option<StarknetContractClassMapping> {
+        self.contract_class_mapping_dumper.lock().unwrap().clone()
+    }
+}
+
+impl ReexecutionStateReader for StorageStateReader {
+    fn get_contract_class(&self, class_hash: &ClassHash) -> StateResult<StarknetContractClass> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(storage_err_to_state_err)?;
+        let state_reader = txn.get_state_reader().map_err(storage_err_to_state_err)?;
+        let contract_class = match state_reader
+            .get_class_definition_at(self.state_number(), class_hash)
+            .map_err(storage_err_to_state_err)?
+        {
+            Some(sierra) => {
+                StarknetContractClass::Sierra(sierra_contract_class_to_flattened_sierra(sierra))
+            }
+            None => StarknetContractClass::Legacy(deprecated_contract_class_to_compressed_legacy(
+                state_reader
+                    .get_deprecated_class_definition_at(self.state_number(), class_hash)
+                    .map_err(storage_err_to_state_err)?
+                    .ok_or(StateError::UndeclaredClassHash(*class_hash))?,
+            )?),
+        };
+        // Create a binding to avoid value being dropped.
+        let mut dumper_binding = self.contract_class_mapping_dumper.lock().unwrap();
+        // If dumper exists, insert the contract class to the mapping.
+        if let Some(contract_class_mapping_dumper) = dumper_binding.as_mut() {
+            contract_class_mapping_dumper.insert(*class_hash, contract_class.clone());
+        }
+        Ok(contract_class)
+    }
+
+    fn get_old_block_hash(&self, old_block_number: BlockNumber) -> ReexecutionResult<BlockHash> {
+        Ok(self
+            .storage_reader
+            .begin_ro_txn()?
+            .get_block_header(old_block_number)?
+            .ok_or(ReexecutionError::MissingBlockInStorage(old_block_number))?
+            .block_hash)
+    }
+}
+
+pub struct ConsecutiveStorageStateReaders {
+    pub last_block_state_reader: StorageStateReader,
+    pub next_block_state_reader: StorageStateReader,
+}
+
+impl ConsecutiveStorageStateReaders {
+    pub fn new(
+        last_constructed_block_number: BlockNumber,
+        storage_reader: StorageReader,
+        chain_id: ChainId,
+        dump_mode: bool,
+    ) -> Self {
+        Self {
+            last_block_state_reader: StorageStateReader::new(
+                storage_reader.clone(),
+                chain_id.clone(),
+                last_constructed_block_number,
+                dump_mode,
+            ),
+            next_block_state_reader: StorageStateReader::new(
+                storage_reader,
+                chain_id,
+                last_constructed_block_number.next().expect("Overflow in block number"),
+                dump_mode,
+            ),
+        }
+    }
+
+    fn get_next_block_starknet_api_txs_and_declared_classes(
+        &self,
+    ) -> ReexecutionResult<(Vec<(Transaction, TransactionHash)>, StarknetContractClassMapping)>
+    {
+        let transactions_next_block = self.next_block_state_reader.get_all_txs_in_block()?;
+        self.next_block_state_reader
+            .api_txs_to_blockifier_txs_next_block(transactions_next_block.clone())?;
+        Ok((
+            transactions_next_block,
+            self.next_block_state_reader.get_contract_class_mapping_dumper().ok_or(
+                StateError::StateReadError("Contract class mapping dumper is None.".to_string()),
+            )?,
+        ))
+    }
+}
+
+impl ConsecutiveReexecutionStateReaders<StorageStateReader> for ConsecutiveStorageStateReaders {
+    fn pre_process_and_create_executor(
+        self,
+        transaction_executor_config: Option<TransactionExecutorConfig>,
+    ) -> ReexecutionResult<TransactionExecutor<StorageStateReader>> {
+        self.last_block_state_reader.get_transaction_executor(
+            self.next_block_state_reader.get_block_context()?,
+            transaction_executor_config,
+        )
+    }
+
+    fn get_next_block_txs(&self) -> ReexecutionResult<Vec<BlockifierTransaction>> {
+        self.next_block_state_reader.api_txs_to_blockifier_txs_next_block(
+            self.next_block_state_reader.get_all_txs_in_block()?,
+        )
+    }
+
+    fn get_next_block_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff> {
+        self.next_block_state_reader.get_state_diff()
+    }
+}
+
+impl DumpingConsecutiveStateReaders<StorageStateReader> for ConsecutiveStorageStateReaders {
+    fn get_serializable_data_next_block(&self) -> ReexecutionResult<SerializableDataNextBlock> {
+        let (transactions_next_block, declared_classes) =
+            self.get_next_block_starknet_api_txs_and_declared_classes()?;
+        assert_matches!(self.get_next_block_txs(), Ok(_));
+        Ok(SerializableDataNextBlock {
+            block_info_next_block: self.next_block_state_reader.get_block_info()?,
+            starknet_version: self.next_block_state_reader.get_starknet_version()?,
+            transactions_next_block,
+            state_diff_next_block: self.next_block_state_reader.get_state_diff()?,
+            declared_classes,
+        })
+    }
+
+    fn get_old_block_hash(&self) -> ReexecutionResult<BlockHash> {
+        self.last_block_state_reader.get_old_block_hash(BlockNumber(
+            self.next_block_state_reader.block_number.0 - constants::STORED_BLOCK_HASH_BUFFER,
+        ))
+    }
+
+    fn get_contract_class_mapping_dumper(
+        state_reader: &StorageStateReader,
+    ) -> Option<StarknetContractClassMapping> {
+        state_reader.get_contract_class_mapping_dumper()
+    }
+}
+
+// Converts a storage error to the error type of the state reader.
+fn storage_err_to_state_err(err: StorageError) -> StateError {
+    StateError::StateReadError(err.to_string())
+}
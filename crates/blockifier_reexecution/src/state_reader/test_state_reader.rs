@@ -42,6 +42,7 @@ use crate::state_reader::errors::ReexecutionResult;
 use crate::state_reader::offline_state_reader::SerializableDataNextBlock;
 use crate::state_reader::reexecution_state_reader::{
     ConsecutiveReexecutionStateReaders,
+    DumpingConsecutiveStateReaders,
     ReexecutionStateReader,
 };
 use crate::state_reader::serde_utils::{
@@ -402,26 +403,6 @@ impl ConsecutiveTestStateReaders {
         }
     }
 
-    pub fn get_serializable_data_next_block(&self) -> ReexecutionResult<SerializableDataNextBlock> {
-        let (transactions_next_block, declared_classes) =
-            self.get_next_block_starknet_api_txs_and_declared_classes()?;
-        assert_matches!(self.get_next_block_txs(), Ok(_));
-        Ok(SerializableDataNextBlock {
-            block_info_next_block: self.next_block_state_reader.get_block_info()?,
-            starknet_version: self.next_block_state_reader.get_starknet_version()?,
-            transactions_next_block,
-            state_diff_next_block: self.next_block_state_reader.get_state_diff()?,
-            declared_classes,
-        })
-    }
-
-    pub fn get_old_block_hash(&self) -> ReexecutionResult<BlockHash> {
-        self.last_block_state_reader.get_old_block_hash(BlockNumber(
-            self.next_block_state_reader.get_block_context()?.block_info().block_number.0
-                - constants::STORED_BLOCK_HASH_BUFFER,
-        ))
-    }
-
     fn get_next_block_starknet_api_txs_and_declared_classes(
         &self,
     ) -> ReexecutionResult<(Vec<(Transaction, TransactionHash)>, StarknetContractClassMapping)>
@@ -459,3 +440,31 @@ impl ConsecutiveReexecutionStateReaders<TestStateReader> for ConsecutiveTestStat
         self.next_block_state_reader.get_state_diff()
     }
 }
+
+impl DumpingConsecutiveStateReaders<TestStateReader> for ConsecutiveTestStateReaders {
+    fn get_serializable_data_next_block(&self) -> ReexecutionResult<SerializableDataNextBlock> {
+        let (transactions_next_block, declared_classes) =
+            self.get_next_block_starknet_api_txs_and_declared_classes()?;
+        assert_matches!(self.get_next_block_txs(), Ok(_));
+        Ok(SerializableDataNextBlock {
+            block_info_next_block: self.next_block_state_reader.get_block_info()?,
+            starknet_version: self.next_block_state_reader.get_starknet_version()?,
+            transactions_next_block,
+            state_diff_next_block: self.next_block_state_reader.get_state_diff()?,
+            declared_classes,
+        })
+    }
+
+    fn get_old_block_hash(&self) -> ReexecutionResult<BlockHash> {
+        self.last_block_state_reader.get_old_block_hash(BlockNumber(
+            self.next_block_state_reader.get_block_context()?.block_info().block_number.0
+                - constants::STORED_BLOCK_HASH_BUFFER,
+        ))
+    }
+
+    fn get_contract_class_mapping_dumper(
+        state_reader: &TestStateReader,
+    ) -> Option<StarknetContractClassMapping> {
+        state_reader.get_contract_class_mapping_dumper()
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::LazyLock;
 
 use apollo_gateway::config::RpcStateReaderConfig;
 use apollo_rpc_execution::{ETH_FEE_CONTRACT_ADDRESS, STRK_FEE_CONTRACT_ADDRESS};
+use apollo_storage::StorageReader;
 use assert_matches::assert_matches;
 use blockifier::context::{ChainInfo, FeeTokenAddresses};
 use blockifier::state::cached_state::{CachedState, CommitmentStateDiff, StateMaps};
@@ -24,7 +25,11 @@ use crate::state_reader::offline_state_reader::{
     SerializableDataPrevBlock,
     SerializableOfflineReexecutionData,
 };
-use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+use crate::state_reader::reexecution_state_reader::{
+    ConsecutiveReexecutionStateReaders,
+    DumpingConsecutiveStateReaders,
+};
+use crate::state_reader::storage_state_reader::ConsecutiveStorageStateReaders;
 use crate::state_reader::test_state_reader::ConsecutiveTestStateReaders;
 
 pub const FULL_RESOURCES_DIR: &str = "./crates/blockifier_reexecution/resources";
@@ -259,6 +264,20 @@ pub fn write_block_reexecution_data_to_file(
     node_url: String,
     chain_id: ChainId,
 ) {
+    get_block_reexecution_data_from_rpc(block_number, node_url, chain_id)
+        .write_to_file(&full_file_path)
+        .unwrap();
+
+    println!("RPC replies required for reexecuting block {block_number} written to json file.");
+}
+
+/// Reexecutes the block using an RPC node, verifies the reexecution, and returns the data required
+/// for reexecuting the block offline.
+pub fn get_block_reexecution_data_from_rpc(
+    block_number: BlockNumber,
+    node_url: String,
+    chain_id: ChainId,
+) -> SerializableOfflineReexecutionData {
     let config = RpcStateReaderConfig::from_url(node_url);
 
     let consecutive_state_readers = ConsecutiveTestStateReaders::new(
@@ -268,6 +287,35 @@ pub fn write_block_reexecution_data_to_file(
         true,
     );
 
+    get_block_reexecution_data(consecutive_state_readers, chain_id)
+}
+
+/// Reexecutes the block using the storage of a node, verifies the reexecution, and returns the
+/// data required for reexecuting the block offline.
+pub fn get_block_reexecution_data_from_storage(
+    block_number: BlockNumber,
+    storage_reader: StorageReader,
+    chain_id: ChainId,
+) -> SerializableOfflineReexecutionData {
+    let consecutive_state_readers = ConsecutiveStorageStateReaders::new(
+        block_number.prev().expect("Should not run with block 0"),
+        storage_reader,
+        chain_id.clone(),
+        true,
+    );
+
+    get_block_reexecution_data(consecutive_state_readers, chain_id)
+}
+
+/// Reexecutes the next block of the given state readers, verifies the reexecution, and returns the
+/// data required for reexecuting the block offline.
+fn get_block_reexecution_data<
+    S: StateReader + Send + Sync + Clone,
+    T: DumpingConsecutiveStateReaders<S>,
+>(
+    consecutive_state_readers: T,
+    chain_id: ChainId,
+) -> SerializableOfflineReexecutionData {
     let serializable_data_next_block =
         consecutive_state_readers.get_serializable_data_next_block().unwrap();
 
@@ -277,20 +325,15 @@ pub fn write_block_reexecution_data_to_file(
     let block_state = reexecute_and_verify_correctness(consecutive_state_readers).unwrap();
     let serializable_data_prev_block = SerializableDataPrevBlock {
         state_maps: block_state.get_initial_reads().unwrap().into(),
-        contract_class_mapping: block_state.state.get_contract_class_mapping_dumper().unwrap(),
+        contract_class_mapping: T::get_contract_class_mapping_dumper(&block_state.state).unwrap(),
     };
 
-    // Write the reexecution data to a json file.
     SerializableOfflineReexecutionData {
         serializable_data_prev_block,
         serializable_data_next_block,
         chain_id,
         old_block_hash,
     }
-    .write_to_file(&full_file_path)
-    .unwrap();
-
-    println!("RPC replies required for reexecuting block {block_number} written to json file.");
 }
 
 /// Asserts equality between two `CommitmentStateDiff` structs, ignoring insertion order.
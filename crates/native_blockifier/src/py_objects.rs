@@ -204,6 +204,7 @@ impl From<PyCairoNativeRunConfig> for CairoNativeRunConfig {
             wait_on_native_compilation: py_cairo_native_run_config.wait_on_native_compilation,
             channel_size: py_cairo_native_run_config.channel_size,
             native_classes_whitelist,
+            ..Default::default()
         }
     }
 }
@@ -233,6 +234,7 @@ impl From<PyContractClassManagerConfig> for ContractClassManagerConfig {
                 .cairo_native_run_config
                 .into(),
             native_compiler_config: py_contract_class_manager_config.native_compiler_config.into(),
+            ..Default::default()
         }
     }
 }
@@ -1,15 +1,26 @@
+use alloy::primitives::{Address as EthereumContractAddress, U256};
+use alloy::rpc::types::Log;
+use alloy::sol_types::SolEvent;
+use assert_matches::assert_matches;
 use mempool_test_utils::in_ci;
 use pretty_assertions::assert_eq;
 use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
-use starknet_api::felt;
+use starknet_api::core::{EntryPointSelector, EthAddress, Nonce};
+use starknet_api::transaction::L1HandlerTransaction;
+use starknet_api::{calldata, contract_address, felt};
 
-use crate::ethereum_base_layer_contract::{EthereumBaseLayerConfig, EthereumBaseLayerContract};
+use crate::ethereum_base_layer_contract::{
+    EthereumBaseLayerConfig,
+    EthereumBaseLayerContract,
+    EthereumBaseLayerError,
+    Starknet,
+};
 use crate::test_utils::{
     anvil_instance_from_config,
     ethereum_base_layer_config_for_anvil,
     get_test_ethereum_node,
 };
-use crate::BaseLayerContract;
+use crate::{BaseLayerContract, EventData, L1Event};
 
 #[tokio::test]
 // Note: the test requires ganache-cli installed, otherwise it is ignored.
@@ -56,15 +67,13 @@ async fn get_proved_block_at_unknown_block_number() {
     let _anvil = anvil_instance_from_config(&config);
     let contract = EthereumBaseLayerContract::new(config);
 
-    assert!(
-        contract
+    assert!(contract
             .get_proved_block_at(123)
             .await
             .unwrap_err()
             // This error is nested way too deep inside `alloy`.
             .to_string()
-            .contains("BlockOutOfRangeError")
-    );
+            .contains("BlockOutOfRangeError"));
 }
 
 #[tokio::test]
@@ -89,3 +98,53 @@ async fn get_gas_price_and_timestamps() {
     assert_eq!(price_sample.base_fee_per_gas, 20168195);
     assert_eq!(price_sample.blob_fee, 0);
 }
+
+#[test]
+fn message_to_l2_cancellation_started_event() {
+    let event = Starknet::MessageToL2CancellationStarted {
+        fromAddress: EthereumContractAddress::left_padding_from(&[0x12]),
+        toAddress: U256::from(0x34),
+        selector: U256::from(0x56),
+        payload: vec![U256::from(7), U256::from(8)],
+        nonce: U256::from(9),
+    };
+    let log = |block_timestamp| Log {
+        inner: alloy::primitives::Log {
+            address: EthereumContractAddress::ZERO,
+            data: event.encode_log_data(),
+        },
+        block_timestamp,
+        ..Default::default()
+    };
+
+    let cancelled_message = EventData {
+        from_address: EthAddress::try_from(felt!(0x12_u8)).unwrap(),
+        to_address: contract_address!(0x34_u8),
+        entry_point_selector: EntryPointSelector(felt!(0x56_u8)),
+        payload: calldata![felt!(7_u8), felt!(8_u8)],
+        nonce: Nonce(felt!(9_u8)),
+    };
+    assert_eq!(
+        L1Event::try_from(log(Some(1000))).unwrap(),
+        L1Event::MessageToL2CancellationStarted {
+            cancelled_message: cancelled_message.clone(),
+            cancellation_request_timestamp: 1000,
+        }
+    );
+    assert_matches!(
+        L1Event::try_from(log(None)),
+        Err(EthereumBaseLayerError::MissingBlockTimestamp(_))
+    );
+
+    // The L1 handler transaction of the message is prefixed with the L1 sender address.
+    assert_eq!(
+        cancelled_message.into_l1_handler_tx(),
+        L1HandlerTransaction {
+            version: L1HandlerTransaction::VERSION,
+            contract_address: contract_address!(0x34_u8),
+            entry_point_selector: EntryPointSelector(felt!(0x56_u8)),
+            nonce: Nonce(felt!(9_u8)),
+            calldata: calldata![felt!(0x12_u8), felt!(7_u8), felt!(8_u8)],
+        }
+    );
+}
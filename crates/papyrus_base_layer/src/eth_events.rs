@@ -2,10 +2,10 @@ use std::sync::Arc;
 
 use alloy::primitives::{Address as EthereumContractAddress, U256};
 use alloy::rpc::types::Log;
-use alloy::sol_types::SolEventInterface;
+use alloy::sol_types::{SolEvent, SolEventInterface};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{EntryPointSelector, Nonce};
 use starknet_api::transaction::fields::{Calldata, Fee};
-use starknet_api::transaction::L1HandlerTransaction;
 use starknet_types_core::felt::Felt;
 
 use crate::ethereum_base_layer_contract::{
@@ -13,13 +13,14 @@ use crate::ethereum_base_layer_contract::{
     EthereumBaseLayerResult,
     Starknet,
 };
-use crate::{EventData, L1Event};
+use crate::{EventData, L1BlockReference, L1Event, L1StateUpdate};
 
 impl TryFrom<Log> for L1Event {
     type Error = EthereumBaseLayerError;
 
     fn try_from(log: Log) -> EthereumBaseLayerResult<Self> {
         let validate = true;
+        let block_timestamp = log.block_timestamp;
         let log = log.inner;
 
         let event = Starknet::StarknetEvents::decode_log(&log, validate)?.data;
@@ -27,20 +28,7 @@ impl TryFrom<Log> for L1Event {
             Starknet::StarknetEvents::LogMessageToL2(event) => {
                 let fee =
                     Fee(event.fee.try_into().map_err(EthereumBaseLayerError::FeeOutOfRange)?);
-                let mut event_data = EventData::try_from(event)?;
-                let payload_inner = Arc::get_mut(&mut event_data.payload.0).expect(
-                    "The event data is the only owner and was initialized in the previous line",
-                );
-                // Prepend the L1 sender address to the calldata.
-                payload_inner.insert(0, event_data.from_address.into());
-
-                let tx = L1HandlerTransaction {
-                    version: L1HandlerTransaction::VERSION,
-                    contract_address: event_data.to_address,
-                    entry_point_selector: event_data.entry_point_selector,
-                    nonce: event_data.nonce,
-                    calldata: event_data.payload,
-                };
+                let tx = EventData::try_from(event)?.into_l1_handler_tx();
                 Ok(L1Event::LogMessageToL2 { tx, fee })
             }
             Starknet::StarknetEvents::ConsumedMessageToL2(event) => {
@@ -50,13 +38,42 @@ impl TryFrom<Log> for L1Event {
                 Ok(L1Event::MessageToL2Canceled(event.try_into()?))
             }
             Starknet::StarknetEvents::MessageToL2CancellationStarted(event) => {
-                Ok(L1Event::MessageToL2CancellationStarted(event.try_into()?))
+                let Some(cancellation_request_timestamp) = block_timestamp else {
+                    return Err(EthereumBaseLayerError::MissingBlockTimestamp(log));
+                };
+                Ok(L1Event::MessageToL2CancellationStarted {
+                    cancelled_message: event.try_into()?,
+                    cancellation_request_timestamp,
+                })
             }
             _ => Err(EthereumBaseLayerError::UnhandledL1Event(log)),
         }
     }
 }
 
+impl TryFrom<Log> for L1StateUpdate {
+    type Error = EthereumBaseLayerError;
+
+    fn try_from(log: Log) -> EthereumBaseLayerResult<Self> {
+        let (Some(l1_block_number), Some(l1_block_hash), Some(l1_transaction_hash)) =
+            (log.block_number, log.block_hash, log.transaction_hash)
+        else {
+            return Err(EthereumBaseLayerError::InvalidStateUpdate(log));
+        };
+        let validate = true;
+        let event = Starknet::LogStateUpdate::decode_log(&log.inner, validate)?.data;
+        // The block number is negative only before the first state update.
+        let Ok(block_number) = u64::try_from(event.blockNumber) else {
+            return Err(EthereumBaseLayerError::InvalidStateUpdate(log));
+        };
+        Ok(L1StateUpdate {
+            block_number: BlockNumber(block_number),
+            l1_block: L1BlockReference { number: l1_block_number, hash: l1_block_hash.0 },
+            l1_transaction_hash: l1_transaction_hash.0,
+        })
+    }
+}
+
 impl TryFrom<Starknet::MessageToL2Canceled> for EventData {
     type Error = EthereumBaseLayerError;
 
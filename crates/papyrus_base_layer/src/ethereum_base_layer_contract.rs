@@ -1,10 +1,11 @@
+use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 use std::future::IntoFuture;
 use std::ops::RangeInclusive;
 
 use alloy::dyn_abi::SolType;
 use alloy::eips::eip7840;
-use alloy::primitives::Address as EthereumContractAddress;
+use alloy::primitives::{keccak256, Address as EthereumContractAddress, FixedBytes};
 use alloy::providers::network::Ethereum;
 use alloy::providers::{Provider, ProviderBuilder, RootProvider};
 use alloy::rpc::json_rpc::RpcError;
@@ -14,8 +15,9 @@ use alloy::rpc::types::eth::{
     BlockTransactionsKind,
     Filter as EthEventFilter,
 };
+use alloy::rpc::types::Log;
 use alloy::sol;
-use alloy::sol_types::sol_data;
+use alloy::sol_types::{sol_data, SolEvent};
 use alloy::transports::http::{Client, Http};
 use alloy::transports::TransportErrorKind;
 use apollo_config::dumping::{ser_param, SerializeConfig};
@@ -24,11 +26,21 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
 use starknet_api::hash::StarkHash;
+use starknet_api::transaction::L1HandlerTransaction;
 use starknet_api::StarknetApiError;
 use url::Url;
 use validator::Validate;
 
-use crate::{BaseLayerContract, L1BlockNumber, L1BlockReference, L1Event, PriceSample};
+use crate::{
+    BaseLayerContract,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+    L1StateUpdate,
+    L1TransactionHash,
+    MessageToL2Cancellation,
+    PriceSample,
+};
 
 pub type EthereumBaseLayerResult<T> = Result<T, EthereumBaseLayerError>;
 
@@ -54,6 +66,32 @@ impl EthereumBaseLayerContract {
         let contract = Starknet::new(config.starknet_contract_address, l1_client);
         Self { contract, config }
     }
+
+    /// Sets the missing block timestamps of message cancellation requests, which are part of the
+    /// events, as some nodes don't include block timestamps in logs.
+    async fn set_missing_block_timestamps(&self, logs: &mut [Log]) -> EthereumBaseLayerResult<()> {
+        let mut block_timestamps = BTreeMap::new();
+        for log in logs.iter_mut().filter(|log| {
+            log.block_timestamp.is_none()
+                && log.topic0() == Some(&Starknet::MessageToL2CancellationStarted::SIGNATURE_HASH)
+        }) {
+            let Some(block_number) = log.block_number else {
+                continue;
+            };
+            log.block_timestamp = match block_timestamps.entry(block_number) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let block = self
+                        .contract
+                        .provider()
+                        .get_block(BlockId::Number(block_number.into()), Default::default())
+                        .await?;
+                    *entry.insert(block.map(|block| block.header.timestamp))
+                }
+            };
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -100,10 +138,37 @@ impl BaseLayerContract for EthereumBaseLayerContract {
     ) -> EthereumBaseLayerResult<Vec<L1Event>> {
         let filter = EthEventFilter::new().select(block_range).events(events);
 
-        let matching_logs = self.contract.provider().get_logs(&filter).await?;
+        let mut matching_logs = self.contract.provider().get_logs(&filter).await?;
+        self.set_missing_block_timestamps(&mut matching_logs).await?;
         matching_logs.into_iter().map(TryInto::try_into).collect()
     }
 
+    async fn transaction_events(
+        &self,
+        transaction_hash: L1TransactionHash,
+    ) -> EthereumBaseLayerResult<Vec<L1Event>> {
+        let Some(receipt) =
+            self.contract.provider().get_transaction_receipt(transaction_hash.into()).await?
+        else {
+            return Ok(vec![]);
+        };
+        let mut logs: Vec<_> = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == *self.contract.address())
+            .cloned()
+            .collect();
+        self.set_missing_block_timestamps(&mut logs).await?;
+        logs.into_iter()
+            .filter_map(|log| match L1Event::try_from(log) {
+                // The contract emits other events as well, e.g. state updates.
+                Err(EthereumBaseLayerError::UnhandledL1Event(_)) => None,
+                event => Some(event),
+            })
+            .collect()
+    }
+
     async fn latest_l1_block_number(
         &self,
         finality: u64,
@@ -177,6 +242,65 @@ impl BaseLayerContract for EthereumBaseLayerContract {
             blob_fee,
         }))
     }
+
+    async fn state_updates(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> EthereumBaseLayerResult<Vec<L1StateUpdate>> {
+        let filter = EthEventFilter::new()
+            .address(*self.contract.address())
+            .select(block_range)
+            .event(Starknet::LogStateUpdate::SIGNATURE);
+        let logs = self.contract.provider().get_logs(&filter).await?;
+        logs.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> EthereumBaseLayerResult<MessageToL2Cancellation> {
+        let message_hash = message_to_l2_hash(message);
+        let call_cancellation_request_timestamp =
+            self.contract.l1ToL2MessageCancellations(message_hash);
+        let call_fee_plus_one = self.contract.l1ToL2Messages(message_hash);
+
+        let (cancellation_request_timestamp, fee_plus_one) = tokio::try_join!(
+            call_cancellation_request_timestamp.call_raw().into_future(),
+            call_fee_plus_one.call_raw().into_future()
+        )?;
+
+        let validate = true;
+        let cancellation_request_timestamp =
+            sol_data::Uint::<64>::abi_decode(&cancellation_request_timestamp, validate)?;
+        let fee_plus_one = sol_data::Uint::<256>::abi_decode(&fee_plus_one, validate)?;
+        // The fee of a message is deleted when the message is consumed or canceled, and the
+        // cancellation request is kept.
+        Ok(match (cancellation_request_timestamp, fee_plus_one.is_zero()) {
+            (0, _) => MessageToL2Cancellation::NotRequested,
+            (_, true) => MessageToL2Cancellation::Canceled,
+            (cancellation_request_timestamp, false) => {
+                MessageToL2Cancellation::Requested { cancellation_request_timestamp }
+            }
+        })
+    }
+}
+
+/// Returns the hash by which the Starknet contract identifies a message from L1 to L2, i.e., the
+/// keccak of the sender, the recipient, the nonce, the selector and the length-prefixed payload.
+fn message_to_l2_hash(message: &L1HandlerTransaction) -> FixedBytes<32> {
+    // The calldata of the transaction is the sender of the message followed by the payload.
+    let from_address = message.calldata.0.first().copied().unwrap_or_default();
+    let payload = message.calldata.0.get(1..).unwrap_or_default();
+    let words = [
+        from_address,
+        *message.contract_address.0.key(),
+        message.nonce.0,
+        message.entry_point_selector.0,
+        StarkHash::from(payload.len()),
+    ]
+    .into_iter()
+    .chain(payload.iter().copied());
+    keccak256(words.flat_map(|word| word.to_bytes_be()).collect::<Vec<_>>())
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -185,6 +309,10 @@ pub enum EthereumBaseLayerError {
     Contract(#[from] alloy::contract::Error),
     #[error("{0}")]
     FeeOutOfRange(alloy::primitives::ruint::FromUintError<u128>),
+    #[error("Invalid state update {0:?}")]
+    InvalidStateUpdate(Log),
+    #[error("Missing block timestamp of {0:?}")]
+    MissingBlockTimestamp(alloy::primitives::Log),
     #[error(transparent)]
     RpcError(#[from] RpcError<TransportErrorKind>),
     #[error("{0}")]
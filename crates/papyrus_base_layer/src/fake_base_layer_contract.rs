@@ -0,0 +1,414 @@
+//! An in-process base layer with a scriptable chain, for testing flows that scrape the base layer
+//! without spawning an L1 node.
+//!
+//! Tests script the chain through a [`FakeBaseLayerContract`] handle, while a clone of it is given
+//! to the tested component (e.g., the L1 scraper, the L1 gas price scraper or central sync). All
+//! clones share the same chain.
+
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use thiserror::Error;
+
+use crate::constants::{
+    EventIdentifier,
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::{
+    BaseLayerContract,
+    EventData,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+    L1StateUpdate,
+    L1TransactionHash,
+    MessageToL2Cancellation,
+    PriceSample,
+};
+
+#[cfg(test)]
+#[path = "fake_base_layer_contract_test.rs"]
+mod fake_base_layer_contract_test;
+
+/// The time between consecutive blocks of the fake chain, in seconds.
+pub const FAKE_L1_BLOCK_TIME_SECONDS: u64 = 12;
+
+pub type FakeBaseLayerResult<T> = Result<T, FakeBaseLayerError>;
+
+#[derive(Debug, Error)]
+pub enum FakeBaseLayerError {
+    #[error("The base layer is unavailable.")]
+    Unavailable,
+}
+
+#[derive(Clone, Debug)]
+struct FakeL1Transaction {
+    hash: L1TransactionHash,
+    events: Vec<L1Event>,
+    // The Starknet block proved by the transaction, if it's a state update.
+    state_update: Option<BlockNumber>,
+}
+
+#[derive(Clone, Debug)]
+struct FakeL1Block {
+    reference: L1BlockReference,
+    price_sample: PriceSample,
+    transactions: Vec<FakeL1Transaction>,
+    // The latest Starknet block proved on the base layer, as of this block.
+    proved_block: BlockHashAndNumber,
+}
+
+#[derive(Debug)]
+struct FakeL1Chain {
+    blocks: Vec<FakeL1Block>,
+    // Transactions that are included in the next block.
+    pending_transactions: Vec<FakeL1Transaction>,
+    pending_proved_block: Option<BlockHashAndNumber>,
+    base_fee_per_gas: u128,
+    blob_fee: u128,
+    n_reorgs: u64,
+    n_transactions: u64,
+    n_messages_to_l2: u64,
+    unavailable: bool,
+}
+
+impl FakeL1Chain {
+    fn block(&self, block_number: L1BlockNumber) -> Option<&FakeL1Block> {
+        self.blocks.get(usize::try_from(block_number).ok()?)
+    }
+
+    fn latest_block(&self) -> &FakeL1Block {
+        self.blocks.last().expect("The fake chain always has a genesis block.")
+    }
+
+    fn latest_block_number_with_finality(&self, finality: u64) -> Option<L1BlockNumber> {
+        self.latest_block().reference.number.checked_sub(finality)
+    }
+
+    fn mine_block(&mut self) -> L1BlockReference {
+        let parent = self.blocks.last();
+        let number = parent.map_or(0, |parent| parent.reference.number + 1);
+        let timestamp =
+            parent.map_or(0, |parent| parent.price_sample.timestamp + FAKE_L1_BLOCK_TIME_SECONDS);
+        let proved_block = self
+            .pending_proved_block
+            .take()
+            .or_else(|| parent.map(|parent| parent.proved_block))
+            .unwrap_or_default();
+
+        // Blocks mined after a reorg have different hashes than the blocks they replace.
+        let mut hash = [0; 32];
+        hash[..8].copy_from_slice(&number.to_be_bytes());
+        hash[8..16].copy_from_slice(&self.n_reorgs.to_be_bytes());
+        let reference = L1BlockReference { number, hash };
+
+        let mut transactions = std::mem::take(&mut self.pending_transactions);
+        for event in transactions.iter_mut().flat_map(|tx| tx.events.iter_mut()) {
+            if let L1Event::MessageToL2CancellationStarted {
+                cancellation_request_timestamp, ..
+            } = event
+            {
+                *cancellation_request_timestamp = timestamp;
+            }
+        }
+
+        self.blocks.push(FakeL1Block {
+            reference,
+            price_sample: PriceSample {
+                timestamp,
+                base_fee_per_gas: self.base_fee_per_gas,
+                blob_fee: self.blob_fee,
+            },
+            transactions,
+            proved_block,
+        });
+        reference
+    }
+
+    fn add_transaction(
+        &mut self,
+        events: Vec<L1Event>,
+        state_update: Option<BlockNumber>,
+    ) -> L1TransactionHash {
+        self.n_transactions += 1;
+        let mut hash = [0; 32];
+        hash[24..].copy_from_slice(&self.n_transactions.to_be_bytes());
+        self.pending_transactions.push(FakeL1Transaction { hash, events, state_update });
+        hash
+    }
+}
+
+/// A base layer contract backed by an in-memory chain, which starts with a genesis block.
+///
+/// Block `n` has the timestamp `n * FAKE_L1_BLOCK_TIME_SECONDS`, and its hash encodes its number
+/// and the number of reorgs that preceded it.
+#[derive(Clone, Debug)]
+pub struct FakeBaseLayerContract {
+    chain: Arc<Mutex<FakeL1Chain>>,
+}
+
+impl Default for FakeBaseLayerContract {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeBaseLayerContract {
+    pub fn new() -> Self {
+        let mut chain = FakeL1Chain {
+            blocks: Vec::new(),
+            pending_transactions: Vec::new(),
+            pending_proved_block: None,
+            base_fee_per_gas: 0,
+            blob_fee: 0,
+            n_reorgs: 0,
+            n_transactions: 0,
+            n_messages_to_l2: 0,
+            unavailable: false,
+        };
+        chain.mine_block();
+        Self { chain: Arc::new(Mutex::new(chain)) }
+    }
+
+    /// Adds a transaction that emits the given events to the next block, and returns its hash.
+    /// The cancellation request timestamps of the events are set to the timestamp of the block.
+    pub fn add_transaction(&self, events: Vec<L1Event>) -> L1TransactionHash {
+        self.lock().add_transaction(events, None)
+    }
+
+    /// Adds a transaction that sends the given message to L2 to the next block, and returns its
+    /// hash. Like the Starknet contract, the message is given the next nonce, ignoring its own.
+    pub fn send_message_to_l2(&self, message: EventData, fee: Fee) -> L1TransactionHash {
+        let mut chain = self.lock();
+        let nonce = Nonce(chain.n_messages_to_l2.into());
+        chain.n_messages_to_l2 += 1;
+        let tx = EventData { nonce, ..message }.into_l1_handler_tx();
+        chain.add_transaction(vec![L1Event::LogMessageToL2 { tx, fee }], None)
+    }
+
+    /// Adds a state update transaction that proves the given Starknet block to the next block, and
+    /// returns its hash.
+    pub fn prove_block(&self, proved_block: BlockHashAndNumber) -> L1TransactionHash {
+        let mut chain = self.lock();
+        chain.pending_proved_block = Some(proved_block);
+        chain.add_transaction(vec![], Some(proved_block.number))
+    }
+
+    /// Sets the gas prices of the blocks mined from now on.
+    pub fn set_prices(&self, base_fee_per_gas: u128, blob_fee: u128) {
+        let mut chain = self.lock();
+        chain.base_fee_per_gas = base_fee_per_gas;
+        chain.blob_fee = blob_fee;
+    }
+
+    /// Mines a block with the pending transactions and state updates.
+    pub fn mine_block(&self) -> L1BlockReference {
+        self.lock().mine_block()
+    }
+
+    /// Mines a block with a single transaction that emits the given events, if there are any, in
+    /// addition to the pending transactions.
+    pub fn add_block(&self, events: Vec<L1Event>) -> L1BlockReference {
+        if !events.is_empty() {
+            self.add_transaction(events);
+        }
+        self.mine_block()
+    }
+
+    /// Removes all the blocks after the fork point, along with their transactions. Blocks mined
+    /// from now on have different hashes than the removed blocks.
+    pub fn reorg(&self, fork_point: L1BlockNumber) {
+        let mut chain = self.lock();
+        let n_blocks = usize::try_from(fork_point).expect("Fork point should fit in usize.") + 1;
+        assert!(n_blocks <= chain.blocks.len(), "Fork point {fork_point} is not in the chain.");
+        chain.blocks.truncate(n_blocks);
+        chain.n_reorgs += 1;
+    }
+
+    /// While the base layer is unavailable, all the queries fail.
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.lock().unavailable = unavailable;
+    }
+
+    pub fn latest_block(&self) -> L1BlockReference {
+        self.lock().latest_block().reference
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeL1Chain> {
+        self.chain.lock().expect("The fake chain lock should not be poisoned.")
+    }
+
+    fn available_chain(&self) -> FakeBaseLayerResult<MutexGuard<'_, FakeL1Chain>> {
+        let chain = self.lock();
+        if chain.unavailable {
+            return Err(FakeBaseLayerError::Unavailable);
+        }
+        Ok(chain)
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for FakeBaseLayerContract {
+    type Error = FakeBaseLayerError;
+
+    /// Returns the default (zero) block if nothing was proved by the given block, like the Starknet
+    /// contract does before its first state update. Blocks after the latest one are treated as the
+    /// latest block.
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> FakeBaseLayerResult<BlockHashAndNumber> {
+        let chain = self.available_chain()?;
+        let block = chain.block(l1_block).unwrap_or_else(|| chain.latest_block());
+        Ok(block.proved_block)
+    }
+
+    async fn latest_proved_block(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<BlockHashAndNumber>> {
+        let chain = self.available_chain()?;
+        Ok(chain
+            .latest_block_number_with_finality(finality)
+            .and_then(|number| chain.block(number))
+            .map(|block| block.proved_block))
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<L1BlockNumber>> {
+        Ok(self.available_chain()?.latest_block_number_with_finality(finality))
+    }
+
+    async fn latest_l1_block(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<L1BlockReference>> {
+        let chain = self.available_chain()?;
+        Ok(chain
+            .latest_block_number_with_finality(finality)
+            .and_then(|number| chain.block(number))
+            .map(|block| block.reference))
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> FakeBaseLayerResult<Option<L1BlockReference>> {
+        Ok(self.available_chain()?.block(block_number).map(|block| block.reference))
+    }
+
+    async fn events<'a>(
+        &'a self,
+        block_range: RangeInclusive<L1BlockNumber>,
+        event_identifiers: &'a [&'a str],
+    ) -> FakeBaseLayerResult<Vec<L1Event>> {
+        let chain = self.available_chain()?;
+        Ok(chain
+            .blocks
+            .iter()
+            .filter(|block| block_range.contains(&block.reference.number))
+            .flat_map(|block| block.transactions.iter().flat_map(|tx| tx.events.iter()))
+            .filter(|event| event_identifiers.contains(&event_identifier(event)))
+            .cloned()
+            .collect())
+    }
+
+    async fn transaction_events(
+        &self,
+        transaction_hash: L1TransactionHash,
+    ) -> FakeBaseLayerResult<Vec<L1Event>> {
+        let chain = self.available_chain()?;
+        Ok(chain
+            .blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter())
+            .find(|tx| tx.hash == transaction_hash)
+            .map(|tx| tx.events.clone())
+            .unwrap_or_default())
+    }
+
+    async fn get_price_sample(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> FakeBaseLayerResult<Option<PriceSample>> {
+        Ok(self.available_chain()?.block(block_number).map(|block| block.price_sample.clone()))
+    }
+
+    async fn state_updates(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> FakeBaseLayerResult<Vec<L1StateUpdate>> {
+        let chain = self.available_chain()?;
+        Ok(chain
+            .blocks
+            .iter()
+            .filter(|block| block_range.contains(&block.reference.number))
+            .flat_map(|block| {
+                block.transactions.iter().filter_map(|tx| {
+                    tx.state_update.map(|block_number| L1StateUpdate {
+                        block_number,
+                        l1_block: block.reference,
+                        l1_transaction_hash: tx.hash,
+                    })
+                })
+            })
+            .collect())
+    }
+
+    /// Follows the cancellation events of the message, like the Starknet contract does.
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> FakeBaseLayerResult<MessageToL2Cancellation> {
+        let chain = self.available_chain()?;
+        let is_message =
+            |event_data: &EventData| event_data.clone().into_l1_handler_tx() == *message;
+        let mut cancellation = MessageToL2Cancellation::NotRequested;
+        for event in chain
+            .blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter())
+            .flat_map(|tx| &tx.events)
+        {
+            cancellation = match (event, cancellation) {
+                (
+                    L1Event::MessageToL2CancellationStarted {
+                        cancelled_message,
+                        cancellation_request_timestamp,
+                    },
+                    _,
+                ) if is_message(cancelled_message) => MessageToL2Cancellation::Requested {
+                    cancellation_request_timestamp: *cancellation_request_timestamp,
+                },
+                (
+                    L1Event::MessageToL2Canceled(event_data)
+                    | L1Event::ConsumedMessageToL2(event_data),
+                    MessageToL2Cancellation::Requested { .. },
+                ) if is_message(event_data) => MessageToL2Cancellation::Canceled,
+                _ => cancellation,
+            };
+        }
+        Ok(cancellation)
+    }
+}
+
+fn event_identifier(event: &L1Event) -> EventIdentifier {
+    match event {
+        L1Event::ConsumedMessageToL2(_) => CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+        L1Event::LogMessageToL2 { .. } => LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+        L1Event::MessageToL2CancellationStarted { .. } => {
+            MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER
+        }
+        L1Event::MessageToL2Canceled(_) => MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
+    }
+}
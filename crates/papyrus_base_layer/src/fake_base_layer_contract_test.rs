@@ -0,0 +1,202 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::fields::Fee;
+use starknet_types_core::felt::Felt;
+
+use crate::constants::{
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::fake_base_layer_contract::{
+    FakeBaseLayerContract,
+    FakeBaseLayerError,
+    FAKE_L1_BLOCK_TIME_SECONDS,
+};
+use crate::{BaseLayerContract, EventData, L1Event, L1StateUpdate, MessageToL2Cancellation};
+
+fn message(nonce: u8) -> EventData {
+    EventData { nonce: Nonce(nonce.into()), ..Default::default() }
+}
+
+fn proved_block(number: u64) -> BlockHashAndNumber {
+    BlockHashAndNumber { number: BlockNumber(number), hash: BlockHash(Felt::from(number)) }
+}
+
+#[tokio::test]
+async fn blocks_and_finality() {
+    let base_layer = FakeBaseLayerContract::new();
+    assert_eq!(base_layer.latest_l1_block_number(0).await.unwrap(), Some(0));
+    assert_eq!(base_layer.latest_l1_block_number(1).await.unwrap(), None);
+
+    let block_1 = base_layer.mine_block();
+    let block_2 = base_layer.mine_block();
+    assert_eq!((block_1.number, block_2.number), (1, 2));
+    assert_eq!(base_layer.latest_l1_block(0).await.unwrap(), Some(block_2));
+    assert_eq!(base_layer.latest_l1_block(1).await.unwrap(), Some(block_1));
+    assert_eq!(base_layer.l1_block_at(1).await.unwrap(), Some(block_1));
+    assert_eq!(base_layer.l1_block_at(3).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn events_and_transactions() {
+    let base_layer = FakeBaseLayerContract::new();
+    let log_message = L1Event::LogMessageToL2 { tx: message(0).into_l1_handler_tx(), fee: Fee(1) };
+    let start_cancellation = L1Event::MessageToL2CancellationStarted {
+        cancelled_message: message(0),
+        cancellation_request_timestamp: 0,
+    };
+
+    let tx_hash = base_layer.add_transaction(vec![log_message.clone()]);
+    // Pending transactions aren't on the chain.
+    assert_eq!(base_layer.transaction_events(tx_hash).await.unwrap(), vec![]);
+    base_layer.mine_block();
+    base_layer.add_block(vec![start_cancellation, L1Event::ConsumedMessageToL2(message(1))]);
+
+    // The cancellation request timestamp is the timestamp of its block.
+    let expected_start_cancellation = L1Event::MessageToL2CancellationStarted {
+        cancelled_message: message(0),
+        cancellation_request_timestamp: 2 * FAKE_L1_BLOCK_TIME_SECONDS,
+    };
+    let events = base_layer
+        .events(
+            0..=2,
+            &[
+                LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+                MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(events, vec![log_message.clone(), expected_start_cancellation]);
+    assert_eq!(
+        base_layer.events(2..=2, &[LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER]).await.unwrap(),
+        vec![]
+    );
+    assert_eq!(base_layer.transaction_events(tx_hash).await.unwrap(), vec![log_message]);
+}
+
+#[tokio::test]
+async fn messages_to_l2_get_consecutive_nonces() {
+    let base_layer = FakeBaseLayerContract::new();
+    // The nonce of the sent message is ignored.
+    let tx_hashes = [
+        base_layer.send_message_to_l2(message(7), Fee(1)),
+        base_layer.send_message_to_l2(message(7), Fee(1)),
+    ];
+    base_layer.mine_block();
+
+    for (nonce, tx_hash) in tx_hashes.into_iter().enumerate() {
+        let nonce = u8::try_from(nonce).unwrap();
+        assert_eq!(
+            base_layer.transaction_events(tx_hash).await.unwrap(),
+            vec![L1Event::LogMessageToL2 { tx: message(nonce).into_l1_handler_tx(), fee: Fee(1) }]
+        );
+    }
+}
+
+#[tokio::test]
+async fn price_samples() {
+    let base_layer = FakeBaseLayerContract::new();
+    base_layer.set_prices(10, 20);
+    base_layer.mine_block();
+
+    let price_sample = base_layer.get_price_sample(1).await.unwrap().unwrap();
+    assert_eq!(
+        (price_sample.timestamp, price_sample.base_fee_per_gas, price_sample.blob_fee),
+        (FAKE_L1_BLOCK_TIME_SECONDS, 10, 20)
+    );
+    let genesis_price_sample = base_layer.get_price_sample(0).await.unwrap().unwrap();
+    assert_eq!(genesis_price_sample.base_fee_per_gas, 0);
+    assert!(base_layer.get_price_sample(2).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn proved_blocks() {
+    let base_layer = FakeBaseLayerContract::new();
+    base_layer.prove_block(proved_block(5));
+    base_layer.mine_block();
+    base_layer.mine_block();
+    base_layer.prove_block(proved_block(7));
+    base_layer.mine_block();
+
+    assert_eq!(base_layer.get_proved_block_at(0).await.unwrap(), BlockHashAndNumber::default());
+    assert_eq!(base_layer.get_proved_block_at(2).await.unwrap(), proved_block(5));
+    assert_eq!(base_layer.latest_proved_block(0).await.unwrap(), Some(proved_block(7)));
+    assert_eq!(base_layer.latest_proved_block(1).await.unwrap(), Some(proved_block(5)));
+    assert_eq!(base_layer.latest_proved_block(4).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn state_updates() {
+    let base_layer = FakeBaseLayerContract::new();
+    let tx_hash = base_layer.prove_block(proved_block(5));
+    let l1_block = base_layer.mine_block();
+    base_layer.mine_block();
+
+    let expected_state_update =
+        L1StateUpdate { block_number: BlockNumber(5), l1_block, l1_transaction_hash: tx_hash };
+    assert_eq!(base_layer.state_updates(0..=2).await.unwrap(), vec![expected_state_update]);
+    assert_eq!(base_layer.state_updates(2..=2).await.unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn message_to_l2_cancellation() {
+    let base_layer = FakeBaseLayerContract::new();
+    let start_cancellation = |nonce| L1Event::MessageToL2CancellationStarted {
+        cancelled_message: message(nonce),
+        cancellation_request_timestamp: 0,
+    };
+    let cancellation = |nonce: u8| {
+        let base_layer = base_layer.clone();
+        async move {
+            base_layer
+                .message_to_l2_cancellation(&message(nonce).into_l1_handler_tx())
+                .await
+                .unwrap()
+        }
+    };
+
+    base_layer.add_block(vec![start_cancellation(0), start_cancellation(1)]);
+    let expected_requested = MessageToL2Cancellation::Requested {
+        cancellation_request_timestamp: FAKE_L1_BLOCK_TIME_SECONDS,
+    };
+    assert_eq!(cancellation(0).await, expected_requested);
+    assert_eq!(cancellation(1).await, expected_requested);
+    assert_eq!(cancellation(2).await, MessageToL2Cancellation::NotRequested);
+
+    // A message whose cancellation was requested isn't pending after it's canceled or consumed.
+    base_layer.add_block(vec![L1Event::MessageToL2Canceled(message(0))]);
+    assert_eq!(cancellation(0).await, MessageToL2Cancellation::Canceled);
+    assert_eq!(cancellation(1).await, expected_requested);
+    base_layer.add_block(vec![L1Event::ConsumedMessageToL2(message(1))]);
+    assert_eq!(cancellation(1).await, MessageToL2Cancellation::Canceled);
+}
+
+#[tokio::test]
+async fn reorg() {
+    let base_layer = FakeBaseLayerContract::new();
+    base_layer.mine_block();
+    let tx_hash = base_layer.add_transaction(vec![L1Event::MessageToL2Canceled(message(0))]);
+    let reorged_block = base_layer.mine_block();
+
+    base_layer.reorg(1);
+    assert_eq!(base_layer.latest_block().number, 1);
+    assert_eq!(base_layer.transaction_events(tx_hash).await.unwrap(), vec![]);
+
+    let new_block = base_layer.mine_block();
+    assert_eq!(new_block.number, reorged_block.number);
+    assert_ne!(new_block.hash, reorged_block.hash);
+}
+
+#[tokio::test]
+async fn unavailable() {
+    let base_layer = FakeBaseLayerContract::new();
+    base_layer.set_unavailable(true);
+    assert_matches!(base_layer.latest_l1_block(0).await, Err(FakeBaseLayerError::Unavailable));
+    assert_matches!(base_layer.get_price_sample(0).await, Err(FakeBaseLayerError::Unavailable));
+
+    base_layer.set_unavailable(false);
+    assert_matches!(base_layer.latest_l1_block(0).await, Ok(Some(_)));
+}
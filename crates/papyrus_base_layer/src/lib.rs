@@ -6,7 +6,7 @@ use async_trait::async_trait;
 #[cfg(any(feature = "testing", test))]
 use mockall::automock;
 use serde::{Deserialize, Serialize};
-use starknet_api::block::BlockHashAndNumber;
+use starknet_api::block::{BlockHashAndNumber, BlockNumber};
 use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress, Nonce};
 use starknet_api::transaction::fields::{Calldata, Fee};
 use starknet_api::transaction::L1HandlerTransaction;
@@ -14,9 +14,13 @@ use thiserror::Error;
 
 pub mod constants;
 pub mod ethereum_base_layer_contract;
+pub mod metrics;
+pub mod quorum_base_layer_contract;
 
 pub(crate) mod eth_events;
 
+#[cfg(any(feature = "testing", test))]
+pub mod fake_base_layer_contract;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 
@@ -24,6 +28,7 @@ pub mod test_utils;
 mod base_layer_test;
 
 pub type L1BlockNumber = u64;
+pub type L1TransactionHash = [u8; 32];
 
 #[derive(Debug, Error)]
 pub enum MockError {}
@@ -68,10 +73,31 @@ pub trait BaseLayerContract {
         event_identifiers: &'a [&'a str],
     ) -> Result<Vec<L1Event>, Self::Error>;
 
+    /// Get the events of the Starknet base contract that were emitted by the given L1 transaction.
+    /// Returns no events if the transaction is unknown.
+    async fn transaction_events(
+        &self,
+        transaction_hash: L1TransactionHash,
+    ) -> Result<Vec<L1Event>, Self::Error>;
+
     async fn get_price_sample(
         &self,
         block_number: L1BlockNumber,
     ) -> Result<Option<PriceSample>, Self::Error>;
+
+    /// Get the state updates of the Starknet base contract between two L1 block numbers, i.e., the
+    /// L1 transactions that proved Starknet blocks.
+    async fn state_updates(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1StateUpdate>, Self::Error>;
+
+    /// Get the cancellation state of the message from L1 to L2 that is executed by the given L1
+    /// handler transaction in the Starknet base contract.
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> Result<MessageToL2Cancellation, Self::Error>;
 }
 
 /// A struct that holds together the data on the base layer's gas prices, for a given timestamp.
@@ -90,12 +116,45 @@ pub struct L1BlockReference {
     pub hash: [u8; 32],
 }
 
+/// A state update of the Starknet base contract, which proves all the Starknet blocks up to and
+/// including its block number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct L1StateUpdate {
+    pub block_number: BlockNumber,
+    pub l1_block: L1BlockReference,
+    pub l1_transaction_hash: L1TransactionHash,
+}
+
+/// The cancellation state of a message from L1 to L2 in the Starknet base contract.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MessageToL2Cancellation {
+    #[default]
+    NotRequested,
+    /// The sender of the message requested to cancel it, and the message is still pending.
+    Requested {
+        /// The timestamp of the L1 block of the request, in seconds.
+        cancellation_request_timestamp: u64,
+    },
+    /// The cancellation was requested and the message isn't pending anymore. Unless the message
+    /// was consumed by a state update before the cancellation was completed, it was canceled.
+    Canceled,
+}
+
 /// Wraps Starknet L1 events with Starknet API types.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum L1Event {
     ConsumedMessageToL2(EventData),
-    LogMessageToL2 { tx: L1HandlerTransaction, fee: Fee },
-    MessageToL2CancellationStarted(EventData),
+    LogMessageToL2 {
+        tx: L1HandlerTransaction,
+        fee: Fee,
+    },
+    /// The sender of the message requested to cancel it; the message can be canceled once the
+    /// cancellation delay of the Starknet contract has passed since the request.
+    MessageToL2CancellationStarted {
+        cancelled_message: EventData,
+        /// The timestamp of the L1 block of the request, in seconds.
+        cancellation_request_timestamp: u64,
+    },
     MessageToL2Canceled(EventData),
 }
 
@@ -108,3 +167,20 @@ pub struct EventData {
     pub payload: Calldata,
     pub nonce: Nonce,
 }
+
+impl EventData {
+    /// Returns the L1 handler transaction that executes the message on L2. The calldata of the
+    /// transaction is the message payload, prepended with the L1 sender address.
+    pub fn into_l1_handler_tx(self) -> L1HandlerTransaction {
+        let calldata = std::iter::once(self.from_address.into())
+            .chain(self.payload.0.iter().copied())
+            .collect::<Vec<_>>();
+        L1HandlerTransaction {
+            version: L1HandlerTransaction::VERSION,
+            contract_address: self.to_address,
+            entry_point_selector: self.entry_point_selector,
+            nonce: self.nonce,
+            calldata: Calldata(calldata.into()),
+        }
+    }
+}
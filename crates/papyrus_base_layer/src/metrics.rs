@@ -0,0 +1,61 @@
+use apollo_metrics::metrics::{LabeledMetricCounter, MetricCounter};
+use apollo_metrics::{define_metrics, generate_permutation_labels};
+use strum::{EnumVariantNames, VariantNames};
+use strum_macros::{EnumIter, FromRepr, IntoStaticStr};
+
+define_metrics!(
+    BaseLayer => {
+        LabeledMetricCounter { BASE_LAYER_ENDPOINT_REQUESTS, "base_layer_endpoint_requests", "Counter of requests sent to each base layer endpoint", init = 0, labels = ENDPOINT_LABELS },
+        LabeledMetricCounter { BASE_LAYER_ENDPOINT_FAILURES, "base_layer_endpoint_failures", "Counter of failed or timed out requests to each base layer endpoint", init = 0, labels = ENDPOINT_LABELS },
+        LabeledMetricCounter { BASE_LAYER_ENDPOINT_DISAGREEMENTS, "base_layer_endpoint_disagreements", "Counter of responses of each base layer endpoint that differ from the agreed response", init = 0, labels = ENDPOINT_LABELS },
+        MetricCounter { BASE_LAYER_QUORUM_FAILURES, "base_layer_quorum_failures", "Counter of requests for which the base layer endpoints didn't reach a quorum", init = 0 },
+    },
+);
+
+pub const LABEL_NAME_ENDPOINT: &str = "endpoint";
+
+generate_permutation_labels! {
+    ENDPOINT_LABELS,
+    (LABEL_NAME_ENDPOINT, EndpointLabelValue),
+}
+
+/// The index of a base layer endpoint, by the order of the endpoints in the config. Endpoints are
+/// labeled by index since their URLs are private.
+#[derive(Clone, Copy, Debug, EnumIter, EnumVariantNames, FromRepr, IntoStaticStr)]
+#[repr(usize)]
+pub enum EndpointLabelValue {
+    #[strum(serialize = "0")]
+    Endpoint0,
+    #[strum(serialize = "1")]
+    Endpoint1,
+    #[strum(serialize = "2")]
+    Endpoint2,
+    #[strum(serialize = "3")]
+    Endpoint3,
+    #[strum(serialize = "4")]
+    Endpoint4,
+    #[strum(serialize = "5")]
+    Endpoint5,
+    #[strum(serialize = "6")]
+    Endpoint6,
+    #[strum(serialize = "7")]
+    Endpoint7,
+}
+
+/// The maximal number of endpoints of a quorum base layer contract.
+pub const MAX_BASE_LAYER_ENDPOINTS: usize = EndpointLabelValue::VARIANTS.len();
+
+impl EndpointLabelValue {
+    pub fn label(endpoint_index: usize) -> [(&'static str, &'static str); 1] {
+        let label_value = Self::from_repr(endpoint_index)
+            .unwrap_or_else(|| panic!("Endpoint index {endpoint_index} has no metric label."));
+        [(LABEL_NAME_ENDPOINT, label_value.into())]
+    }
+}
+
+pub(crate) fn register_base_layer_metrics() {
+    BASE_LAYER_ENDPOINT_REQUESTS.register();
+    BASE_LAYER_ENDPOINT_FAILURES.register();
+    BASE_LAYER_ENDPOINT_DISAGREEMENTS.register();
+    BASE_LAYER_QUORUM_FAILURES.register();
+}
@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use apollo_config::converters::{
+    deserialize_float_seconds_to_duration,
+    deserialize_vec,
+    serialize_slice,
+};
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockHashAndNumber;
+use starknet_api::transaction::L1HandlerTransaction;
+use url::Url;
+use validator::{Validate, ValidationError};
+
+use crate::ethereum_base_layer_contract::{EthereumBaseLayerConfig, EthereumBaseLayerContract};
+use crate::metrics::{
+    register_base_layer_metrics,
+    EndpointLabelValue,
+    BASE_LAYER_ENDPOINT_DISAGREEMENTS,
+    BASE_LAYER_ENDPOINT_FAILURES,
+    BASE_LAYER_ENDPOINT_REQUESTS,
+    BASE_LAYER_QUORUM_FAILURES,
+    MAX_BASE_LAYER_ENDPOINTS,
+};
+use crate::{
+    BaseLayerContract,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+    L1StateUpdate,
+    L1TransactionHash,
+    MessageToL2Cancellation,
+    PriceSample,
+};
+
+#[cfg(test)]
+#[path = "quorum_base_layer_contract_test.rs"]
+mod quorum_base_layer_contract_test;
+
+pub type QuorumBaseLayerResult<T, E> = Result<T, QuorumBaseLayerError<E>>;
+
+/// A base layer contract that sends every request to several endpoints (e.g., L1 nodes of
+/// different providers), so that a single faulty endpoint can neither stall nor mislead it.
+///
+/// A request succeeds once at least `quorum` endpoints respond within the endpoint timeout, and
+/// failing endpoints are skipped. Responses that are expected to be identical across endpoints
+/// must be agreed on by `quorum` endpoints; the latest L1 block is the latest one that `quorum`
+/// endpoints reached, and price samples are the median of the responses.
+#[derive(Clone, Debug)]
+pub struct QuorumBaseLayerContract<B> {
+    pub endpoints: Vec<B>,
+    pub quorum: usize,
+    pub endpoint_timeout: Duration,
+}
+
+impl<B: BaseLayerContract + Send + Sync> QuorumBaseLayerContract<B> {
+    pub fn new(endpoints: Vec<B>, quorum: usize, endpoint_timeout: Duration) -> Self {
+        assert!(
+            (1..=endpoints.len()).contains(&quorum),
+            "Quorum {quorum} must be positive and at most the number of endpoints, {}.",
+            endpoints.len()
+        );
+        assert!(
+            endpoints.len() <= MAX_BASE_LAYER_ENDPOINTS,
+            "At most {MAX_BASE_LAYER_ENDPOINTS} base layer endpoints are supported."
+        );
+        register_base_layer_metrics();
+        Self { endpoints, quorum, endpoint_timeout }
+    }
+
+    /// Sends the query to all endpoints concurrently, and returns the responses of the endpoints
+    /// that answered in time, along with their indices. Fails if fewer than `quorum` endpoints
+    /// answered.
+    async fn query_endpoints<'a, T, Fut>(
+        &'a self,
+        query: impl Fn(&'a B) -> Fut,
+    ) -> QuorumBaseLayerResult<Vec<(usize, T)>, B::Error>
+    where
+        Fut: Future<Output = Result<T, B::Error>>,
+    {
+        let queries = self.endpoints.iter().enumerate().map(|(index, endpoint)| {
+            BASE_LAYER_ENDPOINT_REQUESTS.increment(1, &EndpointLabelValue::label(index));
+            let response = tokio::time::timeout(self.endpoint_timeout, query(endpoint));
+            async move {
+                match response.await {
+                    Ok(Ok(response)) => Ok((index, response)),
+                    Ok(Err(error)) => Err(EndpointError::Failed { index, error }),
+                    Err(_) => Err(EndpointError::TimedOut { index }),
+                }
+            }
+        });
+
+        let mut responses = Vec::new();
+        let mut errors = Vec::new();
+        for result in join_all(queries).await {
+            match result {
+                Ok(response) => responses.push(response),
+                Err(error) => {
+                    BASE_LAYER_ENDPOINT_FAILURES
+                        .increment(1, &EndpointLabelValue::label(error.index()));
+                    errors.push(error);
+                }
+            }
+        }
+
+        if responses.len() < self.quorum {
+            BASE_LAYER_QUORUM_FAILURES.increment(1);
+            return Err(QuorumBaseLayerError::NotEnoughResponses {
+                n_responses: responses.len(),
+                quorum: self.quorum,
+                errors,
+            });
+        }
+        Ok(responses)
+    }
+
+    /// Returns the response that `quorum` of the endpoints agree on. Fails if no response, or more
+    /// than one response, reached a quorum.
+    async fn query_agreed<'a, T: PartialEq, Fut>(
+        &'a self,
+        query: impl Fn(&'a B) -> Fut,
+    ) -> QuorumBaseLayerResult<T, B::Error>
+    where
+        Fut: Future<Output = Result<T, B::Error>>,
+    {
+        // Distinct responses, along with the indices of the endpoints that responded with them.
+        let mut responses: Vec<(T, Vec<usize>)> = Vec::new();
+        for (index, response) in self.query_endpoints(query).await? {
+            match responses.iter_mut().find(|(distinct_response, _)| *distinct_response == response)
+            {
+                Some((_, indices)) => indices.push(index),
+                None => responses.push((response, vec![index])),
+            }
+        }
+
+        let mut agreed_positions = responses
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, indices))| indices.len() >= self.quorum)
+            .map(|(position, _)| position);
+        let (Some(agreed_position), None) = (agreed_positions.next(), agreed_positions.next())
+        else {
+            BASE_LAYER_QUORUM_FAILURES.increment(1);
+            return Err(QuorumBaseLayerError::NoAgreement { quorum: self.quorum });
+        };
+
+        let (agreed_response, _) = responses.swap_remove(agreed_position);
+        for index in responses.into_iter().flat_map(|(_, indices)| indices) {
+            BASE_LAYER_ENDPOINT_DISAGREEMENTS.increment(1, &EndpointLabelValue::label(index));
+        }
+        Ok(agreed_response)
+    }
+}
+
+#[async_trait]
+impl<B> BaseLayerContract for QuorumBaseLayerContract<B>
+where
+    B: BaseLayerContract + Send + Sync,
+    B::Error: Send,
+{
+    type Error = QuorumBaseLayerError<B::Error>;
+
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> QuorumBaseLayerResult<BlockHashAndNumber, B::Error> {
+        self.query_agreed(|endpoint| endpoint.get_proved_block_at(l1_block)).await
+    }
+
+    async fn latest_proved_block(
+        &self,
+        finality: u64,
+    ) -> QuorumBaseLayerResult<Option<BlockHashAndNumber>, B::Error> {
+        let Some(l1_block_number) = self.latest_l1_block_number(finality).await? else {
+            return Ok(None);
+        };
+        self.get_proved_block_at(l1_block_number).await.map(Some)
+    }
+
+    /// Returns the latest L1 block number that at least `quorum` of the endpoints reached, as
+    /// endpoints naturally lag behind each other by a few blocks.
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> QuorumBaseLayerResult<Option<L1BlockNumber>, B::Error> {
+        let mut block_numbers: Vec<_> = self
+            .query_endpoints(|endpoint| endpoint.latest_l1_block_number(finality))
+            .await?
+            .into_iter()
+            .map(|(_, block_number)| block_number)
+            .collect();
+        block_numbers.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(block_numbers[self.quorum - 1])
+    }
+
+    async fn latest_l1_block(
+        &self,
+        finality: u64,
+    ) -> QuorumBaseLayerResult<Option<L1BlockReference>, B::Error> {
+        let Some(block_number) = self.latest_l1_block_number(finality).await? else {
+            return Ok(None);
+        };
+        self.l1_block_at(block_number).await
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> QuorumBaseLayerResult<Option<L1BlockReference>, B::Error> {
+        self.query_agreed(|endpoint| endpoint.l1_block_at(block_number)).await
+    }
+
+    async fn events<'a>(
+        &'a self,
+        block_range: RangeInclusive<L1BlockNumber>,
+        event_identifiers: &'a [&'a str],
+    ) -> QuorumBaseLayerResult<Vec<L1Event>, B::Error> {
+        self.query_agreed(|endpoint| endpoint.events(block_range.clone(), event_identifiers)).await
+    }
+
+    async fn transaction_events(
+        &self,
+        transaction_hash: L1TransactionHash,
+    ) -> QuorumBaseLayerResult<Vec<L1Event>, B::Error> {
+        self.query_agreed(|endpoint| endpoint.transaction_events(transaction_hash)).await
+    }
+
+    async fn state_updates(
+        &self,
+        block_range: RangeInclusive<L1BlockNumber>,
+    ) -> QuorumBaseLayerResult<Vec<L1StateUpdate>, B::Error> {
+        self.query_agreed(|endpoint| endpoint.state_updates(block_range.clone())).await
+    }
+
+    async fn message_to_l2_cancellation(
+        &self,
+        message: &L1HandlerTransaction,
+    ) -> QuorumBaseLayerResult<MessageToL2Cancellation, B::Error> {
+        self.query_agreed(|endpoint| endpoint.message_to_l2_cancellation(message)).await
+    }
+
+    /// Returns the median of the price samples of the endpoints, so that a minority of endpoints
+    /// can't skew the gas prices. Returns `None` if fewer than `quorum` endpoints have the block.
+    async fn get_price_sample(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> QuorumBaseLayerResult<Option<PriceSample>, B::Error> {
+        let samples: Vec<_> = self
+            .query_endpoints(|endpoint| endpoint.get_price_sample(block_number))
+            .await?
+            .into_iter()
+            .filter_map(|(_, sample)| sample)
+            .collect();
+        if samples.len() < self.quorum {
+            return Ok(None);
+        }
+
+        Ok(Some(PriceSample {
+            timestamp: median(samples.iter().map(|sample| sample.timestamp)),
+            base_fee_per_gas: median(samples.iter().map(|sample| sample.base_fee_per_gas)),
+            blob_fee: median(samples.iter().map(|sample| sample.blob_fee)),
+        }))
+    }
+}
+
+impl QuorumBaseLayerContract<EthereumBaseLayerContract> {
+    /// Creates a contract whose first endpoint is the node of the given Ethereum config, and whose
+    /// other endpoints share that config, except for the node URL.
+    pub fn from_config(
+        ethereum_base_layer_config: EthereumBaseLayerConfig,
+        config: QuorumBaseLayerConfig,
+    ) -> Self {
+        let additional_endpoints = config.additional_node_urls.iter().map(|node_url| {
+            EthereumBaseLayerContract::new(EthereumBaseLayerConfig {
+                node_url: node_url.clone(),
+                ..ethereum_base_layer_config.clone()
+            })
+        });
+        let endpoints =
+            std::iter::once(EthereumBaseLayerContract::new(ethereum_base_layer_config.clone()))
+                .chain(additional_endpoints)
+                .collect();
+        Self::new(endpoints, config.quorum, config.endpoint_timeout)
+    }
+}
+
+/// Returns the lower median of the given values, which must not be empty.
+fn median<T: Copy + Ord>(values: impl Iterator<Item = T>) -> T {
+    let mut values: Vec<_> = values.collect();
+    values.sort_unstable();
+    values[(values.len() - 1) / 2]
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum QuorumBaseLayerError<E: std::error::Error> {
+    #[error("No response was agreed on by a quorum of {quorum} base layer endpoints.")]
+    NoAgreement { quorum: usize },
+    #[error(
+        "Only {n_responses} base layer endpoints responded, fewer than the quorum of {quorum}: \
+         {errors:?}."
+    )]
+    NotEnoughResponses { n_responses: usize, quorum: usize, errors: Vec<EndpointError<E>> },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum EndpointError<E: std::error::Error> {
+    #[error("Base layer endpoint {index} failed: {error}")]
+    Failed { index: usize, error: E },
+    #[error("Base layer endpoint {index} timed out.")]
+    TimedOut { index: usize },
+}
+
+impl<E: std::error::Error> EndpointError<E> {
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Failed { index, .. } | Self::TimedOut { index } => *index,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize, Validate)]
+#[validate(schema(function = "validate_quorum_base_layer_config"))]
+pub struct QuorumBaseLayerConfig {
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub additional_node_urls: Vec<Url>,
+    pub quorum: usize,
+    #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
+    pub endpoint_timeout: Duration,
+}
+
+impl Default for QuorumBaseLayerConfig {
+    fn default() -> Self {
+        Self {
+            additional_node_urls: Vec::new(),
+            quorum: 1,
+            endpoint_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl SerializeConfig for QuorumBaseLayerConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "additional_node_urls",
+                &serialize_slice(&self.additional_node_urls),
+                "Space separated URLs of additional Ethereum nodes, which are queried along with \
+                 the node of the base layer config.",
+                ParamPrivacyInput::Private,
+            ),
+            ser_param(
+                "quorum",
+                &self.quorum,
+                "Number of Ethereum nodes that must respond, and agree on the response, for a \
+                 base layer request to succeed.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "endpoint_timeout",
+                &self.endpoint_timeout.as_secs_f64(),
+                "Time in seconds to wait for the response of an Ethereum node, after which the \
+                 node is considered failed for the request.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+fn validate_quorum_base_layer_config(
+    config: &QuorumBaseLayerConfig,
+) -> Result<(), ValidationError> {
+    let n_endpoints = config.additional_node_urls.len() + 1;
+    if !(1..=n_endpoints).contains(&config.quorum) {
+        return Err(ValidationError::new(
+            "quorum must be positive and at most the number of Ethereum nodes",
+        ));
+    }
+    if n_endpoints > MAX_BASE_LAYER_ENDPOINTS {
+        return Err(ValidationError::new("too many Ethereum nodes"));
+    }
+    Ok(())
+}
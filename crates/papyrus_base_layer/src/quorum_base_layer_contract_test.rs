@@ -0,0 +1,303 @@
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use metrics_exporter_prometheus::PrometheusBuilder;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::L1HandlerTransaction;
+use starknet_types_core::felt::Felt;
+
+use crate::metrics::{
+    EndpointLabelValue,
+    BASE_LAYER_ENDPOINT_DISAGREEMENTS,
+    BASE_LAYER_ENDPOINT_FAILURES,
+    BASE_LAYER_QUORUM_FAILURES,
+};
+use crate::quorum_base_layer_contract::{
+    EndpointError,
+    QuorumBaseLayerContract,
+    QuorumBaseLayerError,
+};
+use crate::{
+    BaseLayerContract,
+    EventData,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+    L1StateUpdate,
+    L1TransactionHash,
+    MessageToL2Cancellation,
+    PriceSample,
+};
+
+const ENDPOINT_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Fake endpoint error.")]
+struct FakeEndpointError;
+
+/// A base layer endpoint with a fixed view of L1.
+#[derive(Clone, Copy, Debug)]
+enum FakeEndpoint {
+    Healthy {
+        latest_block_number: L1BlockNumber,
+        /// Distinguishes between the views of L1 of different endpoints.
+        view: u8,
+        base_fee_per_gas: u128,
+    },
+    Failing,
+    Stalled,
+}
+
+impl FakeEndpoint {
+    fn healthy(view: u8) -> Self {
+        Self::Healthy { latest_block_number: 10, view, base_fee_per_gas: 1 }
+    }
+
+    async fn respond<T>(
+        &self,
+        response: impl FnOnce(L1BlockNumber, u8, u128) -> T,
+    ) -> Result<T, FakeEndpointError> {
+        match *self {
+            Self::Healthy { latest_block_number, view, base_fee_per_gas } => {
+                Ok(response(latest_block_number, view, base_fee_per_gas))
+            }
+            Self::Failing => Err(FakeEndpointError),
+            Self::Stalled => std::future::pending().await,
+        }
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for FakeEndpoint {
+    type Error = FakeEndpointError;
+
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> Result<BlockHashAndNumber, FakeEndpointError> {
+        self.respond(|_, view, _| BlockHashAndNumber {
+            number: BlockNumber(l1_block),
+            hash: BlockHash(Felt::from(view)),
+        })
+        .await
+    }
+
+    async fn latest_proved_block(
+        &self,
+        _finality: u64,
+    ) -> Result<Option<BlockHashAndNumber>, FakeEndpointError> {
+        unimplemented!("Not used by the quorum contract.")
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> Result<Option<L1BlockNumber>, FakeEndpointError> {
+        self.respond(|latest_block_number, _, _| latest_block_number.checked_sub(finality)).await
+    }
+
+    async fn latest_l1_block(
+        &self,
+        _finality: u64,
+    ) -> Result<Option<L1BlockReference>, FakeEndpointError> {
+        unimplemented!("Not used by the quorum contract.")
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<L1BlockReference>, FakeEndpointError> {
+        self.respond(|latest_block_number, view, _| {
+            (block_number <= latest_block_number)
+                .then_some(L1BlockReference { number: block_number, hash: [view; 32] })
+        })
+        .await
+    }
+
+    async fn events<'a>(
+        &'a self,
+        _block_range: RangeInclusive<L1BlockNumber>,
+        _event_identifiers: &'a [&'a str],
+    ) -> Result<Vec<L1Event>, FakeEndpointError> {
+        self.respond(|_, view, _| {
+            vec![L1Event::ConsumedMessageToL2(EventData {
+                nonce: Nonce(view.into()),
+                ..Default::default()
+            })]
+        })
+        .await
+    }
+
+    async fn transaction_events(
+        &self,
+        _transaction_hash: L1TransactionHash,
+    ) -> Result<Vec<L1Event>, FakeEndpointError> {
+        self.respond(|_, _, _| vec![]).await
+    }
+
+    async fn get_price_sample(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> Result<Option<PriceSample>, FakeEndpointError> {
+        self.respond(|latest_block_number, _, base_fee_per_gas| {
+            (block_number <= latest_block_number).then_some(PriceSample {
+                timestamp: block_number,
+                base_fee_per_gas,
+                blob_fee: base_fee_per_gas * 2,
+            })
+        })
+        .await
+    }
+
+    async fn state_updates(
+        &self,
+        _block_range: RangeInclusive<L1BlockNumber>,
+    ) -> Result<Vec<L1StateUpdate>, FakeEndpointError> {
+        self.respond(|_, _, _| vec![]).await
+    }
+
+    async fn message_to_l2_cancellation(
+        &self,
+        _message: &L1HandlerTransaction,
+    ) -> Result<MessageToL2Cancellation, FakeEndpointError> {
+        self.respond(|_, _, _| MessageToL2Cancellation::NotRequested).await
+    }
+}
+
+fn quorum_contract(
+    endpoints: impl IntoIterator<Item = FakeEndpoint>,
+    quorum: usize,
+) -> QuorumBaseLayerContract<FakeEndpoint> {
+    QuorumBaseLayerContract::new(endpoints.into_iter().collect(), quorum, ENDPOINT_TIMEOUT)
+}
+
+#[tokio::test]
+async fn agreed_response_despite_faulty_endpoints() {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let _recorder_guard = metrics::set_default_local_recorder(&recorder);
+    let contract = quorum_contract(
+        [
+            FakeEndpoint::healthy(1),
+            FakeEndpoint::Failing,
+            FakeEndpoint::healthy(1),
+            FakeEndpoint::Stalled,
+            FakeEndpoint::healthy(2),
+            FakeEndpoint::healthy(1),
+        ],
+        3,
+    );
+
+    assert_eq!(
+        contract.l1_block_at(5).await.unwrap(),
+        Some(L1BlockReference { number: 5, hash: [1; 32] })
+    );
+    assert_eq!(
+        contract.get_proved_block_at(5).await.unwrap(),
+        BlockHashAndNumber { number: BlockNumber(5), hash: BlockHash(Felt::ONE) }
+    );
+    let events = contract.events(0..=10, &[]).await.unwrap();
+    assert_matches!(&events[..], [L1Event::ConsumedMessageToL2(EventData { nonce, .. })] if *nonce == Nonce(Felt::ONE));
+
+    let metrics = recorder.handle().render();
+    for (endpoint_index, expected_failures, expected_disagreements) in
+        [(0, 0, 0), (1, 3, 0), (3, 3, 0), (4, 0, 3)]
+    {
+        let label = EndpointLabelValue::label(endpoint_index);
+        BASE_LAYER_ENDPOINT_FAILURES.assert_eq(&metrics, expected_failures, &label);
+        BASE_LAYER_ENDPOINT_DISAGREEMENTS.assert_eq(&metrics, expected_disagreements, &label);
+    }
+    BASE_LAYER_QUORUM_FAILURES.assert_eq(&metrics, 0);
+}
+
+#[tokio::test]
+async fn no_agreement() {
+    let contract = quorum_contract(
+        [FakeEndpoint::healthy(1), FakeEndpoint::healthy(1), FakeEndpoint::healthy(2)],
+        3,
+    );
+    assert_matches!(
+        contract.l1_block_at(5).await,
+        Err(QuorumBaseLayerError::NoAgreement { quorum: 3 })
+    );
+
+    // A quorum of at most half of the endpoints may be reached by conflicting responses.
+    let contract = quorum_contract([FakeEndpoint::healthy(1), FakeEndpoint::healthy(2)], 1);
+    assert_matches!(
+        contract.l1_block_at(5).await,
+        Err(QuorumBaseLayerError::NoAgreement { quorum: 1 })
+    );
+}
+
+#[tokio::test]
+async fn not_enough_responses() {
+    let contract = quorum_contract(
+        [FakeEndpoint::healthy(1), FakeEndpoint::Failing, FakeEndpoint::Stalled],
+        2,
+    );
+
+    let result = contract.l1_block_at(5).await;
+    let Err(QuorumBaseLayerError::NotEnoughResponses { n_responses: 1, quorum: 2, errors }) =
+        result
+    else {
+        panic!("Expected not enough responses, got: {result:?}");
+    };
+    assert_matches!(
+        &errors[..],
+        [EndpointError::Failed { index: 1, .. }, EndpointError::TimedOut { index: 2 }]
+    );
+}
+
+#[tokio::test]
+async fn latest_l1_block_reached_by_quorum() {
+    let endpoint_at = |latest_block_number| FakeEndpoint::Healthy {
+        latest_block_number,
+        view: 1,
+        base_fee_per_gas: 1,
+    };
+    let contract = quorum_contract(
+        [endpoint_at(15), endpoint_at(10), FakeEndpoint::Failing, endpoint_at(12)],
+        2,
+    );
+
+    assert_eq!(contract.latest_l1_block_number(0).await.unwrap(), Some(12));
+    assert_eq!(contract.latest_l1_block_number(12).await.unwrap(), Some(0));
+    assert_eq!(contract.latest_l1_block_number(13).await.unwrap(), None);
+    assert_eq!(
+        contract.latest_l1_block(2).await.unwrap(),
+        Some(L1BlockReference { number: 10, hash: [1; 32] })
+    );
+    assert_eq!(
+        contract.latest_proved_block(2).await.unwrap(),
+        Some(BlockHashAndNumber { number: BlockNumber(10), hash: BlockHash(Felt::ONE) })
+    );
+}
+
+#[tokio::test]
+async fn price_sample_is_median() {
+    let endpoint_with_fee = |base_fee_per_gas| FakeEndpoint::Healthy {
+        latest_block_number: 10,
+        view: 1,
+        base_fee_per_gas,
+    };
+    // A single endpoint can't skew the gas prices.
+    let contract = quorum_contract(
+        [endpoint_with_fee(100), endpoint_with_fee(1_000_000), endpoint_with_fee(90)],
+        2,
+    );
+
+    let sample = contract.get_price_sample(10).await.unwrap().unwrap();
+    assert_eq!((sample.timestamp, sample.base_fee_per_gas, sample.blob_fee), (10, 100, 200));
+
+    let contract = quorum_contract(
+        [
+            endpoint_with_fee(100),
+            FakeEndpoint::Healthy { latest_block_number: 9, view: 1, base_fee_per_gas: 100 },
+        ],
+        2,
+    );
+    assert!(contract.get_price_sample(10).await.unwrap().is_none());
+}
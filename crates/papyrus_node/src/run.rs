@@ -29,7 +29,10 @@ use apollo_starknet_client::reader::PendingData;
 use apollo_storage::storage_metrics::update_storage_metrics;
 use apollo_storage::{open_storage, StorageReader, StorageWriter};
 use futures::StreamExt;
-use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
+use papyrus_base_layer::ethereum_base_layer_contract::{
+    EthereumBaseLayerConfig,
+    EthereumBaseLayerContract,
+};
 use papyrus_common::metrics::COLLECT_PROFILING_METRICS;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_monitoring_gateway::MonitoringServer;
@@ -143,6 +146,9 @@ async fn spawn_rpc_server(
         pending_classes,
         storage_reader,
         VERSION_FULL,
+        Some(Arc::new(EthereumBaseLayerContract::new(config.base_layer.clone()))),
+        // The node doesn't run a mempool, so it doesn't serve the suggested tip.
+        None,
     )
     .await?;
     Ok(tokio::spawn(async move {